@@ -0,0 +1,143 @@
+//! Read I/O spent by the scan on every top-level directory (`--io-usage`), so the costly subtrees can be left
+//! out of the next scheduled runs on metered or slow storage.
+//!
+//! Both the directory listings and the contents read by the comparisons (`--by-content`, `--compare-text`) are
+//! accounted. The contents found on the cache aren't read again, so they don't count.
+
+use crate::units::format_size;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Read I/O spent on a directory.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Usage {
+    /// Bytes of content read.
+    pub bytes: u64,
+    /// Files whose content was read.
+    pub files: u64,
+    /// Directory entries listed.
+    pub entries: u64,
+}
+
+impl Usage {
+    fn add(&mut self, other: Usage) {
+        self.bytes += other.bytes;
+        self.files += other.files;
+        self.entries += other.entries;
+    }
+}
+
+/// Read I/O spent by a scan, by directory.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::accounting::{Accounting, Usage};
+/// # use std::path::{Path, PathBuf};
+/// let accounting = Accounting::new();
+/// accounting.listed(Path::new("./photos/2023"), 120);
+/// accounting.read(Path::new("./photos/2023/a.jpg"), 2048);
+/// accounting.read(Path::new("./notes.txt"), 10);
+/// let usage = accounting.per_top_level(Path::new("."));
+/// assert_eq!(usage[0], (PathBuf::from("./photos"), Usage { bytes: 2048, files: 1, entries: 120 }));
+/// assert_eq!(usage[1], (PathBuf::from("."), Usage { bytes: 10, files: 1, entries: 0 }));
+/// ```
+#[derive(Debug, Default)]
+pub struct Accounting {
+    dirs: Mutex<BTreeMap<PathBuf, Usage>>,
+}
+
+impl Accounting {
+    pub const fn new() -> Self {
+        Accounting { dirs: Mutex::new(BTreeMap::new()) }
+    }
+
+    fn add(&self, dir: &Path, usage: Usage) {
+        let mut dirs = self.dirs.lock().unwrap_or_else(|e| e.into_inner());
+        match dirs.get_mut(dir) {
+            Some(total) => total.add(usage),
+            None => {
+                dirs.insert(dir.to_path_buf(), usage);
+            }
+        }
+    }
+
+    /// Accounts the `bytes` read out of the file located at `path`.
+    pub fn read(&self, path: &Path, bytes: u64) {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        self.add(dir, Usage { bytes, files: 1, entries: 0 });
+    }
+
+    /// Accounts the listing of the `entries` of `dir`.
+    pub fn listed(&self, dir: &Path, entries: usize) {
+        self.add(dir, Usage { entries: entries as u64, ..Usage::default() });
+    }
+
+    /// Read I/O spent on every top-level directory of `root` (`root` itself stands for the entries stored
+    /// directly on it), the costliest first.
+    pub fn per_top_level(&self, root: &Path) -> Vec<(PathBuf, Usage)> {
+        let mut tops: BTreeMap<PathBuf, Usage> = BTreeMap::new();
+        for (dir, usage) in self.dirs.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            let top = match dir.strip_prefix(root).map(|relative| relative.iter().next()) {
+                Ok(Some(top)) => root.join(top),
+                Ok(None) => root.to_path_buf(),
+                Err(_) => continue,
+            };
+            tops.entry(top).or_default().add(*usage);
+        }
+        let mut tops: Vec<(PathBuf, Usage)> = tops.into_iter().collect();
+        tops.sort_by_key(|(_, usage)| std::cmp::Reverse((usage.bytes, usage.entries)));
+        tops
+    }
+
+    /// Lines describing the read I/O spent on every top-level directory of `root`.
+    pub fn summary(&self, root: &str) -> String {
+        let mut summary = format!("Read I/O per top-level directory of `{}`:\n", root);
+        for (dir, usage) in self.per_top_level(Path::new(root)) {
+            summary.push_str(&format!(
+                "\t`{}`: {} read from {} file(s), {} entries listed\n",
+                dir.display(),
+                format_size(usage.bytes),
+                usage.files,
+                usage.entries
+            ));
+        }
+        summary
+    }
+}
+
+/// Read I/O spent by the current scan.
+pub static SCAN: Accounting = Accounting::new();
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn usage_is_summed_up_per_top_level_directory() {
+        // Arrange
+        let accounting = Accounting::new();
+        accounting.listed(Path::new("/data"), 3);
+        accounting.listed(Path::new("/data/videos"), 2);
+        accounting.listed(Path::new("/data/videos/2023"), 5);
+        accounting.read(Path::new("/data/videos/2023/a.mp4"), 3 * 1024 * 1024);
+        accounting.read(Path::new("/data/videos/b.mp4"), 1024 * 1024);
+        accounting.listed(Path::new("/data/docs"), 40);
+        accounting.listed(Path::new("/elsewhere"), 1);
+
+        // Act
+        let usage = accounting.per_top_level(Path::new("/data"));
+        let summary = accounting.summary("/data");
+
+        // Assert
+        assert_eq!(
+            usage,
+            vec![
+                (PathBuf::from("/data/videos"), Usage { bytes: 4 * 1024 * 1024, files: 2, entries: 7 }),
+                (PathBuf::from("/data/docs"), Usage { bytes: 0, files: 0, entries: 40 }),
+                (PathBuf::from("/data"), Usage { bytes: 0, files: 0, entries: 3 }),
+            ]
+        );
+        assert!(summary.starts_with("Read I/O per top-level directory of `/data`:\n"));
+        assert!(summary.contains("\t`/data/videos`: 4.0 MiB read from 2 file(s), 7 entries listed\n"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Decisions taken on the interactive loop, recorded (`--record actions.log`) so they can be applied again to a
+//! later scan of the same tree (`--replay actions.log`): a cleanup can be rehearsed on a copy of a directory,
+//! then replayed on the real one once its outcome is checked.
+//!
+//! Every line of the log holds a decision as a JSON object (E.G: `{"action":"delete","path":"2023/a.jpg"}`),
+//! written as soon as it's applied, so an interrupted run keeps the decisions it applied. The paths are relative
+//! to the scanned directory, since the copy and the original don't live at the same place; the entry numbers
+//! aren't recorded, as the listing order may differ from a scan to another.
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// A decision taken about a found file.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum Action {
+    /// The file was deleted (picking its number, or through `auto`).
+    Delete { path: String },
+    /// The file is tolerated on the next runs (`keep <number>`).
+    Keep { path: String },
+    /// The other copies of the file were deleted, and it was renamed to `name` (`keep-as <number> <name>`).
+    KeepAs { path: String, name: String },
+}
+
+impl Action {
+    /// Path of the file the decision is about, relative to the scanned directory.
+    pub fn path(&self) -> &str {
+        match self {
+            Action::Delete { path } | Action::Keep { path } | Action::KeepAs { path, .. } => path,
+        }
+    }
+
+    /// Whether the decision is about the file located at `path`, found under `root`.
+    pub fn targets(&self, root: &Path, path: &Path) -> bool {
+        path.strip_prefix(root).is_ok_and(|relative| relative == Path::new(self.path()))
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Action::Delete { path } => write!(f, "delete `{}`", path),
+            Action::Keep { path } => write!(f, "keep `{}`", path),
+            Action::KeepAs { path, name } => write!(f, "keep `{}` as `{}`", path, name),
+        }
+    }
+}
+
+/// Path of the file located at `path` relative to `root`, as recorded.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::actions::relative;
+/// # use std::path::Path;
+/// assert_eq!(relative(Path::new("./photos"), Path::new("./photos/2023/a.jpg")), "2023/a.jpg");
+/// ```
+pub fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string()
+}
+
+/// Log the decisions are appended to.
+pub struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    /// Starts the log stored at `path` over.
+    pub fn create(path: &str) -> Result<Self, io::Error> {
+        Ok(Recorder { file: File::create(path)? })
+    }
+
+    pub fn record(&mut self, action: &Action) -> Result<(), io::Error> {
+        writeln!(self.file, "{}", serde_json::to_string(action)?)?;
+        self.file.flush()
+    }
+}
+
+/// Reads the decisions recorded on the log stored at `path`, in the order they were taken.
+pub fn load(path: &str) -> Result<Vec<Action>, Box<dyn Error>> {
+    let log = fs::read_to_string(path)?;
+    let lines = log.lines().filter(|line| !line.trim().is_empty());
+    Ok(lines.map(serde_json::from_str).collect::<Result<_, _>>()?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn recorded_actions_are_loaded_back() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let scratch = Scratch::new("actions");
+        let log = scratch.join("actions.log");
+        let log = log.to_str().unwrap();
+        let (rehearsal, real) = (Path::new("/tmp/copy"), Path::new("/home/me/photos"));
+        let actions = vec![
+            Action::Delete { path: relative(rehearsal, &rehearsal.join("2023").join("a.jpg")) },
+            Action::KeepAs { path: String::from("b.jpg"), name: String::from("beach.jpg") },
+        ];
+
+        // Act
+        let mut recorder = Recorder::create(log)?;
+        for action in &actions {
+            recorder.record(action)?;
+        }
+        let loaded = load(log)?;
+
+        // Assert
+        assert_eq!(loaded, actions);
+        assert_eq!(loaded[1].to_string(), "keep `b.jpg` as `beach.jpg`");
+        assert!(loaded[0].targets(real, &real.join("2023").join("a.jpg")));
+        assert!(!loaded[0].targets(real, &real.join("a.jpg")));
+        assert!(fs::read_to_string(log)?.contains(r#"{"action":"keep-as","path":"b.jpg","name":"beach.jpg"}"#));
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! Hints about what the found files are and which application opens them, so the unfamiliar ones
+//! (`~$report.docx`, `.pkpass`...) can be told apart before deleting them.
+//!
+//! What a file is comes from a table of well-known names and extensions. The application comes from the
+//! associations of the system (`xdg-mime` on Linux, `assoc` and `ftype` on Windows; macOS isn't supported), and
+//! is looked up once per extension.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+/// What the file named `name` is, when it's a well-known kind of file.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::apps;
+/// assert_eq!(apps::kind("~$report.docx"), Some("Office owner file, left while the document is open"));
+/// assert_eq!(apps::kind("ticket.PKPASS"), Some("Apple Wallet pass (boarding pass, ticket...)"));
+/// assert_eq!(apps::kind("notes.unknown"), None);
+/// ```
+pub fn kind(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    match name.as_str() {
+        _ if name.starts_with("~$") => return Some("Office owner file, left while the document is open"),
+        _ if name.starts_with(".~lock.") => return Some("LibreOffice lock file, left while the document is open"),
+        ".ds_store" => return Some("Finder folder settings"),
+        "thumbs.db" => return Some("Windows Explorer thumbnail cache"),
+        "desktop.ini" => return Some("Windows Explorer folder settings"),
+        _ => {}
+    }
+    let extension = Path::new(&name).extension()?.to_str()?.to_string();
+    let kind = match extension.as_str() {
+        "pkpass" => "Apple Wallet pass (boarding pass, ticket...)",
+        "doc" | "docx" | "odt" | "rtf" => "text document",
+        "xls" | "xlsx" | "ods" | "csv" => "spreadsheet",
+        "ppt" | "pptx" | "odp" | "key" => "presentation",
+        "pdf" => "PDF document",
+        "ics" => "calendar event",
+        "vcf" => "contact card",
+        "eml" | "msg" => "email message",
+        "lnk" => "Windows shortcut",
+        "desktop" => "application launcher",
+        "url" | "webloc" => "link to a web page",
+        "torrent" => "torrent (points to a download)",
+        "crdownload" | "part" | "partial" => "unfinished download",
+        "tmp" | "temp" => "temporary file",
+        "bak" | "old" => "backup copy",
+        "iso" | "dmg" | "img" | "vhd" | "vhdx" => "disk image",
+        "exe" | "msi" => "Windows program or installer",
+        "apk" => "Android app",
+        "deb" | "rpm" | "pkg" | "appimage" => "application package",
+        "jpg" | "jpeg" | "png" | "gif" | "heic" | "webp" | "bmp" | "tiff" => "picture",
+        "cr2" | "cr3" | "nef" | "arw" | "dng" | "raf" | "orf" => "camera RAW picture",
+        "xmp" => "photo edits sidecar",
+        "aae" => "Apple Photos edits",
+        "mp4" | "mkv" | "mov" | "avi" | "webm" => "video",
+        "srt" | "vtt" => "subtitles",
+        "mp3" | "flac" | "m4a" | "ogg" | "wav" | "opus" => "audio",
+        "zip" | "7z" | "rar" | "tar" | "gz" | "xz" | "zst" => "archive",
+        "sqlite" | "db" => "database",
+        "log" => "log file",
+        "pem" | "p12" | "pfx" | "crt" => "key or certificate",
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Applications opening the files, by extension.
+static APPLICATIONS: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+
+/// Application the system opens the file located at `path` with, if any is associated with its extension.
+pub fn application(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    let applications = APPLICATIONS.get_or_init(Mutex::default);
+    let mut applications = applications.lock().unwrap_or_else(|e| e.into_inner());
+    applications
+        .entry(extension)
+        .or_insert_with_key(|extension| platform::application(path, extension))
+        .clone()
+}
+
+/// Standard output of `program`, when it succeeds.
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let output = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!output.is_empty()).then_some(output)
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::run;
+    use std::path::Path;
+
+    pub fn application(_path: &Path, extension: &str) -> Option<String> {
+        // `.docx=Word.Document.12`, then `Word.Document.12="C:\...\WINWORD.EXE" /n "%1"`.
+        let association = run("cmd", &["/c", "assoc", &format!(".{}", extension)])?;
+        let file_type = association.split_once('=')?.1.to_string();
+        let command = run("cmd", &["/c", "ftype", &file_type])?;
+        let command = command.split_once('=')?.1.trim_start_matches('"');
+        let program = command.split(['"', ' ']).next()?;
+        Path::new(program)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::run;
+    use std::path::Path;
+
+    pub fn application(path: &Path, _extension: &str) -> Option<String> {
+        let mime = run("xdg-mime", &["query", "filetype", &path.to_string_lossy()])?;
+        let desktop = run("xdg-mime", &["query", "default", &mime])?;
+        Some(desktop.trim_end_matches(".desktop").to_string())
+    }
+}
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+mod platform {
+    use std::path::Path;
+
+    pub fn application(_path: &Path, _extension: &str) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unfamiliar_files_are_described() {
+        // Act and Assert
+        assert_eq!(
+            kind(".~lock.report.odt#"),
+            Some("LibreOffice lock file, left while the document is open")
+        );
+        assert_eq!(kind("report.docx"), Some("text document"));
+        assert_eq!(kind("Thumbs.db"), Some("Windows Explorer thumbnail cache"));
+        assert_eq!(kind("README"), None);
+        assert_eq!(application(Path::new("README")), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! `BLAKE3` hash (its 32 bytes default output, without key), following the reference implementation: the input is
+//! split into chunks of 1 KiB, whose chaining values are merged pairwise into a binary tree.
+
+const IV: [u32; 8] = [0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19];
+const PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+const CHUNK_START: u32 = 1;
+const CHUNK_END: u32 = 2;
+const PARENT: u32 = 4;
+const ROOT: u32 = 8;
+
+fn g(state: &mut [u32; 16], [a, b, c, d]: [usize; 4], x: u32, y: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(x);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(y);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+fn round(state: &mut [u32; 16], m: &[u32; 16]) {
+    // Columns, then diagonals.
+    g(state, [0, 4, 8, 12], m[0], m[1]);
+    g(state, [1, 5, 9, 13], m[2], m[3]);
+    g(state, [2, 6, 10, 14], m[4], m[5]);
+    g(state, [3, 7, 11, 15], m[6], m[7]);
+    g(state, [0, 5, 10, 15], m[8], m[9]);
+    g(state, [1, 6, 11, 12], m[10], m[11]);
+    g(state, [2, 7, 8, 13], m[12], m[13]);
+    g(state, [3, 4, 9, 14], m[14], m[15]);
+}
+
+fn compress(cv: &[u32; 8], block: &[u32; 16], counter: u64, block_len: u32, flags: u32) -> [u32; 16] {
+    let mut state = [0; 16];
+    state[..8].copy_from_slice(cv);
+    state[8..12].copy_from_slice(&IV[..4]);
+    state[12..].copy_from_slice(&[counter as u32, (counter >> 32) as u32, block_len, flags]);
+    let mut block = *block;
+    for i in 0..7 {
+        round(&mut state, &block);
+        if i < 6 {
+            block = PERMUTATION.map(|j| block[j]);
+        }
+    }
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= cv[i];
+    }
+    state
+}
+
+fn first_8(words: [u32; 16]) -> [u32; 8] {
+    let mut first = [0; 8];
+    first.copy_from_slice(&words[..8]);
+    first
+}
+
+fn words(bytes: &[u8; BLOCK_LEN]) -> [u32; 16] {
+    let mut words = [0; 16];
+    for (word, bytes) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    words
+}
+
+/// A node of the tree, compressed once its position (root or not) is known.
+struct Output {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8(compress(&self.cv, &self.block, self.counter, self.block_len, self.flags))
+    }
+
+    fn root_hash(&self) -> [u8; 32] {
+        let words = compress(&self.cv, &self.block, 0, self.block_len, self.flags | ROOT);
+        let mut hash = [0; 32];
+        for (bytes, word) in hash.chunks_exact_mut(4).zip(words) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        hash
+    }
+}
+
+fn parent(left: [u32; 8], right: [u32; 8]) -> Output {
+    let mut block = [0; 16];
+    block[..8].copy_from_slice(&left);
+    block[8..].copy_from_slice(&right);
+    Output { cv: IV, block, counter: 0, block_len: BLOCK_LEN as u32, flags: PARENT }
+}
+
+struct Chunk {
+    cv: [u32; 8],
+    counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+    compressed: usize,
+}
+
+impl Chunk {
+    fn new(counter: u64) -> Self {
+        Chunk { cv: IV, counter, block: [0; BLOCK_LEN], block_len: 0, compressed: 0 }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        match self.compressed {
+            0 => CHUNK_START,
+            _ => 0,
+        }
+    }
+
+    fn update(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            // The last block is only compressed by the output, flagged as the end of the chunk.
+            if self.block_len == BLOCK_LEN {
+                let flags = self.start_flag();
+                self.cv = first_8(compress(&self.cv, &words(&self.block), self.counter, BLOCK_LEN as u32, flags));
+                self.compressed += 1;
+                self.block = [0; BLOCK_LEN];
+                self.block_len = 0;
+            }
+            let take = bytes.len().min(BLOCK_LEN - self.block_len);
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&bytes[..take]);
+            self.block_len += take;
+            bytes = &bytes[take..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        let (block, block_len) = (words(&self.block), self.block_len as u32);
+        Output { cv: self.cv, block, counter: self.counter, block_len, flags: self.start_flag() | CHUNK_END }
+    }
+}
+
+/// Streaming `BLAKE3` hash, fed by [`Blake3::update`].
+pub struct Blake3 {
+    chunk: Chunk,
+    /// Chaining values of the complete subtrees not merged yet, the biggest first.
+    stack: Vec<[u32; 8]>,
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Blake3 { chunk: Chunk::new(0), stack: vec![] }
+    }
+}
+
+impl Blake3 {
+    pub fn update(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            if self.chunk.len() == CHUNK_LEN {
+                let mut cv = self.chunk.output().chaining_value();
+                let mut chunks = self.chunk.counter + 1;
+                // Every trailing zero of the amount of chunks completes a subtree.
+                while chunks.is_multiple_of(2) {
+                    cv = parent(self.stack.pop().unwrap_or_default(), cv).chaining_value();
+                    chunks >>= 1;
+                }
+                self.stack.push(cv);
+                self.chunk = Chunk::new(self.chunk.counter + 1);
+            }
+            let take = bytes.len().min(CHUNK_LEN - self.chunk.len());
+            self.chunk.update(&bytes[..take]);
+            bytes = &bytes[take..];
+        }
+    }
+
+    pub fn finish(&self) -> [u8; 32] {
+        let mut output = self.chunk.output();
+        for cv in self.stack.iter().rev() {
+            output = parent(*cv, output.chaining_value());
+        }
+        output.root_hash()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        let mut hasher = Blake3::default();
+        hasher.update(bytes);
+        hasher.finish().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn blake3_matches_the_reference() {
+        // Arrange
+        let long: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+        let huge: Vec<u8> = (0..102400).map(|i| (i % 251) as u8).collect();
+        let mut split = Blake3::default();
+        long.chunks(777).for_each(|bytes| split.update(bytes));
+
+        // Act and Assert
+        // The inputs of 0 and of 1024 bytes or more are the ones of the `test_vectors.json` of the reference
+        // implementation (bytes counting up modulo 251), with their expected digests.
+        assert_eq!(hex(b""), "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262");
+        assert_eq!(hex(b"abc"), "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+        // Spanning several chunks (and levels of the tree).
+        assert_eq!(hex(&long[..1024]), "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7");
+        assert_eq!(hex(&long[..1025]), "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444");
+        assert_eq!(hex(&long[..2048]), "e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a");
+        assert_eq!(hex(&huge), "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085");
+        assert_eq!(split.finish(), {
+            let mut whole = Blake3::default();
+            whole.update(&long);
+            whole.finish()
+        });
+    }
+}
@@ -0,0 +1,535 @@
+//! Cache of the content digests (`--cache <file>`), so unchanged files aren't read again on the next scan.
+//!
+//! A wrong answer from the cache could get a file deleted, so it is defensive about its own state:
+//!
+//! - An entry is only used while the size and the modification time of its file are unchanged.
+//! - The new entries are appended to a journal next to the cache (`<file>.journal`), one line each along with
+//!   its own checksum, so a crash in the middle of a save only loses the line being written.
+//! - Once the journal outgrows the cache, both are compacted: the merged entries are written to a temporary
+//!   file which then replaces the cache, so a crash never leaves it half written.
+//! - Writers take a lock file, so the entries saved by concurrent runs are merged rather than overwritten.
+//! - A checksum of the entries is verified on load. A corrupt cache is set aside (as `<file>.corrupt`) and
+//!   rebuilt from scratch, while the journal lines failing theirs are skipped.
+//!
+//! `--flush-every` saves the new entries while the digests are still being computed, so a crash or a power loss
+//! in the middle of a long scan doesn't lose the work done so far.
+//!
+//! Unless `--cache <file>` is provided (or `--no-cache` passed), the digests are cached at
+//! `$XDG_CACHE_HOME/dir_cleaner/hashes.cache` (`~/.cache/dir_cleaner/hashes.cache` when the variable isn't set,
+//! `%LOCALAPPDATA%\dir_cleaner\hashes.cache` on Windows).
+//!
+//! The cache is a versioned JSON document (`{"version", "checksum", "entries"}`) plus its journal, not an SQLite
+//! database: hence the `hashes.cache` name rather than `hashes.db`. It's read whole on load, but a save only
+//! appends the entries inserted since the previous one, which keeps the crate free of a native SQLite dependency
+//! without making the saves cost more as the cache grows.
+
+use crate::checksum::fnv1a;
+use crate::errors;
+use crate::schema::{self, SchemaError, CACHE_VERSION};
+use crate::units::{parse_duration, UnitError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fmt::{self, Formatter};
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long a writer waits for the lock before giving up.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Locks older than this were left behind by a crashed run.
+const STALE_LOCK: Duration = Duration::from_secs(60);
+/// The journal isn't compacted before reaching this size (in bytes), however small the cache is.
+const COMPACT_AFTER: u64 = 1 << 20;
+
+/// A cached value, along with the state of the file it was computed from.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub size: u64,
+    /// Modification time, in nanoseconds since the epoch.
+    pub modified: u64,
+    pub value: String,
+}
+
+/// Line of the journal: an entry saved since the last compaction.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    key: String,
+    #[serde(flatten)]
+    entry: Entry,
+    checksum: String,
+}
+
+impl Record {
+    fn new(key: &str, entry: &Entry) -> Self {
+        Record { key: key.to_string(), entry: entry.clone(), checksum: Record::checksum_of(key, entry) }
+    }
+
+    fn checksum_of(key: &str, entry: &Entry) -> String {
+        let serialized = format!("{}\0{}\0{}\0{}", key, entry.size, entry.modified, entry.value);
+        format!("{:016x}", fnv1a(serialized.as_bytes()))
+    }
+}
+
+/// Location of the cache when none is provided explicitly.
+pub fn default_path() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    };
+    base.map(|base| base.join("dir_cleaner").join("hashes.cache"))
+}
+
+/// Size and modification time of the file located at `path`.
+fn stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((metadata.len(), modified.as_nanos() as u64))
+}
+
+/// Key of the value of `kind` (E.G: `text`) computed for the file located at `path`.
+fn key(kind: &str, path: &Path) -> String {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    format!("{}:{}", kind, path.to_string_lossy())
+}
+
+/// Why a cache couldn't be read.
+#[derive(Debug, PartialEq)]
+enum LoadError {
+    /// Written by a newer version of dir_cleaner: left untouched.
+    Newer(SchemaError),
+    Corrupt(String),
+}
+
+/// Reported when the cache can't be used or saved. The run goes on anyway, computing again what it would have held.
+#[derive(Debug)]
+pub enum CacheError {
+    /// Written by a newer version of dir_cleaner, the cache located at `path` is left untouched.
+    Newer { path: PathBuf, source: SchemaError },
+    /// The cache located at `path` was moved to `moved_to`, and is rebuilt.
+    Corrupt { path: PathBuf, reason: String, moved_to: PathBuf },
+    Unsaved(io::Error),
+}
+
+impl Error for CacheError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CacheError::Newer { source, .. } => Some(source),
+            CacheError::Corrupt { .. } => None,
+            CacheError::Unsaved(err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CacheError::Newer { path, source } => write!(f, "Not using the cache `{}`: {}", path.display(), source),
+            CacheError::Corrupt { path, reason, moved_to } => write!(
+                f,
+                "The cache `{}` is corrupt ({}), it was moved to `{}` and will be rebuilt.",
+                path.display(),
+                reason,
+                moved_to.display()
+            ),
+            CacheError::Unsaved(err) => write!(f, "Couldn't save the cache: {}", err),
+        }
+    }
+}
+
+/// Location of the journal of the cache located at `path`.
+fn journal_of(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.journal", path.display()))
+}
+
+/// Applies the journal of the cache located at `path` to its `entries`, skipping the lines left incomplete by a
+/// crash (or otherwise failing their checksum).
+fn replay(path: &Path, entries: &mut BTreeMap<String, Entry>) {
+    let content = fs::read_to_string(journal_of(path)).unwrap_or_default();
+    for line in content.lines() {
+        match serde_json::from_str::<Record>(line) {
+            Ok(record) if record.checksum == Record::checksum_of(&record.key, &record.entry) => {
+                entries.insert(record.key, record.entry);
+            }
+            _ => continue,
+        }
+    }
+}
+
+/// Parses the contents of a cache file, verifying its version and checksum.
+fn parse(content: &str) -> Result<BTreeMap<String, Entry>, LoadError> {
+    let document: Value = serde_json::from_str(content).map_err(|e| LoadError::Corrupt(e.to_string()))?;
+    let version = schema::version("cache", &document, CACHE_VERSION).map_err(LoadError::Newer)?;
+    let entries = document.get("entries").cloned().unwrap_or(Value::Null);
+    let mut entries: BTreeMap<String, Entry> =
+        serde_json::from_value(entries).map_err(|e| LoadError::Corrupt(e.to_string()))?;
+    match document.get("checksum").and_then(Value::as_str) {
+        Some(checksum) if checksum == checksum_of(&entries) => {
+            schema::migrate_cache(&mut entries, version);
+            Ok(entries)
+        }
+        _ => Err(LoadError::Corrupt(String::from("checksum mismatch"))),
+    }
+}
+
+fn checksum_of(entries: &BTreeMap<String, Entry>) -> String {
+    let serialized = serde_json::to_string(entries).unwrap_or_default();
+    format!("{:016x}", fnv1a(serialized.as_bytes()))
+}
+
+/// How often the new entries are saved while the digests are computed.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::cache::Flush;
+/// # use std::time::Duration;
+/// assert_eq!("5m".parse::<Flush>().unwrap(), Flush::Every(Duration::from_secs(300)));
+/// assert_eq!("1000-files".parse::<Flush>().unwrap(), Flush::Files(1000));
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Flush {
+    /// E.G: `90s`, `5m` or `1h`.
+    Every(Duration),
+    /// E.G: `1000-files`.
+    Files(usize),
+}
+
+impl FromStr for Flush {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().strip_suffix("-files") {
+            Some(files) => match files.parse::<usize>() {
+                Ok(files) if files > 0 => Ok(Flush::Files(files)),
+                _ => Err(UnitError(s.to_string())),
+            },
+            None => parse_duration(s).map(Flush::Every),
+        }
+    }
+}
+
+/// Digest cache shared by the content comparisons; it can be used from several threads at once.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::cache::Cache;
+/// # use std::path::Path;
+/// # std::fs::write("./cache-example.txt", "text").unwrap();
+/// let cache = Cache::default();
+/// let path = Path::new("./cache-example.txt");
+/// cache.insert("text", path, "text:0123");
+/// assert_eq!(cache.get("text", path).as_deref(), Some("text:0123"));
+/// std::fs::write(path, "changed text").unwrap();
+/// assert_eq!(cache.get("text", path), None);
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct Cache {
+    /// Where the cache is persisted (`None` keeps it in memory).
+    path: Option<PathBuf>,
+    entries: Mutex<BTreeMap<String, Entry>>,
+    /// Entries inserted since the last save, waiting to be appended to the journal.
+    pending: Mutex<BTreeMap<String, Entry>>,
+    flush: Option<Flush>,
+    /// Amount of entries inserted since the last save, and when it happened.
+    unsaved: AtomicUsize,
+    saved: Mutex<Option<Instant>>,
+}
+
+impl Cache {
+    /// Loads the cache stored at `path`, starting from scratch when it doesn't exist yet or is corrupt.
+    pub fn open(path: &Path) -> Self {
+        let mut entries = match fs::read_to_string(path) {
+            Ok(content) => match parse(&content) {
+                Ok(entries) => entries,
+                Err(LoadError::Newer(source)) => {
+                    let err = CacheError::Newer { path: path.to_path_buf(), source };
+                    errors::report(&err, path.to_str(), err.to_string());
+                    return Cache::default();
+                }
+                Err(LoadError::Corrupt(reason)) => {
+                    let moved_to = PathBuf::from(format!("{}.corrupt", path.display()));
+                    let _ = fs::rename(path, &moved_to);
+                    let err = CacheError::Corrupt { path: path.to_path_buf(), reason, moved_to };
+                    errors::report(&err, path.to_str(), err.to_string());
+                    BTreeMap::new()
+                }
+            },
+            Err(_) => BTreeMap::new(),
+        };
+        replay(path, &mut entries);
+        Self { path: Some(path.to_path_buf()), entries: Mutex::new(entries), ..Cache::default() }
+    }
+
+    /// Saves the new entries as they're inserted, as often as `flush` says.
+    pub fn flush_every(&mut self, flush: Flush) {
+        self.flush = Some(flush);
+        *self.saved.get_mut().unwrap() = Some(Instant::now());
+    }
+
+    /// Value of `kind` cached for the file located at `path`, as long as the file didn't change since.
+    pub fn get(&self, kind: &str, path: &Path) -> Option<String> {
+        let (size, modified) = stamp(path)?;
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(&key(kind, path))?;
+        (entry.size == size && entry.modified == modified).then(|| entry.value.clone())
+    }
+
+    pub fn insert(&self, kind: &str, path: &Path, value: &str) {
+        let (size, modified) = match stamp(path) {
+            Some(stamp) => stamp,
+            None => return,
+        };
+        let (key, entry) = (key(kind, path), Entry { size, modified, value: value.to_string() });
+        if self.path.is_some() {
+            self.pending.lock().unwrap().insert(key.clone(), entry.clone());
+        }
+        self.entries.lock().unwrap().insert(key, entry);
+        self.flush_if_due();
+    }
+
+    fn flush_if_due(&self) {
+        let flush = match self.flush {
+            Some(flush) => flush,
+            None => return,
+        };
+        let unsaved = self.unsaved.fetch_add(1, Ordering::Relaxed) + 1;
+        // Another thread is already saving.
+        let mut saved = match self.saved.try_lock() {
+            Ok(saved) => saved,
+            Err(_) => return,
+        };
+        let due = match flush {
+            Flush::Files(files) => unsaved >= files,
+            Flush::Every(interval) => saved.is_some_and(|saved| saved.elapsed() >= interval),
+        };
+        if !due {
+            return;
+        }
+        self.unsaved.store(0, Ordering::Relaxed);
+        *saved = Some(Instant::now());
+        if let Err(err) = self.save() {
+            let path = self.path.as_deref().and_then(Path::to_str);
+            errors::report(&err, path, err.to_string());
+        }
+    }
+
+    /// Persists the entries inserted since the last save, appending them to the journal of the cache (which is
+    /// compacted once it outgrows the cache).
+    pub fn save(&self) -> Result<(), CacheError> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        // Taken before writing them, so the ones inserted meanwhile are saved next time.
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let result = append(path, &pending);
+        if result.is_err() {
+            let mut unsaved = self.pending.lock().unwrap();
+            pending.into_iter().for_each(|(key, entry)| {
+                unsaved.entry(key).or_insert(entry);
+            });
+        }
+        result.map_err(CacheError::Unsaved)
+    }
+}
+
+/// Appends `entries` to the journal of the cache located at `path`, compacting both when the journal got too big.
+fn append(path: &Path, entries: &BTreeMap<String, Entry>) -> Result<(), io::Error> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let _lock = Lock::acquire(path)?;
+    let journal = journal_of(path);
+    let mut file = fs::OpenOptions::new().read(true).create(true).append(true).open(&journal)?;
+    let mut lines = String::new();
+    // A line left incomplete by a crash is ended first, so it doesn't swallow the next one.
+    let mut last = [b'\n'];
+    if file.metadata()?.len() > 0 {
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last)?;
+    }
+    if last[0] != b'\n' {
+        lines.push('\n');
+    }
+    for (key, entry) in entries {
+        lines.push_str(&serde_json::to_string(&Record::new(key, entry)).map_err(io::Error::from)?);
+        lines.push('\n');
+    }
+    file.write_all(lines.as_bytes())?;
+    file.sync_all()?;
+
+    let size = |path: &Path| fs::metadata(path).map(|m| m.len()).unwrap_or_default();
+    if size(&journal) > size(path).max(COMPACT_AFTER) {
+        compact(path)?;
+    }
+    Ok(())
+}
+
+/// Merges the journal of the cache located at `path` into the cache itself, then removes the journal. Must be
+/// called while holding the [`Lock`].
+fn compact(path: &Path) -> Result<(), io::Error> {
+    let mut merged = fs::read_to_string(path).ok().and_then(|c| parse(&c).ok()).unwrap_or_default();
+    replay(path, &mut merged);
+    let document = serde_json::json!({
+        "version": CACHE_VERSION,
+        "checksum": checksum_of(&merged),
+        "entries": merged,
+    });
+
+    let temporary = PathBuf::from(format!("{}.{}.tmp", path.display(), std::process::id()));
+    let mut file = fs::File::create(&temporary)?;
+    file.write_all(document.to_string().as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)?;
+    // A crash before this point only leaves entries to replay again, which the cache already holds.
+    match fs::remove_file(journal_of(path)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Lock file held while the cache is written; removed when dropped.
+struct Lock(PathBuf);
+
+impl Lock {
+    fn acquire(path: &Path) -> Result<Self, io::Error> {
+        let lock = PathBuf::from(format!("{}.lock", path.display()));
+        let start = SystemTime::now();
+        loop {
+            match fs::OpenOptions::new().write(true).create_new(true).open(&lock) {
+                Ok(_) => return Ok(Lock(lock)),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    let age = fs::metadata(&lock).and_then(|m| m.modified()).ok().and_then(|m| m.elapsed().ok());
+                    if age.is_some_and(|age| age > STALE_LOCK) {
+                        let _ = fs::remove_file(&lock);
+                        continue;
+                    }
+                    if start.elapsed().unwrap_or_default() > LOCK_TIMEOUT {
+                        let message = format!("the cache is locked by another run (`{}`)", lock.display());
+                        return Err(io::Error::new(io::ErrorKind::WouldBlock, message));
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn save_merges_and_recovers_from_corruption() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("cache");
+        let (a, b, path) = (root.join("a.txt"), root.join("b.txt"), root.join("digests.cache"));
+        fs::write(&a, "a")?;
+        fs::write(&b, "b")?;
+        let first = Cache::open(&path);
+        let second = Cache::open(&path);
+
+        // Act
+        first.insert("text", &a, "text:a");
+        second.insert("text", &b, "text:b");
+        first.save()?;
+        second.save()?;
+        let merged = Cache::open(&path);
+        compact(&path)?;
+        let compacted = Cache::open(&path);
+        let tampered = fs::read_to_string(&path)?.replace("text:a", "text:x");
+        fs::write(&path, tampered)?;
+        let rebuilt = Cache::open(&path);
+
+        // Assert
+        assert_eq!(merged.get("text", &a).as_deref(), Some("text:a"));
+        assert_eq!(merged.get("text", &b).as_deref(), Some("text:b"));
+        assert_eq!(compacted.get("text", &b).as_deref(), Some("text:b"));
+        assert!(!journal_of(&path).exists());
+        assert_eq!(rebuilt.get("text", &a), None);
+        assert!(root.join("digests.cache.corrupt").exists());
+        assert!(!root.join("digests.cache.lock").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn flush_saves_while_inserting() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("cache-flush");
+        // Its directory is created along with the cache.
+        let (a, b, path) = (root.join("a.txt"), root.join("b.txt"), root.join("dir_cleaner").join("digests.cache"));
+        fs::write(&a, "a")?;
+        fs::write(&b, "b")?;
+        let mut cache = Cache::open(&path);
+        cache.flush_every(Flush::Files(2));
+
+        // Act
+        cache.insert("text", &a, "text:a");
+        let before = journal_of(&path).exists();
+        cache.insert("text", &b, "text:b");
+
+        // Assert
+        assert!(!before);
+        assert_eq!(Cache::open(&path).get("text", &b).as_deref(), Some("text:b"));
+        Ok(())
+    }
+
+    #[test]
+    fn saves_append_to_the_journal_and_skip_its_broken_lines() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("cache-journal");
+        let (a, b, path) = (root.join("a.txt"), root.join("b.txt"), root.join("digests.cache"));
+        fs::write(&a, "a")?;
+        fs::write(&b, "b")?;
+        let cache = Cache::open(&path);
+        cache.insert("text", &a, "text:a");
+        cache.save()?;
+        let saved = fs::read_to_string(journal_of(&path))?;
+        // A crash in the middle of a save leaves an incomplete line behind.
+        fs::write(journal_of(&path), format!("{}{}", saved, &saved[..saved.len() / 2]))?;
+
+        // Act
+        cache.insert("text", &b, "text:b");
+        cache.save()?;
+        cache.save()?;
+        let reopened = Cache::open(&path);
+
+        // Assert
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(journal_of(&path))?.lines().count(), 3);
+        assert_eq!(reopened.get("text", &a).as_deref(), Some("text:a"));
+        assert_eq!(reopened.get("text", &b).as_deref(), Some("text:b"));
+        Ok(())
+    }
+
+    #[test]
+    fn newer_caches_are_left_untouched() {
+        // Act and Assert
+        assert!(matches!(parse(r#"{"version": 99, "entries": {}}"#), Err(LoadError::Newer(_))));
+        assert!(matches!(parse(r#"{"version": 1, "entries": {}}"#), Err(LoadError::Corrupt(_))));
+        assert!(matches!(parse("{\"version\": 1, \"entr"), Err(LoadError::Corrupt(_))));
+        let checksum = checksum_of(&BTreeMap::new());
+        let valid = format!(r#"{{"version": 1, "checksum": "{}", "entries": {{}}}}"#, checksum);
+        assert_eq!(parse(&valid), Ok(BTreeMap::new()));
+    }
+}
@@ -0,0 +1,141 @@
+//! Cheap, non-cryptographic checksums.
+
+/// 64 bits `FNV-1a` hash of `bytes`.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+const PRIME_1: u64 = 0x9E3779B185EBCA87;
+const PRIME_2: u64 = 0xC2B2AE3D27D4EB4F;
+const PRIME_3: u64 = 0x165667B19E3779F9;
+const PRIME_4: u64 = 0x85EBCA77C2B2AE63;
+const PRIME_5: u64 = 0x27D4EB2F165667C5;
+
+/// Streaming `XXH64` hash (with a seed of `0`), fed by [`Xxh64::update`].
+#[derive(Debug, Clone)]
+pub struct Xxh64 {
+    lanes: [u64; 4],
+    buffer: [u8; 32],
+    buffered: usize,
+    len: u64,
+}
+
+impl Default for Xxh64 {
+    fn default() -> Self {
+        Xxh64 {
+            lanes: [PRIME_1.wrapping_add(PRIME_2), PRIME_2, 0, PRIME_1.wrapping_neg()],
+            buffer: [0; 32],
+            buffered: 0,
+            len: 0,
+        }
+    }
+}
+
+fn round(lane: u64, input: u64) -> u64 {
+    lane.wrapping_add(input.wrapping_mul(PRIME_2)).rotate_left(31).wrapping_mul(PRIME_1)
+}
+
+fn merge(hash: u64, lane: u64) -> u64 {
+    (hash ^ round(0, lane)).wrapping_mul(PRIME_1).wrapping_add(PRIME_4)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap_or_default())
+}
+
+impl Xxh64 {
+    pub fn update(&mut self, mut bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+        if self.buffered > 0 {
+            let take = bytes.len().min(32 - self.buffered);
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&bytes[..take]);
+            self.buffered += take;
+            bytes = &bytes[take..];
+            if self.buffered < 32 {
+                return;
+            }
+            let stripe = self.buffer;
+            self.stripe(&stripe);
+            self.buffered = 0;
+        }
+        let mut stripes = bytes.chunks_exact(32);
+        for stripe in &mut stripes {
+            self.stripe(stripe);
+        }
+        let rest = stripes.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    fn stripe(&mut self, stripe: &[u8]) {
+        for (lane, input) in self.lanes.iter_mut().zip(stripe.chunks_exact(8)) {
+            *lane = round(*lane, read_u64(input));
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        let [a, b, c, d] = self.lanes;
+        let mut hash = match self.len >= 32 {
+            true => {
+                let hash = a.rotate_left(1).wrapping_add(b.rotate_left(7));
+                let hash = hash.wrapping_add(c.rotate_left(12)).wrapping_add(d.rotate_left(18));
+                self.lanes.iter().fold(hash, |hash, lane| merge(hash, *lane))
+            }
+            false => PRIME_5,
+        };
+        hash = hash.wrapping_add(self.len);
+        let mut rest = &self.buffer[..self.buffered];
+        while rest.len() >= 8 {
+            hash ^= round(0, read_u64(rest));
+            hash = hash.rotate_left(27).wrapping_mul(PRIME_1).wrapping_add(PRIME_4);
+            rest = &rest[8..];
+        }
+        if rest.len() >= 4 {
+            let word = u32::from_le_bytes(rest[..4].try_into().unwrap_or_default()) as u64;
+            hash ^= word.wrapping_mul(PRIME_1);
+            hash = hash.rotate_left(23).wrapping_mul(PRIME_2).wrapping_add(PRIME_3);
+            rest = &rest[4..];
+        }
+        for byte in rest {
+            hash ^= (*byte as u64).wrapping_mul(PRIME_5);
+            hash = hash.rotate_left(11).wrapping_mul(PRIME_1);
+        }
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(PRIME_2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(PRIME_3);
+        hash ^ (hash >> 32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn xxh64_matches_the_reference() {
+        // Arrange
+        let hash = |bytes: &[u8], split: usize| {
+            let mut hasher = Xxh64::default();
+            hasher.update(&bytes[..split]);
+            hasher.update(&bytes[split..]);
+            hasher.finish()
+        };
+        let long: Vec<u8> = (0..100).collect();
+
+        let fox = b"The quick brown fox jumps over the lazy dog";
+
+        // Act and Assert
+        // Expected values computed by the reference `xxHash` library (`XXH64` with a seed of `0`).
+        assert_eq!(hash(b"", 0), 0xef46db3751d8e999);
+        assert_eq!(hash(b"abc", 1), 0x44bc2cf5ad770999);
+        assert_eq!(hash(b"xxhash", 3), 0x32dd38952c4bc720);
+        assert_eq!(hash(b"message digest", 7), 0x066ed728fceeb3be);
+        assert_eq!(hash(fox, 20), 0x0b242d361fda71bc);
+        assert_eq!(hash(&long, 0), 0x6ac1e58032166597);
+        assert_eq!(hash(&long, 5), hash(&long, 0));
+        assert_eq!(hash(&long, 33), hash(&long, 100));
+    }
+}
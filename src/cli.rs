@@ -0,0 +1,1122 @@
+//! Parsing of the arguments provided through the command line.
+
+use crate::cache::Flush;
+use crate::dates::parse_bound;
+use crate::drives;
+use crate::hash::Algorithm;
+use crate::keep::Strategy;
+use crate::limits::Limits;
+use crate::output::{DateFormat, Output};
+use crate::schedule;
+use crate::prompt::DefaultAnswer;
+use crate::report::Format;
+use crate::storage::Storage;
+use crate::units::{parse_duration, parse_percent, parse_size};
+use chrono::NaiveTime;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+/// Returned when an argument isn't recognized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownArgError {
+    /// Subcommand being parsed, whose usage is shown.
+    pub command: Command,
+    pub argument: String,
+}
+
+impl Error for UnknownArgError {}
+
+impl fmt::Display for UnknownArgError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Unknown argument `{}`.\nUsage: {}", self.argument, self.command.usage())
+    }
+}
+
+/// Returned when the value given to a flag can't be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidValueError {
+    /// The flag (E.G: `--threads`).
+    pub argument: String,
+    pub value: String,
+    /// What the flag expects (E.G: `a number above 0`).
+    pub expected: &'static str,
+}
+
+impl Error for InvalidValueError {}
+
+impl fmt::Display for InvalidValueError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid value `{}` for `{}`, expected {}.", self.value, self.argument, self.expected)
+    }
+}
+
+/// Returned when an argument requires a feature that wasn't enabled at build time.
+#[derive(Debug, Clone)]
+pub struct MissingFeatureError(pub &'static str);
+
+impl Error for MissingFeatureError {}
+
+impl fmt::Display for MissingFeatureError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "This build of dir_cleaner doesn't include the `{}` feature.", self.0)
+    }
+}
+
+/// Returned when an argument a subcommand expects isn't provided.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingArgError {
+    pub command: Command,
+    /// Name of the missing argument (E.G: `<dir>`).
+    pub argument: &'static str,
+}
+
+impl Error for MissingArgError {}
+
+impl fmt::Display for MissingArgError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "`{}` expects {}.\nUsage: {}", self.command.name(), self.argument, self.command.usage())
+    }
+}
+
+/// Returned when a subcommand is given more arguments than it expects (or one it doesn't know).
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnexpectedArgError {
+    pub command: Command,
+    pub argument: String,
+}
+
+impl Error for UnexpectedArgError {}
+
+impl fmt::Display for UnexpectedArgError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Unexpected argument `{}`.\nUsage: {}", self.argument, self.command.usage())
+    }
+}
+
+/// Returned when a flag is given to a subcommand it doesn't apply to (E.G: `query … --delete-all`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct InapplicableArgError {
+    pub command: Command,
+    /// The flag (E.G: `--delete-all`).
+    pub argument: String,
+}
+
+impl Error for InapplicableArgError {}
+
+impl fmt::Display for InapplicableArgError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}` doesn't apply to `{}`.\nUsage: {}",
+            self.argument,
+            self.command.name(),
+            self.command.usage()
+        )
+    }
+}
+
+/// Returned when the options provided can't be used together (or one of them needs another one).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictError(pub &'static str);
+
+impl Error for ConflictError {}
+
+impl fmt::Display for ConflictError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// What the program was asked to do.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Command {
+    /// List the found files and help with their deletion (`dir_cleaner [clean] <dir>`, or `dir_cleaner dedup <dir>`
+    /// to compare them by content).
+    #[default]
+    Clean,
+    /// Only list the found files, without asking nor deleting anything (`dir_cleaner scan <dir>`).
+    Scan,
+    /// Print a report of the duplicates found (`dir_cleaner report <dir>`).
+    Report,
+    /// Filter the files of a saved session (`dir_cleaner query <session> <expression>`).
+    Query,
+    /// Rewrite a saved session with the current version of the format (`dir_cleaner migrate <session>`).
+    Migrate,
+    /// Copy a tree, leaving out the contents already found at the destination (`dir_cleaner copy <src> <dst>`).
+    Copy,
+    /// Plan the consolidation of several drives onto a target (`dir_cleaner consolidate <target> <drive>...`).
+    Consolidate,
+    /// Write the content digests of a tree to a manifest (`dir_cleaner export-hashes <dir> <manifest>`).
+    ExportHashes,
+    /// List the files of a tree whose content is found on a manifest (`dir_cleaner import-hashes <dir> <manifest>`).
+    ImportHashes,
+    /// Show how the duplicates evolved across the past runs (`dir_cleaner stats trend [<dir>]`).
+    Stats,
+    /// Compare the deletions of two policies, without deleting anything (`dir_cleaner simulate <dir> --compare <policy>
+    /// <policy>`).
+    Simulate,
+}
+
+impl Command {
+    /// Every subcommand, in the order `--help` lists them.
+    pub const ALL: [Command; 11] = [
+        Command::Clean,
+        Command::Scan,
+        Command::Report,
+        Command::Query,
+        Command::Migrate,
+        Command::Copy,
+        Command::Consolidate,
+        Command::ExportHashes,
+        Command::ImportHashes,
+        Command::Stats,
+        Command::Simulate,
+    ];
+
+    /// Name of the subcommand, as written on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Clean => "clean",
+            Command::Scan => "scan",
+            Command::Report => "report",
+            Command::Query => "query",
+            Command::Migrate => "migrate",
+            Command::Copy => "copy",
+            Command::Consolidate => "consolidate",
+            Command::ExportHashes => "export-hashes",
+            Command::ImportHashes => "import-hashes",
+            Command::Stats => "stats",
+            Command::Simulate => "simulate",
+        }
+    }
+
+    /// Whether the subcommand looks for duplicates (E.G: `scan`), rather than copying, exporting or inspecting
+    /// something.
+    pub fn looks_for_duplicates(&self) -> bool {
+        matches!(self, Command::Clean | Command::Scan | Command::Report | Command::Simulate)
+    }
+
+    /// How the subcommand is invoked.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::cli::Command;
+    /// assert_eq!(Command::Query.usage(), "`dir_cleaner query <session> <expression>`");
+    /// ```
+    pub fn usage(&self) -> &'static str {
+        match self {
+            Command::Clean => "`dir_cleaner [clean|dedup] <dir> [--name <name>] [options]`",
+            Command::Scan => "`dir_cleaner scan <dir> [--name <name>] [--output text|json|csv] [options]`",
+            Command::Report => "`dir_cleaner report <dir> [--name <name>] [--format <format>] [options]`",
+            Command::Query => "`dir_cleaner query <session> <expression>`",
+            Command::Migrate => "`dir_cleaner migrate <session>`",
+            Command::Copy => "`dir_cleaner copy <src> <dst> [options]`",
+            Command::Consolidate => "`dir_cleaner consolidate <target> <drive>... [options]`",
+            Command::ExportHashes => "`dir_cleaner export-hashes <dir> <manifest>`",
+            Command::ImportHashes => "`dir_cleaner import-hashes <dir> <manifest>`",
+            Command::Stats => "`dir_cleaner stats trend [<dir>]`",
+            Command::Simulate => "`dir_cleaner simulate <dir> --compare <policy> <policy> [options]`",
+        }
+    }
+
+    /// What `--help` prints: how the subcommand is invoked, along with the other subcommands for the default one.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::cli::Command;
+    /// assert_eq!(Command::Migrate.help(), "Usage: `dir_cleaner migrate <session>`\n");
+    /// assert!(Command::Clean.help().contains("`dir_cleaner scan <dir>"));
+    /// ```
+    pub fn help(&self) -> String {
+        let mut help = format!("Usage: {}\n", self.usage());
+        if *self == Command::Clean {
+            help.push_str("\nSubcommands:\n");
+            for command in Command::ALL.iter().filter(|command| **command != Command::Clean) {
+                help.push_str(&format!("  {}\n", command.usage()));
+            }
+            help.push_str("\nPass `--help` after a subcommand in order to see its usage.\n");
+            help.push_str("A directory named after a subcommand goes after `--` (E.G: `dir_cleaner -- scan`).\n");
+        }
+        if self.looks_for_duplicates() {
+            help.push_str("Manifests passed as a URL to `--reference-manifest` are downloaded through `curl`.\n");
+        }
+        help
+    }
+}
+
+/// Options gathered from the command line.
+/// ## Example
+/// ```
+/// # use dir_cleaner::cli::Options;
+/// let args = ["dir_cleaner", "./test", "--contain-strict"].iter().map(|a| a.to_string());
+/// let options = Options::parse(args).unwrap();
+/// assert_eq!(options.directory, "./test");
+/// assert!(options.contain_strict);
+/// ```
+#[derive(Debug, PartialEq, Default)]
+pub struct Options {
+    pub command: Command,
+    /// Print the usage of `command` instead of running it (`--help`); nothing else is checked then.
+    pub help: bool,
+    /// Directory that will be inspected.
+    pub directory: String,
+    /// Directory the `copy` subcommand copies `directory` to, or the `consolidate` subcommand merges `roots` onto.
+    pub destination: Option<String>,
+    /// Canonicalize and verify every target right before deleting it.
+    pub contain_strict: bool,
+    /// Review the found files through the two-pane interface (requires the `tui` feature).
+    pub tui: bool,
+    /// Start the review of `--tui` while the scan still runs, the files appearing as they're found.
+    pub live: bool,
+    /// Config file to use instead of the default one.
+    pub config: Option<String>,
+    /// Copy the paths of the found files to the clipboard.
+    pub copy: bool,
+    /// Name of the files to search; the user is prompted for it when missing.
+    pub name: Option<String>,
+    /// Format of the report.
+    pub format: Format,
+    /// How the found files are written out (`json` and `csv` write them and quit, without asking anything).
+    pub output: Output,
+    /// How the dates of the `json` and `csv` outputs are written (`--date-format` and `--local-dates`).
+    pub date_format: DateFormat,
+    /// File the scan results are saved to.
+    pub save: Option<String>,
+    /// Snapshot of the scanned directories, reused by the next scans (see [`incremental`](crate::incremental)).
+    pub incremental: Option<String>,
+    /// Script of `git rm` commands written instead of deleting the entries tracked by git.
+    pub git_rm_script: Option<String>,
+    /// File the decisions about the duplicates are remembered on, across runs.
+    pub decisions: Option<String>,
+    /// Log the decisions taken on the interactive loop are recorded on (see [`actions`](crate::actions)).
+    pub record: Option<String>,
+    /// Log of the decisions applied again to the found files, instead of going through the interactive loop.
+    pub replay: Option<String>,
+    /// File the summaries of the runs are recorded on (see [`history`](crate::history)).
+    pub history: Option<String>,
+    /// Lowest confidence (in percent) a heuristic match needs for its deletion to be suggested.
+    pub min_confidence: Option<u8>,
+    /// Prefix the listed entries with an icon telling the type of the file.
+    pub icons: bool,
+    /// Write simple labeled lines, without colors nor control sequences (E.G: for screen readers).
+    pub plain: bool,
+    /// How long the prompts wait for an answer before applying `default_answer`.
+    pub prompt_timeout: Option<Duration>,
+    /// Answer applied to the prompts left unanswered for `prompt_timeout`.
+    pub default_answer: Option<DefaultAnswer>,
+    /// File the answers applied on behalf of the user (and the batch deletions) are recorded on.
+    pub audit_log: Option<String>,
+    /// Delete every found file without going through the interactive loop.
+    pub delete_all: bool,
+    /// Keep the oldest copy of every group of duplicates when deleting them all.
+    pub keep_one: bool,
+    /// Local time the deletions of `--delete-all` are postponed to (see [`schedule`](crate::schedule)).
+    pub at: Option<NaiveTime>,
+    /// Never prompt: the batch deletions are confirmed, and anything else gets the safe answer.
+    pub yes: bool,
+    /// Delete the files for good instead of moving them to the trash.
+    pub permanent: bool,
+    /// Compare the files found by content with their copies byte by byte right before deleting them.
+    pub paranoid: bool,
+    /// Let the plans delete every copy of a group of files holding the same bytes (see
+    /// [`verification`](crate::verification)).
+    pub allow_delete_all: bool,
+    /// Delete the files located outside the user data and the caches without asking first (see
+    /// [`safety`](crate::safety)), which the batch runs would keep otherwise.
+    pub allow_unknown_locations: bool,
+    /// Don't draw the progress of the scan on the standard error.
+    pub no_progress: bool,
+    /// Whether the hidden entries are left out (`--skip-hidden`) or scanned (`--hidden`), overriding the `skip_hidden`
+    /// setting of the configuration file.
+    pub skip_hidden: Option<bool>,
+    /// Amount of kept files read again, picked at random, once an automated cleanup is done.
+    pub verify_sample: Option<usize>,
+    /// Session read by the `query` subcommand.
+    pub session: Option<String>,
+    /// Manifest written by the `export-hashes` subcommand, or read by the `import-hashes` one.
+    pub manifest: Option<String>,
+    /// Manifest (a path or an `http(s)://` URL) the found files are checked against.
+    pub reference_manifest: Option<String>,
+    /// Expression evaluated by the `query` subcommand.
+    pub query: Option<String>,
+    /// Policies compared by the `simulate` subcommand (see [`policy`](crate::policy)).
+    pub policies: Vec<String>,
+    /// Point out the symbolic links and shortcuts to the found files, which break once they're deleted.
+    pub check_links: bool,
+    /// Flag the found files that look like they contain secrets, asking again before deleting them.
+    pub check_secrets: bool,
+    /// Treat `name` as a glob pattern (E.G: `*.log`).
+    pub glob: bool,
+    /// Treat `name` as a regular expression (E.G: `backup_\d{4}\.sql`).
+    pub regex: bool,
+    /// Only group the files holding the same bytes, whatever their names when `name` is missing.
+    pub by_content: bool,
+    /// Only group documents holding the same text (requires the `documents` feature).
+    pub compare_text: bool,
+    /// Group the videos that look alike, even when re-encoded (requires the `video` feature).
+    pub compare_video: bool,
+    /// Strategy proposing which copy of every group of duplicates should be kept.
+    pub keep: Option<Strategy>,
+    /// Look for the `JPEG`s that have a `RAW` sibling instead of a file name, proposing their deletion.
+    pub cull_jpegs: bool,
+    /// Only compare every directory against its sibling directories.
+    pub compare_siblings: bool,
+    /// Directories exceeding these limits are skipped.
+    pub limits: Limits,
+    /// Count the files under the directory and estimate the scan duration before starting it.
+    pub estimate: bool,
+    /// Report the read I/O the scan spent on every top-level directory.
+    pub io_usage: bool,
+    /// Kind of device the directory is stored on; detected when missing.
+    pub storage: Option<Storage>,
+    /// Amount of files read at once by the content comparisons; tuned to the storage when missing.
+    pub readers: Option<usize>,
+    /// Most threads hashing or comparing the files at once, whatever the amount of readers.
+    pub threads: Option<usize>,
+    /// Algorithm the contents are digested with (`--hash`).
+    pub hash: Algorithm,
+    /// Entries listed at once; `0` lists them all.
+    pub page_size: Option<usize>,
+    /// File the content digests are cached on, so unchanged files aren't read again (see
+    /// [`cache::default_path`](crate::cache::default_path) when missing).
+    pub cache: Option<String>,
+    /// Keep the content digests in memory only.
+    pub no_cache: bool,
+    /// How often the partial results are saved to `cache` during long scans.
+    pub flush: Option<Flush>,
+    /// Scan every fixed drive of the machine instead of `directory` (Windows only).
+    pub all_drives: bool,
+    /// Scan the system-managed directories as well when scanning every drive.
+    pub include_system: bool,
+    /// Directories that are scanned: `directory`, or every fixed drive with `all_drives`.
+    pub roots: Vec<String>,
+    /// Read the online-only placeholders of cloud storage clients as well (downloading them).
+    pub include_placeholders: bool,
+    /// Leave out the files owned by other users, both from the results and from the deletions.
+    pub only_mine: bool,
+}
+
+impl Options {
+    /// Builds the `Options` out of `args`; the first item is expected to be the program name.
+    pub fn parse(mut args: impl Iterator<Item=String>) -> Result<Self, Box<dyn Error>> {
+        args.next();
+
+        let mut positionals = vec![];
+        // Flags given, checked against the subcommand once it's known.
+        let mut flags = vec![];
+        // Whether `--` was given, and whether the first positional argument came after it (so it can't be a
+        // subcommand, E.G: `dir_cleaner -- scan` scans the `scan` directory).
+        let (mut literal, mut first_is_literal) = (false, false);
+        let command_of = |positionals: &[String], first_is_literal| match first_is_literal {
+            true => None,
+            false => positionals.first().and_then(|first| subcommand(first)),
+        };
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            if literal {
+                positionals.push(arg);
+                continue;
+            }
+            // The subcommand comes first, so the errors tell the usage of the one being parsed.
+            let command = command_of(&positionals, first_is_literal).unwrap_or_default();
+            if arg.starts_with("--") && arg != "--" {
+                flags.push(arg.clone());
+            }
+            match arg.as_str() {
+                "--" => {
+                    literal = true;
+                    first_is_literal = positionals.is_empty();
+                }
+                "--help" | "-h" => options.help = true,
+                "--contain-strict" => options.contain_strict = true,
+                "--copy" => options.copy = true,
+                "--check-secrets" => options.check_secrets = true,
+                "--check-links" => options.check_links = true,
+                "--cull-jpegs" => options.cull_jpegs = true,
+                "--by-content" => options.by_content = true,
+                "--glob" => options.glob = true,
+                "--regex" => options.regex = true,
+                "--delete-all" => options.delete_all = true,
+                "--keep-one" => options.keep_one = true,
+                "--at" => options.at = Some(schedule::parse_time(&value(command, &arg, args.next())?)?),
+                "--yes" => options.yes = true,
+                "--permanent" => options.permanent = true,
+                "--paranoid" => options.paranoid = true,
+                "--allow-delete-all" => options.allow_delete_all = true,
+                "--allow-unknown-locations" => options.allow_unknown_locations = true,
+                "--no-progress" => options.no_progress = true,
+                "--compare-siblings" => options.compare_siblings = true,
+                "--estimate" => options.estimate = true,
+                "--io-usage" => options.io_usage = true,
+                "--all-drives" => options.all_drives = true,
+                "--include-system" => options.include_system = true,
+                "--skip-repos" => options.limits.skip_repos = true,
+                "--respect-gitignore" => options.limits.respect_gitignore = true,
+                "--one-file-system" => options.limits.one_file_system = true,
+                "--skip-hidden" => options.skip_hidden = Some(true),
+                "--hidden" => options.skip_hidden = Some(false),
+                "--ext" => options.limits.extensions.add(&value(command, &arg, args.next())?)?,
+                "--type" => options.limits.types.add(&value(command, &arg, args.next())?)?,
+                "--exclude" => options.limits.exclude.add(&value(command, &arg, args.next())?)?,
+                "--compare" => {
+                    options.policies = vec![value(command, &arg, args.next())?, value(command, &arg, args.next())?];
+                }
+                "--icons" => options.icons = true,
+                "--plain" => options.plain = true,
+                "--biggest-first" => options.limits.biggest_first = true,
+                "--dir-summaries" => options.limits.dir_summaries = true,
+                "--include-placeholders" => options.include_placeholders = true,
+                "--only-mine" => options.only_mine = true,
+                "--name" => options.name = Some(value(command, &arg, args.next())?),
+                "--format" => options.format = parsed(command, &arg, args.next(), "`markdown` or `dot`")?,
+                "--output" => options.output = parsed(command, &arg, args.next(), "`text`, `json` or `csv`")?,
+                "--date-format" => {
+                    let local = options.date_format.local;
+                    let format: DateFormat = parsed(command, &arg, args.next(), "a `strftime` pattern or `rfc3339`")?;
+                    options.date_format = DateFormat { local, ..format };
+                }
+                "--local-dates" => options.date_format.local = true,
+                "--config" => options.config = Some(value(command, &arg, args.next())?),
+                "--save" => options.save = Some(value(command, &arg, args.next())?),
+                "--incremental" => options.incremental = Some(value(command, &arg, args.next())?),
+                "--git-rm-script" => options.git_rm_script = Some(value(command, &arg, args.next())?),
+                "--decisions" => options.decisions = Some(value(command, &arg, args.next())?),
+                "--record" => options.record = Some(value(command, &arg, args.next())?),
+                "--replay" => options.replay = Some(value(command, &arg, args.next())?),
+                "--history" => options.history = Some(value(command, &arg, args.next())?),
+                "--reference-manifest" => options.reference_manifest = Some(value(command, &arg, args.next())?),
+                "--min-confidence" => {
+                    options.min_confidence = Some(parse_percent(&value(command, &arg, args.next())?)?);
+                }
+                "--prompt-timeout" => {
+                    options.prompt_timeout = Some(parse_duration(&value(command, &arg, args.next())?)?);
+                }
+                "--default-answer" => options.default_answer = Some(parsed(command, &arg, args.next(), "`keep`")?),
+                "--audit-log" => options.audit_log = Some(value(command, &arg, args.next())?),
+                "--cache" => options.cache = Some(value(command, &arg, args.next())?),
+                "--no-cache" => options.no_cache = true,
+                "--flush-every" => {
+                    let expected = "a duration (E.G: `5m`) or an amount of files (E.G: `1000-files`)";
+                    options.flush = Some(parsed(command, &arg, args.next(), expected)?);
+                }
+                "--storage" => options.storage = Some(parsed(command, &arg, args.next(), "`hdd` or `ssd`")?),
+                "--readers" => {
+                    let readers: NonZeroUsize = parsed(command, &arg, args.next(), "a number above 0")?;
+                    options.readers = Some(readers.get());
+                }
+                "--threads" => {
+                    let threads: NonZeroUsize = parsed(command, &arg, args.next(), "a number above 0")?;
+                    options.threads = Some(threads.get());
+                }
+                "--hash" => options.hash = value(command, &arg, args.next())?.parse()?,
+                "--verify-sample" => {
+                    options.verify_sample = Some(parsed(command, &arg, args.next(), "an amount of files")?);
+                }
+                "--page-size" => options.page_size = Some(parsed(command, &arg, args.next(), "an amount of entries")?),
+                "--min-size" => options.limits.min_size = Some(parse_size(&value(command, &arg, args.next())?)?),
+                "--max-size" => options.limits.max_size = Some(parse_size(&value(command, &arg, args.next())?)?),
+                "--older-than" => {
+                    let before = parse_bound(&value(command, &arg, args.next())?, SystemTime::now())?;
+                    options.limits.dates.before = Some(before);
+                }
+                "--newer-than" => {
+                    let after = parse_bound(&value(command, &arg, args.next())?, SystemTime::now())?;
+                    options.limits.dates.after = Some(after);
+                }
+                "--skip-dirs-larger-than" => {
+                    let expected = "an amount of entries (E.G: `500K-entries`) or a size (E.G: `10GB`)";
+                    options.limits.add(parsed(command, &arg, args.next(), expected)?);
+                }
+                "--keep" => {
+                    let strategy: Strategy = parsed(command, &arg, args.next(), "`highest-bitrate`")?;
+                    match strategy.feature() {
+                        Some("audio") if !cfg!(feature = "audio") => return Err(MissingFeatureError("audio").into()),
+                        _ => options.keep = Some(strategy),
+                    }
+                }
+                "--tui" if cfg!(feature = "tui") => options.tui = true,
+                "--tui" => return Err(MissingFeatureError("tui").into()),
+                "--live" if cfg!(feature = "tui") => options.live = true,
+                "--live" => return Err(MissingFeatureError("tui").into()),
+                "--compare-text" if cfg!(feature = "documents") => options.compare_text = true,
+                "--compare-text" => return Err(MissingFeatureError("documents").into()),
+                "--compare-video" if cfg!(feature = "video") => options.compare_video = true,
+                "--compare-video" => return Err(MissingFeatureError("video").into()),
+                flag if flag.starts_with("--") => return Err(UnknownArgError { command, argument: arg }.into()),
+                _ => positionals.push(arg),
+            }
+        }
+
+        let found = command_of(&positionals, first_is_literal);
+        let mut positionals = positionals.into_iter();
+        let mut first = positionals.next();
+        if let Some(command) = found {
+            options.command = command;
+            options.by_content |= first.as_deref() == Some("dedup");
+            first = positionals.next();
+        }
+        if options.help {
+            return Ok(options);
+        }
+        let command = options.command;
+        if let Some(flag) = flags.into_iter().find(|flag| !applies(command, flag)) {
+            return Err(InapplicableArgError { command, argument: flag }.into());
+        }
+        let missing = |argument| MissingArgError { command, argument };
+        let unexpected = |argument| UnexpectedArgError { command, argument };
+        if options.command == Command::Query {
+            options.session = Some(first.ok_or(missing("the path of a saved session"))?);
+            options.query = Some(positionals.next().ok_or(missing("an expression (E.G: `size > 1MB`)"))?);
+        } else if options.command == Command::Migrate {
+            options.session = Some(first.ok_or(missing("the path of a saved session"))?);
+        } else if options.command == Command::Copy {
+            options.directory = first.ok_or(missing("the directory to copy"))?;
+            options.roots = vec![options.directory.clone()];
+            options.destination = Some(positionals.next().ok_or(missing("the destination of the copy"))?);
+        } else if options.command == Command::ExportHashes || options.command == Command::ImportHashes {
+            options.directory = first.ok_or(missing("the directory to scan"))?;
+            options.roots = vec![options.directory.clone()];
+            options.manifest = Some(positionals.next().ok_or(missing("the path of the manifest"))?);
+        } else if options.command == Command::Stats {
+            match first {
+                Some(arg) if arg == "trend" => (),
+                Some(arg) => return Err(unexpected(arg).into()),
+                None => return Err(missing("a statistic (`trend`)").into()),
+            }
+            // Every root is shown when none is given.
+            options.directory = positionals.next().unwrap_or_default();
+        } else if options.command == Command::Consolidate {
+            options.destination = Some(first.ok_or(missing("the target drive"))?);
+            options.roots = positionals.by_ref().collect();
+            if options.roots.is_empty() {
+                return Err(missing("at least a drive to consolidate").into());
+            }
+            options.directory = options.roots.join(", ");
+        } else if options.all_drives {
+            let drives = drives::fixed_drives()?;
+            if !options.include_system {
+                options.limits.protected.extend(drives.iter().flat_map(|drive| drives::protected_dirs(drive)));
+            }
+            options.roots = drives.iter().map(|drive| drive.to_string_lossy().to_string()).collect();
+            options.directory = options.roots.join(", ");
+            if let Some(arg) = first {
+                return Err(unexpected(arg).into());
+            }
+        } else {
+            options.directory = first.ok_or(missing("the directory to scan"))?;
+            options.roots = vec![options.directory.clone()];
+        }
+        if let Some(arg) = positionals.next() {
+            return Err(unexpected(arg).into());
+        }
+        if options.no_cache && options.cache.is_some() {
+            return Err(ConflictError("`--no-cache` can't be used along with `--cache`.").into());
+        }
+        if options.flush.is_some() && options.cache.is_none() && options.save.is_none() {
+            let message = "`--flush-every` needs `--cache` or `--save`, where the partial results are written.";
+            return Err(ConflictError(message).into());
+        }
+        if (options.yes || options.keep_one || options.at.is_some()) && !options.delete_all {
+            return Err(ConflictError("`--yes`, `--keep-one` and `--at` only apply to `--delete-all`.").into());
+        }
+        if options.yes && options.prompt_timeout.is_some() {
+            let message = "`--yes` never waits for an answer, `--prompt-timeout` can't be used along with it.";
+            return Err(ConflictError(message).into());
+        }
+        if options.yes && options.name.is_none() && !options.by_content && !options.cull_jpegs {
+            return Err(ConflictError("`--yes` never prompts, so the name must be provided through `--name`.").into());
+        }
+        if (options.command == Command::Simulate) == options.policies.is_empty() {
+            let message = "`simulate` compares the two policies given through `--compare <policy> <policy>`.";
+            return Err(ConflictError(message).into());
+        }
+        if options.output != Output::Text && (options.tui || options.delete_all) {
+            let message = "`--output` only writes the found files, it excludes `--tui` and `--delete-all`.";
+            return Err(ConflictError(message).into());
+        }
+        if options.live && !options.tui {
+            return Err(ConflictError("`--live` only applies to `--tui`, the listing waits for the whole scan.").into());
+        }
+        let compares = options.by_content || options.compare_text || options.compare_video || options.compare_siblings;
+        let merges = options.roots.len() > 1 || options.limits.follow_symlinks || options.cull_jpegs;
+        let replays = options.incremental.is_some() || options.save.is_some() || options.reference_manifest.is_some();
+        if options.live && (compares || merges || replays) {
+            let message = "`--live` only reviews the scans by name of a single root: the content comparisons, the \
+                merged roots and links, the saved sessions and the manifests need every file beforehand.";
+            return Err(ConflictError(message).into());
+        }
+        if options.limits.min_size.zip(options.limits.max_size).is_some_and(|(min, max)| min > max) {
+            return Err(ConflictError("`--min-size` can't exceed `--max-size`, no file would be collected.").into());
+        }
+        if options.limits.dates.is_unsatisfiable() {
+            let message = "`--newer-than` must be earlier than `--older-than`, no file would be collected.";
+            return Err(ConflictError(message).into());
+        }
+        if options.replay.is_some() && (options.record.is_some() || options.tui || options.delete_all) {
+            let message = "`--replay` applies the recorded decisions on its own, it excludes `--record`, `--tui` and \
+                `--delete-all`.";
+            return Err(ConflictError(message).into());
+        }
+        if options.glob && options.regex {
+            return Err(ConflictError("`--glob` and `--regex` can't be used together.").into());
+        }
+        if options.prompt_timeout.is_some() != options.default_answer.is_some() {
+            let message = "`--prompt-timeout` and `--default-answer` go together: the answer applies once it runs out.";
+            return Err(ConflictError(message).into());
+        }
+        Ok(options)
+    }
+}
+
+/// Returned when a flag that expects a value is the last argument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingValueError {
+    /// Subcommand being parsed, whose usage is shown.
+    pub command: Command,
+    /// The flag (E.G: `--name`).
+    pub argument: String,
+}
+
+impl Error for MissingValueError {}
+
+impl fmt::Display for MissingValueError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "`{}` expects a value.\nUsage: {}", self.argument, self.command.usage())
+    }
+}
+
+fn value(command: Command, flag: &str, value: Option<String>) -> Result<String, MissingValueError> {
+    value.ok_or_else(|| MissingValueError { command, argument: flag.to_string() })
+}
+
+/// Parses the `value` given to `flag`, telling what was `expected` when it can't be read.
+fn parsed<T: FromStr>(
+    command: Command,
+    flag: &str,
+    value: Option<String>,
+    expected: &'static str,
+) -> Result<T, Box<dyn Error>> {
+    let value = self::value(command, flag, value)?;
+    match value.parse() {
+        Ok(parsed) => Ok(parsed),
+        Err(_) => Err(InvalidValueError { argument: flag.to_string(), value, expected }.into()),
+    }
+}
+
+/// Flags understood by every subcommand.
+const GLOBAL_FLAGS: [&str; 4] = ["--help", "--config", "--output", "--plain"];
+
+/// Flags telling which files are read and how, understood by every subcommand going through a tree.
+const READING_FLAGS: [&str; 22] = [
+    "--skip-repos",
+    "--respect-gitignore",
+    "--one-file-system",
+    "--skip-hidden",
+    "--hidden",
+    "--ext",
+    "--type",
+    "--exclude",
+    "--biggest-first",
+    "--dir-summaries",
+    "--min-size",
+    "--max-size",
+    "--older-than",
+    "--newer-than",
+    "--skip-dirs-larger-than",
+    "--include-placeholders",
+    "--storage",
+    "--readers",
+    "--threads",
+    "--hash",
+    "--cache",
+    "--no-cache",
+];
+
+/// Flags about the review of the found files and their deletion, only understood by `clean`.
+const CLEANING_FLAGS: [&str; 22] = [
+    "--delete-all",
+    "--keep-one",
+    "--at",
+    "--yes",
+    "--permanent",
+    "--paranoid",
+    "--allow-delete-all",
+    "--allow-unknown-locations",
+    "--contain-strict",
+    "--verify-sample",
+    "--git-rm-script",
+    "--decisions",
+    "--record",
+    "--replay",
+    "--copy",
+    "--page-size",
+    "--icons",
+    "--check-links",
+    "--check-secrets",
+    "--keep",
+    "--tui",
+    "--live",
+];
+
+/// Whether `flag` applies to `command`. The subcommands scanning a tree (`clean`, `scan`, `report` and `simulate`)
+/// understand every flag but the ones about the review and the deletions (only understood by `clean`).
+fn applies(command: Command, flag: &str) -> bool {
+    if GLOBAL_FLAGS.contains(&flag) {
+        return true;
+    }
+    match command {
+        Command::Clean => true,
+        Command::Scan | Command::Report | Command::Simulate => !CLEANING_FLAGS.contains(&flag),
+        Command::Copy | Command::Consolidate | Command::ExportHashes | Command::ImportHashes => {
+            READING_FLAGS.contains(&flag)
+        }
+        Command::Stats => flag == "--history",
+        Command::Query | Command::Migrate => false,
+    }
+}
+
+/// Subcommand named `arg`, if any (`dedup` being `clean` along with `--by-content`).
+fn subcommand(arg: &str) -> Option<Command> {
+    match arg {
+        "clean" | "dedup" => Some(Command::Clean),
+        "scan" => Some(Command::Scan),
+        "report" => Some(Command::Report),
+        "query" => Some(Command::Query),
+        "migrate" => Some(Command::Migrate),
+        "copy" => Some(Command::Copy),
+        "consolidate" => Some(Command::Consolidate),
+        "export-hashes" => Some(Command::ExportHashes),
+        "import-hashes" => Some(Command::ImportHashes),
+        "stats" => Some(Command::Stats),
+        "simulate" => Some(Command::Simulate),
+        _ => None,
+    }
+}
+
+/// Output requested through `args` (the default one when it can't be told), so the errors aborting the run follow
+/// it as well, the ones raised by [`Options::parse`] included (see [`errors`](crate::errors)).
+/// ## Examples
+/// ```
+/// # use dir_cleaner::cli::requested_output;
+/// # use dir_cleaner::output::Output;
+/// let args = ["dir_cleaner", "scan", "--output", "json", "--nmae"].map(String::from);
+/// assert_eq!(requested_output(&args), Output::Json);
+/// assert_eq!(requested_output(&args[..3]), Output::Text);
+/// ```
+pub fn requested_output(args: &[String]) -> Output {
+    let flagged = args.windows(2).rev().find(|pair| pair[0] == "--output");
+    flagged.and_then(|pair| pair[1].parse().ok()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item=String> {
+        let mut all = vec!["dir_cleaner".to_string()];
+        all.extend(args.iter().map(|a| a.to_string()));
+        all.into_iter()
+    }
+
+    #[test]
+    fn parse_directory() {
+        // Arrange
+        let expected = Options {
+            command: Command::Clean,
+            help: false,
+            directory: "./test".to_string(),
+            destination: None,
+            contain_strict: false,
+            tui: false,
+            live: false,
+            config: None,
+            copy: false,
+            name: None,
+            format: Format::Markdown,
+            output: Output::Text,
+            date_format: DateFormat::default(),
+            save: None,
+            incremental: None,
+            git_rm_script: None,
+            decisions: None,
+            record: None,
+            replay: None,
+            history: None,
+            min_confidence: None,
+            icons: false,
+            plain: false,
+            prompt_timeout: None,
+            default_answer: None,
+            audit_log: None,
+            delete_all: false,
+            keep_one: false,
+            at: None,
+            yes: false,
+            permanent: false,
+            paranoid: false,
+            allow_delete_all: false,
+            allow_unknown_locations: false,
+            no_progress: false,
+            skip_hidden: None,
+            verify_sample: None,
+            session: None,
+            manifest: None,
+            reference_manifest: None,
+            query: None,
+            policies: vec![],
+            check_links: false,
+            check_secrets: false,
+            glob: false,
+            regex: false,
+            by_content: false,
+            compare_text: false,
+            compare_video: false,
+            keep: None,
+            cull_jpegs: false,
+            compare_siblings: false,
+            limits: Limits::default(),
+            estimate: false,
+            io_usage: false,
+            storage: None,
+            readers: None,
+            threads: None,
+            hash: Algorithm::Sha256,
+            page_size: None,
+            cache: None,
+            no_cache: false,
+            flush: None,
+            all_drives: false,
+            include_system: false,
+            roots: vec!["./test".to_string()],
+            include_placeholders: false,
+            only_mine: false,
+        };
+
+        // Act
+        let options = Options::parse(args(&["./test"])).unwrap();
+
+        // Assert
+        assert_eq!(expected, options);
+    }
+
+    #[test]
+    fn parse_report() {
+        // Act
+        let options = Options::parse(args(&["report", "./report", "--name", "a.txt", "--format", "md"])).unwrap();
+
+        // Assert
+        assert_eq!(options.command, Command::Report);
+        assert_eq!(options.directory, "./report");
+        assert_eq!(options.name.as_deref(), Some("a.txt"));
+        assert_eq!(options.format, Format::Markdown);
+    }
+
+    #[test]
+    fn parse_query() {
+        // Act
+        let options = Options::parse(args(&["query", "scan.dcs", "size > 1MB"])).unwrap();
+
+        // Assert
+        assert_eq!(options.command, Command::Query);
+        assert_eq!(options.session.as_deref(), Some("scan.dcs"));
+        assert_eq!(options.query.as_deref(), Some("size > 1MB"));
+    }
+
+    #[test]
+    fn parse_migrate() {
+        // Act
+        let options = Options::parse(args(&["migrate", "scan.dcs"])).unwrap();
+
+        // Assert
+        assert_eq!(options.command, Command::Migrate);
+        assert_eq!(options.session.as_deref(), Some("scan.dcs"));
+        assert!(Options::parse(args(&["migrate"])).is_err());
+    }
+
+    #[test]
+    fn parse_copy() {
+        // Act
+        let options = Options::parse(args(&["copy", "/mnt/old", "/mnt/new"])).unwrap();
+
+        // Assert
+        assert_eq!(options.command, Command::Copy);
+        assert_eq!(options.roots, vec![String::from("/mnt/old")]);
+        assert_eq!(options.destination.as_deref(), Some("/mnt/new"));
+        assert!(Options::parse(args(&["copy", "/mnt/old"])).is_err());
+    }
+
+    #[test]
+    fn parse_subcommands() {
+        // Act
+        let scan = Options::parse(args(&["scan", "./test", "--by-content"])).unwrap();
+        let clean = Options::parse(args(&["clean", "./test"])).unwrap();
+        let dedup = Options::parse(args(&["dedup", "./test", "--delete-all"])).unwrap();
+
+        // Assert
+        assert_eq!((scan.command, scan.directory.as_str()), (Command::Scan, "./test"));
+        assert_eq!(clean, Options::parse(args(&["./test"])).unwrap());
+        assert_eq!(dedup.command, Command::Clean);
+        assert!(dedup.by_content && dedup.delete_all);
+        assert!(Options::parse(args(&["scan", "./test", "--delete-all"])).is_err());
+        assert!(Options::parse(args(&["dedup"])).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_the_flags_of_other_subcommands() {
+        // Act
+        let query = Options::parse(args(&["query", "scan.dcs", "size > 1MB", "--delete-all"]));
+        let copy = Options::parse(args(&["copy", "/mnt/old", "/mnt/new", "--ext", "jpg", "--output", "json"]));
+
+        // Assert
+        let usage = "Usage: `dir_cleaner query <session> <expression>`";
+        let query = query.map(|_| ()).map_err(|e| e.to_string());
+        assert_eq!(query, Err(format!("`--delete-all` doesn't apply to `query`.\n{}", usage)));
+        assert_eq!(copy.map(|copy| copy.output).ok(), Some(Output::Json));
+        assert!(Options::parse(args(&["report", "./test", "--permanent"])).is_err());
+        assert!(Options::parse(args(&["copy", "/mnt/old", "/mnt/new", "--name", "a.txt"])).is_err());
+        assert!(Options::parse(args(&["stats", "trend", "--history", "runs.json"])).is_ok());
+        assert!(Options::parse(args(&["stats", "trend", "--hash", "blake3"])).is_err());
+        assert!(Options::parse(args(&["migrate", "scan.dcs", "--delete-all", "--help"])).is_ok());
+    }
+
+    #[test]
+    fn parse_directories_named_after_a_subcommand() {
+        // Act
+        let cleaned = Options::parse(args(&["--", "scan"])).unwrap();
+        let scanned = Options::parse(args(&["scan", "--name", "a.txt", "--", "report"])).unwrap();
+        let flagged = Options::parse(args(&["scan", "--", "--odd"])).unwrap();
+
+        // Assert
+        assert_eq!((cleaned.command, cleaned.directory.as_str()), (Command::Clean, "scan"));
+        assert_eq!((scanned.command, scanned.directory.as_str()), (Command::Scan, "report"));
+        assert_eq!(flagged.directory, "--odd");
+        assert!(Options::parse(args(&["scan"])).is_err());
+    }
+
+    #[test]
+    fn parse_hashes() {
+        // Act
+        let export = Options::parse(args(&["export-hashes", "/home/me", "hashes.json"])).unwrap();
+        let import = Options::parse(args(&["import-hashes", "/home/me", "hashes.json"])).unwrap();
+
+        // Assert
+        assert_eq!(export.command, Command::ExportHashes);
+        assert_eq!(import.command, Command::ImportHashes);
+        assert_eq!(import.directory, "/home/me");
+        assert_eq!(import.manifest.as_deref(), Some("hashes.json"));
+        assert!(Options::parse(args(&["export-hashes", "/home/me"])).is_err());
+    }
+
+    #[test]
+    fn parse_stats() {
+        // Act
+        let all = Options::parse(args(&["stats", "trend", "--history", "runs.json"])).unwrap();
+        let one = Options::parse(args(&["stats", "trend", "/home/me"])).unwrap();
+
+        // Assert
+        assert_eq!((all.command, all.directory.as_str()), (Command::Stats, ""));
+        assert_eq!(all.history.as_deref(), Some("runs.json"));
+        assert_eq!(one.directory, "/home/me");
+        assert!(Options::parse(args(&["stats", "/home/me"])).is_err());
+    }
+
+    #[test]
+    fn parse_simulate() {
+        // Act
+        let options = Options::parse(args(&["simulate", "./test", "--compare", "a.toml", "b.toml"])).unwrap();
+
+        // Assert
+        assert_eq!((options.command, options.directory.as_str()), (Command::Simulate, "./test"));
+        assert_eq!(options.policies, vec!["a.toml", "b.toml"]);
+        assert!(Options::parse(args(&["simulate", "./test"])).is_err());
+        assert!(Options::parse(args(&["simulate", "./test", "--compare", "a.toml"])).is_err());
+        assert!(Options::parse(args(&["./test", "--compare", "a.toml", "b.toml"])).is_err());
+    }
+
+    #[test]
+    fn parse_consolidate() {
+        // Act
+        let options = Options::parse(args(&["consolidate", "/mnt/new", "/mnt/a", "/mnt/b"])).unwrap();
+
+        // Assert
+        assert_eq!(options.command, Command::Consolidate);
+        assert_eq!(options.destination.as_deref(), Some("/mnt/new"));
+        assert_eq!(options.roots, vec![String::from("/mnt/a"), String::from("/mnt/b")]);
+        assert!(Options::parse(args(&["consolidate", "/mnt/new"])).is_err());
+    }
+
+    #[test]
+    fn parse_help() {
+        // Act
+        let clean = Options::parse(args(&["--help"])).unwrap();
+        let copy = Options::parse(args(&["copy", "-h"])).unwrap();
+
+        // Assert
+        assert_eq!((clean.help, clean.command), (true, Command::Clean));
+        assert_eq!((copy.help, copy.command), (true, Command::Copy));
+        assert_eq!(copy.command.help(), "Usage: `dir_cleaner copy <src> <dst> [options]`\n");
+    }
+
+    #[test]
+    fn parse_rejects_missing_and_unknown_args() {
+        // Act and Assert
+        let missing = Options::parse(args(&["copy", "/mnt/old"])).map(|_| ()).map_err(|e| e.to_string());
+        let usage = "Usage: `dir_cleaner copy <src> <dst> [options]`";
+        assert_eq!(missing, Err(format!("`copy` expects the destination of the copy.\n{}", usage)));
+        let unexpected = Options::parse(args(&["stats", "/home/me"])).map(|_| ()).map_err(|e| e.to_string());
+        let usage = "Usage: `dir_cleaner stats trend [<dir>]`";
+        assert_eq!(unexpected, Err(format!("Unexpected argument `/home/me`.\n{}", usage)));
+        let unknown = Options::parse(args(&["scan", "./test", "--nmae"])).map(|_| ()).map_err(|e| e.to_string());
+        let usage = "Usage: `dir_cleaner scan <dir> [--name <name>] [--output text|json|csv] [options]`";
+        assert_eq!(unknown, Err(format!("Unknown argument `--nmae`.\n{}", usage)));
+        let no_value = Options::parse(args(&["migrate", "--config"])).map(|_| ()).map_err(|e| e.to_string());
+        assert_eq!(no_value, Err(String::from("`--config` expects a value.\nUsage: `dir_cleaner migrate <session>`")));
+        let invalid = Options::parse(args(&["./test", "--threads", "0"])).map(|_| ()).map_err(|e| e.to_string());
+        assert_eq!(invalid, Err(String::from("Invalid value `0` for `--threads`, expected a number above 0.")));
+        assert!(Options::parse(args(&["./test", "--readers", "0"])).is_err());
+        assert!(Options::parse(args(&["./test", "--page-size", "all"])).is_err());
+        assert!(Options::parse(args(&[])).is_err());
+        assert!(Options::parse(args(&["./test", "--nope"])).is_err());
+        assert!(Options::parse(args(&["./test", "./other"])).is_err());
+        assert!(Options::parse(args(&["./test", "--config"])).is_err());
+        assert!(Options::parse(args(&["./test", "--reference-manifest"])).is_err());
+        assert!(Options::parse(args(&["report"])).is_err());
+        assert!(Options::parse(args(&["query", "scan.dcs"])).is_err());
+        assert!(Options::parse(args(&["stats"])).is_err());
+        assert!(Options::parse(args(&["./test", "--history"])).is_err());
+        assert!(Options::parse(args(&["./test", "--incremental"])).is_err());
+        assert!(Options::parse(args(&["./test", "--exclude", "[a-"])).is_err());
+        assert!(Options::parse(args(&["./test", "--min-size", "big"])).is_err());
+        assert!(Options::parse(args(&["./test", "--ext", "jpg,"])).is_err());
+        assert!(Options::parse(args(&["./test", "--type", "image,spreadsheet"])).is_err());
+        assert!(Options::parse(args(&["./test", "--live"])).is_err());
+        assert!(Options::parse(args(&["./test", "--tui", "--live", "--by-content"])).is_err());
+        assert!(Options::parse(args(&["./test", "--older-than", "stale"])).is_err());
+        assert!(Options::parse(args(&["./test", "--older-than", "2020-01-01", "--newer-than", "30d"])).is_err());
+        assert!(Options::parse(args(&["./test", "--min-size", "5MB", "--max-size", "10K"])).is_err());
+        assert!(Options::parse(args(&["report", "./test", "--format", "pdf"])).is_err());
+        assert!(Options::parse(args(&["./test", "--storage", "tape"])).is_err());
+        assert!(Options::parse(args(&["./test", "--readers", "many"])).is_err());
+        assert!(Options::parse(args(&["./test", "--threads", "-1"])).is_err());
+        assert!(Options::parse(args(&["./test", "--verify-sample", "few"])).is_err());
+        assert!(Options::parse(args(&["./test", "--delete-all", "--at", "2am"])).is_err());
+        assert!(Options::parse(args(&["./test", "--at", "02:00"])).is_err());
+        assert!(Options::parse(args(&["./test", "--hash", "md5"])).is_err());
+        assert!(Options::parse(args(&["./test", "--min-confidence", "120%"])).is_err());
+        assert!(Options::parse(args(&["./test", "--flush-every", "5m"])).is_err());
+        assert!(Options::parse(args(&["./test", "--no-cache", "--cache", "a.cache"])).is_err());
+        assert!(Options::parse(args(&["./test", "--prompt-timeout", "60s"])).is_err());
+        assert!(Options::parse(args(&["./test", "--glob", "--regex"])).is_err());
+        assert!(Options::parse(args(&["./test", "--record", "a.log", "--replay", "a.log"])).is_err());
+        assert!(Options::parse(args(&["./test", "--output", "yaml"])).is_err());
+        assert!(Options::parse(args(&["./test", "--date-format", "%Y-%Q"])).is_err());
+        assert!(Options::parse(args(&["./test", "--output", "json", "--delete-all"])).is_err());
+        assert!(Options::parse(args(&["./test", "--name", "a.txt", "--yes"])).is_err());
+        assert!(Options::parse(args(&["./test", "--delete-all", "--yes"])).is_err());
+        assert!(Options::parse(args(&["./test", "--prompt-timeout", "60s", "--default-answer", "delete"])).is_err());
+        assert!(Options::parse(args(&["./test", "--flush-every", "0-files", "--cache", "a.cache"])).is_err());
+        assert_eq!(Options::parse(args(&["--all-drives"])).is_err(), !cfg!(windows));
+    }
+}
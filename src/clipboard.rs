@@ -0,0 +1,120 @@
+//! Puts text (usually a list of paths) onto the system clipboard.
+//!
+//! The platform's clipboard tool is used when available (`pbcopy` on macOS, `clip` on Windows and
+//! `wl-copy`, `xclip` or `xsel` elsewhere). When none of them can be found, the text is sent to the
+//! terminal through an `OSC 52` escape sequence, which most modern terminals (even over SSH) understand.
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Returned when no clipboard could be reached.
+#[derive(Debug, Clone)]
+pub struct ClipboardError;
+
+impl Error for ClipboardError {}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Couldn't access the clipboard (install `wl-copy`, `xclip` or `xsel`).")
+    }
+}
+
+fn tools() -> &'static [(&'static str, &'static [&'static str])] {
+    if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    }
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> io::Result<bool> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    Ok(child.wait()?.success())
+}
+
+/// Copies `text` to the clipboard.
+pub fn copy(text: &str) -> Result<(), ClipboardError> {
+    for (program, args) in tools() {
+        if let Ok(true) = pipe_to(program, args, text) {
+            return Ok(());
+        }
+    }
+
+    let mut stdout = io::stdout();
+    if stdout.is_terminal() {
+        if let Ok(()) = stdout.write_all(osc52(text).as_bytes()).and_then(|_| stdout.flush()) {
+            return Ok(());
+        }
+    }
+    Err(ClipboardError)
+}
+
+/// Copies the provided `paths`, one per line.
+/// ## Examples
+/// ```no_run
+/// # use dir_cleaner::clipboard;
+/// clipboard::copy_paths(&["./test/a.txt", "./test/b.txt"]).unwrap();
+/// ```
+pub fn copy_paths<S: AsRef<str>>(paths: &[S]) -> Result<(), ClipboardError> {
+    let text: Vec<&str> = paths.iter().map(|p| p.as_ref()).collect();
+    copy(&text.join("\n"))
+}
+
+/// Escape sequence asking the terminal to store `text` on its clipboard.
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn base64_encoding() {
+        // Act and Assert
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"./a.txt\n./b.txt"), "Li9hLnR4dAouL2IudHh0");
+    }
+
+    #[test]
+    fn osc52_sequence() {
+        // Act and Assert
+        assert_eq!(osc52("foo"), "\x1b]52;c;Zm9v\x07");
+    }
+}
@@ -0,0 +1,94 @@
+//! Detection of the placeholders left by cloud storage clients (OneDrive, Dropbox, iCloud...) for the files that
+//! are only kept online. Reading one of them makes the client download the whole file, so the content
+//! comparisons skip them unless `--include-placeholders` is provided.
+//!
+//! Placeholders are recognized through the attributes the clients set: `RECALL_ON_DATA_ACCESS`,
+//! `RECALL_ON_OPEN` and `OFFLINE` on Windows, the `SF_DATALESS` flag on macOS, along with the `.<name>.icloud`
+//! stubs written by older iCloud versions.
+
+use crate::File;
+use std::fs::Metadata;
+use std::path::Path;
+
+/// Whether the file located at `path` is an online-only placeholder.
+pub fn is_placeholder(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    if name.starts_with('.') && name.ends_with(".icloud") {
+        return true;
+    }
+    std::fs::symlink_metadata(path).is_ok_and(|metadata| has_placeholder_attributes(&metadata))
+}
+
+#[cfg(windows)]
+fn has_placeholder_attributes(metadata: &Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x40000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x400000;
+    let recall = FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS;
+    metadata.file_attributes() & recall != 0
+}
+
+#[cfg(target_os = "macos")]
+fn has_placeholder_attributes(metadata: &Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+
+    const SF_DATALESS: u32 = 0x40000000;
+    metadata.st_flags() & SF_DATALESS != 0
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn has_placeholder_attributes(_metadata: &Metadata) -> bool {
+    false
+}
+
+/// Whether the content of every file may be read: duplicates only, leaving out the placeholders unless
+/// `include_placeholders` is set.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::cloud;
+/// let files = vec![
+///     File::new("a.pdf", "./x", "2022-07-23 12:33:01", "./x/a.pdf"),
+///     File::new("a.pdf", "./y", "2022-07-23 12:33:01", "./y/a.pdf"),
+///     File::new(".b.pdf.icloud", "./y", "2022-07-23 12:33:01", "./y/.b.pdf.icloud"),
+/// ];
+/// assert_eq!(cloud::readable(&files, false), vec![true, true, false]);
+/// ```
+pub fn readable(files: &[File], include_placeholders: bool) -> Vec<bool> {
+    crate::has_duplicate(files)
+        .into_iter()
+        .zip(files)
+        .map(|(duplicated, file)| duplicated && (include_placeholders || !is_placeholder(Path::new(&*file.path()))))
+        .collect()
+}
+
+/// Amount of duplicates whose content isn't read because they're placeholders.
+pub fn skipped(files: &[File]) -> usize {
+    let duplicated = crate::has_duplicate(files);
+    files
+        .iter()
+        .zip(duplicated)
+        .filter(|(file, duplicated)| *duplicated && is_placeholder(Path::new(&*file.path())))
+        .count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn icloud_stubs_are_placeholders() {
+        // Arrange
+        let file = |name: &str| File::new(name, "./y", "2022-07-23 12:33:01", &format!("./y/{}", name));
+        let files = vec![file(".a.pdf.icloud"), file(".a.pdf.icloud"), file("a.pdf"), file("a.pdf")];
+
+        // Act and Assert
+        assert!(is_placeholder(Path::new("./y/.report.docx.icloud")));
+        assert!(!is_placeholder(Path::new("./y/report.docx.icloud")));
+        assert_eq!(readable(&files, false), vec![false, false, true, true]);
+        assert_eq!(readable(&files, true), vec![true; 4]);
+        assert_eq!(skipped(&files), 2);
+    }
+}
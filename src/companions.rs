@@ -0,0 +1,124 @@
+//! Companion files following their parent file (E.G: `.xmp` sidecars, `.srt` subtitles or `.aae` edits),
+//! so they can be handled together with it instead of being left behind as orphans.
+//!
+//! A companion lives on the same folder as its parent, and is named after either the stem of the parent
+//! (`IMG_0001.xmp` for `IMG_0001.CR2`) or its whole name (`IMG_0001.CR2.xmp`). The companion extensions are
+//! declared on the `[companions]` section of the config file:
+//!
+//! ```toml
+//! [companions]
+//! extensions = ["xmp", "aae", "srt", "vtt"]
+//! ```
+
+use crate::config::CompanionsConfig;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_EXTENSIONS: [&str; 3] = ["xmp", "srt", "aae"];
+
+/// Rules telling which files follow their parent.
+/// ## Example
+/// ```
+/// # use dir_cleaner::companions::Companions;
+/// # use std::path::Path;
+/// let companions = Companions::default();
+/// assert!(companions.is_companion_of(Path::new("./a/movie.srt"), Path::new("./a/movie.mkv")));
+/// assert!(!companions.is_companion_of(Path::new("./a/movie.srt"), Path::new("./b/movie.mkv")));
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Companions {
+    extensions: Vec<String>,
+}
+
+impl Default for Companions {
+    fn default() -> Self {
+        Self {
+            extensions: DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+}
+
+impl Companions {
+    pub fn from_config(config: &CompanionsConfig) -> Self {
+        match &config.extensions {
+            Some(extensions) => Self {
+                extensions: extensions.iter().map(|e| e.trim_start_matches('.').to_lowercase()).collect(),
+            },
+            None => Self::default(),
+        }
+    }
+
+    fn is_companion_extension(&self, path: &Path) -> bool {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        self.extensions.contains(&extension.to_lowercase())
+    }
+
+    /// Whether the file located at `path` follows the one located at `parent`.
+    pub fn is_companion_of(&self, path: &Path, parent: &Path) -> bool {
+        let related = path != parent && path.parent() == parent.parent();
+        if !related || !self.is_companion_extension(path) || self.is_companion_extension(parent) {
+            return false;
+        }
+        let lowercase = |name: Option<&std::ffi::OsStr>| name.map(|n| n.to_string_lossy().to_lowercase());
+        let stem = lowercase(path.file_stem());
+        stem.is_some() && (stem == lowercase(parent.file_stem()) || stem == lowercase(parent.file_name()))
+    }
+
+    /// Companions of the file located at `parent` that currently exist, sorted by path.
+    pub fn of(&self, parent: &Path) -> Vec<PathBuf> {
+        let folder = match parent.parent() {
+            Some(folder) if folder.as_os_str().is_empty() => Path::new("."),
+            Some(folder) => folder,
+            None => return vec![],
+        };
+        let entries = match fs::read_dir(folder) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+        let mut companions: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+            .map(|e| parent.with_file_name(e.file_name()))
+            .filter(|path| self.is_companion_of(path, parent))
+            .collect();
+        companions.sort();
+        companions
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn detect_companions() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("companions");
+        for name in ["IMG_1.CR2", "IMG_1.xmp", "IMG_1.CR2.XMP", "IMG_1.txt", "IMG_12.xmp", "movie.srt"] {
+            fs::write(root.join(name), b"")?;
+        }
+        let companions = Companions::default();
+
+        // Act
+        let found = companions.of(&root.join("IMG_1.CR2"));
+
+        // Assert
+        assert_eq!(found, vec![root.join("IMG_1.CR2.XMP"), root.join("IMG_1.xmp")]);
+        assert!(companions.of(&root.join("IMG_1.xmp")).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn extensions_from_config() {
+        // Arrange
+        let config = CompanionsConfig { extensions: Some(vec![String::from(".VTT")]) };
+
+        // Act
+        let companions = Companions::from_config(&config);
+
+        // Assert
+        assert!(companions.is_companion_of(Path::new("a/movie.vtt"), Path::new("a/movie.mkv")));
+        assert!(!companions.is_companion_of(Path::new("a/movie.srt"), Path::new("a/movie.mkv")));
+    }
+}
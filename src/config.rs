@@ -0,0 +1,163 @@
+//! User configuration, read from a `TOML` file.
+//!
+//! Unless `--config <path>` is provided, the file is looked up at `$XDG_CONFIG_HOME/dir_cleaner/config.toml`
+//! (`~/.config/dir_cleaner/config.toml` when the variable isn't set, `%APPDATA%\dir_cleaner\config.toml` on
+//! Windows). A missing default file simply means "use the defaults".
+//!
+//! ```toml
+//! [keys]
+//! preset = "vim"
+//!
+//! [keys.bindings]
+//! mark = ["x", "Space"]
+//! apply = ["ctrl+d"]
+//!
+//! [theme]
+//! preset = "colorblind"
+//!
+//! [companions]
+//! extensions = ["xmp", "srt", "aae"]
+//!
+//! [scan]
+//! skip_hidden = false
+//! ```
+
+use crate::cli::Command;
+use crate::schema::{DocumentError, Failed};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+/// Settings read from the configuration file.
+/// ## Example
+/// ```
+/// # use dir_cleaner::config::Config;
+/// let config = Config::parse("[keys]\npreset = \"vim\"").unwrap();
+/// assert_eq!(config.keys.preset.as_deref(), Some("vim"));
+/// ```
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub keys: KeysConfig,
+    pub theme: ThemeConfig,
+    pub companions: CompanionsConfig,
+    pub scan: ScanConfig,
+}
+
+/// Key bindings of the interactive views: a `preset` (`default`, `arrows` or `vim`) and per-action overrides.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    pub preset: Option<String>,
+    pub bindings: BTreeMap<String, Vec<String>>,
+}
+
+/// Colors of the listings and the interactive views: a `preset` and per-role overrides (see [`crate::theme`]).
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub preset: Option<String>,
+    pub colors: BTreeMap<String, String>,
+}
+
+/// Extensions of the files following their parent (see [`crate::companions`]); the defaults are used when missing.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompanionsConfig {
+    pub extensions: Option<Vec<String>>,
+}
+
+/// Defaults of the scans, which the command line options override.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScanConfig {
+    /// Leave the hidden entries out unless `--hidden` is passed (see [`Limits::skip_hidden`](crate::limits::Limits)),
+    /// which is the default. Set to `false` in order to scan them unless `--skip-hidden` is passed.
+    pub skip_hidden: bool,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        ScanConfig { skip_hidden: true }
+    }
+}
+
+impl ScanConfig {
+    /// Whether `command` leaves the hidden entries out when the command line doesn't tell: only the subcommands
+    /// looking for duplicates follow `skip_hidden`, the ones copying or exporting a tree keep every entry of it.
+    pub fn skips_hidden(&self, command: Command) -> bool {
+        self.skip_hidden && command.looks_for_duplicates()
+    }
+}
+
+impl Config {
+    /// Parses the `TOML` document stored on `content`.
+    pub fn parse(content: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Loads the file located at `path`, or the one stored on the default location when `path` is `None`.
+    pub fn load(path: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => match default_path() {
+                Some(path) if path.is_file() => path,
+                _ => return Ok(Config::default()),
+            },
+        };
+        let content = fs::read_to_string(&path)
+            .map_err(|e| DocumentError::new("config file", path.display(), Failed::Read, e))?;
+        Config::parse(&content)
+            .map_err(|e| DocumentError::new("config file", path.display(), Failed::Parse, e).into())
+    }
+}
+
+/// Location of the configuration file when none is provided explicitly.
+pub fn default_path() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base.map(|base| base.join("dir_cleaner").join("config.toml"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_keys() {
+        // Arrange
+        let content = "[keys]\npreset = \"arrows\"\n[keys.bindings]\nmark = [\"m\", \"Space\"]\n";
+
+        // Act
+        let config = Config::parse(content).unwrap();
+
+        // Assert
+        assert_eq!(config.keys.preset.as_deref(), Some("arrows"));
+        assert_eq!(config.keys.bindings["mark"], vec!["m", "Space"]);
+        assert!(Config::parse("[scan]\nskip_hidden = true").unwrap().scan.skip_hidden);
+        assert!(!Config::parse("[scan]\nskip_hidden = false").unwrap().scan.skip_hidden);
+        assert!(Config::parse("").unwrap().scan.skip_hidden);
+        assert!(!Config::default().scan.skips_hidden(Command::Copy));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_fields() {
+        // Act and Assert
+        assert!(Config::parse("[keyz]\npreset = \"vim\"").is_err());
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn load_missing_explicit_file() {
+        // Act and Assert
+        assert!(Config::load(Some("./does-not-exist.toml")).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! Plans the consolidation of several drives (E.G: old external backup drives) onto a target drive
+//! (`dir_cleaner consolidate <target> <drive>...`): which contents are found on a single drive, and the steps
+//! merging every content onto the target exactly once.
+//!
+//! Nothing is copied nor deleted: the plan is printed for review. The contents are compared the way the `copy`
+//! subcommand does (see [`copy`](crate::copy)).
+
+use crate::hash;
+use crate::limits::{Limits, Skipped};
+use crate::storage::Reading;
+use crate::units::format_size;
+use crate::File;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::Formatter;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A single step of a plan.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Step {
+    /// Copy a content missing on the target.
+    Copy { from: PathBuf, to: PathBuf, bytes: u64 },
+    /// Delete a redundant copy found on the target.
+    Delete { path: PathBuf, copy_of: PathBuf, bytes: u64 },
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Step::Copy { from, to, .. } => write!(f, "copy `{}` to `{}`", from.display(), to.display()),
+            Step::Delete { path, copy_of, .. } => {
+                write!(f, "delete `{}` (a copy of `{}`)", path.display(), copy_of.display())
+            }
+        }
+    }
+}
+
+/// Contents only found on one of the drives.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Exclusive {
+    pub drive: String,
+    pub files: Vec<PathBuf>,
+    pub bytes: u64,
+}
+
+/// Consolidation plan of several drives onto a target.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Plan {
+    /// Distinct contents found on the drives and on the target.
+    pub contents: usize,
+    /// Bytes the distinct contents add up to.
+    pub bytes: u64,
+    /// Contents found on a single drive (and not on the target), by drive.
+    pub exclusive: Vec<Exclusive>,
+    pub steps: Vec<Step>,
+    /// Directories left out because they exceeded the limits.
+    pub skipped: Vec<Skipped>,
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "{} distinct content(s) found ({}).", self.contents, format_size(self.bytes))?;
+        for exclusive in self.exclusive.iter().filter(|exclusive| !exclusive.files.is_empty()) {
+            writeln!(
+                f,
+                "{} file(s) ({}) are only found on `{}`:",
+                exclusive.files.len(),
+                format_size(exclusive.bytes),
+                exclusive.drive
+            )?;
+            for file in &exclusive.files {
+                writeln!(f, "\t`{}`", file.display())?;
+            }
+        }
+        let (mut copied, mut freed) = (0, 0);
+        for step in &self.steps {
+            match step {
+                Step::Copy { bytes, .. } => copied += bytes,
+                Step::Delete { bytes, .. } => freed += bytes,
+            }
+        }
+        writeln!(
+            f,
+            "Plan ({} step(s), {} copied, {} freed on the target):",
+            self.steps.len(),
+            format_size(copied),
+            format_size(freed)
+        )?;
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(f, "\t{}. {}", i + 1, step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plans the consolidation of `drives` onto `target`: every content missing on the target is copied from the
+/// first drive holding it (to the same relative path, or under a directory named after the drive when that path
+/// is taken), and the redundant copies found on the target are deleted.
+pub fn plan(target: &str, drives: &[String], limits: &Limits, reading: &Reading) -> Result<Plan, io::Error> {
+    let mut plan = Plan::default();
+    let mut found: Vec<(usize, File)> = vec![];
+    // The target comes first, so its copies are the ones kept.
+    for (i, root) in [target.to_string()].iter().chain(drives).enumerate() {
+        if i == 0 && !Path::new(root).is_dir() {
+            continue;
+        }
+        let (files, mut skipped) = crate::get_dir_files_limited(root, &|_| true, limits)?;
+        found.extend(files.into_iter().map(|file| (i, file)));
+        plan.skipped.append(&mut skipped);
+    }
+    let digests = hash::shared_size_digests(&found.iter().map(|(_, file)| file).collect::<Vec<&File>>(), reading);
+    // Files without a digest have a unique size (or couldn't be read): no other file holds their content.
+    let contents: Vec<String> = found
+        .iter()
+        .zip(digests)
+        .map(|((_, file), digest)| digest.unwrap_or_else(|| format!("path:{}", file.path())))
+        .collect();
+    let mut holders: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, content) in contents.iter().enumerate() {
+        holders.entry(content).or_default().push(index);
+    }
+    plan.contents = holders.len();
+    plan.exclusive = drives.iter().map(|drive| Exclusive { drive: drive.clone(), ..Exclusive::default() }).collect();
+    let mut taken: HashSet<PathBuf> =
+        found.iter().filter(|(i, _)| *i == 0).map(|(_, file)| PathBuf::from(&*file.path())).collect();
+    let mut planned: HashSet<&str> = HashSet::new();
+    for (index, content) in contents.iter().enumerate() {
+        if !planned.insert(content) {
+            continue;
+        }
+        let copies = &holders[content.as_str()];
+        let (drive, file) = &found[index];
+        plan.bytes += file.size;
+        let on_target: Vec<&File> =
+            copies.iter().map(|c| &found[*c]).filter(|(i, _)| *i == 0).map(|(_, file)| file).collect();
+        if let Some((kept, redundant)) = on_target.split_first() {
+            for copy in redundant {
+                let (path, copy_of) = (PathBuf::from(&*copy.path()), PathBuf::from(&*kept.path()));
+                plan.steps.push(Step::Delete { path, copy_of, bytes: copy.size });
+            }
+            continue;
+        }
+        let drives_holding: HashSet<usize> = copies.iter().map(|c| found[*c].0).collect();
+        if drives_holding.len() == 1 {
+            let exclusive = &mut plan.exclusive[drive - 1];
+            exclusive.files.push(PathBuf::from(&*file.path()));
+            exclusive.bytes += file.size;
+        }
+        let root = &drives[drive - 1];
+        let path = file.path();
+        let relative = Path::new(&*path).strip_prefix(root).unwrap_or(Path::new(&*file.name));
+        let mut to = Path::new(target).join(relative);
+        if taken.contains(&to) {
+            let label = Path::new(root).file_name().map(|name| name.to_string_lossy().to_string());
+            to = Path::new(target).join(label.unwrap_or_else(|| drive.to_string())).join(relative);
+        }
+        taken.insert(to.clone());
+        plan.steps.push(Step::Copy { from: PathBuf::from(&*file.path()), to, bytes: file.size });
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn drives_are_merged_onto_the_target() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("consolidate");
+        let (target, first, second) = (root.join("target"), root.join("first"), root.join("second"));
+        for dir in [&target, &first, &second] {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(target.join("a.txt"), "aaaa")?;
+        fs::write(target.join("a-copy.txt"), "aaaa")?;
+        fs::write(first.join("a.txt"), "aaaa")?;
+        fs::write(first.join("b.txt"), "bbbb")?;
+        fs::write(second.join("b-renamed.txt"), "bbbb")?;
+        fs::write(second.join("c.txt"), "unique size")?;
+        fs::write(second.join("a.txt"), "cccc")?;
+        let cache = Cache::default();
+        let algorithm = hash::Algorithm::Sha256;
+        let reading = Reading { readers: 2, cache: &cache, include_placeholders: false, algorithm };
+        let path = |dir: &PathBuf| dir.to_string_lossy().to_string();
+
+        // Act
+        let plan = plan(&path(&target), &[path(&first), path(&second)], &Limits::default(), &reading)?;
+
+        // Assert
+        assert_eq!((plan.contents, plan.bytes), (4, 23));
+        assert!(plan.exclusive[0].files.is_empty());
+        assert_eq!(plan.exclusive[1].files, vec![second.join("a.txt"), second.join("c.txt")]);
+        assert_eq!(
+            plan.steps,
+            vec![
+                Step::Delete { path: target.join("a.txt"), copy_of: target.join("a-copy.txt"), bytes: 4 },
+                Step::Copy { from: first.join("b.txt"), to: target.join("b.txt"), bytes: 4 },
+                Step::Copy { from: second.join("a.txt"), to: target.join("second").join("a.txt"), bytes: 4 },
+                Step::Copy { from: second.join("c.txt"), to: target.join("c.txt"), bytes: 11 },
+            ]
+        );
+        assert!(plan.to_string().contains("Plan (4 step(s), 19 B copied, 4 B freed on the target):\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+//! Checks guaranteeing that destructive actions never touch files outside the roots provided by the user.
+//!
+//! The default check is lexical: `.` and `..` components are resolved without accessing the filesystem
+//! and the target must remain under the root. The strict check (`--contain-strict`) canonicalizes both
+//! paths right before acting, so a directory swapped by a symlink after the scan is caught as well.
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Returned whenever a target can't be proven to live under its root.
+#[derive(Debug)]
+pub struct ContainmentError {
+    pub root: PathBuf,
+    pub target: PathBuf,
+    reason: String,
+}
+
+impl Error for ContainmentError {}
+
+impl fmt::Display for ContainmentError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Refusing to touch `{}`: {} (root: `{}`).",
+            self.target.display(),
+            self.reason,
+            self.root.display()
+        )
+    }
+}
+
+impl ContainmentError {
+    fn new(root: &Path, target: &Path, reason: &str) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            target: target.to_path_buf(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Resolves the `.` and `..` components of `path` without accessing the filesystem.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::containment::normalize;
+/// # use std::path::{Path, PathBuf};
+/// assert_eq!(normalize(Path::new("./a/../b/./c.txt")), PathBuf::from("b/c.txt"));
+/// assert_eq!(normalize(Path::new("../a")), PathBuf::from("../a"));
+/// ```
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                let can_pop = matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                );
+                if can_pop {
+                    normalized.pop();
+                } else if !normalized.has_root() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// Lexically checks whether `target` is located under `root`.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::containment::is_contained;
+/// # use std::path::Path;
+/// assert!(is_contained(Path::new("./test"), Path::new("./test/a/b.txt")));
+/// assert!(!is_contained(Path::new("./test"), Path::new("./test/../b.txt")));
+/// ```
+pub fn is_contained(root: &Path, target: &Path) -> bool {
+    let root = normalize(root);
+    let target = normalize(target);
+    target != root && target.starts_with(&root)
+}
+
+/// Verifies that `target` can be safely acted upon, returning an error explaining why it can't otherwise.
+/// When `strict` is set, both paths are canonicalized (following every symlink on the way) and the target
+/// itself must be a regular file rather than a link.
+pub fn verify(root: &Path, target: &Path, strict: bool) -> Result<(), Box<dyn Error>> {
+    if !is_contained(root, target) {
+        return Err(ContainmentError::new(root, target, "it is outside of the scanned directory").into());
+    }
+    if !strict {
+        return Ok(());
+    }
+
+    let metadata = fs::symlink_metadata(target)?;
+    if !metadata.file_type().is_file() {
+        return Err(ContainmentError::new(root, target, "it is not a regular file").into());
+    }
+    let canonical_root = fs::canonicalize(root)?;
+    let canonical_target = fs::canonicalize(target)?;
+    if !canonical_target.starts_with(&canonical_root) {
+        return Err(ContainmentError::new(
+            root,
+            target,
+            &format!("it resolves to `{}`", canonical_target.display()),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn normalize_resolves_dots() {
+        // Arrange
+        let path = Path::new("./a/./b/../c.txt");
+
+        // Act
+        let normalized = normalize(path);
+
+        // Assert
+        assert_eq!(normalized, PathBuf::from("a/c.txt"));
+    }
+
+    #[test]
+    fn parent_components_escape_the_root() {
+        // Arrange
+        let root = Path::new("./test");
+        let escaping = Path::new("./test/a/../../other/f.txt");
+        let contained = Path::new("test/a/../f.txt");
+
+        // Act and Assert
+        assert!(!is_contained(root, escaping));
+        assert!(is_contained(root, contained));
+        assert!(!is_contained(root, root));
+    }
+
+    #[test]
+    fn strict_verification_rejects_symlinks() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("containment");
+        let file = root.join("file.txt");
+        fs::write(&file, b"")?;
+
+        // Act and Assert
+        assert!(verify(&root, &file, true).is_ok());
+        #[cfg(unix)]
+        {
+            let scratch = Scratch::new("outside");
+            let outside = scratch.join("outside.txt");
+            fs::write(&outside, b"")?;
+            let link = root.join("link.txt");
+            std::os::unix::fs::symlink(&outside, &link)?;
+            assert!(verify(&root, &link, false).is_ok());
+            assert!(verify(&root, &link, true).is_err());
+        }
+        Ok(())
+    }
+}
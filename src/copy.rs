@@ -0,0 +1,160 @@
+//! Copying a tree while leaving out the files whose content is already found at the destination
+//! (`dir_cleaner copy <src> <dst>`), so several old backup drives can be consolidated into one without
+//! recreating their duplicates.
+//!
+//! The contents are compared by their SHA-256 digest (see [`hash`](crate::hash)), which is only computed for the
+//! files whose size is shared with another one: a file of a unique size can't have a copy.
+
+use crate::hash;
+use crate::limits::{Limits, Skipped};
+use crate::storage::Reading;
+use crate::units::format_size;
+use crate::File;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Outcome of a copy.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Copied {
+    pub files: usize,
+    pub bytes: u64,
+    /// Files left out, along with the file already holding their content.
+    pub skipped: Vec<(PathBuf, PathBuf)>,
+    pub skipped_bytes: u64,
+    /// Files left out because another file with the same name (and another content) is already there.
+    pub conflicts: Vec<PathBuf>,
+    /// Source directories left out because they exceeded the limits.
+    pub skipped_dirs: Vec<Skipped>,
+}
+
+impl fmt::Display for Copied {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Copied {} file(s) ({}), skipped {} file(s) ({}) whose content was already there.",
+            self.files,
+            format_size(self.bytes),
+            self.skipped.len(),
+            format_size(self.skipped_bytes)
+        )
+    }
+}
+
+/// Copies the files located under `source` to the same relative paths under `destination`, leaving out the ones
+/// whose content is already found there (or was just copied), and never overwriting anything.
+pub fn copy_tree(source: &str, destination: &str, limits: &Limits, reading: &Reading) -> Result<Copied, io::Error> {
+    let (sources, skipped_dirs) = crate::get_dir_files_limited(source, &|_| true, limits)?;
+    let existing = match Path::new(destination).is_dir() {
+        true => crate::get_dir_files_limited(destination, &|_| true, &Limits::default())?.0,
+        false => vec![],
+    };
+    let mut digests = hash::shared_size_digests(&existing.iter().chain(&sources).collect::<Vec<&File>>(), reading);
+    let source_digests = digests.split_off(existing.len());
+    let mut holders: HashMap<String, PathBuf> = HashMap::new();
+    for (file, digest) in existing.iter().zip(digests) {
+        if let Some(digest) = digest {
+            holders.entry(digest).or_insert_with(|| PathBuf::from(&*file.path()));
+        }
+    }
+    let mut copied = Copied { skipped_dirs, ..Copied::default() };
+    let mut created: HashSet<PathBuf> = HashSet::new();
+    for (file, digest) in sources.iter().zip(source_digests) {
+        let path = file.path();
+        let from = Path::new(&*path);
+        if let Some(holder) = digest.as_ref().and_then(|digest| holders.get(digest)) {
+            copied.skipped.push((from.to_path_buf(), holder.clone()));
+            copied.skipped_bytes += file.size;
+            continue;
+        }
+        let relative = from.strip_prefix(source).unwrap_or(from);
+        let to = Path::new(destination).join(relative);
+        if fs::symlink_metadata(&to).is_ok() {
+            copied.conflicts.push(from.to_path_buf());
+            continue;
+        }
+        if let Some(parent) = to.parent().filter(|parent| created.insert(parent.to_path_buf())) {
+            fs::create_dir_all(parent)?;
+        }
+        copied.bytes += fs::copy(from, &to)?;
+        copied.files += 1;
+        if let Some(digest) = digest {
+            holders.insert(digest, to);
+        }
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::cli::Command;
+    use crate::config::Config;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn copies_already_at_the_destination_are_skipped() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("copy");
+        let (source, destination) = (root.join("old-drive"), root.join("new-drive"));
+        fs::create_dir_all(source.join("photos"))?;
+        fs::create_dir_all(destination.join("backup"))?;
+        fs::write(source.join("photos").join("a.jpg"), "same")?;
+        fs::write(source.join("photos").join("b.jpg"), "new!")?;
+        fs::write(source.join("photos").join("b-copy.jpg"), "new!")?;
+        fs::write(source.join("notes.txt"), "unique size")?;
+        fs::write(source.join("c.txt"), "mine")?;
+        fs::write(destination.join("backup").join("a-renamed.jpg"), "same")?;
+        fs::write(destination.join("c.txt"), "more")?;
+        let cache = Cache::default();
+        let algorithm = hash::Algorithm::Sha256;
+        let reading = Reading { readers: 2, cache: &cache, include_placeholders: false, algorithm };
+        let (source, destination) = (source.to_string_lossy(), destination.to_string_lossy());
+
+        // Act
+        let copied = copy_tree(&source, &destination, &Limits::default(), &reading)?;
+
+        // Assert
+        let destination = Path::new(&*destination);
+        assert_eq!((copied.files, copied.bytes), (2, 15));
+        assert_eq!(copied.skipped.len(), 2);
+        assert_eq!(copied.skipped_bytes, 8);
+        assert_eq!(copied.skipped[0].1, destination.join("backup").join("a-renamed.jpg"));
+        assert_eq!(copied.skipped[1].1, destination.join("photos").join("b-copy.jpg"));
+        assert_eq!(copied.conflicts, vec![Path::new(&*source).join("c.txt")]);
+        assert_eq!(fs::read_to_string(destination.join("notes.txt"))?, "unique size");
+        assert_eq!(fs::read_to_string(destination.join("c.txt"))?, "more");
+        assert!(!destination.join("photos").join("a.jpg").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn dotfiles_are_copied_under_the_default_config() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("copy-hidden");
+        let (source, destination) = (root.join("home"), root.join("backup"));
+        fs::create_dir_all(source.join(".config"))?;
+        fs::write(source.join("visible.txt"), "1")?;
+        fs::write(source.join(".bashrc"), "22")?;
+        fs::write(source.join(".config").join("x"), "333")?;
+        let cache = Cache::default();
+        let algorithm = hash::Algorithm::Sha256;
+        let reading = Reading { readers: 2, cache: &cache, include_placeholders: false, algorithm };
+        let limits = Limits { skip_hidden: Config::default().scan.skips_hidden(Command::Copy), ..Limits::default() };
+        let (source, destination) = (source.to_string_lossy(), destination.to_string_lossy());
+
+        // Act
+        let copied = copy_tree(&source, &destination, &limits, &reading)?;
+
+        // Assert
+        let destination = Path::new(&*destination);
+        assert_eq!(copied.files, 3);
+        assert_eq!(fs::read_to_string(destination.join(".bashrc"))?, "22");
+        assert_eq!(fs::read_to_string(destination.join(".config").join("x"))?, "333");
+        Ok(())
+    }
+}
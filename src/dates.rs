@@ -0,0 +1,89 @@
+//! Date range of the collected files (`--older-than <when>`, `--newer-than <when>`), so the cleanups can target
+//! the stale files only. `when` is either an age (E.G: `90d`, see [`parse_duration`]) or a date (`2024-01-01`,
+//! optionally followed by a time, in UTC like the listed dates).
+//!
+//! The files are judged by their last modification, or by their creation when the filesystem doesn't keep track
+//! of the former. The ones whose dates can't be read are left out as soon as a bound is given.
+
+use crate::units::{parse_duration, UnitError};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::time::SystemTime;
+
+/// Parses `value` into the instant it designates, the ages counting back from `now`.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::dates::parse_bound;
+/// # use std::time::{Duration, SystemTime};
+/// let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 24 * 60 * 60);
+/// assert_eq!(parse_bound("90d", now), Ok(SystemTime::UNIX_EPOCH + Duration::from_secs(10 * 24 * 60 * 60)));
+/// assert_eq!(parse_bound("1970-01-02", now), Ok(SystemTime::UNIX_EPOCH + Duration::from_secs(24 * 60 * 60)));
+/// assert!(parse_bound("last week", now).is_err());
+/// ```
+pub fn parse_bound(value: &str, now: SystemTime) -> Result<SystemTime, UnitError> {
+    let trimmed = value.trim();
+    let date = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDate::parse_from_str(trimmed, "%Y-%m-%d").map(|date| date.and_time(Default::default())));
+    if let Ok(date) = date {
+        return Ok(date.and_utc().into());
+    }
+    let age = parse_duration(value)?;
+    Ok(now.checked_sub(age).unwrap_or(SystemTime::UNIX_EPOCH))
+}
+
+/// Bounds the dates of the collected files must respect (both excluded).
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct DateRange {
+    /// The files must date from before this instant (`--older-than`).
+    pub before: Option<SystemTime>,
+    /// The files must date from after this instant (`--newer-than`).
+    pub after: Option<SystemTime>,
+}
+
+impl DateRange {
+    pub fn is_empty(&self) -> bool {
+        self.before.is_none() && self.after.is_none()
+    }
+
+    /// Whether not a single date falls within the range.
+    pub fn is_unsatisfiable(&self) -> bool {
+        self.before.zip(self.after).is_some_and(|(before, after)| before <= after)
+    }
+
+    /// Whether a file last modified on `modified` (or created on `created`, when the modification is unknown)
+    /// falls within the range.
+    pub fn contains(&self, modified: Option<SystemTime>, created: Option<SystemTime>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        match modified.or(created) {
+            Some(date) => self.before.is_none_or(|before| date < before) && self.after.is_none_or(|after| date > after),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use std::time::Duration;
+
+    #[test]
+    fn only_the_dates_within_the_range_are_kept() {
+        // Arrange
+        let day = |days: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(days * 24 * 60 * 60);
+        let range = DateRange { before: Some(day(90)), after: Some(day(10)) };
+
+        // Act and Assert
+        assert!(range.contains(Some(day(50)), None));
+        assert!(range.contains(None, Some(day(50))));
+        assert!(!range.contains(Some(day(95)), Some(day(50))));
+        assert!(!range.contains(Some(day(10)), None));
+        assert!(!range.contains(None, None));
+        assert!(DateRange::default().contains(None, None));
+        assert!(DateRange { before: Some(day(10)), after: Some(day(90)) }.is_unsatisfiable());
+        assert!(!range.is_unsatisfiable());
+        let noon = parse_bound("2024-01-01 12:00:00", day(0)).map(DateTime::<Utc>::from);
+        assert_eq!(noon.map(|date| date.to_rfc3339()), Ok(String::from("2024-01-01T12:00:00+00:00")));
+    }
+}
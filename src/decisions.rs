@@ -0,0 +1,115 @@
+//! Decisions remembered across runs (`--decisions <file>`), so repeated scans of a living directory don't ask
+//! again about the duplicates that were already dealt with.
+//!
+//! A decision applies to the copies of a given content found in a given folder: the entries tolerated through
+//! `keep <number>` are left out of the next listings, while the ones deleted are suggested for deletion (see the
+//! `auto` command) whenever a copy shows up there again. The content is told by the digest of the file when
+//! its content was compared, and by its name and size otherwise.
+
+use crate::schema::{self, DocumentError, Failed, DECISIONS_VERSION};
+use crate::File;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+
+/// What was decided about the copies of a content found in a folder.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    /// The copy is tolerated there.
+    Keep,
+    /// The copy was deleted from there.
+    Delete,
+}
+
+/// Decisions read from (and written back to) a file.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::decisions::{Decision, Decisions};
+/// let mut decisions = Decisions::default();
+/// let file = File::new("a.txt", "./old", "2022-07-23 12:33:01", "./old/a.txt");
+/// decisions.record(&file, Decision::Keep);
+/// let copy = File::new("a.txt", "./old", "2023-01-01 00:00:00", "./old/a.txt");
+/// assert_eq!(decisions.get(&copy), Some(Decision::Keep));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct Decisions {
+    /// Version of the format the decisions were written with (see [`schema`](crate::schema)).
+    pub version: u32,
+    /// Decisions keyed by `<content>@<folder>`.
+    pub decisions: BTreeMap<String, Decision>,
+}
+
+/// Key of the decisions about `file`.
+fn key(file: &File) -> String {
+    match &file.digest {
+        Some(digest) => format!("{}@{}", digest, file.folder),
+        None => format!("name:{}:{}@{}", file.name, file.size, file.folder),
+    }
+}
+
+impl Decisions {
+    /// Reads the decisions stored on the file located at `path` (none when it doesn't exist yet).
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Ok(Decisions::default()),
+        };
+        let document: Value =
+            serde_json::from_str(&content).map_err(|e| DocumentError::new("decisions", path, Failed::Parse, e))?;
+        schema::version("decisions file", &document, DECISIONS_VERSION)?;
+        let decisions =
+            serde_json::from_value(document).map_err(|e| DocumentError::new("decisions", path, Failed::Parse, e))?;
+        Ok(decisions)
+    }
+
+    pub fn get(&self, file: &File) -> Option<Decision> {
+        self.decisions.get(&key(file)).copied()
+    }
+
+    pub fn record(&mut self, file: &File, decision: Decision) {
+        self.decisions.insert(key(file), decision);
+    }
+
+    /// Writes the decisions to the file located at `path`.
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let decisions = Decisions { version: DECISIONS_VERSION, decisions: self.decisions.clone() };
+        fs::write(path, serde_json::to_string_pretty(&decisions)?)
+            .map_err(|e| DocumentError::new("decisions", path, Failed::Save, e).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn decisions_survive_a_save() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let scratch = Scratch::new("decisions");
+        let path = scratch.join("decisions.json");
+        let path = path.to_str().unwrap();
+        let mut kept = File::new("a.txt", "./old", "2022-07-23 12:33:01", "./old/a.txt");
+        kept.digest = Some(String::from("text:0123"));
+        let deleted = File::new("a.txt", "./tmp", "2022-07-23 12:33:01", "./tmp/a.txt");
+        let mut decisions = Decisions::default();
+
+        // Act
+        decisions.record(&kept, Decision::Keep);
+        decisions.record(&deleted, Decision::Delete);
+        decisions.save(path)?;
+        let loaded = Decisions::load(path)?;
+
+        // Assert
+        assert_eq!(loaded.get(&kept), Some(Decision::Keep));
+        assert_eq!(loaded.get(&deleted), Some(Decision::Delete));
+        kept.digest = Some(String::from("text:4567"));
+        assert_eq!(loaded.get(&kept), None);
+        assert_eq!(Decisions::load("./missing-decisions.json")?, Decisions::default());
+        Ok(())
+    }
+}
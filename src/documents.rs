@@ -0,0 +1,198 @@
+//! Comparison of documents by their extracted text (requires the `documents` feature), so the same report
+//! exported twice (with different metadata, fonts or compression) is still recognized as a duplicate even
+//! though both files differ byte by byte.
+
+use crate::accounting;
+use crate::checksum::fnv1a;
+use crate::cloud;
+use crate::progress;
+use crate::storage::{map_parallel, Reading};
+use crate::warnings;
+use crate::File;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Document kinds whose text can be extracted.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Kind {
+    Pdf,
+    Docx,
+}
+
+impl Kind {
+    /// Guesses the kind of the document located at `path` from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "pdf" => Some(Kind::Pdf),
+            "docx" => Some(Kind::Docx),
+            _ => None,
+        }
+    }
+}
+
+/// Extracts the text of the document located at `path`, or returns `None` when it isn't a supported document.
+pub fn extract_text(path: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    let text = match Kind::from_path(path) {
+        Some(Kind::Pdf) => {
+            let content = fs::read(path)?;
+            // Malformed documents can make the extractor panic instead of failing gracefully.
+            std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(&content))
+                .map_err(|_| "the document seems to be malformed")??
+        }
+        Some(Kind::Docx) => {
+            let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+            let mut xml = String::new();
+            archive.by_name("word/document.xml")?.read_to_string(&mut xml)?;
+            docx_text(&xml)
+        }
+        None => return Ok(None),
+    };
+    Ok(Some(text))
+}
+
+/// Reported when the text of the document located at `path` can't be extracted.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TextError {
+    pub path: String,
+    pub reason: String,
+}
+
+impl Error for TextError {}
+
+impl fmt::Display for TextError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Couldn't extract the text of `{}`: {}", self.path, self.reason)
+    }
+}
+
+/// Collapses every run of whitespace into a single space, so layout differences don't matter.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::documents::normalize;
+/// assert_eq!(normalize("  Quarterly\n\treport \r\n 2024 "), "Quarterly report 2024");
+/// ```
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Digest of the normalized text of the document located at `path`, or `None` when it isn't a
+/// supported document.
+pub fn text_digest(path: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    Ok(extract_text(path)?.map(|text| format!("text:{:016x}", fnv1a(normalize(&text).as_bytes()))))
+}
+
+/// Refines the `digest` of the supported documents among `files`, so they're only grouped with the copies
+/// holding the same text. Documents without duplicates (or only kept online, see [`cloud`](crate::cloud))
+/// are skipped, and the ones whose text can't be extracted are reported and left untouched.
+pub fn digest_files(files: &mut [File], reading: &Reading) {
+    let readable = cloud::readable(files, reading.include_placeholders);
+    let candidates: Vec<usize> = (0..files.len()).filter(|i| readable[*i]).collect();
+    let cache = reading.cache;
+    let digests = map_parallel(&candidates, reading.readers, |i| -> Result<Option<String>, TextError> {
+        let path = files[*i].path();
+        let path = Path::new(&*path);
+        if let Some(digest) = cache.get("text", path) {
+            return Ok(Some(digest));
+        }
+        let digest = text_digest(path)
+            .map_err(|err| TextError { path: path.display().to_string(), reason: err.to_string() })?;
+        accounting::SCAN.read(path, files[*i].size);
+        progress::SCAN.hashed(files[*i].size);
+        if let Some(digest) = &digest {
+            cache.insert("text", path, digest);
+        }
+        Ok(digest)
+    });
+    for (i, digest) in candidates.into_iter().zip(digests) {
+        match digest {
+            Ok(Some(digest)) => files[i].add_digest(&digest),
+            Ok(None) => {}
+            Err(err) => warnings::unreadable_content(&err, &err.path, err.to_string()),
+        }
+    }
+}
+
+/// Text of the `word/document.xml` part of a `.docx` file, a line per paragraph.
+fn docx_text(xml: &str) -> String {
+    let mut text = String::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&unescape(&rest[..start]));
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let tag = &rest[start + 1..end];
+        if tag == "/w:p" || tag.starts_with("w:br") || tag.starts_with("w:tab") {
+            text.push('\n');
+        }
+        rest = &rest[end + 1..];
+    }
+    text
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 64 bits FNV-1a hash, stable across runs and platforms.
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+    use std::io::Write;
+
+    fn write_docx(path: &Path, body: &str) -> Result<(), Box<dyn Error>> {
+        let mut zip = zip::ZipWriter::new(fs::File::create(path)?);
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("docProps/core.xml", options)?;
+        zip.write_all(path.to_string_lossy().as_bytes())?;
+        zip.start_file("word/document.xml", options)?;
+        zip.write_all(body.as_bytes())?;
+        zip.finish()?;
+        Ok(())
+    }
+
+    #[test]
+    fn docx_text_keeps_paragraphs() {
+        // Act and Assert
+        assert_eq!(
+            docx_text("<w:body><w:p><w:r><w:t>Q&amp;A</w:t></w:r></w:p><w:p><w:r><w:t>2024</w:t></w:r></w:p></w:body>"),
+            "Q&A\n2024\n"
+        );
+    }
+
+    #[test]
+    fn same_text_same_digest() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("documents");
+        write_docx(&root.join("a.docx"), "<w:p><w:r><w:t>Quarterly  report</w:t></w:r></w:p>")?;
+        write_docx(&root.join("b.docx"), "<w:p><w:r><w:t>Quarterly</w:t></w:r><w:r><w:t> report</w:t></w:r></w:p>")?;
+        write_docx(&root.join("c.docx"), "<w:p><w:r><w:t>Annual report</w:t></w:r></w:p>")?;
+        fs::write(root.join("d.txt"), "Quarterly report")?;
+
+        // Act
+        let a = text_digest(&root.join("a.docx"))?;
+        let b = text_digest(&root.join("b.docx"))?;
+        let c = text_digest(&root.join("c.docx"))?;
+        let d = text_digest(&root.join("d.txt"))?;
+
+        // Assert
+        assert_ne!(fs::read(root.join("a.docx"))?, fs::read(root.join("b.docx"))?);
+        assert!(a.is_some());
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(d, None);
+        assert!(text_digest(&root.join("missing.pdf")).is_err());
+        Ok(())
+    }
+}
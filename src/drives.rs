@@ -0,0 +1,84 @@
+//! Whole machine scans on Windows (`--all-drives`): every fixed drive is scanned as a root of its own.
+//!
+//! The directories managed by the system (`C:\Windows`, the recycle bin, restore points...) are skipped unless
+//! `--include-system` is provided, since the duplicates found there must never be deleted by hand.
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+
+/// Directories of every drive that are managed by the system.
+const PROTECTED: [&str; 7] = [
+    "Windows",
+    "$Recycle.Bin",
+    "System Volume Information",
+    "Recovery",
+    "Config.Msi",
+    "$WinREAgent",
+    "$SysReset",
+];
+
+/// Returned when the drives of the machine can't be enumerated.
+#[derive(Debug, Clone)]
+pub struct DrivesError(pub &'static str);
+
+impl Error for DrivesError {}
+
+impl fmt::Display for DrivesError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Couldn't list the drives of this machine: {}.", self.0)
+    }
+}
+
+#[cfg(windows)]
+mod ffi {
+    pub const DRIVE_FIXED: u32 = 3;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn GetLogicalDrives() -> u32;
+        pub fn GetDriveTypeW(root: *const u16) -> u32;
+    }
+}
+
+/// Roots of the fixed (non removable, non network) drives of the machine, E.G: `C:\` and `D:\`.
+#[cfg(windows)]
+pub fn fixed_drives() -> Result<Vec<PathBuf>, DrivesError> {
+    // SAFETY: `GetLogicalDrives` takes no arguments and only returns a bit mask.
+    let mask = unsafe { ffi::GetLogicalDrives() };
+    if mask == 0 {
+        return Err(DrivesError("GetLogicalDrives failed"));
+    }
+    let mut drives = vec![];
+    for (i, letter) in ('A'..='Z').enumerate() {
+        if mask & (1 << i) == 0 {
+            continue;
+        }
+        let root = format!("{}:\\", letter);
+        let wide: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+        // SAFETY: `wide` is a NUL terminated UTF-16 string that outlives the call.
+        if unsafe { ffi::GetDriveTypeW(wide.as_ptr()) } == ffi::DRIVE_FIXED {
+            drives.push(PathBuf::from(root));
+        }
+    }
+    Ok(drives)
+}
+
+/// Roots of the fixed (non removable, non network) drives of the machine, E.G: `C:\` and `D:\`.
+#[cfg(not(windows))]
+pub fn fixed_drives() -> Result<Vec<PathBuf>, DrivesError> {
+    Err(DrivesError("`--all-drives` is only available on Windows"))
+}
+
+/// System-managed directories of the drive whose root is `drive`.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::drives;
+/// # use std::path::Path;
+/// let protected = drives::protected_dirs(Path::new("C:\\"));
+/// assert!(protected.contains(&Path::new("C:\\").join("Windows")));
+/// ```
+pub fn protected_dirs(drive: &Path) -> Vec<PathBuf> {
+    PROTECTED.iter().map(|name| drive.join(name)).collect()
+}
@@ -0,0 +1,78 @@
+//! Deletion with elevated privileges of the files the user isn't allowed to delete.
+//!
+//! It is only used once a deletion failed for lack of permissions and the user agreed to retry it: the helper
+//! (`sudo` on Unix, a UAC prompt on Windows) is handed the paths that were already confirmed and nothing else,
+//! and never goes through a shell.
+
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// How the privileges are requested, as told to the user.
+#[cfg(unix)]
+pub const METHOD: &str = "sudo";
+/// How the privileges are requested, as told to the user.
+#[cfg(windows)]
+pub const METHOD: &str = "UAC";
+/// How the privileges are requested, as told to the user.
+#[cfg(not(any(unix, windows)))]
+pub const METHOD: &str = "unavailable";
+
+/// Deletes the files located at `paths` with elevated privileges, failing unless every one of them is gone.
+pub fn remove_files(paths: &[&Path]) -> Result<(), io::Error> {
+    let mut command = command(paths)?;
+    let status = command.status()?;
+    let remaining: Vec<String> = paths.iter().filter(|p| p.exists()).map(|p| p.display().to_string()).collect();
+    if !remaining.is_empty() {
+        let message = format!("{} exited with {} without deleting {}", METHOD, status, remaining.join(", "));
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, message));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn command(paths: &[&Path]) -> Result<Command, io::Error> {
+    let mut command = Command::new("sudo");
+    command.args(["--", "rm", "-f", "--"]).args(paths);
+    Ok(command)
+}
+
+#[cfg(windows)]
+fn command(paths: &[&Path]) -> Result<Command, io::Error> {
+    // The elevated PowerShell receives the paths as single quoted literals, with their quotes doubled.
+    let literals: Vec<String> =
+        paths.iter().map(|p| format!("'{}'", p.display().to_string().replace('\'', "''"))).collect();
+    let removal = format!("Remove-Item -Force -LiteralPath {}", literals.join(","));
+    let arguments = format!("'-NoProfile','-Command','{}'", removal.replace('\'', "''"));
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-Command"]).arg(format!(
+        "Start-Process powershell -Verb RunAs -Wait -WindowStyle Hidden -ArgumentList {}",
+        arguments
+    ));
+    Ok(command)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn command(_paths: &[&Path]) -> Result<Command, io::Error> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "elevated deletions aren't available on this platform"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn sudo_only_receives_the_confirmed_paths() {
+        // Arrange
+        let paths = [Path::new("/srv/a.txt"), Path::new("-rf")];
+
+        // Act
+        let command = command(&paths).unwrap();
+
+        // Assert
+        assert_eq!(command.get_program(), "sudo");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["--", "rm", "-f", "--", "/srv/a.txt", "-rf"]);
+    }
+}
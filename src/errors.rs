@@ -0,0 +1,220 @@
+//! Errors aborting a run, as told to the wrappers of dir_cleaner. Along with `--output json`, they're written on
+//! the standard error as a JSON object (`{"error": "<code>", "message": "…"}`) instead of a sentence, the code
+//! telling what failed whatever the wording of the message (see [`code`]). The other outputs keep the sentence.
+//!
+//! The codes are stable: new ones may show up, but the existing ones keep their meaning. Only the errors raised
+//! as a plain sentence get the generic `error` one.
+//!
+//! The failures which don't abort the run (E.G: a file that couldn't be read, and so wasn't compared) go through
+//! [`report`] instead, which follows the same format and adds the `path` of the entry at fault.
+
+use crate::output::Output;
+use serde::Serialize;
+use std::error::Error;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// Format the failures which don't abort the run are reported in (see [`report_as`]).
+static REPORTED_AS: Mutex<Output> = Mutex::new(Output::Text);
+
+/// An error, as written by the JSON output.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Failure {
+    #[serde(rename = "error")]
+    pub code: &'static str,
+    /// Entry at fault, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Sentence describing the error.
+    pub message: String,
+}
+
+impl Failure {
+    pub fn new(err: &(dyn Error + 'static)) -> Self {
+        Failure { code: code(err), path: None, message: err.to_string() }
+    }
+}
+
+/// Stable code of `err` (E.G: `unknown-argument` or `not-found`).
+/// ## Examples
+/// ```
+/// # use dir_cleaner::cli::{Command, UnknownArgError};
+/// # use dir_cleaner::errors::code;
+/// let unknown = UnknownArgError { command: Command::Scan, argument: String::from("--nmae") };
+/// assert_eq!(code(&unknown), "unknown-argument");
+/// assert_eq!(code(&std::io::Error::from(std::io::ErrorKind::PermissionDenied)), "permission-denied");
+/// ```
+pub fn code(err: &(dyn Error + 'static)) -> &'static str {
+    if let Some(err) = err.downcast_ref::<io::Error>() {
+        return match err.kind() {
+            io::ErrorKind::NotFound => "not-found",
+            io::ErrorKind::PermissionDenied => "permission-denied",
+            io::ErrorKind::AlreadyExists => "already-exists",
+            io::ErrorKind::Interrupted => "interrupted",
+            _ => "io",
+        };
+    }
+    #[cfg(feature = "video")]
+    if err.is::<crate::video::VideoError>() {
+        return "video";
+    }
+    if let Some(err) = err.downcast_ref::<crate::schema::DocumentError>() {
+        return match err.failed {
+            crate::schema::Failed::Parse if err.source.is::<crate::schema::SchemaError>() => "newer-schema",
+            crate::schema::Failed::Read => "unreadable-document",
+            crate::schema::Failed::Parse => "invalid-document",
+            crate::schema::Failed::Save => "unsaved-document",
+            crate::schema::Failed::Download => "failed-download",
+        };
+    }
+    if let Some(err) = err.downcast_ref::<crate::cache::CacheError>() {
+        return match err {
+            crate::cache::CacheError::Newer { .. } => "newer-schema",
+            crate::cache::CacheError::Corrupt { .. } => "corrupt-cache",
+            crate::cache::CacheError::Unsaved(_) => "unsaved-cache",
+        };
+    }
+    #[cfg(feature = "documents")]
+    if err.is::<crate::documents::TextError>() {
+        return "unextractable-text";
+    }
+    #[cfg(feature = "tui")]
+    if err.is::<crate::tui::keys::KeyConfigError>() {
+        return "invalid-keys";
+    }
+    let codes = [
+        (err.is::<crate::cli::MissingArgError>(), "missing-arguments"),
+        (err.is::<crate::cli::UnexpectedArgError>(), "unexpected-argument"),
+        (err.is::<crate::cli::InapplicableArgError>(), "inapplicable-argument"),
+        (err.is::<crate::cli::ConflictError>(), "conflicting-options"),
+        (err.is::<crate::cli::UnknownArgError>(), "unknown-argument"),
+        (err.is::<crate::cli::MissingValueError>(), "missing-value"),
+        (err.is::<crate::cli::InvalidValueError>(), "invalid-value"),
+        (err.is::<crate::cli::MissingFeatureError>(), "missing-feature"),
+        (err.is::<crate::units::UnitError>(), "invalid-unit"),
+        (err.is::<crate::glob::GlobError>(), "invalid-glob"),
+        (err.is::<crate::regex::RegexError>(), "invalid-regex"),
+        (err.is::<crate::extensions::ExtensionError>(), "invalid-extensions"),
+        (err.is::<crate::mime::TypeError>(), "invalid-type"),
+        (err.is::<crate::output::OutputError>(), "invalid-output"),
+        (err.is::<crate::output::DateFormatError>(), "invalid-date-format"),
+        (err.is::<crate::report::FormatError>(), "invalid-report-format"),
+        (err.is::<crate::query::QueryError>(), "invalid-query"),
+        (err.is::<crate::prompt::UnknownAnswerError>(), "invalid-answer"),
+        (err.is::<crate::keep::StrategyError>(), "invalid-strategy"),
+        (err.is::<crate::hash::AlgorithmError>(), "invalid-algorithm"),
+        (err.is::<crate::storage::StorageError>(), "invalid-storage"),
+        (err.is::<crate::theme::ThemeError>(), "invalid-theme"),
+        (err.is::<crate::schema::SchemaError>(), "newer-schema"),
+        (err.is::<crate::containment::ContainmentError>(), "outside-root"),
+        (err.is::<crate::ownership::OwnershipError>(), "unsupported-ownership"),
+        (err.is::<crate::drives::DrivesError>(), "unavailable-drives"),
+        (err.is::<crate::clipboard::ClipboardError>(), "unavailable-clipboard"),
+        (err.is::<crate::manifest::CurlError>(), "missing-curl"),
+        (err.is::<crate::verification::LastCopyError>(), "last-copy"),
+        (err.is::<crate::verification::KeptError>(), "kept-files"),
+        (err.is::<serde_json::Error>(), "invalid-json"),
+    ];
+    codes.iter().find(|(is, _)| *is).map_or("error", |(_, code)| code)
+}
+
+/// Writes `err` to `out` following the format of `output`: a JSON object on a line of its own, or the sentence
+/// describing it.
+pub fn write(out: &mut impl Write, err: &(dyn Error + 'static), output: Output) -> Result<(), io::Error> {
+    match output {
+        Output::Json => writeln!(out, "{}", serde_json::to_string(&Failure::new(err))?),
+        Output::Text | Output::Csv => writeln!(out, "{}", err),
+    }
+}
+
+/// Sets the format of the failures reported from now on (the sentences of the text output by default).
+pub fn report_as(output: Output) {
+    *REPORTED_AS.lock().unwrap() = output;
+}
+
+/// Reports on the standard error a failure which doesn't abort the run: `err`, described by `message` and raised
+/// by the entry located at `path` (if any).
+pub fn report(err: &(dyn Error + 'static), path: Option<&str>, message: String) {
+    let failure = Failure { code: code(err), path: path.map(str::to_string), message };
+    let output = *REPORTED_AS.lock().unwrap();
+    let _ = write_failure(&mut io::stderr().lock(), &failure, output);
+}
+
+fn write_failure(out: &mut impl Write, failure: &Failure, output: Output) -> Result<(), io::Error> {
+    match output {
+        Output::Json => writeln!(out, "{}", serde_json::to_string(failure)?),
+        Output::Text | Output::Csv => writeln!(out, "{}", failure.message),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::{DocumentError, Failed};
+
+    #[test]
+    fn json_errors_carry_their_code() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let errors: Vec<Box<dyn Error>> = vec![
+            Box::new(crate::cli::MissingArgError { command: crate::cli::Command::Scan, argument: "<dir>" }),
+            Box::new(io::Error::from(io::ErrorKind::NotFound)),
+            "Something else went wrong.".into(),
+        ];
+        let (mut json, mut text) = (vec![], vec![]);
+
+        // Act
+        for err in &errors {
+            write(&mut json, err.as_ref(), Output::Json)?;
+        }
+        write(&mut text, errors[2].as_ref(), Output::Text)?;
+
+        // Assert
+        let lines: Vec<serde_json::Value> =
+            String::from_utf8(json)?.lines().map(serde_json::from_str).collect::<Result<_, _>>()?;
+        let codes: Vec<&str> = lines.iter().filter_map(|line| line["error"].as_str()).collect();
+        assert_eq!(codes, vec!["missing-arguments", "not-found", "error"]);
+        assert_eq!(lines[2]["message"], "Something else went wrong.");
+        assert_eq!(String::from_utf8(text)?, "Something else went wrong.\n");
+        Ok(())
+    }
+
+    #[test]
+    fn reported_failures_carry_their_path() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        let message = String::from("Couldn't read `./a.txt`: permission denied");
+        let failure = Failure { code: code(&denied), path: Some(String::from("./a.txt")), message };
+        let (mut json, mut text) = (vec![], vec![]);
+
+        // Act
+        write_failure(&mut json, &failure, Output::Json)?;
+        write_failure(&mut text, &failure, Output::Text)?;
+
+        // Assert
+        let line: serde_json::Value = serde_json::from_slice(&json)?;
+        assert_eq!(line["error"], "permission-denied");
+        assert_eq!(line["path"], "./a.txt");
+        assert_eq!(String::from_utf8(text)?, "Couldn't read `./a.txt`: permission denied\n");
+        Ok(())
+    }
+
+    #[test]
+    fn document_errors_tell_what_failed() {
+        // Arrange
+        let newer = crate::schema::SchemaError { kind: "session", found: 9, supported: 1 };
+
+        // Act
+        let codes: Vec<&str> = [
+            DocumentError::new("session", "a.dcs", Failed::Read, io::Error::from(io::ErrorKind::NotFound)),
+            DocumentError::new("session", "a.dcs", Failed::Parse, newer),
+            DocumentError::new("session", "a.dcs", Failed::Parse, "expected value"),
+            DocumentError::new("session", "a.dcs", Failed::Save, io::Error::from(io::ErrorKind::PermissionDenied)),
+        ]
+        .iter()
+        .map(|err| code(err))
+        .collect();
+
+        // Assert
+        assert_eq!(codes, vec!["unreadable-document", "newer-schema", "invalid-document", "unsaved-document"]);
+    }
+}
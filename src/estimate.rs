@@ -0,0 +1,177 @@
+//! Fast pre-pass (`--estimate`) counting the files and bytes stored under the inspected directory, so the
+//! user knows how long the full scan would take before starting it.
+//!
+//! The pre-pass only reads metadata. The time needed to read the contents of the files (which the content
+//! comparisons do) is extrapolated from the throughput measured while reading a small sample of them.
+
+use crate::limits::Limits;
+use crate::repos;
+use crate::units::{format_duration, format_size};
+use crate::vfs::{Kind, Vfs};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Amount of files whose beginning is read in order to measure the throughput.
+const SAMPLE_FILES: usize = 16;
+/// Bytes read from every sampled file.
+const SAMPLE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// What the pre-pass found.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Estimate {
+    pub dirs: u64,
+    pub files: u64,
+    pub bytes: u64,
+    /// Time spent by the pre-pass itself, roughly what listing the files takes.
+    pub elapsed: Duration,
+    /// Bytes per second read while sampling (`None` when nothing could be sampled).
+    pub throughput: Option<f64>,
+}
+
+impl Estimate {
+    /// Time needed to read the contents of every file at the measured throughput.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::estimate::Estimate;
+    /// # use std::time::Duration;
+    /// let estimate = Estimate {
+    ///     dirs: 10,
+    ///     files: 100,
+    ///     bytes: 200 * 1024 * 1024,
+    ///     elapsed: Duration::from_millis(20),
+    ///     throughput: Some(100.0 * 1024.0 * 1024.0),
+    /// };
+    /// assert_eq!(estimate.content_duration(), Some(Duration::from_secs(2)));
+    /// ```
+    pub fn content_duration(&self) -> Option<Duration> {
+        let throughput = self.throughput.filter(|t| *t > 0.0)?;
+        Some(Duration::from_secs_f64(self.bytes as f64 / throughput))
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} file(s) ({}) across {} folder(s). Listing them takes about {}",
+            self.files,
+            format_size(self.bytes),
+            self.dirs,
+            format_duration(self.elapsed)
+        );
+        match (self.content_duration(), self.throughput) {
+            (Some(duration), Some(throughput)) => summary.push_str(&format!(
+                ", reading their contents about {} (at {}/s).",
+                format_duration(duration),
+                format_size(throughput as u64)
+            )),
+            _ => summary.push('.'),
+        }
+        summary
+    }
+}
+
+/// Quick estimate of the size of `dir`, listed through `vfs`: the bytes stored directly on it (its subdirectories
+/// aren't counted).
+pub fn direct_bytes(vfs: &dyn Vfs, dir: &Path) -> u64 {
+    let entries = match vfs.read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+    entries
+        .filter(|entry| entry.kind == Kind::File)
+        .filter_map(|entry| entry.meta.or_else(|| vfs.symlink_metadata(&entry.path).ok()))
+        .map(|meta| meta.len)
+        .sum()
+}
+
+/// Counts the directories, files and bytes stored under `root` (leaving out the directories exceeding
+/// `limits`, as the scan does), then samples the read throughput.
+pub fn measure(root: &str, limits: &Limits) -> Result<Estimate, io::Error> {
+    let start = Instant::now();
+    let mut estimate = Estimate { dirs: 0, files: 0, bytes: 0, elapsed: Duration::ZERO, throughput: None };
+    let mut samples: Vec<PathBuf> = vec![];
+    let mut pending = vec![PathBuf::from(root)];
+    while let Some(dir) = pending.pop() {
+        if limits.is_protected(&dir) || (limits.skip_repos && repos::is_repository(&dir)) {
+            continue;
+        }
+        let entries: Vec<fs::DirEntry> = match fs::read_dir(&dir) {
+            Ok(entries) => entries.flatten().collect(),
+            Err(err) if dir.as_os_str() == root => return Err(err),
+            Err(_) => continue,
+        };
+        if limits.max_entries.is_some_and(|max| entries.len() > max) {
+            continue;
+        }
+        let (mut files, mut bytes) = (0, 0);
+        let mut dir_samples = vec![];
+        for entry in entries {
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(_) => continue,
+            };
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                files += 1;
+                bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                if samples.len() + dir_samples.len() < SAMPLE_FILES {
+                    dir_samples.push(entry.path());
+                }
+            }
+        }
+        if limits.max_bytes.is_some_and(|max| bytes > max) {
+            continue;
+        }
+        estimate.dirs += 1;
+        estimate.files += files;
+        estimate.bytes += bytes;
+        samples.append(&mut dir_samples);
+    }
+    estimate.elapsed = start.elapsed();
+    estimate.throughput = sample_throughput(&samples);
+    Ok(estimate)
+}
+
+fn sample_throughput(samples: &[PathBuf]) -> Option<f64> {
+    let start = Instant::now();
+    let mut read = 0;
+    let mut buffer = vec![];
+    for path in samples {
+        buffer.clear();
+        if let Ok(file) = fs::File::open(path) {
+            read += file.take(SAMPLE_BYTES).read_to_end(&mut buffer).unwrap_or(0) as u64;
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    (read > 0 && elapsed > 0.0).then(|| read as f64 / elapsed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn measure_tree() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("estimate");
+        fs::create_dir_all(root.join("a/b"))?;
+        fs::create_dir_all(root.join("crowded"))?;
+        fs::write(root.join("x.txt"), vec![0; 1000])?;
+        fs::write(root.join("a/b/y.txt"), vec![0; 24])?;
+        for i in 0..5 {
+            fs::write(root.join("crowded").join(i.to_string()), b"")?;
+        }
+        let limits = Limits { max_entries: Some(4), ..Limits::default() };
+
+        // Act
+        let estimate = measure(root.to_str().unwrap(), &limits)?;
+
+        // Assert
+        assert_eq!((estimate.dirs, estimate.files, estimate.bytes), (3, 2, 1024));
+        assert!(estimate.throughput.is_some());
+        assert!(measure("./does-not-exist", &limits).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Paths left out of the scans (`--exclude <glob>`, repeatable), E.G: `**/node_modules/**` or `*.tmp`. The
+//! excluded directories are never listed, sparing the reads of everything stored under them.
+//!
+//! A pattern without any `/` is matched against the names of the entries, and a pattern holding one against their
+//! path relative to the root of the scan (`/` separated on every platform), where `*` matches across the
+//! separators as well (see [`glob`](crate::glob)). A directory is excluded when its path followed by a `/` matches
+//! too, so `build/**` leaves out the `build` directory itself, and a leading `**/` matches at the root as well.
+
+use crate::glob::{GlobError, Pattern};
+use std::path::{Component, Path};
+
+#[derive(Debug, PartialEq, Clone)]
+struct Exclusion {
+    pattern: Pattern,
+    /// Whether the pattern is matched against the relative path rather than the name.
+    by_path: bool,
+    /// Whether the pattern starts with `**/`.
+    at_any_depth: bool,
+}
+
+/// Patterns of the excluded entries.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::exclude::Exclusions;
+/// let mut exclusions = Exclusions::default();
+/// exclusions.add("**/node_modules/**").unwrap();
+/// exclusions.add("*.tmp").unwrap();
+/// assert!(exclusions.excludes("web/node_modules", true));
+/// assert!(exclusions.excludes("web/draft.tmp", false));
+/// assert!(!exclusions.excludes("web/index.html", false));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Exclusions {
+    exclusions: Vec<Exclusion>,
+}
+
+impl Exclusions {
+    /// Excludes the entries matching `pattern` as well.
+    pub fn add(&mut self, pattern: &str) -> Result<(), GlobError> {
+        self.exclusions.push(Exclusion {
+            pattern: pattern.parse()?,
+            by_path: pattern.contains('/'),
+            at_any_depth: pattern.starts_with("**/"),
+        });
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exclusions.is_empty()
+    }
+
+    /// Whether the entry located at `relative` (`/` separated, relative to the root of the scan) is excluded,
+    /// `dir` telling whether it's a directory.
+    pub fn excludes(&self, relative: &str, dir: bool) -> bool {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        self.exclusions.iter().any(|exclusion| {
+            if !exclusion.by_path {
+                return exclusion.pattern.matches(name);
+            }
+            let paths = [Some(relative.to_string()), dir.then(|| format!("{}/", relative))];
+            let matches = |path: &String| match exclusion.at_any_depth {
+                true => exclusion.pattern.matches(path) || exclusion.pattern.matches(&format!("/{}", path)),
+                false => exclusion.pattern.matches(path),
+            };
+            paths.iter().flatten().any(matches)
+        })
+    }
+}
+
+/// Path of `entry` relative to `root`, `/` separated.
+pub(crate) fn relative(root: &Path, entry: &Path) -> String {
+    let components = entry.strip_prefix(root).unwrap_or(entry).components();
+    let names: Vec<String> = components
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    names.join("/")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::get_dir_files_limited;
+    use crate::limits::Limits;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn excluded_entries_are_left_out_of_the_walk() -> Result<(), Box<dyn std::error::Error>> {
+        // Arrange
+        let root = Scratch::new("exclude");
+        fs::create_dir_all(root.join("node_modules").join("pkg"))?;
+        fs::create_dir_all(root.join("web").join("node_modules"))?;
+        fs::create_dir_all(root.join("build"))?;
+        let stored = ["a.txt", "b.tmp", "node_modules/pkg/a.txt", "web/a.txt", "web/node_modules/a.txt", "build/a.txt"];
+        for file in stored {
+            fs::write(root.join(file), "same")?;
+        }
+        let mut limits = Limits::default();
+        for pattern in ["**/node_modules/**", "*.tmp", "build/**"] {
+            limits.exclude.add(pattern)?;
+        }
+
+        // Act
+        let (files, skipped) = get_dir_files_limited(root.to_str().unwrap(), &|_| true, &limits)?;
+
+        // Assert
+        let found: Vec<String> = files.iter().map(|file| relative(&root, Path::new(&*file.path()))).collect();
+        assert_eq!(found, vec!["a.txt", "web/a.txt"]);
+        assert!(skipped.is_empty());
+        assert!(!limits.exclude.excludes("build", false));
+        assert!(limits.exclude.excludes("build", true));
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+//! Sets of file extensions (`--ext jpg,png,gif`, repeatable), so only the files bearing one of them are
+//! considered. They're checked along with the name, `--glob` or `--regex` looked for (both must match), whatever
+//! their case, and a listed extension may span several dots (E.G: `tar.gz`).
+//!
+//! Library users can hand them to [`Scanner::with_filter`](crate::scanner::Scanner::with_filter), as a
+//! [`Filter`].
+
+use crate::scanner::Filter;
+use crate::File;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// Returned when a list of extensions holds an empty one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionError(pub String);
+
+impl Error for ExtensionError {}
+
+impl fmt::Display for ExtensionError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid extensions `{}` (expected a comma separated list, E.G: jpg,png,gif).", self.0)
+    }
+}
+
+/// Extensions the considered files bear one of (any file when empty).
+/// ## Examples
+/// ```
+/// # use dir_cleaner::extensions::Extensions;
+/// let extensions: Extensions = "jpg, .PNG,tar.gz".parse().unwrap();
+/// assert!(extensions.allows("IMG_0001.JPG"));
+/// assert!(extensions.allows("backup.tar.gz"));
+/// assert!(!extensions.allows("notes.txt"));
+/// assert!(!extensions.allows("png"));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Extensions {
+    /// Lowercase, without their leading dot.
+    extensions: BTreeSet<String>,
+}
+
+impl FromStr for Extensions {
+    type Err = ExtensionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut extensions = Extensions::default();
+        extensions.add(s)?;
+        Ok(extensions)
+    }
+}
+
+impl Extensions {
+    /// Considers the files bearing one of the comma separated extensions of `list` as well.
+    pub fn add(&mut self, list: &str) -> Result<(), ExtensionError> {
+        for extension in list.split(',').map(|extension| extension.trim().trim_start_matches('.')) {
+            if extension.is_empty() {
+                return Err(ExtensionError(list.to_string()));
+            }
+            self.extensions.insert(extension.to_lowercase());
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    /// Whether the file named `name` bears one of the extensions.
+    pub fn allows(&self, name: &str) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let name = name.to_lowercase();
+        self.extensions.iter().any(|extension| {
+            name.strip_suffix(extension.as_str())
+                .and_then(|stem| stem.strip_suffix('.'))
+                .is_some_and(|stem| !stem.is_empty())
+        })
+    }
+}
+
+impl Filter for Extensions {
+    fn keeps(&self, file: &File) -> bool {
+        self.allows(&file.name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scanner::Scanner;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn only_the_listed_extensions_are_kept() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("extensions");
+        for file in ["a.jpg", "b.PNG", "c.gif", "d.txt", ".jpg"] {
+            fs::write(root.join(file), "same")?;
+        }
+        let mut extensions: Extensions = "jpg,png".parse()?;
+        extensions.add("gif")?;
+
+        // Act
+        let scanner = Scanner::new(root.to_str().unwrap()).matching(|name| name != "c.gif");
+        let scan = scanner.with_filter(extensions).scan()?;
+
+        // Assert
+        let mut names: Vec<&str> = scan.files.iter().map(|file| &*file.name).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["a.jpg", "b.PNG"]);
+        assert!("jpg,,png".parse::<Extensions>().is_err());
+        assert!(Extensions::default().allows("anything"));
+        Ok(())
+    }
+}
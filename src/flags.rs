@@ -0,0 +1,114 @@
+//! Detection of the file flags that prevent the deletion of a file, even by its owner.
+//!
+//! The immutable and append-only flags (`chattr +i`/`+a` on Linux, `chflags uchg`/`uappnd` on macOS) block the
+//! removal of the file they're set on, as well as the removal of the entries of a directory they're set on. The
+//! read-only attribute does the same on Windows. Such files are pointed out before deleting anything, along
+//! with the flag to clear.
+
+use std::path::Path;
+
+/// Why the file located at `path` can't be deleted, when one of its flags (or of its directory) prevents it.
+pub fn removal_blocker(path: &Path) -> Option<String> {
+    if let Some(flag) = platform::blocking_flag(path) {
+        return Some(format!("it has the {} flag", flag));
+    }
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty())?;
+    platform::blocking_flag(parent).map(|flag| format!("its directory has the {} flag", flag))
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs::File;
+    use std::os::raw::{c_int, c_ulong};
+    use std::os::unix::io::AsRawFd;
+    use std::path::Path;
+
+    /// `_IOR('f', 1, long)`.
+    const FS_IOC_GETFLAGS: c_ulong = if cfg!(target_pointer_width = "64") { 0x80086601 } else { 0x80046601 };
+    const FS_IMMUTABLE_FL: c_int = 0x10;
+    const FS_APPEND_FL: c_int = 0x20;
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    pub fn blocking_flag(path: &Path) -> Option<&'static str> {
+        let file = File::open(path).ok()?;
+        let mut flags: c_int = 0;
+        // SAFETY: the descriptor is open for the duration of the call and `flags` outlives it.
+        if unsafe { ioctl(file.as_raw_fd(), FS_IOC_GETFLAGS, &mut flags as *mut c_int) } != 0 {
+            return None;
+        }
+        match flags {
+            _ if flags & FS_IMMUTABLE_FL != 0 => Some("immutable (chattr -i clears it)"),
+            _ if flags & FS_APPEND_FL != 0 => Some("append-only (chattr -a clears it)"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::os::macos::fs::MetadataExt;
+    use std::path::Path;
+
+    const UF_IMMUTABLE: u32 = 0x2;
+    const UF_APPEND: u32 = 0x4;
+    const SF_IMMUTABLE: u32 = 0x20000;
+    const SF_APPEND: u32 = 0x40000;
+
+    pub fn blocking_flag(path: &Path) -> Option<&'static str> {
+        let flags = std::fs::symlink_metadata(path).ok()?.st_flags();
+        match flags {
+            _ if flags & UF_IMMUTABLE != 0 => Some("immutable (chflags nouchg clears it)"),
+            _ if flags & UF_APPEND != 0 => Some("append-only (chflags nouappnd clears it)"),
+            _ if flags & SF_IMMUTABLE != 0 => Some("system immutable (sudo chflags noschg clears it)"),
+            _ if flags & SF_APPEND != 0 => Some("system append-only (sudo chflags nosappnd clears it)"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::os::windows::fs::MetadataExt;
+    use std::path::Path;
+
+    const FILE_ATTRIBUTE_READONLY: u32 = 0x1;
+
+    pub fn blocking_flag(path: &Path) -> Option<&'static str> {
+        let metadata = std::fs::symlink_metadata(path).ok()?;
+        // The read-only attribute of a directory is only a hint for the shell, it doesn't protect its entries.
+        let read_only = metadata.is_file() && metadata.file_attributes() & FILE_ATTRIBUTE_READONLY != 0;
+        read_only.then_some("read-only (attrib -r clears it)")
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use std::path::Path;
+
+    pub fn blocking_flag(_path: &Path) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn plain_files_can_be_deleted() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("flags");
+        let path = root.join("a.txt");
+        fs::write(&path, "a")?;
+
+        // Act and Assert
+        assert_eq!(removal_blocker(&path), None);
+        assert_eq!(removal_blocker(&root.join("missing.txt")), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+//! Ignore files honored by the scans with `--respect-gitignore`, so the build artifacts and the other files a
+//! source tree ignores aren't considered: the `.gitignore` and `.ignore` files found on the scanned directories
+//! (the latter win), the `.git/info/exclude` file of the repositories, and the global excludes of git
+//! (`core.excludesFile`, or `~/.config/git/ignore` by default). The `.git` directories are left out as well, and
+//! the ignored directories aren't even listed.
+//!
+//! The patterns follow the syntax of git: `#` starts a comment, `!` includes again what an earlier pattern
+//! ignored, a trailing `/` only matches directories, a pattern holding another `/` is relative to the directory of
+//! its file (otherwise it matches at any depth), `**` matches any amount of directories, and the rest of the
+//! segments are [`glob`](crate::glob) patterns. The deepest files win over the ones of their parents.
+
+use crate::glob::Pattern;
+use crate::vfs::Vfs;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Ignore files read on every directory, the last ones winning.
+const IGNORE_FILES: [&str; 3] = [".git/info/exclude", ".gitignore", ".ignore"];
+
+#[derive(Debug, PartialEq, Clone)]
+enum Segment {
+    /// `**`, any amount of directories.
+    AnyDepth,
+    Name(Pattern),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Rule {
+    segments: Vec<Segment>,
+    /// Whether the pattern only matches directories (trailing `/`).
+    dir_only: bool,
+    /// Whether the pattern includes again what it matches (leading `!`).
+    negated: bool,
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(line) => (true, line),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        if line.is_empty() {
+            return None;
+        }
+        let mut segments = match anchored {
+            true => vec![],
+            false => vec![Segment::AnyDepth],
+        };
+        for segment in line.split('/') {
+            segments.push(match segment {
+                "**" => Segment::AnyDepth,
+                segment => Segment::Name(segment.parse().ok()?),
+            });
+        }
+        Some(Rule { segments, dir_only, negated })
+    }
+}
+
+/// Whether the `segments` of a pattern match the components of a `path`.
+fn matches(segments: &[Segment], path: &[String]) -> bool {
+    match segments.split_first() {
+        None => path.is_empty(),
+        Some((Segment::AnyDepth, rest)) => (0..=path.len()).any(|skipped| matches(rest, &path[skipped..])),
+        Some((Segment::Name(pattern), rest)) => {
+            path.split_first().is_some_and(|(first, others)| pattern.matches(first) && matches(rest, others))
+        }
+    }
+}
+
+/// Patterns of one or several ignore files, in the order they were read.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::gitignore::Rules;
+/// let rules = Rules::parse("target/\n*.o\n!keep.o\n/notes.txt");
+/// assert_eq!(rules.decide(&["target"], true), Some(true));
+/// assert_eq!(rules.decide(&["src", "main.o"], false), Some(true));
+/// assert_eq!(rules.decide(&["src", "keep.o"], false), Some(false));
+/// assert_eq!(rules.decide(&["src", "notes.txt"], false), None);
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+impl Rules {
+    /// Parses the content of an ignore file (the invalid patterns are left out, as git does).
+    pub fn parse(content: &str) -> Self {
+        Rules { rules: content.lines().filter_map(Rule::parse).collect() }
+    }
+
+    fn extend(&mut self, rules: Rules) {
+        self.rules.extend(rules.rules);
+    }
+
+    /// Whether the entry located at `path` (relative to the directory of the rules, component by component) is
+    /// ignored, `dir` telling whether it's a directory, or `None` when no pattern matches it.
+    pub fn decide<S: AsRef<str>>(&self, path: &[S], dir: bool) -> Option<bool> {
+        let path: Vec<String> = path.iter().map(|component| component.as_ref().to_string()).collect();
+        let rule = self.rules.iter().rev().find(|rule| (dir || !rule.dir_only) && matches(&rule.segments, &path));
+        rule.map(|rule| !rule.negated)
+    }
+}
+
+/// Ignore files met by a scan, read once per directory.
+pub struct Ignores<'a> {
+    vfs: &'a dyn Vfs,
+    root: PathBuf,
+    global: Rules,
+    dirs: HashMap<PathBuf, Rules>,
+}
+
+impl<'a> Ignores<'a> {
+    /// Honors the ignore files found on `root` (and its subdirectories) through `vfs`, along with the global
+    /// excludes of git.
+    pub fn new(vfs: &'a dyn Vfs, root: &Path) -> Self {
+        let global = global_excludes().and_then(|path| fs::read_to_string(path).ok()).unwrap_or_default();
+        Ignores { vfs, root: root.to_path_buf(), global: Rules::parse(&global), dirs: HashMap::new() }
+    }
+
+    /// Whether the entry located at `path` is ignored, `dir` telling whether it's a directory.
+    pub fn ignores(&mut self, path: &Path, dir: bool) -> bool {
+        if dir && path.file_name().is_some_and(|name| name == ".git") {
+            return true;
+        }
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let components: Vec<String> =
+            relative.components().map(|component| component.as_os_str().to_string_lossy().to_string()).collect();
+        let mut ignored = self.global.decide(&components, dir).unwrap_or(false);
+        let mut parent = self.root.clone();
+        for depth in 0..components.len() {
+            if depth > 0 {
+                parent.push(&components[depth - 1]);
+            }
+            if let Some(decision) = self.rules(&parent).decide(&components[depth..], dir) {
+                ignored = decision;
+            }
+        }
+        ignored
+    }
+
+    /// Rules of the ignore files stored on `dir`.
+    fn rules(&mut self, dir: &Path) -> &Rules {
+        let vfs = self.vfs;
+        self.dirs.entry(dir.to_path_buf()).or_insert_with(|| {
+            let mut rules = Rules::default();
+            for file in IGNORE_FILES {
+                let mut content = String::new();
+                if vfs.open(&dir.join(file)).and_then(|mut read| read.read_to_string(&mut content)).is_ok() {
+                    rules.extend(Rules::parse(&content));
+                }
+            }
+            rules
+        })
+    }
+}
+
+/// Location of the global excludes of git.
+fn global_excludes() -> Option<PathBuf> {
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")).map(PathBuf::from);
+    let configured = home.as_ref().and_then(|home| fs::read_to_string(home.join(".gitconfig")).ok());
+    if let Some(path) = configured.as_deref().and_then(excludes_file) {
+        return match (path.strip_prefix("~/"), &home) {
+            (Some(path), Some(home)) => Some(home.join(path)),
+            _ => Some(PathBuf::from(path)),
+        };
+    }
+    let config = env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).or_else(|| Some(home?.join(".config")))?;
+    Some(config.join("git").join("ignore"))
+}
+
+/// Value of `core.excludesFile` on the git configuration stored on `config`.
+fn excludes_file(config: &str) -> Option<&str> {
+    let mut core = false;
+    for line in config.lines().map(str::trim) {
+        if let Some(section) = line.strip_prefix('[') {
+            core = section.trim_end_matches(']').trim().eq_ignore_ascii_case("core");
+            continue;
+        }
+        match line.split_once('=') {
+            Some((key, value)) if core && key.trim().eq_ignore_ascii_case("excludesfile") => {
+                return Some(value.trim().trim_matches('"'));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::get_dir_files_limited;
+    use crate::limits::Limits;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn ignored_entries_are_left_out_of_the_walk() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("gitignore");
+        for dir in ["target", "src/generated", ".git/info"] {
+            fs::create_dir_all(root.join(dir))?;
+        }
+        fs::write(root.join(".gitignore"), "# Build outputs\ntarget/\n*.o\n!keep.o\n/notes.txt\n")?;
+        fs::write(root.join(".ignore"), "secret.txt\n")?;
+        fs::write(root.join(".git/info/exclude"), "local.txt\n")?;
+        fs::write(root.join("src/.gitignore"), "generated/**\n!secret.txt\n")?;
+        let stored = ["notes.txt", "local.txt", "target/a.o", "src/a.o", "src/keep.o", "src/notes.txt"];
+        for file in stored.iter().chain(&["src/secret.txt", "src/generated/a.rs", "secret.txt", ".git/HEAD"]) {
+            fs::write(root.join(file), "same")?;
+        }
+        let limits = Limits { respect_gitignore: true, ..Limits::default() };
+        // The ignore files themselves aren't looked for.
+        let wanted = |name: &str| !name.starts_with('.');
+
+        // Act
+        let (files, _) = get_dir_files_limited(root.to_str().unwrap(), &wanted, &limits)?;
+
+        // Assert
+        let paths: Vec<String> = files.iter().map(|file| file.path().into_owned()).collect();
+        let found: Vec<&Path> = paths.iter().filter_map(|path| Path::new(path).strip_prefix(&root).ok()).collect();
+        assert_eq!(found, vec![Path::new("src/keep.o"), Path::new("src/notes.txt"), Path::new("src/secret.txt")]);
+        let config = "[user]\nname = me\n[core]\n\texcludesFile = \"~/.gitignore_global\"";
+        assert_eq!(excludes_file(config), Some("~/.gitignore_global"));
+        assert_eq!(excludes_file("[user]\nexcludesfile = ~/.other"), None);
+        Ok(())
+    }
+}
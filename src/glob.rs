@@ -0,0 +1,163 @@
+//! Glob patterns matched against the file names (`--glob`), so `*.log` or `report-??.pdf` can be searched
+//! instead of a single name.
+//!
+//! `*` matches any run of characters, `?` a single one, and `[abc]`, `[a-z]` or `[!0-9]` a single one out of
+//! (or outside of) a set. Patterns are matched against the whole name, case included.
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// Returned when a pattern opens a set of characters (`[`) without closing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobError(pub String);
+
+impl Error for GlobError {}
+
+impl fmt::Display for GlobError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid pattern `{}`: a `[` is never closed.", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Char(char),
+    Any,
+    AnyRun,
+    /// Ranges of characters, and whether the set is negated.
+    Set(Vec<(char, char)>, bool),
+}
+
+/// A compiled glob pattern.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::glob::Pattern;
+/// let pattern: Pattern = "report-??.pdf".parse().unwrap();
+/// assert!(pattern.matches("report-07.pdf"));
+/// assert!(!pattern.matches("report-2023.pdf"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+}
+
+impl FromStr for Pattern {
+    type Err = GlobError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let mut tokens = vec![];
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '*' => Token::AnyRun,
+                '?' => Token::Any,
+                '[' => {
+                    let negated = chars.next_if(|c| *c == '!' || *c == '^').is_some();
+                    let mut ranges = vec![];
+                    // A `]` right after the opening bracket belongs to the set.
+                    let mut first = true;
+                    loop {
+                        let start = match chars.next() {
+                            Some(']') if !first => break,
+                            Some(c) => c,
+                            None => return Err(GlobError(pattern.to_string())),
+                        };
+                        first = false;
+                        let end = match chars.peek() {
+                            Some('-') => {
+                                chars.next();
+                                match chars.next() {
+                                    Some(']') => {
+                                        ranges.push((start, start));
+                                        ranges.push(('-', '-'));
+                                        break;
+                                    }
+                                    Some(end) => end,
+                                    None => return Err(GlobError(pattern.to_string())),
+                                }
+                            }
+                            _ => start,
+                        };
+                        ranges.push((start, end));
+                    }
+                    Token::Set(ranges, negated)
+                }
+                c => Token::Char(c),
+            };
+            tokens.push(token);
+        }
+        Ok(Pattern { tokens })
+    }
+}
+
+impl Pattern {
+    /// Whether the whole `name` matches the pattern.
+    pub fn matches(&self, name: &str) -> bool {
+        let name: Vec<char> = name.chars().collect();
+        // Only the last `*` is backtracked: anything an earlier one could match instead is reachable through it.
+        let (mut t, mut n) = (0, 0);
+        let mut star: Option<(usize, usize)> = None;
+        while n < name.len() {
+            match self.tokens.get(t) {
+                Some(Token::AnyRun) => {
+                    star = Some((t, n));
+                    t += 1;
+                    continue;
+                }
+                Some(token) if accepts(token, name[n]) => {
+                    t += 1;
+                    n += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            match star {
+                Some((star_t, star_n)) => {
+                    t = star_t + 1;
+                    n = star_n + 1;
+                    star = Some((star_t, star_n + 1));
+                }
+                None => return false,
+            }
+        }
+        self.tokens[t..].iter().all(|token| *token == Token::AnyRun)
+    }
+}
+
+/// Whether a token other than `*` accepts `c`.
+fn accepts(token: &Token, c: char) -> bool {
+    match token {
+        Token::Char(expected) => *expected == c,
+        Token::Any => true,
+        Token::Set(ranges, negated) => ranges.iter().any(|(start, end)| (*start..=*end).contains(&c)) != *negated,
+        Token::AnyRun => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn patterns_match_whole_names() -> Result<(), GlobError> {
+        // Arrange
+        let logs: Pattern = "*.log".parse()?;
+        let sets: Pattern = "IMG_[0-9][!a-z]*.[jJ]p*g".parse()?;
+        let brackets: Pattern = "[]-]*".parse()?;
+
+        // Act and Assert
+        assert!(logs.matches("server.log"));
+        assert!(logs.matches(".log"));
+        assert!(!logs.matches("server.log.1"));
+        assert!(!sets.matches("IMG_01.JPG"));
+        assert!(sets.matches("IMG_01.jpeg"));
+        assert!(sets.matches("IMG_9X.Jpg"));
+        assert!(!sets.matches("IMG_9x.jpg"));
+        assert!(brackets.matches("]a") && brackets.matches("-a") && !brackets.matches("a"));
+        assert!("a*b*c".parse::<Pattern>()?.matches("aXXbYYbZc"));
+        assert_eq!("report-[0-9".parse::<Pattern>(), Err(GlobError(String::from("report-[0-9"))));
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+//! Groups of files considered identical (see [`File::group_key`]): the same name and, when their contents were
+//! compared, the same digest (or only the same digest when it identifies the whole content). The listings, the
+//! interactive interface, the reports and the exports all iterate over them.
+
+use crate::File;
+use std::collections::BTreeMap;
+
+/// Files considered identical, among the ones they were grouped from.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::groups;
+/// let mut files = vec![
+///     File::new("a.txt", "./test", "2022-07-23 12:33:01", "./test/a.txt"),
+///     File::new("b.txt", "./test", "2022-07-23 12:33:01", "./test/b.txt"),
+///     File::new("a.txt", "./test/old", "2022-07-24 08:00:00", "./test/old/a.txt"),
+/// ];
+/// files[0].size = 10;
+/// files[2].size = 10;
+/// let groups = groups::duplicates(&files);
+/// assert_eq!(groups.len(), 1);
+/// assert_eq!((groups[0].name(), groups[0].indexes.clone()), ("a.txt", vec![0, 2]));
+/// assert_eq!((groups[0].total_size(), groups[0].reclaimable_size()), (20, 10));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct DuplicateGroup<'a> {
+    grouped: &'a [File],
+    /// Indexes of the copies among the files they were grouped from, in the order they were found.
+    pub indexes: Vec<usize>,
+}
+
+impl<'a> DuplicateGroup<'a> {
+    /// Copies of the group, in the order they were found.
+    pub fn files(&self) -> impl Iterator<Item = &'a File> + '_ {
+        self.indexes.iter().map(|i| &self.grouped[*i])
+    }
+
+    pub fn len(&self) -> usize {
+        self.indexes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    /// Name of the first copy found.
+    pub fn name(&self) -> &'a str {
+        &self.grouped[self.indexes[0]].name
+    }
+
+    /// The [`File::group_key`] shared by every copy.
+    pub fn key(&self) -> (&'a str, Option<&'a str>) {
+        self.grouped[self.indexes[0]].group_key()
+    }
+
+    /// Bytes taken by every copy.
+    pub fn total_size(&self) -> u64 {
+        self.files().map(|file| file.size).sum()
+    }
+
+    /// Bytes freed by deleting every copy but the first one found.
+    pub fn reclaimable_size(&self) -> u64 {
+        self.files().skip(1).map(|file| file.size).sum()
+    }
+
+    /// Whether any copy was matched by a heuristic comparison (see [`File::heuristic_match`]).
+    pub fn heuristic_match(&self) -> bool {
+        self.files().any(File::heuristic_match)
+    }
+}
+
+/// Every group of `files` (the files without copies make a group of their own), ordered by their key.
+pub fn all(files: &[File]) -> Vec<DuplicateGroup<'_>> {
+    let mut groups: BTreeMap<(&str, Option<&str>), Vec<usize>> = BTreeMap::new();
+    for (i, file) in files.iter().enumerate() {
+        groups.entry(file.group_key()).or_default().push(i);
+    }
+    groups.into_values().map(|indexes| DuplicateGroup { grouped: files, indexes }).collect()
+}
+
+/// The groups of `files` holding more than one copy, ordered by their key.
+pub fn duplicates(files: &[File]) -> Vec<DuplicateGroup<'_>> {
+    all(files).into_iter().filter(|group| group.len() > 1).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn copies_are_grouped_by_their_key() {
+        // Arrange
+        let mut files = vec![
+            File::new("a.txt", "./x", "2022-07-23 12:33:01", "./x/a.txt"),
+            File::new("renamed.txt", "./y", "2022-07-23 12:33:01", "./y/renamed.txt"),
+            File::new("a.txt", "./z", "2022-07-23 12:33:01", "./z/a.txt"),
+        ];
+        files[0].digest = Some(String::from("=sha256:ba78"));
+        files[1].digest = Some(String::from("=sha256:ba78"));
+        files[2].digest = Some(String::from("=sha256:cb00"));
+
+        // Act
+        let all = all(&files);
+        let duplicates = duplicates(&files);
+
+        // Assert
+        assert_eq!(all.len(), 2);
+        assert_eq!(duplicates.len(), 1);
+        let paths: Vec<String> = duplicates[0].files().map(|file| file.path().into_owned()).collect();
+        assert_eq!(paths, vec!["./x/a.txt", "./y/renamed.txt"]);
+        assert_eq!(duplicates[0].key(), ("", Some("=sha256:ba78")));
+        assert!(!duplicates[0].heuristic_match());
+    }
+}
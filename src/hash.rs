@@ -0,0 +1,444 @@
+//! Comparison of files by the digest of their whole content (`--by-content`), so the files that merely share a
+//! name aren't mistaken for duplicates, and the copies that were renamed are found as well.
+//!
+//! The contents are digested with SHA-256 by default, or with the [`Algorithm`] picked through `--hash`: `blake3`
+//! is cryptographic as well but faster, while `xxh64` is much faster still but not meant to resist tampering, so
+//! it suits the local cleanups rather than the audit reports. `xxh3` is refused rather than read as `xxh64`: XXH3
+//! itself isn't implemented, and its digests wouldn't match the ones of XXH64.
+//!
+//! Content digests start with `=`: the files sharing one are duplicates whatever their names (see
+//! [`File::group_key`]).
+//!
+//! The candidates are grouped by size first, and only the groups holding more than one file are read: a file
+//! whose size isn't shared can't have a copy. The big files are then told apart by a partial digest of their
+//! first and last [`PARTIAL_LEN`] bytes, so only the ones whose partial digest is shared are read whole.
+
+use crate::accounting;
+use crate::blake3::Blake3;
+use crate::cache::Cache;
+use crate::checksum::Xxh64;
+use crate::cloud;
+use crate::interrupt;
+use crate::limits::{Limits, Skipped};
+use crate::progress;
+use crate::storage::{map_parallel, Reading};
+use crate::vfs::{self, Vfs};
+use crate::warnings;
+use crate::File;
+use sha2::{Digest, Sha256};
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Returned when the hash algorithm is unknown.
+#[derive(Debug, Clone)]
+pub struct AlgorithmError(pub String);
+
+impl Error for AlgorithmError {}
+
+impl fmt::Display for AlgorithmError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.0.eq_ignore_ascii_case("xxh3") {
+            return write!(f, "The hash algorithm `{}` isn't supported, use `xxh64` instead.", self.0);
+        }
+        write!(f, "Unknown hash algorithm `{}` (expected `sha256`, `blake3` or `xxh64`).", self.0)
+    }
+}
+
+/// Algorithm the contents are digested with.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Algorithm {
+    #[default]
+    Sha256,
+    Blake3,
+    /// Not cryptographic.
+    Xxh64,
+}
+
+impl FromStr for Algorithm {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sha256" => Ok(Algorithm::Sha256),
+            "blake3" => Ok(Algorithm::Blake3),
+            "xxh64" | "xxhash" => Ok(Algorithm::Xxh64),
+            _ => Err(AlgorithmError(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Algorithm::Sha256 => "sha256",
+            Algorithm::Blake3 => "blake3",
+            Algorithm::Xxh64 => "xxh64",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Algorithm {
+    /// Algorithm the content digest `digest` (as stored on [`File::digest`]) was computed with.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::hash::Algorithm;
+    /// assert_eq!(Algorithm::of("=blake3:6437b3ac"), Some(Algorithm::Blake3));
+    /// assert_eq!(Algorithm::of("size:3"), None);
+    /// ```
+    pub fn of(digest: &str) -> Option<Algorithm> {
+        digest.strip_prefix('=')?.split_once(':')?.0.parse().ok()
+    }
+}
+
+/// Digest of the bytes fed so far.
+enum Hasher {
+    Sha256(Sha256),
+    Blake3(Box<Blake3>),
+    Xxh64(Xxh64),
+}
+
+impl Hasher {
+    fn new(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            Algorithm::Blake3 => Hasher::Blake3(Box::default()),
+            Algorithm::Xxh64 => Hasher::Xxh64(Xxh64::default()),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(bytes),
+            Hasher::Blake3(hasher) => hasher.update(bytes),
+            Hasher::Xxh64(hasher) => hasher.update(bytes),
+        }
+    }
+
+    /// Hexadecimal digest.
+    fn finish(self) -> String {
+        let hex = |bytes: &[u8]| bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+        match self {
+            Hasher::Sha256(hasher) => hex(&hasher.finalize()),
+            Hasher::Blake3(hasher) => hex(&hasher.finish()),
+            Hasher::Xxh64(hasher) => format!("{:016x}", hasher.finish()),
+        }
+    }
+}
+
+/// Hexadecimal SHA-256 digest of the content of the file located at `path`.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::hash;
+/// # std::fs::write("./hash-test.txt", "abc").unwrap();
+/// let digest = hash::sha256(std::path::Path::new("./hash-test.txt")).unwrap();
+/// assert_eq!(digest, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+/// # std::fs::remove_file("./hash-test.txt").unwrap();
+/// ```
+pub fn sha256(path: &Path) -> Result<String, io::Error> {
+    file_digest(path, Algorithm::Sha256)
+}
+
+/// Hexadecimal digest of the content of the file located at `path`, computed with `algorithm`.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::hash::{self, Algorithm};
+/// # std::fs::write("./hash-algorithm-test.txt", "abc").unwrap();
+/// let digest = hash::file_digest(std::path::Path::new("./hash-algorithm-test.txt"), Algorithm::Xxh64).unwrap();
+/// assert_eq!(digest, "44bc2cf5ad770999");
+/// # std::fs::remove_file("./hash-algorithm-test.txt").unwrap();
+/// ```
+pub fn file_digest(path: &Path, algorithm: Algorithm) -> Result<String, io::Error> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Hasher::new(algorithm);
+    let mut buffer = vec![0; 64 * 1024];
+    let mut bytes = 0;
+    loop {
+        match file.read(&mut buffer)? {
+            0 => break,
+            read => {
+                hasher.update(&buffer[..read]);
+                bytes += read as u64;
+                progress::SCAN.hashed(read as u64);
+            }
+        }
+    }
+    accounting::SCAN.read(path, bytes);
+    Ok(hasher.finish())
+}
+
+/// Bytes digested at the start and at the end of the big files before reading them whole.
+pub const PARTIAL_LEN: u64 = 64 * 1024;
+
+/// Digest of the first and the last [`PARTIAL_LEN`] bytes of the file located at `path` (reusing the cached one),
+/// cheap to compute whatever the size of the file. It's not meant to be stored on [`File::digest`].
+pub fn partial_digest(path: &Path, cache: &Cache) -> Result<String, io::Error> {
+    if let Some(digest) = cache.get("partial", path) {
+        return Ok(digest);
+    }
+    let mut file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut hasher = Xxh64::default();
+    let mut buffer = Vec::with_capacity(PARTIAL_LEN as usize);
+    let mut bytes = 0;
+    for start in [0, len.saturating_sub(PARTIAL_LEN)] {
+        file.seek(SeekFrom::Start(start))?;
+        buffer.clear();
+        bytes += file.by_ref().take(PARTIAL_LEN).read_to_end(&mut buffer)? as u64;
+        hasher.update(&buffer);
+    }
+    accounting::SCAN.read(path, bytes);
+    progress::SCAN.hashed(bytes);
+    let digest = format!("partial:{:016x}", hasher.finish());
+    cache.insert("partial", path, &digest);
+    Ok(digest)
+}
+
+/// Refines the `digest` of the `files` that have duplicates with the digest of their content, so they're only
+/// grouped with the copies holding the same bytes. Cloud placeholders are skipped (see [`cloud`]), and the files
+/// that can't be read are reported and left untouched.
+pub fn digest_files(files: &mut [File], reading: &Reading) {
+    let readable = cloud::readable(files, reading.include_placeholders);
+    let candidates: Vec<usize> = (0..files.len()).filter(|i| readable[*i]).collect();
+    let (candidates, unique) = by_shared_size(files, candidates, true);
+    for i in unique {
+        let digest = format!("size:{}", files[i].size);
+        files[i].add_digest(&digest);
+    }
+    let (candidates, unique) = by_shared_partial(files, candidates, true, reading);
+    for (i, digest) in unique {
+        files[i].add_digest(&digest);
+    }
+    digest(files, candidates, reading);
+}
+
+/// Gathers the files located under `path` (leaving out the directories exceeding `limits`, which are returned as
+/// well) whose content is found more than once, whatever their names. Copies are listed next to each other.
+pub fn find_duplicates(
+    path: &str,
+    limits: &Limits,
+    reading: &Reading,
+) -> Result<(Vec<File>, Vec<Skipped>), io::Error> {
+    find_duplicates_on(&vfs::Local, path, limits, reading)
+}
+
+/// Same as [`find_duplicates`], but lists the directories through `vfs`.
+pub fn find_duplicates_on(
+    vfs: &dyn Vfs,
+    path: &str,
+    limits: &Limits,
+    reading: &Reading,
+) -> Result<(Vec<File>, Vec<Skipped>), io::Error> {
+    let mut skipped = vec![];
+    let mut files = crate::walk(vfs, path, &|_| true, limits, &mut skipped)?;
+    keep_duplicates(&mut files, reading);
+    Ok((files, skipped))
+}
+
+/// Only keeps the `files` whose content is found more than once among them, whatever their names, digesting it.
+/// Copies are listed next to each other. Cloud placeholders are left out (see [`cloud`]), and so are the files
+/// that can't be read (which are reported).
+pub fn keep_duplicates(files: &mut Vec<File>, reading: &Reading) {
+    let candidates: Vec<usize> = (0..files.len())
+        .filter(|i| reading.include_placeholders || !cloud::is_placeholder(Path::new(&*files[*i].path())))
+        .collect();
+    let (candidates, _) = by_shared_size(files, candidates, false);
+    let (candidates, _) = by_shared_partial(files, candidates, false, reading);
+    digest(files, candidates, reading);
+    files.retain(|file| file.digest.is_some());
+    let mut duplicated = crate::has_duplicate(files).into_iter();
+    files.retain(|_| duplicated.next().unwrap_or_default());
+    files.sort_by(|a, b| a.digest.cmp(&b.digest));
+}
+
+/// Content digest of the file located at `path` computed with `algorithm`, as stored on [`File::digest`] (reusing
+/// the cached one).
+pub fn content_digest(path: &Path, algorithm: Algorithm, cache: &Cache) -> Result<String, io::Error> {
+    let kind = algorithm.to_string();
+    if let Some(digest) = cache.get(&kind, path) {
+        return Ok(digest);
+    }
+    let digest = format!("={}:{}", kind, file_digest(path, algorithm)?);
+    cache.insert(&kind, path, &digest);
+    Ok(digest)
+}
+
+/// Records that the content of `file` couldn't be read (so it's left without a digest).
+pub(crate) fn unreadable(file: &File, err: &io::Error) {
+    let path = file.path();
+    warnings::unreadable_content(err, &path, format!("Couldn't read `{}`: {}", path, err));
+}
+
+/// Content digests of the `files` sharing their size (and their partial digest, for the big ones) with another one
+/// of them (the other files can't have a copy, so they aren't read whole). The files that can't be read are
+/// reported and left without a digest.
+pub fn shared_size_digests(files: &[&File], reading: &Reading) -> Vec<Option<String>> {
+    let mut sizes: HashMap<u64, usize> = HashMap::new();
+    for file in files {
+        *sizes.entry(file.size).or_default() += 1;
+    }
+    let candidates = (0..files.len()).filter(|i| sizes[&files[*i].size] > 1).collect();
+    let shared: HashSet<usize> = by_shared_partial(files, candidates, false, reading).0.into_iter().collect();
+    let indexes: Vec<usize> = (0..files.len()).collect();
+    map_parallel(&indexes, reading.readers, |i| match shared.contains(i) {
+        false => None,
+        true => content_digest(Path::new(&*files[*i].path()), reading.algorithm, reading.cache)
+            .inspect_err(|err| unreadable(files[*i], err))
+            .ok(),
+    })
+}
+
+/// Splits the `candidates` among `files` between the ones sharing their size with another candidate (of their
+/// group when `within_groups`, see [`File::group_key`]) and the ones that don't, which can't have a copy.
+fn by_shared_size(files: &[File], candidates: Vec<usize>, within_groups: bool) -> (Vec<usize>, Vec<usize>) {
+    let key = |i: usize| match within_groups {
+        true => (files[i].group_key(), files[i].size),
+        false => (("", None), files[i].size),
+    };
+    let mut sizes: HashMap<((&str, Option<&str>), u64), usize> = HashMap::new();
+    for i in &candidates {
+        *sizes.entry(key(*i)).or_default() += 1;
+    }
+    candidates.into_iter().partition(|i| sizes[&key(*i)] > 1)
+}
+
+/// Splits the `candidates` among `files` between the ones that may still have a copy and the ones whose partial
+/// digest (see [`partial_digest`]) isn't shared with another candidate of their size (and of their group when
+/// `within_groups`), returned along with it. The files too small to be worth it, and the ones whose partial
+/// digest can't be computed, may still have a copy.
+fn by_shared_partial(
+    files: &[impl Borrow<File> + Sync],
+    candidates: Vec<usize>,
+    within_groups: bool,
+    reading: &Reading,
+) -> (Vec<usize>, Vec<(usize, String)>) {
+    let big: Vec<usize> = candidates.iter().copied().filter(|i| files[*i].borrow().size > 2 * PARTIAL_LEN).collect();
+    let digests = map_parallel(&big, reading.readers, |i| {
+        partial_digest(Path::new(&*files[*i].borrow().path()), reading.cache).ok()
+    });
+    let partials: HashMap<usize, String> = big.into_iter().zip(digests).filter_map(|(i, d)| Some((i, d?))).collect();
+    let key = |i: usize| {
+        let file = files[i].borrow();
+        let group = if within_groups { file.group_key() } else { ("", None) };
+        (group, file.size, partials.get(&i))
+    };
+    let mut shared: HashMap<_, usize> = HashMap::new();
+    for i in &candidates {
+        *shared.entry(key(*i)).or_default() += 1;
+    }
+    let (candidates, unique): (Vec<usize>, Vec<usize>) =
+        candidates.into_iter().partition(|i| !partials.contains_key(i) || shared[&key(*i)] > 1);
+    let unique = unique.into_iter().map(|i| (i, partials[&i].clone())).collect();
+    (candidates, unique)
+}
+
+/// Adds the content digest of the `candidates` among `files` (reusing the cached ones).
+fn digest(files: &mut [File], candidates: Vec<usize>, reading: &Reading) {
+    let digests = map_parallel(&candidates, reading.readers, |i| match interrupt::interrupted() {
+        true => Err(io::Error::from(io::ErrorKind::Interrupted)),
+        false => content_digest(Path::new(&*files[*i].path()), reading.algorithm, reading.cache),
+    });
+    for (i, digest) in candidates.into_iter().zip(digests) {
+        match digest {
+            Ok(digest) => files[i].add_digest(&digest),
+            // The files left out by an interruption stay without a digest.
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => unreadable(&files[i], &err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn copies_are_found_by_content() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("hash");
+        fs::create_dir_all(root.join("old"))?;
+        fs::write(root.join("notes.txt"), "same")?;
+        fs::write(root.join("old").join("notes (copy).txt"), "same")?;
+        fs::write(root.join("old").join("notes.txt"), "different")?;
+        let cache = Cache::default();
+        let reading = Reading { readers: 2, cache: &cache, include_placeholders: false, algorithm: Algorithm::Sha256 };
+
+        // Act
+        let (found, _) = find_duplicates(&root.to_string_lossy(), &Limits::default(), &reading)?;
+        let mut named = crate::get_dir_files(&root.to_string_lossy(), "notes.txt")?;
+        digest_files(&mut named, &reading);
+
+        // Assert
+        let mut names: Vec<&str> = found.iter().map(|f| &*f.name).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["notes (copy).txt", "notes.txt"]);
+        assert_eq!(found[0].group_key(), found[1].group_key());
+        assert_eq!(named.len(), 2);
+        assert_ne!(named[0].group_key(), named[1].group_key());
+        assert_eq!(named[1].digest.as_deref(), Some("size:9"));
+        assert!(cache.get("sha256", &root.join("old").join("notes.txt")).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn big_files_are_only_read_whole_when_their_ends_match() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("partial");
+        let content = vec![b'a'; 3 * PARTIAL_LEN as usize];
+        let (mut middle, mut head) = (content.clone(), content.clone());
+        middle[PARTIAL_LEN as usize + 1] = b'b';
+        head[0] = b'b';
+        fs::write(root.join("original.bin"), &content)?;
+        fs::write(root.join("copy.bin"), &content)?;
+        fs::write(root.join("middle.bin"), &middle)?;
+        fs::write(root.join("head.bin"), &head)?;
+        let cache = Cache::default();
+        let reading = Reading { readers: 2, cache: &cache, include_placeholders: false, algorithm: Algorithm::Sha256 };
+
+        // Act
+        let (found, _) = find_duplicates(&root.to_string_lossy(), &Limits::default(), &reading)?;
+
+        // Assert
+        let mut names: Vec<&str> = found.iter().map(|f| &*f.name).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["copy.bin", "original.bin"]);
+        assert!(cache.get("sha256", &root.join("middle.bin")).is_some());
+        assert!(cache.get("sha256", &root.join("head.bin")).is_none());
+        assert!(cache.get("partial", &root.join("head.bin")).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn contents_are_digested_with_the_chosen_algorithm() -> Result<(), io::Error> {
+        // Arrange
+        let scratch = Scratch::new("algorithm");
+        let path = scratch.join("abc.txt");
+        fs::write(&path, "abc")?;
+        let cache = Cache::default();
+
+        // Act
+        let digests: Vec<String> = ["sha256", "blake3", "xxh64"]
+            .iter()
+            .map(|name| content_digest(&path, name.parse().unwrap_or_default(), &cache))
+            .collect::<Result<_, _>>()?;
+
+        // Assert
+        assert_eq!(digests[0], "=sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(digests[1], "=blake3:6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+        assert_eq!(digests[2], "=xxh64:44bc2cf5ad770999");
+        assert_eq!(cache.get("blake3", &path).as_deref(), Some(&*digests[1]));
+        assert_eq!(Algorithm::of(&digests[2]), Some(Algorithm::Xxh64));
+        let xxh3 = "xxh3".parse::<Algorithm>().map_err(|e| e.to_string());
+        assert_eq!(xxh3, Err(String::from("The hash algorithm `xxh3` isn't supported, use `xxh64` instead.")));
+        assert!("md5".parse::<Algorithm>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+//! Summaries of the past runs (`dir_cleaner stats trend [<dir>]`), so the users can tell whether the duplicates
+//! of their trees are shrinking over time.
+//!
+//! Every scan appends the amount of duplicates it found, along with the space they waste, to the history file
+//! (`--history <file>`, or [`default_path`]). The runs are told apart by their root and by the options deciding
+//! what is compared, since a scan by name and a scan by content of the same tree can't be compared together.
+
+use crate::groups;
+use crate::schema::{self, DocumentError, Failed, HISTORY_VERSION};
+use crate::units::format_size;
+use crate::File;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What a run found.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Run {
+    pub root: String,
+    /// What was searched, and how (E.G: `files with the same content --by-content`).
+    pub search: String,
+    pub at: String,
+    /// Files found.
+    pub files: usize,
+    /// Copies found beyond the first one of every group.
+    pub duplicates: usize,
+    /// Bytes freed by deleting those copies.
+    pub wasted: u64,
+}
+
+impl Run {
+    /// Summary of the `files` found by a run on `root`, searching `search`, at `at`.
+    pub fn new(root: &str, search: &str, at: &str, files: &[File]) -> Self {
+        let groups = groups::duplicates(files);
+        Run {
+            root: root.to_string(),
+            search: search.to_string(),
+            at: at.to_string(),
+            files: files.len(),
+            duplicates: groups.iter().map(|group| group.len() - 1).sum(),
+            wasted: groups.iter().map(|group| group.reclaimable_size()).sum(),
+        }
+    }
+}
+
+/// Runs read from (and written back to) a file, the oldest first.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::history::{History, Run};
+/// let files = vec![
+///     File::new("a.txt", "./old", "2022-07-23 12:33:01", "./old/a.txt"),
+///     File::new("a.txt", "./new", "2022-07-23 12:33:01", "./new/a.txt"),
+/// ];
+/// let mut history = History::default();
+/// history.record(Run::new("./test", "a.txt", "2024-01-01 10:00:00", &files));
+/// history.record(Run::new("./test", "a.txt", "2024-02-01 10:00:00", &files[..1]));
+/// assert!(history.trend(None).contains("0 (-1) duplicate(s)"));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    /// Version of the format the history was written with (see [`schema`](crate::schema)).
+    pub version: u32,
+    pub runs: Vec<Run>,
+}
+
+/// Location of the history when none is provided explicitly.
+pub fn default_path() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+    };
+    base.map(|base| base.join("dir_cleaner").join("history.json"))
+}
+
+/// `value` along with its change since `previous` (E.G: `12 (-3)`).
+fn with_delta(value: u64, previous: Option<u64>, format: fn(u64) -> String) -> String {
+    match previous {
+        Some(previous) if value > previous => format!("{} (+{})", format(value), format(value - previous)),
+        Some(previous) if value < previous => format!("{} (-{})", format(value), format(previous - value)),
+        _ => format(value),
+    }
+}
+
+impl History {
+    /// Reads the runs stored on the file located at `path` (none when it doesn't exist yet).
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let location = path.display();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Ok(History::default()),
+        };
+        let document: Value =
+            serde_json::from_str(&content).map_err(|e| DocumentError::new("history", &location, Failed::Parse, e))?;
+        schema::version("history file", &document, HISTORY_VERSION)?;
+        let history =
+            serde_json::from_value(document).map_err(|e| DocumentError::new("history", &location, Failed::Parse, e))?;
+        Ok(history)
+    }
+
+    pub fn record(&mut self, run: Run) {
+        self.runs.push(run);
+    }
+
+    /// Writes the runs to the file located at `path`, creating its directory when needed.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let history = History { version: HISTORY_VERSION, runs: self.runs.clone() };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&history)?)
+            .map_err(|e| DocumentError::new("history", path.display(), Failed::Save, e).into())
+    }
+
+    /// How the duplicates evolved across the runs on `root` (every root when `None`), every change being told
+    /// against the previous run of the same search.
+    pub fn trend(&self, root: Option<&str>) -> String {
+        let mut searches: BTreeMap<(&str, &str), Vec<&Run>> = BTreeMap::new();
+        for run in self.runs.iter().filter(|run| root.is_none_or(|root| run.root == root)) {
+            searches.entry((&run.root, &run.search)).or_default().push(run);
+        }
+        if searches.is_empty() {
+            return String::from("No run was recorded yet.\n");
+        }
+        let mut trend = String::new();
+        for ((root, search), runs) in searches {
+            let _ = writeln!(trend, "`{}` ({}):", root, search);
+            let mut previous: Option<&Run> = None;
+            for run in runs {
+                let count = |run: &Run| run.duplicates as u64;
+                let duplicates = with_delta(count(run), previous.map(count), |count| count.to_string());
+                let wasted = with_delta(run.wasted, previous.map(|p| p.wasted), format_size);
+                let _ = writeln!(trend, "  {}: {} duplicate(s), {} wasted.", run.at, duplicates, wasted);
+                previous = Some(run);
+            }
+        }
+        trend
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn runs_survive_a_save_and_show_their_trend() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let path = Scratch::new("history");
+        let file = path.join("history.json");
+        let mut files = vec![
+            File::new("a.txt", "./x", "2022-07-23 12:33:01", "./x/a.txt"),
+            File::new("a.txt", "./y", "2022-07-23 12:33:01", "./y/a.txt"),
+            File::new("a.txt", "./z", "2022-07-23 12:33:01", "./z/a.txt"),
+        ];
+        files.iter_mut().for_each(|file| file.size = 1024);
+        let mut history = History::default();
+
+        // Act
+        history.record(Run::new("./test", "a.txt", "2024-01-01 10:00:00", &files));
+        history.record(Run::new("./other", "a.txt", "2024-01-02 10:00:00", &files));
+        history.record(Run::new("./test", "a.txt", "2024-02-01 10:00:00", &files[..2]));
+        history.save(&file)?;
+        let loaded = History::load(&file)?;
+
+        // Assert
+        assert_eq!(loaded.runs, history.runs);
+        assert_eq!(
+            loaded.trend(Some("./test")),
+            "`./test` (a.txt):\n  2024-01-01 10:00:00: 2 duplicate(s), 2.0 KiB wasted.\n  \
+            2024-02-01 10:00:00: 1 (-1) duplicate(s), 1.0 KiB (-1.0 KiB) wasted.\n"
+        );
+        assert_eq!(loaded.trend(Some("./missing")), "No run was recorded yet.\n");
+        assert_eq!(History::load(Path::new("./missing-history.json"))?, History::default());
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+//! Incremental scans (`--incremental <snapshot>`): the listing of every scanned directory is stored on a snapshot
+//! along with its modification time, and the next scans reuse the listings of the directories whose modification
+//! time didn't change instead of listing them again. The inspection of the files themselves isn't cached here:
+//! their metadata is read again, and their content digests are reused through the [`cache`](crate::cache)
+//! whenever their size and modification time didn't change.
+//!
+//! A directory is only modified when an entry is added to it, removed from it or renamed, so its listing is
+//! reused as long as it holds the same entries. [`Incremental`] plugs the snapshot into the scans as a [`Vfs`]
+//! wrapping another one.
+
+use crate::schema::{self, DocumentError, Failed, SNAPSHOT_VERSION};
+use crate::vfs::{DirEntry, Kind, Meta, Vfs};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+/// The entries of a directory, as they were listed.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+struct Listing {
+    modified: SystemTime,
+    /// Name and kind of every entry (the ones whose name isn't valid UTF-8 are left out, the scans skip them).
+    entries: Vec<(String, Kind)>,
+}
+
+/// Listings of the directories scanned by a run, read from (and written back to) a file.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Version of the format the snapshot was written with (see [`schema`](crate::schema)).
+    pub version: u32,
+    /// Listings keyed by the path of their directory.
+    dirs: BTreeMap<String, Listing>,
+}
+
+impl Snapshot {
+    /// Reads the snapshot stored on the file located at `path` (an empty one when it doesn't exist yet).
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Ok(Snapshot::default()),
+        };
+        let document: Value =
+            serde_json::from_str(&content).map_err(|e| DocumentError::new("snapshot", path, Failed::Parse, e))?;
+        schema::version("snapshot", &document, SNAPSHOT_VERSION)?;
+        let snapshot =
+            serde_json::from_value(document).map_err(|e| DocumentError::new("snapshot", path, Failed::Parse, e))?;
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot to the file located at `path`.
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let snapshot = Snapshot { version: SNAPSHOT_VERSION, dirs: self.dirs.clone() };
+        fs::write(path, serde_json::to_string(&snapshot)?)
+            .map_err(|e| DocumentError::new("snapshot", path, Failed::Save, e).into())
+    }
+
+    /// Directories listed on the snapshot.
+    pub fn len(&self) -> usize {
+        self.dirs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dirs.is_empty()
+    }
+}
+
+/// A [`Vfs`] reusing the listings of the directories `previous` holds whenever they weren't modified since, and
+/// recording the listings of the scanned directories on a new snapshot.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::incremental::{Incremental, Snapshot};
+/// # use dir_cleaner::scanner::Scanner;
+/// # use dir_cleaner::vfs::Memory;
+/// let mut memory = Memory::default();
+/// memory.add_file("/backup/a.txt", "same");
+/// let first = Incremental::new(Snapshot::default(), &memory);
+/// Scanner::new("/backup").name("a.txt").vfs(&first).scan().unwrap();
+/// let second = Incremental::new(first.snapshot(), &memory);
+/// let scan = Scanner::new("/backup").name("a.txt").vfs(&second).scan().unwrap();
+/// assert_eq!((scan.files.len(), second.reused()), (1, 1));
+/// ```
+pub struct Incremental<'a> {
+    inner: &'a dyn Vfs,
+    previous: Snapshot,
+    current: Mutex<BTreeMap<String, Listing>>,
+    reused: AtomicUsize,
+}
+
+impl<'a> Incremental<'a> {
+    pub fn new(previous: Snapshot, inner: &'a dyn Vfs) -> Self {
+        Incremental { inner, previous, current: Mutex::new(BTreeMap::new()), reused: AtomicUsize::new(0) }
+    }
+
+    /// Directories whose listing was reused so far.
+    pub fn reused(&self) -> usize {
+        self.reused.load(Ordering::Relaxed)
+    }
+
+    /// Listings of the directories scanned so far, to be reused by the next scans.
+    pub fn snapshot(&self) -> Snapshot {
+        let dirs = self.current.lock().map(|current| current.clone()).unwrap_or_default();
+        Snapshot { version: SNAPSHOT_VERSION, dirs }
+    }
+
+    fn record(&self, dir: String, listing: Listing) {
+        if let Ok(mut current) = self.current.lock() {
+            current.insert(dir, listing);
+        }
+    }
+}
+
+impl Vfs for Incremental<'_> {
+    fn read_dir(&self, dir: &Path) -> Result<Box<dyn Iterator<Item = DirEntry> + '_>, io::Error> {
+        // Read before listing the directory, so a change made meanwhile is caught by the next scan.
+        let modified = self.inner.symlink_metadata(dir)?.modified;
+        let key = dir.to_string_lossy().to_string();
+        let previous = self.previous.dirs.get(&key).filter(|listing| Some(listing.modified) == modified);
+        if let Some(listing) = previous {
+            self.reused.fetch_add(1, Ordering::Relaxed);
+            self.record(key, listing.clone());
+            let dir = dir.to_path_buf();
+            let entries = listing.entries.iter().map(move |(name, kind)| {
+                DirEntry { name: name.into(), path: dir.join(name), kind: *kind, meta: None }
+            });
+            return Ok(Box::new(entries));
+        }
+        let entries: Vec<DirEntry> = self.inner.read_dir(dir)?.collect();
+        if let Some(modified) = modified {
+            let listed = entries.iter().filter_map(|entry| Some((entry.name.to_str()?.to_string(), entry.kind)));
+            self.record(key, Listing { modified, entries: listed.collect() });
+        }
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> Result<Meta, io::Error> {
+        self.inner.symlink_metadata(path)
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Meta, io::Error> {
+        self.inner.metadata(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, io::Error> {
+        self.inner.canonicalize(path)
+    }
+
+    fn open(&self, path: &Path) -> Result<Box<dyn Read + '_>, io::Error> {
+        self.inner.open(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scanner::Scanner;
+    use crate::scratch::Scratch;
+    use crate::vfs::Local;
+
+    #[test]
+    fn unmodified_directories_are_not_listed_again() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let scratch = Scratch::new("incremental");
+        let root = scratch.join("root");
+        let snapshot = scratch.join("root.snapshot");
+        let snapshot = snapshot.to_str().unwrap();
+        fs::create_dir_all(root.join("old"))?;
+        fs::create_dir_all(root.join("new"))?;
+        fs::write(root.join("old").join("a.txt"), "same")?;
+        let path = root.to_str().unwrap();
+        let first = Incremental::new(Snapshot::load(snapshot)?, &Local);
+        Scanner::new(path).name("a.txt").vfs(&first).scan()?;
+        first.snapshot().save(snapshot)?;
+
+        // Act
+        fs::write(root.join("new").join("a.txt"), "same")?;
+        let second = Incremental::new(Snapshot::load(snapshot)?, &Local);
+        let scan = Scanner::new(path).name("a.txt").vfs(&second).scan()?;
+
+        // Assert
+        assert_eq!((first.reused(), first.snapshot().len()), (0, 3));
+        assert_eq!(scan.files.len(), 2);
+        assert_eq!(second.reused(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+//! Interning of the strings shared by many files (the folders of a directory and, since duplicates share
+//! their name, the names), so every distinct one is stored once no matter how many files hold it.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+pub struct Interner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    /// Shared copy of `value`, allocated the first time it's seen.
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(value) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<str> = Arc::from(value);
+        self.strings.insert(Arc::clone(&interned));
+        interned
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn intern_shares_equal_strings() {
+        // Arrange
+        let mut interner = Interner::default();
+
+        // Act
+        let a = interner.intern("./photos/2023");
+        let b = interner.intern("./photos/2023");
+        let c = interner.intern("./photos/2024");
+
+        // Assert
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(&*c, "./photos/2024");
+    }
+}
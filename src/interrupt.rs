@@ -0,0 +1,155 @@
+//! Graceful interruptions (Ctrl+C, on Unix). While a scan or a batch of deletions runs, `SIGINT` doesn't kill the
+//! process: it's recorded, the scan stops walking and reading files at its next step (keeping what it found so
+//! far, which is listed, reported or saved as usual), and the deletions stop before the next file, so a file is
+//! never left half deleted. Outside of them (E.G: while a prompt waits), `SIGINT` quits right away as usual.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Interruptions deferred by the work guarding itself from them.
+#[derive(Debug, Default)]
+pub struct Interruptions {
+    interrupted: AtomicBool,
+    guards: AtomicUsize,
+}
+
+impl Interruptions {
+    pub const fn new() -> Self {
+        Interruptions { interrupted: AtomicBool::new(false), guards: AtomicUsize::new(0) }
+    }
+
+    /// Defers the interruptions until the returned guard is dropped.
+    pub fn guard(&self) -> Guard<'_> {
+        self.guards.fetch_add(1, Ordering::SeqCst);
+        Guard(self)
+    }
+
+    pub fn is_guarded(&self) -> bool {
+        self.guards.load(Ordering::SeqCst) > 0
+    }
+
+    /// Records an interruption.
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether an interruption was deferred, which the work should stop at.
+    pub fn interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+
+    /// Same as [`Interruptions::interrupted`], forgetting about the interruption (which was dealt with).
+    pub fn take(&self) -> bool {
+        self.interrupted.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Defers the interruptions while it's alive.
+pub struct Guard<'a>(&'a Interruptions);
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.0.guards.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Interruptions of the process, deferred through [`guard`].
+pub static SIGNAL: Interruptions = Interruptions::new();
+
+/// Defers the `SIGINT` received until the returned guard is dropped: they're only recorded, for [`interrupted`] to
+/// tell.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::interrupt;
+/// let guard = interrupt::guard();
+/// for step in 0..3 {
+///     if interrupt::interrupted() {
+///         break;
+///     }
+///     // One step of the work.
+/// }
+/// drop(guard);
+/// ```
+pub fn guard() -> Guard<'static> {
+    platform::handle_signals();
+    SIGNAL.guard()
+}
+
+/// Whether a `SIGINT` was deferred.
+pub fn interrupted() -> bool {
+    SIGNAL.interrupted()
+}
+
+/// Same as [`interrupted`], forgetting about the `SIGINT` (which was dealt with).
+pub fn take() -> bool {
+    SIGNAL.take()
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::SIGNAL;
+    use crate::pause;
+    use std::os::raw::{c_int, c_void};
+    use std::sync::Once;
+
+    const SIGINT: c_int = 2;
+    const NOTICE: &[u8] = b"\nInterrupted, stopping once the current step is done.\n";
+
+    extern "C" {
+        fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+        fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+        fn _exit(status: c_int) -> !;
+    }
+
+    /// Only touches atomics, writes and exits, which are safe to do from a signal handler.
+    extern "C" fn on_signal(_: c_int) {
+        if !SIGNAL.is_guarded() {
+            // SAFETY: `_exit` is async-signal-safe.
+            unsafe { _exit(130) }
+        }
+        SIGNAL.interrupt();
+        // A paused scan has to go on in order to stop.
+        pause::SCAN.resume();
+        // SAFETY: `write` is async-signal-safe, and `NOTICE` lives for the whole life of the process.
+        unsafe {
+            write(2, NOTICE.as_ptr().cast(), NOTICE.len());
+        }
+    }
+
+    pub fn handle_signals() {
+        static HANDLED: Once = Once::new();
+        HANDLED.call_once(|| {
+            // SAFETY: `on_signal` is async-signal-safe, and stays valid for the whole life of the process.
+            unsafe {
+                signal(SIGINT, on_signal);
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    pub fn handle_signals() {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interruptions_are_deferred_while_guarded() {
+        // Arrange
+        let interruptions = Interruptions::new();
+
+        // Act
+        let guard = interruptions.guard();
+        let guarded = interruptions.is_guarded();
+        interruptions.interrupt();
+        drop(guard);
+
+        // Assert
+        assert!(guarded && !interruptions.is_guarded());
+        assert!(interruptions.interrupted());
+        assert!(interruptions.take());
+        assert!(!interruptions.interrupted());
+    }
+}
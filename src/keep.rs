@@ -0,0 +1,420 @@
+//! Strategies choosing which copy of every group of duplicates should be kept (`--keep <strategy>`), so
+//! the remaining copies can be deleted without reviewing every group by hand.
+//!
+//! Along with the duplicates, the audio strategy judges together the copies of a song encoded in different
+//! formats (E.G: `song.flac` and `song.mp3`), told apart by their stem and their tags (see [`Song`]). Such copies
+//! only match heuristically: the suggestions give them an `~audio` digest (see [`Suggestion::mark`]), so their
+//! deletion asks for the usual confirmation.
+
+use crate::checksum::fnv1a;
+use crate::groups;
+use crate::File;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Returned when the requested strategy doesn't exist.
+#[derive(Debug, Clone)]
+pub struct StrategyError(pub String);
+
+impl Error for StrategyError {}
+
+impl fmt::Display for StrategyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Unknown keep strategy `{}` (available: highest-bitrate).", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Strategy {
+    /// Keep the best sounding copy of audio files: lossless formats (FLAC, ALAC, WAV...) first, then the
+    /// highest bitrate. Requires the `audio` feature.
+    HighestBitrate,
+}
+
+impl Strategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::HighestBitrate => "highest-bitrate",
+        }
+    }
+
+    /// Feature the strategy depends on, if any.
+    pub fn feature(&self) -> Option<&'static str> {
+        match self {
+            Strategy::HighestBitrate => Some("audio"),
+        }
+    }
+
+    /// How the strategy judges the file located at `path`, or `None` when it can't judge it (in which case it's
+    /// never proposed for deletion).
+    fn judge(&self, path: &Path) -> Option<Judgement> {
+        match self {
+            Strategy::HighestBitrate => judge_audio(path),
+        }
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = StrategyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "highest-bitrate" | "lossless" => Ok(Strategy::HighestBitrate),
+            _ => Err(StrategyError(s.to_string())),
+        }
+    }
+}
+
+/// What a strategy tells about a file.
+#[derive(Debug, PartialEq, Clone)]
+struct Judgement {
+    /// How much the strategy wants to keep the file (the higher the better).
+    rank: u64,
+    /// Song the file holds, when it's an audio file.
+    song: Option<Song>,
+}
+
+/// Confidence (in percent) of the copies of a song matched across formats.
+pub const SONG_CONFIDENCE: u8 = 90;
+
+/// What the copies of a song share whatever their format: the stem of their name (lowercase), their tags and
+/// their duration, in whole seconds. Only the songs whose artist and title are both tagged are told apart.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Song {
+    pub stem: String,
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub seconds: u64,
+}
+
+impl Song {
+    /// Whether the tags tell the song apart from the tracks that merely share its stem and duration.
+    fn tagged(&self) -> bool {
+        self.artist.is_some() && self.title.is_some()
+    }
+
+    /// Heuristic digest of the copies of the song (see [`File::heuristic_match`]).
+    fn digest(&self) -> String {
+        let artist = self.artist.as_deref().unwrap_or_default();
+        let title = self.title.as_deref().unwrap_or_default();
+        let id = fnv1a(format!("{}\0{}\0{}\0{}", self.stem, artist, title, self.seconds).as_bytes());
+        format!("~audio:{:016x}@{}", id, SONG_CONFIDENCE)
+    }
+}
+
+#[cfg(feature = "audio")]
+fn judge_audio(path: &Path) -> Option<Judgement> {
+    use lofty::file::FileType;
+    use lofty::prelude::*;
+
+    let file = lofty::read_from_path(path).ok()?;
+    let properties = file.properties();
+    let lossless = match file.file_type() {
+        FileType::Flac | FileType::Wav | FileType::Aiff | FileType::Ape | FileType::WavPack => true,
+        // Only the lossless codecs (ALAC and FLAC) store a bit depth on MP4 containers.
+        FileType::Mp4 => properties.bit_depth().is_some(),
+        _ => false,
+    };
+    let bitrate = properties.audio_bitrate().or(properties.overall_bitrate())?;
+    let tag = file.primary_tag().or(file.first_tag());
+    let song = path.file_stem().map(|stem| Song {
+        stem: stem.to_string_lossy().to_lowercase(),
+        artist: tag.and_then(|tag| tag.artist()).map(|artist| artist.to_string()),
+        title: tag.and_then(|tag| tag.title()).map(|title| title.to_string()),
+        seconds: properties.duration().as_secs_f64().round() as u64,
+    });
+    Some(Judgement { rank: (lossless as u64) << 32 | bitrate as u64, song })
+}
+
+#[cfg(not(feature = "audio"))]
+fn judge_audio(_path: &Path) -> Option<Judgement> {
+    None
+}
+
+/// What a strategy proposes for a group of duplicates (as indexes of the files it was given).
+#[derive(Debug, PartialEq)]
+pub struct Suggestion {
+    pub keep: usize,
+    pub delete: Vec<usize>,
+    /// Heuristic digest of the group, when it holds the copies of a song encoded in different formats.
+    pub song: Option<String>,
+}
+
+impl Suggestion {
+    /// Gives the files of the suggestion (among the `files` it was made for) the heuristic digest of its song, if
+    /// any, so deleting them asks for a confirmation.
+    pub fn mark(&self, files: &mut [File]) {
+        let song = match &self.song {
+            Some(song) => song,
+            None => return,
+        };
+        for i in self.delete.iter().chain([&self.keep]) {
+            if !files[*i].digest.as_deref().is_some_and(|digest| digest.split('+').any(|part| part == song)) {
+                files[*i].add_digest(song);
+            }
+        }
+    }
+}
+
+/// Applies `strategy` to every group of duplicates among `files` (merged with the copies of the same song).
+/// Groups where the strategy can't judge any file are left out.
+pub fn suggest(strategy: Strategy, files: &[File]) -> Vec<Suggestion> {
+    let judgements: Vec<Option<Judgement>> = files.iter().map(|f| strategy.judge(Path::new(&*f.path()))).collect();
+    let ranks: Vec<Option<u64>> = judgements.iter().map(|judgement| judgement.as_ref().map(|j| j.rank)).collect();
+    let songs: Vec<Option<Song>> = judgements.into_iter().map(|judgement| judgement.and_then(|j| j.song)).collect();
+    suggest_ranked(files, &ranks, &songs)
+}
+
+fn suggest_ranked(files: &[File], ranks: &[Option<u64>], songs: &[Option<Song>]) -> Vec<Suggestion> {
+    let mut suggestions = vec![];
+    for (group, song) in candidates(files, songs) {
+        let ranked: Vec<usize> = group.into_iter().filter(|i| ranks[*i].is_some()).collect();
+        // The first copy found wins the ties.
+        let keep = match ranked.iter().copied().rev().max_by_key(|i| ranks[*i]) {
+            Some(keep) => keep,
+            None => continue,
+        };
+        let delete = ranked.into_iter().filter(|i| *i != keep).collect();
+        suggestions.push(Suggestion { keep, delete, song: song.map(Song::digest) });
+    }
+    suggestions
+}
+
+/// Indexes of the files judged together: every group of duplicates, merged with the files holding the same tagged
+/// song (which is returned along with the groups it merged). The groups are ordered like the duplicates, the
+/// copies like the files.
+fn candidates<'a>(files: &[File], songs: &'a [Option<Song>]) -> Vec<(Vec<usize>, Option<&'a Song>)> {
+    fn root(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+    let mut parents: Vec<usize> = (0..files.len()).collect();
+    let join = |parents: &mut Vec<usize>, a: usize, b: usize| {
+        let (a, b) = (root(parents, a), root(parents, b));
+        parents[a.max(b)] = a.min(b);
+    };
+    let duplicates = groups::duplicates(files);
+    for group in &duplicates {
+        for pair in group.indexes.windows(2) {
+            join(&mut parents, pair[0], pair[1]);
+        }
+    }
+    let mut firsts: HashMap<&Song, usize> = HashMap::new();
+    let mut merging = vec![];
+    for (i, song) in songs.iter().enumerate() {
+        if let Some(song) = song.as_ref().filter(|song| song.tagged()) {
+            match firsts.entry(song) {
+                Entry::Occupied(first) => {
+                    if root(&mut parents, *first.get()) != root(&mut parents, i) {
+                        merging.push((i, song));
+                    }
+                    join(&mut parents, *first.get(), i)
+                }
+                Entry::Vacant(first) => {
+                    first.insert(i);
+                }
+            }
+        }
+    }
+    let mut merged_songs: HashMap<usize, &Song> = HashMap::new();
+    for (i, song) in merging {
+        merged_songs.insert(root(&mut parents, i), song);
+    }
+    let mut merged: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..files.len() {
+        merged.entry(root(&mut parents, i)).or_default().push(i);
+    }
+    let order = duplicates.iter().map(|group| group.indexes[0]).chain(0..files.len());
+    let mut candidates = vec![];
+    for first in order {
+        let first = root(&mut parents, first);
+        if let Some(group) = merged.remove(&first).filter(|group| group.len() > 1) {
+            candidates.push((group, merged_songs.get(&first).copied()));
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(feature = "audio")]
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn keep_the_best_ranked_copy() {
+        // Arrange
+        let files = vec![
+            File::new("song.m4a", "./a", "2022-07-23 12:33:01", "./a/song.m4a"),
+            File::new("song.m4a", "./b", "2022-07-23 12:33:01", "./b/song.m4a"),
+            File::new("song.m4a", "./c", "2022-07-23 12:33:01", "./c/song.m4a"),
+            File::new("song.m4a", "./d", "2022-07-23 12:33:01", "./d/song.m4a"),
+            File::new("cover.jpg", "./a", "2022-07-23 12:33:01", "./a/cover.jpg"),
+            File::new("cover.jpg", "./b", "2022-07-23 12:33:01", "./b/cover.jpg"),
+        ];
+        let lossless = 1 << 32;
+        let ranks = vec![Some(256), Some(lossless | 1000), None, Some(lossless | 1000), None, None];
+
+        // Act
+        let suggestions = suggest_ranked(&files, &ranks, &vec![None; files.len()]);
+
+        // Assert
+        assert_eq!(suggestions, vec![Suggestion { keep: 1, delete: vec![0, 3], song: None }]);
+    }
+
+    #[test]
+    fn the_formats_of_a_song_are_judged_together() {
+        // Arrange
+        let files = vec![
+            File::new("song.flac", "./a", "2022-07-23 12:33:01", "./a/song.flac"),
+            File::new("song.mp3", "./b", "2022-07-23 12:33:01", "./b/song.mp3"),
+            File::new("song.mp3", "./c", "2022-07-23 12:33:01", "./c/song.mp3"),
+            File::new("intro.mp3", "./a", "2022-07-23 12:33:01", "./a/intro.mp3"),
+        ];
+        let song = |stem: &str, seconds| Song {
+            stem: String::from(stem),
+            artist: Some(String::from("Band")),
+            title: Some(String::from("Song")),
+            seconds,
+        };
+        let songs = vec![Some(song("song", 200)), Some(song("song", 200)), Some(song("song", 200))];
+        let songs: Vec<Option<Song>> = songs.into_iter().chain([Some(song("intro", 200))]).collect();
+        let ranks = vec![Some(1 << 32 | 900), Some(320), Some(128), Some(128)];
+
+        // Act
+        let suggestions = suggest_ranked(&files, &ranks, &songs);
+        // Marking the files twice (E.G: once they're regrouped) leaves them alike.
+        let mut marked = files;
+        suggestions.iter().for_each(|suggestion| suggestion.mark(&mut marked));
+        suggestions.iter().for_each(|suggestion| suggestion.mark(&mut marked));
+
+        // Assert
+        let digest = Some(song("song", 200).digest());
+        assert_eq!(suggestions, vec![Suggestion { keep: 0, delete: vec![1, 2], song: digest.clone() }]);
+        let confidences: Vec<u8> = marked.iter().map(|file| file.confidence()).collect();
+        assert_eq!(confidences, vec![SONG_CONFIDENCE, SONG_CONFIDENCE, SONG_CONFIDENCE, 100]);
+        assert_eq!(marked[1].digest, digest);
+    }
+
+    #[test]
+    fn untagged_tracks_are_not_judged_together() {
+        // Arrange
+        let files = vec![
+            File::new("01.mp3", "./a", "2022-07-23 12:33:01", "./a/01.mp3"),
+            File::new("01.flac", "./b", "2022-07-23 12:33:01", "./b/01.flac"),
+            File::new("01.ogg", "./c", "2022-07-23 12:33:01", "./c/01.ogg"),
+        ];
+        let track = |artist: Option<&str>| Song {
+            stem: String::from("01"),
+            artist: artist.map(String::from),
+            title: Some(String::from("Track 1")).filter(|_| artist.is_some()),
+            seconds: 200,
+        };
+        let songs = vec![Some(track(None)), Some(track(None)), Some(track(Some("Band")))];
+        let ranks = vec![Some(320), Some(1 << 32 | 900), Some(256)];
+
+        // Act
+        let suggestions = suggest_ranked(&files, &ranks, &songs);
+
+        // Assert
+        assert!(suggestions.is_empty());
+    }
+
+    #[test]
+    fn parse_strategy() {
+        // Act and Assert
+        assert_eq!("highest-bitrate".parse::<Strategy>().unwrap(), Strategy::HighestBitrate);
+        assert_eq!("LOSSLESS".parse::<Strategy>().unwrap(), Strategy::HighestBitrate);
+        assert!("largest".parse::<Strategy>().is_err());
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn wav_files_are_lossless() -> Result<(), std::io::Error> {
+        // Arrange
+        let scratch = Scratch::new("keep");
+        let path = scratch.join("keep.wav");
+        let samples = vec![0u8; 44100 * 2];
+        let mut wav = b"RIFF".to_vec();
+        wav.extend((36 + samples.len() as u32).to_le_bytes());
+        wav.extend(b"WAVEfmt ");
+        wav.extend(16u32.to_le_bytes());
+        wav.extend(1u16.to_le_bytes()); // PCM.
+        wav.extend(1u16.to_le_bytes()); // Mono.
+        wav.extend(44100u32.to_le_bytes());
+        wav.extend((44100u32 * 2).to_le_bytes());
+        wav.extend(2u16.to_le_bytes());
+        wav.extend(16u16.to_le_bytes());
+        wav.extend(b"data");
+        wav.extend((samples.len() as u32).to_le_bytes());
+        wav.extend(samples);
+        std::fs::write(&path, wav)?;
+
+        // Act
+        let judgement = Strategy::HighestBitrate.judge(&path);
+
+        // Assert
+        assert_eq!(judgement.map(|judgement| judgement.rank >> 32), Some(1));
+        Ok(())
+    }
+
+    /// Tags the audio file located at `path` with the song `title`.
+    #[cfg(feature = "audio")]
+    fn tag(path: &Path, title: &str) -> Result<(), Box<dyn Error>> {
+        use lofty::config::WriteOptions;
+        use lofty::prelude::*;
+        use lofty::tag::Tag;
+
+        let mut file = lofty::read_from_path(path)?;
+        let mut tag = Tag::new(file.primary_tag_type());
+        tag.set_artist(String::from("Band"));
+        tag.set_title(String::from(title));
+        file.insert_tag(tag);
+        file.save_to_path(path, WriteOptions::default())?;
+        Ok(())
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn flac_copies_are_kept_over_mp3_ones() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("keep-songs");
+        // Three seconds of FLAC: the STREAMINFO block only (44.1kHz, stereo, 16 bits, 132300 samples).
+        let mut flac = b"fLaC\x80\0\0\x22".to_vec();
+        flac.extend([0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+        flac.extend(((44100u64 << 44) | (1 << 41) | (15 << 36) | 132300).to_be_bytes());
+        flac.extend([0; 16]);
+        // Three seconds of MP3: 115 frames of 128 kbit/s at 44.1kHz.
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0x64];
+        frame.resize(417, 0);
+        let mp3 = frame.repeat(115);
+        let (lossless, lossy, other) = (root.join("song.flac"), root.join("song.mp3"), root.join("other.mp3"));
+        std::fs::write(&lossless, flac)?;
+        std::fs::write(&lossy, &mp3)?;
+        std::fs::write(&other, &mp3)?;
+        tag(&lossless, "Song")?;
+        tag(&lossy, "Song")?;
+        tag(&other, "Other song")?;
+        let folder = root.to_str().unwrap();
+        let files: Vec<File> = ["song.mp3", "song.flac", "other.mp3"]
+            .iter()
+            .map(|name| File::new(name, folder, "2022-07-23 12:33:01", root.join(name).to_str().unwrap()))
+            .collect();
+
+        // Act
+        let suggestions = suggest(Strategy::HighestBitrate, &files);
+
+        // Assert
+        assert_eq!(suggestions.iter().map(|s| (s.keep, s.delete.clone())).collect::<Vec<_>>(), vec![(1, vec![0])]);
+        assert!(suggestions[0].song.is_some());
+        Ok(())
+    }
+}
@@ -18,86 +18,1501 @@
 //! Then, the program will proceed to expose the list of the
 //! found files (along with their `creation_date` and their `relative path`) and ask you if you want to keep all of them,
 //! if that's not the case it will help you with the deletion process.
+//!
+//! The subcommands (`scan`, `report`, `query`, `copy`...) and every flag are described on the README, while
+//! `dir_cleaner --help` (or `dir_cleaner <subcommand> --help`) prints how they're invoked (see [`cli`]).
+//!
+//! ## Library
+//!
+//! What the command line does is available to the library users as well:
+//!
+//! - [`scanner::Scanner`] scans one or several roots, gathering the files whose name is looked for and comparing
+//!   them as the command line does, along with its [`warnings`]. The [`limits::Limits`] leave out directories and
+//!   files (by size, date, extension, type, `.gitignore`...), and the hidden entries are scanned unless
+//!   [`limits::Limits::skip_hidden`] is set.
+//! - [`hash`] compares the files by content (and the `documents` and `video` modules by their text and frames),
+//!   reusing the digests of the [`cache`]; [`photos`] and [`siblings`] narrow the search instead.
+//! - [`groups`] splits the found files into groups of duplicates, and [`keep`] and [`policy`] tell which copies
+//!   are kept.
+//! - [`verification`] checks the planned deletions (never the last copy of a content), and [`trash`],
+//!   [`containment`] and [`paranoid`] guard the deletions themselves.
+//! - [`session`], [`manifest`], [`decisions`], [`actions`] and [`history`] read and write the documents kept on
+//!   disk, versioned by [`schema`].
+//! - [`output`] and [`report`] write the found files, and [`warnings`] and [`errors`] what went wrong.
+//! - [`vfs`] abstracts the file system the scans go through, so they can run on an in-memory tree.
+//!
+//! ## Features
+//!
+//! - `tui`: the two-pane interface of `--tui` and `--live` (the `tui` module).
+//! - `documents`: the comparison of PDF and DOCX files by their text (`--compare-text`).
+//! - `video`: the comparison of videos by their frames (`--compare-video`, through `ffmpeg`).
+//! - `audio`: the `--keep highest-bitrate` strategy.
+
+pub mod accounting;
+pub mod actions;
+pub mod apps;
+pub mod cache;
+pub mod cli;
+pub mod clipboard;
+pub mod cloud;
+pub mod companions;
+pub mod config;
+pub mod consolidate;
+pub mod containment;
+pub mod copy;
+pub mod dates;
+pub mod decisions;
+pub mod drives;
+pub mod elevate;
+pub mod errors;
+#[cfg(feature = "documents")]
+pub mod documents;
+pub mod estimate;
+pub mod exclude;
+pub mod extensions;
+pub mod flags;
+pub mod gitignore;
+pub mod glob;
+pub mod groups;
+pub mod hash;
+pub mod interrupt;
+pub mod history;
+pub mod incremental;
+pub mod keep;
+pub mod limits;
+pub mod links;
+pub mod listing;
+pub mod manifest;
+pub mod mime;
+pub mod output;
+pub mod overlap;
+pub mod ownership;
+pub mod paranoid;
+pub mod pause;
+pub mod photos;
+pub mod policy;
+pub mod progress;
+pub mod prompt;
+pub mod provenance;
+pub mod query;
+pub mod relocate;
+pub mod rename;
+pub mod repos;
+pub mod safety;
+pub mod regex;
+pub mod regroup;
+pub mod report;
+pub mod risks;
+pub mod sampling;
+pub mod scanner;
+pub mod schedule;
+pub mod schema;
+#[cfg(test)]
+mod scratch;
+pub mod secrets;
+pub mod session;
+pub mod siblings;
+pub mod storage;
+pub mod streams;
+pub mod summaries;
+pub mod terminal;
+pub mod theme;
+pub mod trash;
+mod blake3;
+mod checksum;
+mod intern;
+pub mod units;
+pub mod verification;
+pub mod vfs;
+pub mod warnings;
+#[cfg(feature = "video")]
+pub mod video;
+#[cfg(feature = "tui")]
+pub mod tui;
 
 use chrono::{DateTime, Utc};
-use std::{fs, fmt};
-use std::ops::Not;
+use cli::{Command, ConflictError, Options};
+use companions::Companions;
+use actions::{Action, Recorder};
+use decisions::{Decision, Decisions};
+use output::Output;
+use limits::{Limits, Skipped};
+use serde::{Deserialize, Serialize};
+use intern::Interner;
+use theme::Theme;
+use vfs::Vfs;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::{env, fs};
 use std::error::Error;
-use std::fs::Metadata;
-use std::path::PathBuf;
 use std::io;
-use std::fmt::Formatter;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Proceeds to gather the `files` located on the directory represented by the `path` specified through `args`,
+/// as long as they match the `name` provided by the user when prompted to do. Once the information
+/// of the different `files` is collected, the user will have the option to `keep` or `delete` all of them.
+pub fn run(args: impl Iterator<Item=String>) -> Result<(), Box<dyn Error>> {
+    let mut options = Options::parse(args)?;
+    if options.help {
+        print!("{}", options.command.help());
+        return Ok(());
+    }
+    errors::report_as(options.output);
+    if options.only_mine {
+        ownership::check_supported()?;
+    }
+    let config = config::Config::load(options.config.as_deref())?;
+    options.limits.skip_hidden = options.skip_hidden.unwrap_or(config.scan.skips_hidden(options.command));
+    // Nothing changes the options from now on.
+    let options = options;
+    let directory = &options.directory;
+    let mut theme = Theme::from_config(&config.theme)?;
+    theme.enabled = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none() && !options.plain;
+    if let (Some(timeout), Some(default)) = (options.prompt_timeout, options.default_answer) {
+        prompt::unattend(prompt::Unattended { timeout, default, audit_log: options.audit_log.clone() });
+    }
+    if options.yes {
+        let default = prompt::DefaultAnswer::Keep;
+        prompt::unattend(prompt::Unattended { timeout: Duration::ZERO, default, audit_log: options.audit_log.clone() });
+    }
+    if let Some(unattended) = unattended_when_piped(&options, io::stdout().is_terminal())? {
+        prompt::unattend(unattended);
+    }
+
+    if options.command == Command::Query {
+        return run_query(&options, &theme);
+    }
+    if options.command == Command::Migrate {
+        let path = options.session.as_deref().unwrap_or_default();
+        match session::Session::migrate(path)? {
+            Some(version) => println!(
+                "Migrated `{}` from version {} (the original was kept as `{}.v{}.bak`).",
+                path, version, path, version
+            ),
+            None => println!("`{}` already uses the current version of the format.", path),
+        }
+        return Ok(());
+    }
+    if options.command == Command::Copy || options.command == Command::Consolidate {
+        return run_copy(&options);
+    }
+    if options.command == Command::ExportHashes || options.command == Command::ImportHashes {
+        return run_hashes(&options);
+    }
+    if options.command == Command::Stats {
+        return run_stats(&options);
+    }
+    // Read before the scan, so a typo doesn't waste it.
+    let policies = match options.policies.as_slice() {
+        [first, second] => Some((policy::Policy::load(first)?, policy::Policy::load(second)?)),
+        _ => None,
+    };
+    let companions = Companions::from_config(&config.companions);
+    if options.estimate {
+        for root in &options.roots {
+            println!("{}", estimate::measure(root, &options.limits)?.summary());
+        }
+        let answer = prompt::ask("Do you want to proceed with the scan? \n(y/n)", "n");
+        if !answer.trim().eq("y") {
+            println!("Good Bye!");
+            return Ok(());
+        }
+    }
+
+    let name = match &options.name {
+        _ if options.cull_jpegs => String::from("JPEGs with a RAW sibling"),
+        Some(name) => name.to_string(),
+        None if options.by_content => String::from("files with the same content"),
+        None if options.glob => prompt::ask("Please, provide the pattern of the file names to search (E.G: *.log)", ""),
+        None if options.regex => prompt::ask("Please, provide the expression the file names must match", ""),
+        None => prompt::ask(
+            "Please, provide the name of the file you want to search (including its file extension)",
+            "",
+        ),
+    };
+    let name = name.trim();
+    let pattern = options.glob.then(|| name.parse::<glob::Pattern>()).transpose()?;
+    let expression = options.regex.then(|| name.parse::<regex::Regex>()).transpose()?;
+    let matches = |file_name: &str| match (&pattern, &expression) {
+        (Some(pattern), _) => pattern.matches(file_name),
+        (_, Some(expression)) => expression.is_match(file_name),
+        _ => file_name == name,
+    };
+
+    let compares_contents = options.by_content || options.compare_text || options.compare_video;
+    let mut cache = open_cache(&options, compares_contents || options.reference_manifest.is_some());
+    if let Some(flush) = options.flush {
+        cache.flush_every(flush);
+    }
+    let reading = storage::Reading {
+        readers: storage::readers_for(Path::new(directory), options.storage, options.readers, options.threads),
+        cache: &cache,
+        include_placeholders: options.include_placeholders,
+        algorithm: options.hash,
+    };
+    // The files found by content only, or culled, didn't match any name.
+    let criterion: Option<Arc<str>> = match options.cull_jpegs || options.name.is_none() && options.by_content {
+        true => None,
+        false => Some(Arc::from(provenance::criterion(name, options.glob, options.regex))),
+    };
+    #[cfg(feature = "tui")]
+    if options.tui && options.plain {
+        let message = "The interactive interface can't be used with --plain, which avoids control sequences.";
+        return Err(ConflictError(message).into());
+    }
+    #[cfg(feature = "tui")]
+    if options.tui && options.prompt_timeout.is_some() {
+        let message = "The interactive interface doesn't time out, --prompt-timeout only applies to the prompts.";
+        return Err(ConflictError(message).into());
+    }
+    #[cfg(feature = "tui")]
+    if options.tui && options.delete_all {
+        let message = "The interactive interface can't be used with --delete-all, which doesn't ask anything.";
+        return Err(ConflictError(message).into());
+    }
+    #[cfg(feature = "tui")]
+    if options.tui && options.all_drives {
+        return Err(ConflictError("The interactive interface can't browse several drives at once yet.").into());
+    }
+    #[cfg(feature = "tui")]
+    if options.live {
+        let search = tui::live::Search {
+            root: directory,
+            matches: &matches,
+            criterion,
+            limits: &options.limits,
+            only_mine: options.only_mine,
+        };
+        return tui::live::review(search, &options, &config);
+    }
+    // The TUI reads the terminal on its own, the standard input can't be handed to the controls then (nor can the
+    // JSON or CSV outputs be mixed with their messages).
+    let interactive = !options.yes && !options.tui && options.output == Output::Text;
+    let controls = pause::Controls::start(io::stdin().is_terminal() && interactive);
+    let guard = interrupt::guard();
+    // The summaries of the directories are printed on the same stream, they'd be mixed with the progress.
+    let shows_progress = !options.no_progress && !options.limits.dir_summaries && io::stderr().is_terminal();
+    let progress = progress::Display::start(shows_progress);
+    let incremental = match &options.incremental {
+        Some(path) => Some(incremental::Incremental::new(incremental::Snapshot::load(path)?, &vfs::Local)),
+        None => None,
+    };
+    let listing: &dyn Vfs = match &incremental {
+        Some(incremental) => incremental,
+        None => &vfs::Local,
+    };
+    let scanner = options.roots.iter().fold(scanner::Scanner::default(), |scanner, root| scanner.root(root));
+    let scanner = match criterion.is_some() {
+        true => scanner.matching(matches),
+        false => scanner,
+    };
+    let comparison = scanner::Comparison {
+        content: options.by_content,
+        text: options.compare_text,
+        video: options.compare_video,
+        siblings: options.compare_siblings,
+    };
+    let scanner = scanner
+        .limits(options.limits.clone())
+        .vfs(listing)
+        .cull_jpegs(options.cull_jpegs)
+        .only_mine(options.only_mine)
+        .comparison(comparison)
+        .reading(&reading);
+    let scanner = match options.min_confidence {
+        Some(min) => scanner.min_confidence(min),
+        None => scanner,
+    };
+    let mut scan = scanner.list()?;
+    scan.files.iter_mut().for_each(|file| file.matched_by = criterion.clone());
+    if let (Some(path), Some(incremental)) = (&options.incremental, &incremental) {
+        incremental.snapshot().save(path)?;
+    }
+    let reused = incremental.as_ref().map_or(0, incremental::Incremental::reused);
+    if options.flush.is_some() {
+        // The found files are kept as well, so only the digests are left to compute after a crash.
+        if let Some(path) = &options.save {
+            let session = session::Session::new(directory, name, scan.files);
+            session.save(path)?;
+            scan.files = session.files;
+        }
+    }
+    scanner.compare(&mut scan);
+    let scanner::Scan { files: mut files_info, collapsed, others, warnings, .. } = scan;
+    if let Some(location) = &options.reference_manifest {
+        let manifest = manifest::Manifest::load(location)?;
+        let summary = manifest.summary(&manifest.matching(&files_info, &reading));
+        match options.command == Command::Report || options.output != Output::Text {
+            true => eprint!("{}", summary),
+            false => print!("{}", summary),
+        }
+    }
+    drop(progress);
+    drop(controls);
+    drop(guard);
+    // The scan warned about its interruption already.
+    interrupt::take();
+    cache.save()?;
+    if options.io_usage {
+        for root in &options.roots {
+            let summary = accounting::SCAN.summary(root);
+            // The reports and the JSON output are meant to be processed further, the summary stays out of them.
+            match options.command == Command::Report || options.output != Output::Text {
+                true => eprint!("{}", summary),
+                false => print!("{}", summary),
+            }
+        }
+    }
+    if let Some(path) = &options.save {
+        let session = session::Session::new(directory, name, files_info);
+        session.save(path)?;
+        files_info = session.files;
+    }
+    // A run that can't be recorded is still worth reviewing.
+    if let Err(err) = record_run(&options, name, &files_info) {
+        eprintln!("{}", theme.paint(theme.warning, &format!("Couldn't record the run: {}", err)));
+    }
+    if options.command == Command::Report {
+        print!("{}", report::render(options.format, directory, name, &files_info, &warnings));
+        return Ok(());
+    }
+    if let Some((first, second)) = &policies {
+        let comparison = policy::compare(&files_info, first, second);
+        print!("{}", comparison.render(&files_info, &options.policies[0], &options.policies[1]));
+        warnings.write(&mut io::stderr().lock(), Output::Text)?;
+        return Ok(());
+    }
+    if options.output != Output::Text || options.command == Command::Scan {
+        options.output.write_files_dated(&mut io::stdout().lock(), &files_info, &theme, &options.date_format)?;
+        warnings.write(&mut io::stderr().lock(), options.output)?;
+        return Ok(());
+    }
+    #[cfg(feature = "tui")]
+    if options.tui {
+        return tui::run(files_info, &options, &config, None);
+    }
+
+    let mut decisions = match &options.decisions {
+        Some(path) => Decisions::load(path)?,
+        None => Decisions::default(),
+    };
+    let tolerated = files_info.len();
+    files_info.retain(|file| decisions.get(file) != Some(Decision::Keep));
+    let tolerated = tolerated - files_info.len();
+    // The most convincing matches are reviewed first (the sort is stable, so exact matches keep their order).
+    files_info.sort_by_key(|file| Reverse(file.confidence()));
+
+    for warning in warnings.iter() {
+        println!("{}", theme.paint(theme.warning, &warning.message));
+    }
+    if tolerated > 0 {
+        let note = format!("{} entry(ies) left out, you chose to keep them on an earlier run.", tolerated);
+        println!("{}", theme.paint(theme.warning, &note));
+    }
+    if others > 0 {
+        println!("{}", theme.paint(theme.warning, &format!("{} file(s) owned by other users were left out.", others)));
+    }
+    if collapsed > 0 {
+        let note = format!("{} file(s) reached through several roots or links were only counted once.", collapsed);
+        println!("{}", theme.paint(theme.warning, &note));
+    }
+    if reused > 0 {
+        println!("{} directory(ies) unchanged since the last scan weren't listed again.", reused);
+    }
+    let (page_size, layout) = listing_layout(&options);
+    show_page(&files_info, 0, page_size, &theme, layout);
+    for (i, file) in files_info.iter().enumerate() {
+        if let Some(repository) = repos::repository_of(Path::new(&*file.path())) {
+            let note = format!(
+                "Entry {} lives in the git repository `{}`, deleting it leaves a dirty checkout.",
+                i + 1,
+                repository.display()
+            );
+            println!("{}", theme.paint(theme.warning, &note));
+        }
+        if let Some(blocker) = flags::removal_blocker(Path::new(&*file.path())) {
+            println!("{}", theme.paint(theme.warning, &format!("Entry {} can't be deleted: {}.", i + 1, blocker)));
+        }
+    }
+    if options.check_links {
+        let references = links::References::find(&options.roots, &files_info);
+        for (i, file) in files_info.iter().enumerate() {
+            let referrers: Vec<String> =
+                references.of(Path::new(&*file.path())).iter().map(|r| format!("`{}`", r.display())).collect();
+            if !referrers.is_empty() {
+                let referrers = referrers.join(", ");
+                let note = format!("Entry {} is pointed to by {}, deleting it breaks them.", i + 1, referrers);
+                println!("{}", theme.paint(theme.warning, &note));
+            }
+        }
+    }
+    if options.check_secrets {
+        for (i, file) in files_info.iter().enumerate() {
+            if let Some(warning) = secrets_warning(file) {
+                println!("{}", theme.paint(theme.warning, &format!("Entry {} {}", i + 1, warning)));
+            }
+        }
+    }
+    let suggested = suggestions(&mut files_info, &options, &decisions, &theme);
+    if options.copy {
+        copy_entries(&files_info, "");
+    }
 
-struct DirInfo {
-    metadata: Metadata,
-    path_buf: PathBuf,
+    if options.delete_all {
+        return delete_all(files_info, &options, &companions, &mut decisions, &theme);
+    }
+    if let Some(log) = &options.replay {
+        return replay(files_info, &actions::load(log)?, &options, &companions, &mut decisions, &theme);
+    }
+    review(files_info, suggested, name, &options, &companions, &mut decisions, &theme)
 }
 
-#[derive(Debug,Clone)]
-struct ArgsError;
+/// Reviews the found `files` on the interactive loop until the user is done, the `suggested` deletions being applied
+/// through `auto` (see [`apply_suggestions`]).
+fn review(
+    mut files: Vec<File>,
+    mut suggested: Vec<(String, &'static str)>,
+    name: &str,
+    options: &Options,
+    companions: &Companions,
+    decisions: &mut Decisions,
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>> {
+    let (page_size, layout) = listing_layout(options);
+    let mut page = 0;
+    let recorder = options.record.as_deref().map(Recorder::create).transpose()?;
+    let mut tally = Tally { recorder, ..Tally::default() };
+    let answer = prompt::ask("Do you want to keep every file? \n(y/n)", "y");
+    if answer.trim().eq("y") {
+        println!("Good Bye!");
+        return Ok(());
+    }
 
-impl Error for ArgsError {}
+    loop {
+        let answer = prompt::ask(
+            "Please provide the number associated to the file you want to delete.\n\
+            Write copy (optionally followed by some numbers) to copy their paths to the clipboard.\n\
+            Write auto to delete the files suggested by --keep (or --cull-jpegs, or your earlier decisions).\n\
+            Write sample (optionally followed by a count) to review some groups picked at random.\n\
+            Write keep followed by a number to stop asking about that entry on the next runs (with --decisions).\n\
+            Write keep-as followed by a number and a name to delete the other copies and rename that one.\n\
+            Write split followed by some numbers to move them out of their group, or merge followed by two numbers\n\
+            to move the group of the second one to the group of the first one.\n\
+            Write more (or page followed by a number) to list other entries.\n\
+            Write done to quit",
+            "done",
+        );
+        let cleaned_answer = answer.trim();
+        if cleaned_answer.eq("done") {
+            println!("Good Bye!");
+            break;
+        }
+        let pages = listing::Page::count(files.len(), page_size);
+        if cleaned_answer.eq("more") {
+            page = (page + 1) % pages;
+            show_page(&files, page, page_size, theme, layout);
+            continue;
+        }
+        if let Some(number) = cleaned_answer.strip_prefix("page ") {
+            match number.trim().parse::<usize>() {
+                Ok(number) if number >= 1 && number <= pages => {
+                    page = number - 1;
+                    show_page(&files, page, page_size, theme, layout);
+                }
+                _ => println!("Please provide a page between 1 and {}.", pages),
+            }
+            continue;
+        }
+        if cleaned_answer.eq("auto") {
+            let suggested = std::mem::take(&mut suggested);
+            if apply_suggestions(&mut files, suggested, options, companions, decisions, &mut tally, theme)? {
+                break;
+            }
+            continue;
+        }
+        if let Some(count) = cleaned_answer.strip_prefix("sample") {
+            let count = match count.trim() {
+                "" => Ok(sampling::SAMPLE_SIZE),
+                count => count.parse::<usize>(),
+            };
+            match count {
+                Ok(count) => {
+                    let seed = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+                    let paths: Vec<String> = suggested.iter().map(|(path, _)| path.clone()).collect();
+                    print!("{}", sampling::review(&files, count, &paths, seed));
+                }
+                Err(_) => println!("Please provide the amount of groups to review."),
+            }
+            continue;
+        }
+        let regrouped = match cleaned_answer.split_once(' ') {
+            Some(("split", numbers)) => Some(parse_entries(numbers).and_then(|indexes| {
+                regroup::split(&mut files, &indexes).map(|_| format!("{} entry(ies) split.", indexes.len()))
+            })),
+            Some(("merge", numbers)) => Some(parse_entries(numbers).and_then(|indexes| match indexes[..] {
+                [into, from] => {
+                    regroup::merge(&mut files, into, from).map(|moved| format!("{} entry(ies) merged.", moved))
+                }
+                _ => Err(String::from("Please provide two of the listed numbers")),
+            })),
+            _ => None,
+        };
+        if let Some(regrouped) = regrouped {
+            match regrouped {
+                Ok(note) => {
+                    println!("{}", note);
+                    if let Some(path) = &options.save {
+                        let session = session::Session::new(&options.directory, name, std::mem::take(&mut files));
+                        session.save(path)?;
+                        files = session.files;
+                    }
+                    // The suggestions were made for the former groups.
+                    suggested = suggestions(&mut files, options, decisions, theme);
+                    show_page(&files, page, page_size, theme, layout);
+                }
+                Err(err) => println!("{}.", err),
+            }
+            continue;
+        }
+        if let Some(arguments) = cleaned_answer.strip_prefix("keep-as ") {
+            let (number, new_name) = arguments.trim().split_once(' ').unwrap_or((arguments, ""));
+            match number.parse::<usize>() {
+                Ok(index) if index > 0 && index <= files.len() => {
+                    let (kept, before) = (files[index - 1].path().into_owned(), files.len());
+                    let bytes = keep_as(
+                        &mut files,
+                        index - 1,
+                        new_name.trim(),
+                        options,
+                        companions,
+                        &mut tally.removals,
+                        decisions,
+                    )?;
+                    tally.reclaimed.add("keep-as", bytes);
+                    if files.len() < before || files.iter().all(|file| !file.is_at(&kept)) {
+                        let name = String::from(new_name.trim());
+                        record(&mut tally.recorder, Action::KeepAs { path: recorded_path(options, &kept), name })?;
+                    }
+                    if interrupt::take() {
+                        break;
+                    }
+                }
+                _ => println!("Please provide one of the listed numbers, followed by the new name!"),
+            }
+            continue;
+        }
+        if let Some(number) = cleaned_answer.strip_prefix("keep ") {
+            let path = match &options.decisions {
+                Some(path) => path,
+                None => {
+                    println!("Pass --decisions <file> in order to remember what you keep.");
+                    continue;
+                }
+            };
+            match number.trim().parse::<usize>() {
+                Ok(index) if index > 0 && index <= files.len() => {
+                    let kept = recorded_path(options, &files[index - 1].path());
+                    record(&mut tally.recorder, Action::Keep { path: kept })?;
+                    decisions.record(&files.remove(index - 1), Decision::Keep);
+                    decisions.save(path)?;
+                    println!("Entry {} kept, it won't be listed on the next runs.", index);
+                }
+                _ => println!("Please provide one of the listed numbers!"),
+            }
+            continue;
+        }
+        if let Some(numbers) = cleaned_answer.strip_prefix("copy") {
+            if numbers.is_empty() || numbers.starts_with(char::is_whitespace) {
+                copy_entries(&files, numbers);
+                continue;
+            }
+        }
+        let index = cleaned_answer.parse::<usize>();
+        let index = match index {
+            Ok(index) => index,
+            Err(_) => {
+                println!("Invalid number provided.");
+                continue;
+            }
+        };
+        //let file = &files[&index - 1];
+        if index > files.len() || index == 0 {
+            println!("Please provide one of the listed numbers!");
+            break;
+        }
+        let (path, before) = (files[index - 1].path().into_owned(), files.len());
+        let bytes =
+            delete_entry(&mut files, index - 1, options, companions, &mut tally.removals, decisions)?;
+        tally.reclaimed.add("by hand", bytes);
+        if files.len() < before {
+            record(&mut tally.recorder, Action::Delete { path: recorded_path(options, &path) })?;
+        }
+        if interrupt::take() {
+            break;
+        }
+    }
+    if !tally.reclaimed.is_empty() {
+        println!("{}", tally.reclaimed);
+    }
+    Ok(())
+}
+
+/// What the deletions of a review keep track of.
+#[derive(Default)]
+struct Tally {
+    /// Removals left to `git rm` (see `--git-rm-script`).
+    removals: repos::Removals,
+    reclaimed: report::Reclaimed,
+    /// The `--record` log, when there's one.
+    recorder: Option<Recorder>,
+}
+
+/// Applies the deletions `suggested` by the rules (see [`suggestions`]) to the found `files`: once the conflicts
+/// between the rules are resolved, the plan is checked (see [`verification::Planned`]) and confirmed by the user.
+/// Returns whether the cleanup was interrupted.
+fn apply_suggestions(
+    files: &mut Vec<File>,
+    mut suggested: Vec<(String, &'static str)>,
+    options: &Options,
+    companions: &Companions,
+    decisions: &mut Decisions,
+    tally: &mut Tally,
+    theme: &Theme,
+) -> Result<bool, Box<dyn Error>> {
+    if !resolve_conflicts(&mut suggested, companions) {
+        println!("Nothing deleted.");
+        return Ok(false);
+    }
+    let paths = suggested.iter().map(|(path, _)| path.as_str());
+    let planned = match verification::Planned::new(files, paths, options.allow_delete_all) {
+        Ok(planned) => planned,
+        Err(err) => {
+            println!("{}", theme.paint(theme.warning, &err.to_string()));
+            return Ok(false);
+        }
+    };
+    if !confirm_plan(files, &suggested, options, theme) {
+        println!("Nothing deleted.");
+        return Ok(false);
+    }
+    let guard = interrupt::guard();
+    for (path, rule) in suggested {
+        if interrupt::interrupted() {
+            break;
+        }
+        if let Some(index) = files.iter().position(|f| f.is_at(&path)) {
+            let before = files.len();
+            let bytes = delete_entry(files, index, options, companions, &mut tally.removals, decisions)?;
+            tally.reclaimed.add(rule, bytes);
+            if files.len() < before {
+                record(&mut tally.recorder, Action::Delete { path: recorded_path(options, &path) })?;
+            }
+        }
+    }
+    drop(guard);
+    if interrupt::take() {
+        println!("Interrupted, the other suggested deletions were left out.");
+        return Ok(true);
+    }
+    if let Some(count) = options.verify_sample {
+        verify_kept(&planned, files, count, theme);
+    }
+    Ok(false)
+}
+
+/// Entries listed at once, and how they're laid out.
+fn listing_layout(options: &Options) -> (usize, listing::Layout) {
+    let width = terminal::width().filter(|_| !options.plain);
+    let layout = listing::Layout { width, icons: options.icons && width.is_some(), plain: options.plain };
+    (options.page_size.unwrap_or(listing::PAGE_SIZE), layout)
+}
+
+/// How the prompts behave when the standard output isn't a `terminal` (E.G: `dir_cleaner . --name a.log | tee
+/// found.txt`): nobody may read them, so they get the default answer right away (keeping the files) instead of
+/// waiting for the standard input. The runs that would have to ask for the name fail instead, and the ones whose
+/// prompts already time out (`--prompt-timeout`) or don't ask anything are left alone.
+fn unattended_when_piped(options: &Options, terminal: bool) -> Result<Option<prompt::Unattended>, Box<dyn Error>> {
+    let scans = options.command.looks_for_duplicates();
+    if terminal || !scans || options.yes || options.prompt_timeout.is_some() {
+        return Ok(None);
+    }
+    #[cfg(feature = "tui")]
+    if options.tui {
+        let message = "The interactive interface needs a terminal, but the standard output isn't one.";
+        return Err(ConflictError(message).into());
+    }
+    if options.name.is_none() && !options.by_content && !options.cull_jpegs {
+        let message = "The standard output isn't a terminal, so the name must be provided through `--name`.";
+        return Err(ConflictError(message).into());
+    }
+    let default = options.default_answer.unwrap_or(prompt::DefaultAnswer::Keep);
+    Ok(Some(prompt::Unattended { timeout: Duration::ZERO, default, audit_log: options.audit_log.clone() }))
+}
 
-impl fmt::Display for ArgsError {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "Insufficient arguments provided.\n Usage: `low_level_four |relative_path_to_folder|`")
+/// Records `action` on the `--record` log, when there's one.
+fn record(recorder: &mut Option<Recorder>, action: Action) -> Result<(), io::Error> {
+    match recorder {
+        Some(recorder) => recorder.record(&action),
+        None => Ok(()),
     }
 }
 
-/// Proceeds to gather the `files` located on the directory represented by the `path` specified through `args`,
-/// as long as they match the `name` provided by the user when prompted to do. Once the information
-/// of the different `files` is collected, the user will have the option to `keep` or `delete` all of them.
-pub fn run(mut args: impl Iterator<Item=String>) -> Result<(), Box<dyn Error>> {
-    args.next();
+/// Path of the found file located at `path`, relative to the directory it was found under.
+fn recorded_path(options: &Options, path: &str) -> String {
+    actions::relative(root_of(options, Path::new(path)), Path::new(path))
+}
+
+/// Applies the decisions `recorded` on a `--replay` log to the found files, once the user confirms it (and once
+/// checked that they leave a copy of every content behind, see [`verification::Planned`]). The decisions about
+/// files this scan didn't find are pointed out and left out, and the deletions still ask for the confirmations
+/// they need (secrets, heuristic matches, companions...).
+fn replay(
+    mut files: Vec<File>,
+    recorded: &[Action],
+    options: &Options,
+    companions: &Companions,
+    decisions: &mut Decisions,
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>> {
+    // The recorded path is looked for under every root, so the files aren't joined to their path one by one.
+    let position = |files: &[File], action: &Action| {
+        options.roots.iter().find_map(|root| {
+            let joined = Path::new(root).join(action.path());
+            let path = joined.to_str()?;
+            let targeted = || action.targets(root_of(options, &joined), &joined);
+            files.iter().position(|file| file.is_at(path)).filter(|_| targeted())
+        })
+    };
+    let mut applicable = vec![];
+    for action in recorded {
+        match position(&files, action) {
+            Some(_) => {
+                println!("Replaying: {}", action);
+                applicable.push(action);
+            }
+            None => {
+                let note = format!("`{}` wasn't found by this scan, its decision is left out.", action.path());
+                println!("{}", theme.paint(theme.warning, &note));
+            }
+        }
+    }
+    if applicable.is_empty() {
+        println!("Nothing to replay.");
+        return Ok(());
+    }
+    let doomed: Vec<String> = applicable
+        .iter()
+        .filter(|action| matches!(action, Action::Delete { .. }))
+        .filter_map(|action| position(&files, action).map(|i| files[i].path().into_owned()))
+        .collect();
+    verification::Planned::new(&files, doomed.iter().map(String::as_str), options.allow_delete_all)?;
+    let answer = prompt::ask(&format!("Apply these {} recorded decision(s)? (y/n)", applicable.len()), "n");
+    if !answer.trim().eq("y") {
+        println!("Nothing deleted.");
+        return Ok(());
+    }
+    let (mut removals, mut reclaimed) = (repos::Removals::default(), report::Reclaimed::default());
+    let guard = interrupt::guard();
+    for action in applicable {
+        if interrupt::interrupted() {
+            break;
+        }
+        // An earlier decision may have dealt with the file already (E.G: the copies deleted by `keep-as`).
+        let index = match position(&files, action) {
+            Some(index) => index,
+            None => continue,
+        };
+        match action {
+            Action::Delete { .. } => {
+                let bytes = delete_entry(&mut files, index, options, companions, &mut removals, decisions)?;
+                reclaimed.add("replay", bytes);
+            }
+            Action::Keep { path } => match &options.decisions {
+                Some(log) => {
+                    decisions.record(&files.remove(index), Decision::Keep);
+                    decisions.save(log)?;
+                }
+                None => println!("Pass --decisions <file> in order to remember what you keep, `{}` was left.", path),
+            },
+            Action::KeepAs { name, .. } => {
+                let bytes = keep_as(&mut files, index, name, options, companions, &mut removals, decisions)?;
+                reclaimed.add("replay", bytes);
+            }
+        }
+    }
+    drop(guard);
+    if interrupt::take() {
+        println!("Interrupted, the other recorded decisions were left out.");
+    }
+    if !reclaimed.is_empty() {
+        println!("{}", reclaimed);
+    }
+    Ok(())
+}
+
+/// Indexes of the entries whose numbers are listed on `numbers` (separated by spaces or commas).
+fn parse_entries(numbers: &str) -> Result<Vec<usize>, String> {
+    let numbers = numbers.split(|c: char| c.is_whitespace() || c == ',').filter(|number| !number.is_empty());
+    let indexes: Option<Vec<usize>> =
+        numbers.map(|number| number.parse::<usize>().ok().filter(|n| *n > 0).map(|n| n - 1)).collect();
+    match indexes {
+        Some(indexes) if !indexes.is_empty() => Ok(indexes),
+        _ => Err(String::from("Please provide some of the listed numbers")),
+    }
+}
+
+/// Deletions suggested by `--keep`, `--cull-jpegs` and the earlier decisions, printed along with the entries left for
+/// the user to review. The copies of a song matched across formats are marked as heuristic matches (see
+/// [`keep::Suggestion::mark`]).
+fn suggestions(
+    files: &mut [File],
+    options: &Options,
+    decisions: &Decisions,
+    theme: &Theme,
+) -> Vec<(String, &'static str)> {
+    let confident = |file: &File| options.min_confidence.is_none_or(|min| file.confidence() >= min);
+    let mut suggested = vec![];
+    if let Some(strategy) = options.keep {
+        for suggestion in keep::suggest(strategy, files) {
+            suggestion.mark(files);
+            let numbers: Vec<String> = suggestion.delete.iter().map(|i| (i + 1).to_string()).collect();
+            if !confident(&files[suggestion.keep]) {
+                let note = format!(
+                    "{}: entries {} are left for you to review, their match is only {}% confident",
+                    strategy.name(),
+                    numbers.join(", "),
+                    files[suggestion.keep].confidence()
+                );
+                println!("{}", theme.paint(theme.warning, &note));
+                continue;
+            }
+            println!(
+                "{}",
+                theme.paint(
+                    theme.duplicate,
+                    &format!(
+                        "{}: keep entry {}, delete entries {}",
+                        strategy.name(),
+                        suggestion.keep + 1,
+                        numbers.join(", ")
+                    )
+                )
+            );
+            suggested.extend(suggestion.delete.into_iter().map(|i| (files[i].path().into_owned(), strategy.name())));
+        }
+    }
+    for (i, file) in files.iter().enumerate() {
+        if decisions.get(file) == Some(Decision::Delete) && confident(file) {
+            let note = format!("Entry {}: you deleted this copy from its folder on an earlier run.", i + 1);
+            println!("{}", theme.paint(theme.duplicate, &note));
+            suggested.push((file.path().into_owned(), "earlier decisions"));
+        }
+    }
+    if options.cull_jpegs {
+        println!("{} JPEG(s) have a RAW sibling.", files.len());
+        suggested.extend(files.iter().map(|f| (f.path().into_owned(), "cull-jpegs")));
+    }
+    suggested
+}
+
+/// Deletes every found file (but the oldest copy of every group of duplicates with `--keep-one`), without going
+/// through the interactive loop, once the user confirms it (right away with `--yes`). The files a check kept are
+/// listed once done, and fail the unattended runs (see [`verification::KeptError`]).
+fn delete_all(
+    mut files: Vec<File>,
+    options: &Options,
+    companions: &Companions,
+    decisions: &mut Decisions,
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>> {
+    let groups = match options.keep_one {
+        true => groups::all(&files),
+        false => vec![],
+    };
+    // The first copy found wins the ties.
+    let oldest = groups.iter().filter_map(|group| group.files().min_by_key(|file| &file.creation_date));
+    let kept: HashSet<String> = oldest.map(|file| file.path().into_owned()).collect();
+    let doomed: Vec<String> =
+        files.iter().map(|file| file.path().into_owned()).filter(|path| !kept.contains(path)).collect();
+    if !kept.is_empty() {
+        println!("{} file(s) kept, the oldest copy of every group.", kept.len());
+    }
+    if doomed.is_empty() {
+        println!("Nothing to delete.");
+        return Ok(());
+    }
+    let planned = verification::Planned::new(&files, doomed.iter().map(String::as_str), options.allow_delete_all)?;
+    if !options.yes {
+        let answer = prompt::ask(&format!("Do you want to delete {} file(s)? (y/n)", doomed.len()), "n");
+        if !answer.trim().eq("y") {
+            println!("Good Bye!");
+            return Ok(());
+        }
+    }
+    if let Some(at) = options.at {
+        if !schedule::wait(at) {
+            println!("Cancelled, nothing was deleted.");
+            return Ok(());
+        }
+    }
+    let mut removals = repos::Removals::default();
+    let mut reclaimed = report::Reclaimed::default();
+    let mut kept = vec![];
+    let guard = interrupt::guard();
+    for path in doomed {
+        if interrupt::interrupted() {
+            println!("Interrupted, the other files were kept.");
+            break;
+        }
+        if options.at.is_some() && Path::new(&path).symlink_metadata().is_err() {
+            println!("Skipped `{}`: it's gone since the scan.", path);
+            continue;
+        }
+        if let Some(index) = files.iter().position(|f| f.is_at(&path)) {
+            let before = files.len();
+            let bytes = delete_entry(&mut files, index, options, companions, &mut removals, decisions)?;
+            reclaimed.add("delete-all", bytes);
+            if files.len() == before {
+                kept.push(path.clone());
+            }
+            if let Some(log) = &options.audit_log {
+                let event = format!("--delete-all processed `{}` ({} reclaimed)", path, units::format_size(bytes));
+                prompt::record(log, &event)?;
+            }
+        }
+    }
+    drop(guard);
+    let interrupted = interrupt::take();
+    if !reclaimed.is_empty() {
+        println!("{}", reclaimed);
+    }
+    if let Some(count) = options.verify_sample.filter(|_| !interrupted) {
+        verify_kept(&planned, &files, count, theme);
+    }
+    if kept.is_empty() {
+        return Ok(());
+    }
+    let kept = verification::KeptError { paths: kept };
+    // Nobody chose to keep the files of a batch run, which fails so its scripts can tell.
+    match prompt::unattended() {
+        true => Err(kept.into()),
+        false => {
+            println!("{}", theme.paint(theme.warning, &kept.to_string()));
+            Ok(())
+        }
+    }
+}
+
+/// Reads again `count` of the `remaining` files the `planned` deletions relied on, picked at random, and prints
+/// whether they're intact.
+fn verify_kept(planned: &verification::Planned, remaining: &[File], count: usize, theme: &Theme) {
+    let kept = planned.relied_on(remaining);
+    if kept.is_empty() {
+        return;
+    }
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    let verification = verification::verify(&kept, count, seed);
+    match verification.problems.is_empty() {
+        true => println!("{}", verification),
+        false => println!("{}", theme.paint(theme.warning, &verification.to_string())),
+    }
+}
+
+/// Keeps the entry stored at `index` out of its group, deleting the other copies and renaming it to `new_name`
+/// (see [`rename`]). Nothing is deleted when the new name is held by a file that isn't one of those copies.
+/// Returns the amount of bytes reclaimed.
+fn keep_as(
+    files: &mut Vec<File>,
+    index: usize,
+    new_name: &str,
+    options: &Options,
+    companions: &Companions,
+    removals: &mut repos::Removals,
+    decisions: &mut Decisions,
+) -> Result<u64, Box<dyn Error>> {
+    let kept = files[index].path().into_owned();
+    let target = match rename::target(Path::new(&kept), new_name) {
+        Ok(target) => target,
+        Err(err) => {
+            println!("{}, nothing was done.", err);
+            return Ok(0);
+        }
+    };
+    let key = files[index].group_key();
+    let copies: Vec<String> = files
+        .iter()
+        .filter(|file| file.group_key() == key && !file.is_at(&kept))
+        .map(|f| f.path().into_owned())
+        .collect();
+    let renamed = target != Path::new(&kept);
+    let held = copies.iter().any(|copy| Path::new(copy) == target);
+    if renamed && !held && target.symlink_metadata().is_ok() {
+        println!("`{}` already exists, nothing was done.", target.display());
+        return Ok(0);
+    }
+    let mut reclaimed = 0;
+    let _guard = interrupt::guard();
+    for copy in copies {
+        if interrupt::interrupted() {
+            println!("Interrupted, `{}` wasn't renamed.", kept);
+            return Ok(reclaimed);
+        }
+        if let Some(index) = files.iter().position(|file| file.is_at(&copy)) {
+            reclaimed += delete_entry(files, index, options, companions, removals, decisions)?;
+        }
+    }
+    if !renamed {
+        return Ok(reclaimed);
+    }
+    let file = match files.iter_mut().find(|file| file.is_at(&kept)) {
+        Some(file) => file,
+        None => return Ok(reclaimed),
+    };
+    match rename::rename(Path::new(&kept), &target) {
+        Ok(()) => {
+            file.moved_to(&target.to_string_lossy(), new_name);
+            println!("`{}` kept as `{}`.", kept, file.path());
+        }
+        Err(err) => println!("`{}` was kept, but couldn't be renamed: {}.", kept, err),
+    }
+    Ok(reclaimed)
+}
+
+/// Prints page `page` of the listing, along with a footer when there are others.
+fn show_page(files: &[File], page: usize, page_size: usize, theme: &Theme, layout: listing::Layout) {
+    let page = listing::Page::new(files, page, page_size, theme).with_layout(layout);
+    print!("{}", page);
+    if let Some(footer) = page.footer(files.len()) {
+        println!("{}", theme.paint(theme.warning, &footer));
+    }
+}
+
+/// Asks the user which rule applies to every path the `planned` deletions conflict on (see
+/// [`verification::conflicts`]), leaving the other ones out of the plan. Returns `false` when the plan is cancelled
+/// instead.
+fn resolve_conflicts(planned: &mut Vec<(String, &'static str)>, companions: &Companions) -> bool {
+    let conflicts = verification::conflicts(planned, companions);
+    if conflicts.is_empty() {
+        return true;
+    }
+    println!("{} path(s) are targeted by several rules, which must be resolved first.", conflicts.len());
+    let mut dropped: HashSet<usize> = HashSet::new();
+    for conflict in conflicts {
+        let actions: Vec<usize> = conflict.actions.into_iter().filter(|i| !dropped.contains(i)).collect();
+        if actions.len() < 2 {
+            continue;
+        }
+        let mut question = format!("`{}` is targeted by:", conflict.path);
+        for (number, i) in actions.iter().enumerate() {
+            let (path, rule) = &planned[*i];
+            question.push_str(&format!("\n\t{}. rule `{}`, deleting `{}`", number + 1, rule, path));
+        }
+        question.push_str("\nWhich one applies? (its number, anything else cancels the plan)");
+        match prompt::ask(&question, "").trim().parse::<usize>() {
+            Ok(number) if number >= 1 && number <= actions.len() => {
+                dropped.extend(actions.iter().enumerate().filter(|(other, _)| other + 1 != number).map(|(_, i)| *i))
+            }
+            _ => return false,
+        }
+    }
+    let mut index = 0;
+    planned.retain(|_| {
+        index += 1;
+        !dropped.contains(&(index - 1))
+    });
+    true
+}
+
+/// Prints the deletions `planned` by the rules along with their risks (see [`risks`]), the riskiest first, and
+/// asks the user to confirm them.
+fn confirm_plan(files: &[File], planned: &[(String, &'static str)], options: &Options, theme: &Theme) -> bool {
+    if planned.is_empty() {
+        println!("No deletion is suggested.");
+        return false;
+    }
+    let references = links::References::find(&options.roots, files);
+    let now = std::time::SystemTime::now();
+    let mut plan: Vec<(usize, &str, Vec<String>)> = planned
+        .iter()
+        .filter_map(|(path, rule)| files.iter().position(|f| f.is_at(path)).map(|i| (i, *rule)))
+        .map(|(i, rule)| (i, rule, risks::assess(files, i, &references, now)))
+        .collect();
+    plan.sort_by_key(|(_, _, risks)| Reverse(risks.len()));
+    println!("{} deletion(s) planned, the riskiest first:", plan.len());
+    for (i, rule, risks) in &plan {
+        println!("Entry {} `{}` (rule `{}`)", i + 1, files[*i].path(), rule);
+        for risk in risks {
+            println!("{}", theme.paint(theme.warning, &format!("\trisk: {}", risk)));
+        }
+    }
+    let answer = prompt::ask("Do you want to apply the plan? (y/n)", "n");
+    answer.trim().eq("y")
+}
+
+/// Scanned root holding the file located at `path` (the inspected directory when none does, so containment
+/// refuses it).
+fn root_of<'a>(options: &'a Options, path: &Path) -> &'a Path {
+    options
+        .roots
+        .iter()
+        .map(Path::new)
+        .find(|root| containment::is_contained(root, path))
+        .unwrap_or_else(|| Path::new(&options.directory))
+}
 
-    let directory = match args.next() {
-        Some(str) => str,
-        None => return Err(ArgsError.into()),
+/// Deletes the file stored at `index`, as long as it's contained on the inspected directory, it isn't the last
+/// copy of its content left among `files` (see [`verification::Planned`]) and the user confirms the deletion of
+/// the files that look like they contain secrets or were matched heuristically. The user is offered to delete the
+/// companions of the file as well. Returns the amount of bytes reclaimed.
+fn delete_entry(
+    files: &mut Vec<File>,
+    index: usize,
+    options: &Options,
+    companions: &Companions,
+    removals: &mut repos::Removals,
+    decisions: &mut Decisions,
+) -> Result<u64, Box<dyn Error>> {
+    // The companions go along with the file, they're deleted before an interruption is dealt with.
+    let _guard = interrupt::guard();
+    let file = &files[index];
+    let root = root_of(options, Path::new(&*file.path()));
+    if let Err(err) = containment::verify(root, Path::new(&*file.path()), options.contain_strict) {
+        println!("{}", err);
+        return Ok(0);
+    }
+    if let Some(blocker) = flags::removal_blocker(Path::new(&*file.path())) {
+        println!("`{}` can't be deleted: {}.", file.path(), blocker);
+        return Ok(0);
+    }
+    // Whichever way it was picked, the entry goes through the check the plans go through.
+    if let Err(err) = verification::Planned::new(files, [&*file.path()], options.allow_delete_all) {
+        println!("{}", err);
+        return Ok(0);
+    }
+    let class = safety::classify(Path::new(&*file.path()));
+    if class.needs_confirmation() && !options.allow_unknown_locations {
+        let question = format!(
+            "`{}` isn't known to hold user data nor caches (its location is {}).\nDelete it anyway? (y/n)",
+            file.path(), class
+        );
+        let answer = prompt::ask(&question, "n");
+        if !answer.trim().eq("y") {
+            println!("File kept.");
+            return Ok(0);
+        }
+    }
+    if options.cull_jpegs && photos::raw_sibling(Path::new(&*file.path())).is_none() {
+        println!("`{}` kept, its RAW sibling is gone.", file.path());
+        return Ok(0);
+    }
+    if let Some(warning) = secrets_warning(file).filter(|_| options.check_secrets) {
+        let answer = prompt::ask(&format!("`{}` {}.\nDelete it anyway? (y/n)", file.path(), warning), "n");
+        if !answer.trim().eq("y") {
+            println!("File kept.");
+            return Ok(0);
+        }
+    }
+    if file.heuristic_match() {
+        let question = format!(
+            "`{}` was matched heuristically.\nHave you checked it is a copy of another file? (y/n)",
+            file.path()
+        );
+        let answer = prompt::ask(&question, "n");
+        if !answer.trim().eq("y") {
+            println!("File kept.");
+            return Ok(0);
+        }
+    }
+    let streams = streams::extra_streams(Path::new(&*file.path()));
+    if !streams.is_empty() {
+        let question = format!(
+            "`{}` carries {}, its copies may not be identical on this platform.\nDelete it anyway? (y/n)",
+            file.path(),
+            streams.join(", ")
+        );
+        let answer = prompt::ask(&question, "n");
+        if !answer.trim().eq("y") {
+            println!("File kept.");
+            return Ok(0);
+        }
+    }
+    let verified = match options.paranoid {
+        true => paranoid::verify(file, files.iter()),
+        false => Ok(()),
     };
+    if let Err(reason) = verified {
+        println!("{}, it was kept.", reason);
+        return Ok(0);
+    }
+    let file = &files.swap_remove(index);
+    let followers = companions.of(Path::new(&*file.path()));
+    if let Some(script) = &options.git_rm_script {
+        if let Some(repository) = repos::repository_of(Path::new(&*file.path())) {
+            removals.add(&repository, Path::new(&*file.path()));
+            for follower in followers.iter().filter(|f| repos::repository_of(f).as_ref() == Some(&repository)) {
+                removals.add(&repository, follower);
+            }
+            removals.save(Path::new(script))?;
+            println!("`{}` added to `{}`.", file.path(), script);
+            return Ok(0);
+        }
+    }
+    remove_file(Path::new(&*file.path()), options.permanent)?;
+    match options.permanent {
+        true => println!("File deleted!"),
+        false => println!("File moved to the trash!"),
+    }
+    if let Some(path) = &options.decisions {
+        decisions.record(file, Decision::Delete);
+        decisions.save(path)?;
+    }
 
-    let input = get_input(
-        "Please, provide the name of the file you want to search (including its file extension)",
+    if followers.is_empty() {
+        return Ok(file.size);
+    }
+    let names: Vec<String> = followers.iter().map(|p| p.display().to_string()).collect();
+    let question = format!(
+        "`{}` had {} companion file(s): {}.\nDelete them as well? (y/n)",
+        file.path(),
+        followers.len(),
+        names.join(", ")
     );
+    let answer = prompt::ask(&question, "n");
+    if !answer.trim().eq("y") {
+        return Ok(file.size);
+    }
+    let mut reclaimed = file.size;
+    for follower in followers {
+        if options.only_mine && !ownership::is_mine(&follower) {
+            println!("`{}` kept, it's owned by another user.", follower.display());
+            continue;
+        }
+        let size = fs::metadata(&follower).map_or(0, |m| m.len());
+        let result = containment::verify(root, &follower, options.contain_strict)
+            .and_then(|_| remove_file(&follower, options.permanent).map_err(|e| e.into()));
+        match result {
+            Ok(_) => {
+                match options.permanent {
+                    true => println!("`{}` deleted!", follower.display()),
+                    false => println!("`{}` moved to the trash!", follower.display()),
+                }
+                reclaimed += size;
+            }
+            Err(err) => println!("{}", err),
+        }
+    }
+    Ok(reclaimed)
+}
 
-    let mut files_info = get_dir_files(&directory, input.trim())?;
-    for (i, file) in files_info.iter().enumerate() {
-        println!("Entry {}", &i + 1);
-        file.show_info();
+/// Lists the files of the saved session that match the query provided through the command line.
+fn run_query(options: &Options, theme: &Theme) -> Result<(), Box<dyn Error>> {
+    let session = session::Session::load(options.session.as_deref().unwrap_or_default())?;
+    let query = query::Query::parse(options.query.as_deref().unwrap_or_default())?;
+    let now = Utc::now();
+
+    let matches: Vec<&File> = session.files.iter().filter(|f| query.matches(f, now)).collect();
+    for (i, file) in matches.iter().enumerate() {
+        println!("{}", theme.paint(theme.entry, &format!("Entry {}", i + 1)));
+        println!("{}", file.info(theme));
     }
+    println!(
+        "{} of {} file(s) scanned on {} matched.",
+        matches.len(),
+        session.files.len(),
+        session.scanned_at
+    );
+    Ok(())
+}
 
-    let answer = get_input("Do you want to keep every file? \n(y/n)");
-    if answer.trim().eq("y") {
-        println!("Good Bye!");
+/// Cache of the content digests: the one provided through `--cache`, otherwise the default one when `digests` are
+/// computed (unless `--no-cache` is passed).
+fn open_cache(options: &Options, digests: bool) -> cache::Cache {
+    let path = match (&options.cache, options.no_cache || !digests) {
+        (Some(path), _) => Some(PathBuf::from(path)),
+        (None, false) => cache::default_path(),
+        (None, true) => None,
+    };
+    match path {
+        Some(path) => cache::Cache::open(&path),
+        None => cache::Cache::default(),
+    }
+}
+
+/// History of the runs: the one provided through `--history`, otherwise the default one.
+fn history_path(options: &Options) -> Option<PathBuf> {
+    options.history.as_ref().map(PathBuf::from).or_else(history::default_path)
+}
+
+/// Appends the summary of the `files` found searching `name` to the history of the runs.
+fn record_run(options: &Options, name: &str, files: &[File]) -> Result<(), Box<dyn Error>> {
+    let path = match history_path(options) {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    // The runs are only compared with the ones searching the same files the same way.
+    let flags = [
+        ("--by-content", options.by_content),
+        ("--glob", options.glob),
+        ("--regex", options.regex),
+        ("--compare-text", options.compare_text),
+        ("--compare-video", options.compare_video),
+        ("--compare-siblings", options.compare_siblings),
+        ("--only-mine", options.only_mine),
+    ];
+    let mut search = name.to_string();
+    for (flag, _) in flags.iter().filter(|(_, set)| *set) {
+        search = format!("{} {}", search, flag);
+    }
+    let at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut runs = history::History::load(&path)?;
+    runs.record(history::Run::new(&options.directory, &search, &at, files));
+    runs.save(&path)
+}
+
+/// Prints how the duplicates evolved across the recorded runs.
+fn run_stats(options: &Options) -> Result<(), Box<dyn Error>> {
+    let path = history_path(options).ok_or("The history can't be located, please provide it through --history.")?;
+    let root = Some(options.directory.as_str()).filter(|root| !root.is_empty());
+    print!("{}", history::History::load(&path)?.trend(root));
+    Ok(())
+}
+
+fn run_copy(options: &Options) -> Result<(), Box<dyn Error>> {
+    let destination = options.destination.as_deref().unwrap_or_default();
+    let cache = open_cache(options, true);
+    let reading = storage::Reading {
+        readers: storage::readers_for(Path::new(&options.directory), options.storage, options.readers, options.threads),
+        cache: &cache,
+        include_placeholders: options.include_placeholders,
+        algorithm: options.hash,
+    };
+    if options.command == Command::Consolidate {
+        let plan = consolidate::plan(destination, &options.roots, &options.limits, &reading)?;
+        cache.save()?;
+        for dir in &plan.skipped {
+            println!("Skipped `{}`: {}.", dir.path, dir.reason);
+        }
+        print!("{}", plan);
         return Ok(());
     }
+    let copied = copy::copy_tree(&options.directory, destination, &options.limits, &reading)?;
+    cache.save()?;
+    for dir in &copied.skipped_dirs {
+        println!("Skipped `{}`: {}.", dir.path, dir.reason);
+    }
+    for (file, holder) in &copied.skipped {
+        println!("Skipped `{}`, its content is already at `{}`.", file.display(), holder.display());
+    }
+    for file in &copied.conflicts {
+        println!("Skipped `{}`, another file with the same name is already there.", file.display());
+    }
+    println!("{}", copied);
+    Ok(())
+}
+
+fn run_hashes(options: &Options) -> Result<(), Box<dyn Error>> {
+    let path = options.manifest.as_deref().unwrap_or_default();
+    let cache = open_cache(options, true);
+    let reading = storage::Reading {
+        readers: storage::readers_for(Path::new(&options.directory), options.storage, options.readers, options.threads),
+        cache: &cache,
+        include_placeholders: options.include_placeholders,
+        algorithm: options.hash,
+    };
+    let (matches, skipped) = match options.command {
+        Command::ExportHashes => {
+            let (manifest, skipped) = manifest::Manifest::export(&options.directory, &options.limits, &reading)?;
+            manifest.save(path)?;
+            println!("Wrote the digests of {} file(s) to `{}`.", manifest.entries.len(), path);
+            (None, skipped)
+        }
+        _ => {
+            let manifest = manifest::Manifest::load(path)?;
+            let (matches, skipped) = manifest.matches(&options.directory, &options.limits, &reading)?;
+            (Some((manifest, matches)), skipped)
+        }
+    };
+    cache.save()?;
+    for dir in &skipped {
+        println!("Skipped `{}`: {}.", dir.path, dir.reason);
+    }
+    if let Some((manifest, matches)) = matches {
+        print!("{}", manifest.summary(&matches));
+    }
+    Ok(())
+}
+
+/// Deletes the file located at `path` (moving it to the [`trash`] unless `permanent`), offering to retry with
+/// elevated privileges (see [`elevate`]) when the user isn't allowed to.
+fn remove_file(path: &Path, permanent: bool) -> Result<(), io::Error> {
+    let result = match permanent {
+        true => fs::remove_file(path),
+        false => trash::trash(path),
+    };
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+            // The elevated helper can't reach the trash of the user, it deletes the file for good.
+            let question = match permanent {
+                true => format!(
+                    "You aren't allowed to delete `{}`.\nRetry with elevated privileges ({})? (y/n)",
+                    path.display(),
+                    elevate::METHOD
+                ),
+                false => format!(
+                    "You aren't allowed to move `{}` to the trash.\n\
+                    Delete it for good with elevated privileges ({})? (y/n)",
+                    path.display(),
+                    elevate::METHOD
+                ),
+            };
+            let answer = prompt::ask(&question, "n");
+            match answer.trim().eq("y") {
+                true => elevate::remove_files(&[path]),
+                false => Err(err),
+            }
+        }
+        result => result,
+    }
+}
+
+/// Describes the secrets found on `file`, if any.
+fn secrets_warning(file: &File) -> Option<String> {
+    let findings = secrets::scan(&*file.path()).unwrap_or_default();
+    if findings.is_empty() {
+        return None;
+    }
+    let kinds: Vec<String> = findings.iter().map(|f| f.describe()).collect();
+    Some(format!("may contain secrets: {}", kinds.join(", ")))
+}
 
-    loop {
-        let answer = get_input(
-            "Please provide the number associated to the file you want to delete.\nWrite done to quit",
-        );
-        let cleaned_answer = answer.trim();
-        if cleaned_answer.eq("done") {
-            println!("Good Bye!");
-            break;
-        }
-        let index = cleaned_answer.parse::<usize>();
-        let index = match index {
-            Ok(index) => index,
-            Err(_) => {
-                println!("Invalid number provided.");
-                continue;
+/// Copies the paths of the entries listed on `numbers` (separated by whitespaces or commas) to the
+/// clipboard, or the paths of every entry when `numbers` is blank.
+fn copy_entries(files: &[File], numbers: &str) {
+    let mut paths = vec![];
+    for number in numbers.split(|c: char| c.is_whitespace() || c == ',').filter(|n| !n.is_empty()) {
+        match number.parse::<usize>() {
+            Ok(index) if index > 0 && index <= files.len() => paths.push(files[index - 1].path()),
+            _ => {
+                println!("Please provide one of the listed numbers!");
+                return;
             }
-        };
-        //let file = &files_info[&index - 1];
-        if (index >= *&files_info.len() + 1) || (index <= 0) {
-            println!("Please provide one of the listed numbers!");
-            break;
         }
-        let file = &files_info.swap_remove(&index - 1);
-        file.delete()?;
-        println!("File deleted!");
     }
-    Ok(())
+    if paths.is_empty() {
+        paths = files.iter().map(|f| f.path()).collect();
+    }
+    match clipboard::copy_paths(&paths) {
+        Ok(_) => println!("{} path(s) copied to the clipboard.", paths.len()),
+        Err(err) => println!("{}", err),
+    }
 }
 
 /// Stores relevant information (and some `metadata`) associated with a specific file,
@@ -110,16 +1525,79 @@ pub fn run(mut args: impl Iterator<Item=String>) -> Result<(), Box<dyn Error>> {
 /// let creation_date=  "2022-07-23 12:33:01";
 /// let path=  "./test.txt";
 /// let file = File::new(name, folder, creation_date, path);
-/// assert_eq!(&file.name, name);
-/// assert_eq!(&file.folder, folder);
+/// assert_eq!(&*file.name, name);
+/// assert_eq!(&*file.folder, folder);
 /// assert_eq!(&file.creation_date, creation_date);
 /// ```
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Deserialize)]
+#[serde(from = "Record")]
 pub struct File {
-    pub name: String,
-    pub folder: String,
+    /// Shared by every copy found by a scan (see [`File::folder`]).
+    pub name: Arc<str>,
+    /// Shared by every file found on the same directory by a scan, so deep trees don't store their folders
+    /// once per file, nor their paths: those are the folder joined with the name (see [`File::path`]).
+    pub folder: Arc<str>,
     pub creation_date: String,
-    path: String,
+    /// Size in bytes (`0` unless the file was found by a scan).
+    pub size: u64,
+    /// Digest of the contents of the file, when they were compared (E.G: through `--compare-text`).
+    pub digest: Option<String>,
+    /// Criterion the name of the file matched during the scan, shared by the files it found (see [`provenance`]).
+    pub matched_by: Option<Arc<str>>,
+    /// MIME type detected from the content of the file, when the scan sniffed it (see [`mime`]).
+    pub mime: Option<String>,
+    /// Path of the file when it isn't its folder joined with its name (E.G: the files built by hand).
+    location: Option<Box<str>>,
+}
+
+/// A [`File`] as serialized, along with its whole path.
+#[derive(Serialize, Deserialize)]
+struct Record<'a> {
+    #[serde(borrow)]
+    name: Cow<'a, str>,
+    #[serde(borrow)]
+    folder: Cow<'a, str>,
+    creation_date: Cow<'a, str>,
+    size: u64,
+    #[serde(default)]
+    digest: Option<Cow<'a, str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    matched_by: Option<Cow<'a, str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mime: Option<Cow<'a, str>>,
+    path: Cow<'a, str>,
+}
+
+impl From<Record<'_>> for File {
+    fn from(record: Record) -> Self {
+        let location = File::location(&record.folder, &record.name, &record.path);
+        File {
+            name: Arc::from(record.name),
+            folder: Arc::from(record.folder),
+            creation_date: record.creation_date.into_owned(),
+            size: record.size,
+            digest: record.digest.map(Cow::into_owned),
+            matched_by: record.matched_by.map(Arc::from),
+            mime: record.mime.map(Cow::into_owned),
+            location,
+        }
+    }
+}
+
+impl Serialize for File {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let record = Record {
+            name: Cow::Borrowed(&self.name),
+            folder: Cow::Borrowed(&self.folder),
+            creation_date: Cow::Borrowed(&self.creation_date),
+            size: self.size,
+            digest: self.digest.as_deref().map(Cow::Borrowed),
+            matched_by: self.matched_by.as_deref().map(Cow::Borrowed),
+            mime: self.mime.as_deref().map(Cow::Borrowed),
+            path: self.path(),
+        };
+        record.serialize(serializer)
+    }
 }
 
 impl File {
@@ -136,13 +1614,93 @@ impl File {
     /// ```
     pub fn new(name: &str, folder: &str, creation_date: &str, path: &str) -> Self {
         Self {
-            name: name.to_string(),
-            folder: folder.to_string(),
+            name: Arc::from(name),
+            folder: Arc::from(folder),
             creation_date: creation_date.to_string(),
-            path: path.to_string(),
+            size: 0,
+            digest: None,
+            matched_by: None,
+            mime: None,
+            location: File::location(folder, name, path),
         }
     }
 
+    /// Files sharing this key are considered duplicates of each other: they must have the same name and,
+    /// when their contents were compared, the same `digest`. Names don't matter when the digest identifies the
+    /// whole content of the file (it starts with `=`, see [`hash`]).
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::{File};
+    /// let a = File::new("test.txt", ".", "2022-07-23 12:33:01", "./test.txt");
+    /// let mut b = File::new("test.txt", "./old", "2022-07-23 12:33:01", "./old/test.txt");
+    /// assert_eq!(a.group_key(), b.group_key());
+    /// b.digest = Some(String::from("text:0123"));
+    /// assert_ne!(a.group_key(), b.group_key());
+    /// let mut c = File::new("copy of test.txt", "./old", "2022-07-23 12:33:01", "./old/copy of test.txt");
+    /// b.digest = Some(String::from("=sha256:ba78"));
+    /// c.digest = Some(String::from("=sha256:ba78"));
+    /// assert_eq!(b.group_key(), c.group_key());
+    /// ```
+    pub fn group_key(&self) -> (&str, Option<&str>) {
+        match self.digest.as_deref() {
+            Some(digest) if digest.starts_with('=') => ("", Some(digest)),
+            digest => (&self.name, digest),
+        }
+    }
+
+    /// Whether the `digest` comes from a heuristic comparison (E.G: `--compare-video`), in which case the
+    /// file must be compared manually against its duplicates before deleting it. Such digests (or any of
+    /// their parts) start with `~`.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::{File};
+    /// let mut file = File::new("movie.mkv", ".", "2022-07-23 12:33:01", "./movie.mkv");
+    /// assert!(!file.heuristic_match());
+    /// file.digest = Some(String::from("~video:0"));
+    /// assert!(file.heuristic_match());
+    /// ```
+    pub fn heuristic_match(&self) -> bool {
+        self.digest.as_deref().is_some_and(|digest| digest.split('+').any(|part| part.starts_with('~')))
+    }
+
+    /// How confident (in percent) the comparisons are that the file is a copy of the others of its group: `100`
+    /// for exact matches, the lowest score of its heuristic digests (noted after a `@`) otherwise. Heuristic
+    /// digests without a score count as `0`.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::{File};
+    /// let mut file = File::new("movie.mkv", ".", "2022-07-23 12:33:01", "./movie.mkv");
+    /// assert_eq!(file.confidence(), 100);
+    /// file.digest = Some(String::from("siblings:0+~video:3@87"));
+    /// assert_eq!(file.confidence(), 87);
+    /// ```
+    pub fn confidence(&self) -> u8 {
+        let digest = self.digest.as_deref().unwrap_or_default();
+        digest
+            .split('+')
+            .filter(|part| part.starts_with('~'))
+            .map(|part| part.rsplit_once('@').and_then(|(_, score)| score.parse().ok()).unwrap_or(0))
+            .min()
+            .unwrap_or(100)
+    }
+
+    /// Refines the `digest` of the file with the result of another comparison (parts are joined by `+`).
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::{File};
+    /// let mut file = File::new("movie.mkv", ".", "2022-07-23 12:33:01", "./movie.mkv");
+    /// file.add_digest("siblings:0");
+    /// file.add_digest("~video:3");
+    /// assert_eq!(file.digest.as_deref(), Some("siblings:0+~video:3"));
+    /// assert!(file.heuristic_match());
+    /// ```
+    pub fn add_digest(&mut self, digest: &str) {
+        self.digest = Some(match &self.digest {
+            Some(previous) => format!("{}+{}", previous, digest),
+            None => digest.to_string(),
+        });
+    }
+
     /// Prints the `name`, `folder` and `creation_date` of a `File` using `\t` and `\n` chars,
     /// in order to meet a format equivalent to one level of `indentation`.
     /// ## Examples
@@ -161,13 +1719,83 @@ impl File {
     /// //  2022-07-23 12:33:01
     /// ```
     pub fn show_info(&self) {
-        println!(
+        Output::Text
+            .write_files(&mut io::stdout().lock(), std::slice::from_ref(self), &Theme::plain())
+            .expect("Couldn't print the information of the file.");
+    }
+
+    /// Formats the information printed by `show_info`, coloring the values according to `theme`.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::{File};
+    /// # use dir_cleaner::theme::Theme;
+    /// let file = File::new("test.txt", ".", "2022-07-23 12:33:01", "./test.txt");
+    /// assert_eq!(
+    ///     file.info(&Theme::plain()),
+    ///     "\tfile name: test.txt \n\tdirectory: . \n\tcreation date: 2022-07-23 12:33:01"
+    /// );
+    /// ```
+    pub fn info(&self, theme: &Theme) -> String {
+        format!(
             "\tfile name: {} \n\tdirectory: {} \n\tcreation date: {}",
-            &self.name, &self.folder, &self.creation_date
-        );
+            theme.paint(theme.name, &self.name),
+            theme.paint(theme.folder, &self.folder),
+            theme.paint(theme.date, &self.creation_date)
+        )
+    }
+
+    /// Returns the `path` of the file, which is built from its folder and its name unless the file was given
+    /// another one.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::{File};
+    /// let file = File::new("test.txt", ".", "2022-07-23 12:33:01", "./test.txt");
+    /// assert_eq!(file.path(), "./test.txt");
+    /// let file = File::new("test.txt", ".", "2022-07-23 12:33:01", "/elsewhere/test.txt");
+    /// assert_eq!(file.path(), "/elsewhere/test.txt");
+    /// ```
+    pub fn path(&self) -> Cow<'_, str> {
+        match &self.location {
+            Some(location) => Cow::Borrowed(location),
+            None => Cow::Owned(Path::new(&*self.folder).join(&*self.name).to_string_lossy().into_owned()),
+        }
+    }
+
+    /// Whether the file is located at `path`, told without building its path (see [`File::path`]).
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::{File};
+    /// let file = File::new("test.txt", "./docs", "2022-07-23 12:33:01", "./docs/test.txt");
+    /// assert!(file.is_at("./docs/test.txt"));
+    /// assert!(!file.is_at("./docs/test.txt.bak"));
+    /// assert!(!file.is_at("./docstest.txt"));
+    /// ```
+    pub fn is_at(&self, path: &str) -> bool {
+        if let Some(location) = &self.location {
+            return **location == *path;
+        }
+        let rest = match path.strip_prefix(&*self.folder) {
+            Some(rest) if self.folder.is_empty() || self.folder.ends_with(std::path::is_separator) => Some(rest),
+            Some(rest) => rest.strip_prefix(std::path::MAIN_SEPARATOR),
+            None => None,
+        };
+        rest == Some(&*self.name)
     }
 
-    /// Removes the file located in the `path` contained on the `File` instance that called the method.
+    /// What's stored of `path`, the path of a file named `name` found on `folder`: nothing when it's the folder
+    /// joined with the name.
+    fn location(folder: &str, name: &str, path: &str) -> Option<Box<str>> {
+        (Path::new(folder).join(name).as_os_str() != path).then(|| Box::from(path))
+    }
+
+    /// Records that the file was moved to `path`, where it's named `name` (E.G: once renamed).
+    pub fn moved_to(&mut self, path: &str, name: &str) {
+        self.name = Arc::from(name);
+        self.location = File::location(&self.folder, name, path);
+    }
+
+    /// Removes the file located in the `path` contained on the `File` instance that called the method, for good
+    /// (see [`File::trash`] for a deletion that can be undone).
     /// ## Examples
     /// ```
     /// # use dir_cleaner::{File};
@@ -179,21 +1807,44 @@ impl File {
     ///     let path = "./test.txt";
     ///     let metadata = &f.metadata().unwrap();
     ///     let creation_date = metadata.created().unwrap();
-    ///     let creation_date: DateTime<Utc> = creation_date.clone().into();
+    ///     let creation_date: DateTime<Utc> = creation_date.into();
     ///     let creation_date = creation_date.format("%Y-%m-%d %H:%M:%S").to_string();
     ///     let file = File::new(name, ".", &creation_date, path);
     ///     file.delete()
     /// # }
     /// ```
     pub fn delete(&self) -> Result<(), std::io::Error> {
-        fs::remove_file(&self.path)?;
+        fs::remove_file(&*self.path())?;
         Ok(())
     }
+
+    /// Moves the file located in the `path` contained on the `File` instance to the trash of the system.
+    pub fn trash(&self) -> Result<(), std::io::Error> {
+        trash::trash(Path::new(&*self.path()))
+    }
+}
+
+/// Tells, for every file, whether another one shares its [`File::group_key`]. Files without duplicates
+/// don't need to be compared any further.
+pub fn has_duplicate(files: &[File]) -> Vec<bool> {
+    let mut duplicated = vec![false; files.len()];
+    for group in groups::duplicates(files) {
+        for i in group.indexes {
+            duplicated[i] = true;
+        }
+    }
+    duplicated
 }
 
 /// Recursively traverses the directory located in the provided `path` and its respective subdirectories, in order
 /// to gather the information of the files that have the provided `file_name` and collect it into a
 /// `Vec` of `Files`.
+///
+/// The traversal is deterministic: the entries of every directory are visited in lexicographic order
+/// (files of a directory first, then its subdirectories). Symbolic links are never followed nor reported
+/// (unless [`Limits::follow_symlinks`]), so the scan can't loop forever or leave the provided `path`. Entries
+/// whose metadata can't be read, or whose name isn't valid `UTF-8`, are skipped instead of aborting the whole
+/// scan.
 /// ## Examples
 /// ```
 /// # use dir_cleaner::get_dir_files;
@@ -206,50 +1857,329 @@ impl File {
 ///     },
 /// };
 /// assert_eq!(files.len(), 1);
-/// assert_eq!(files[0].name, "test.txt");
+/// assert_eq!(&*files[0].name, "test.txt");
 /// # files[0].delete();
 /// # files.remove(0);
 /// ```
 pub fn get_dir_files(path: &str, file_name: &str) -> Result<Vec<File>, std::io::Error> {
-    let dir_entry = fs::read_dir(&path)?;
-    let mut sub_dirs: Vec<String> = vec![];
-    let mut files: Vec<File> = dir_entry.filter(|f| f.is_ok())
-        .flatten()
-        .map(|d| {
-            let path = &d.path();
-            let metadata = &d.metadata().unwrap();
-            DirInfo {
-                path_buf: path.to_owned(),
-                metadata: metadata.to_owned(),
+    get_dir_files_matching(path, &|name| name == file_name)
+}
+
+/// Same as [`get_dir_files`], but gathers every file whose name satisfies `matches`.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::get_dir_files_matching;
+/// # std::fs::File::create("./matching-test.txt").unwrap();
+/// let files = get_dir_files_matching("./", &|name| name.starts_with("matching-")).unwrap();
+/// assert_eq!(&*files[0].name, "matching-test.txt");
+/// # files[0].delete().unwrap();
+/// ```
+pub fn get_dir_files_matching(path: &str, matches: &dyn Fn(&str) -> bool) -> Result<Vec<File>, std::io::Error> {
+    let (files, _) = get_dir_files_limited(path, matches, &Limits::default())?;
+    Ok(files)
+}
+
+/// Same as [`get_dir_files_matching`], but leaves out the directories exceeding `limits` (which are returned
+/// along with the found files) instead of scanning them.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::get_dir_files_limited;
+/// # use dir_cleaner::limits::Limits;
+/// let limits = Limits { max_entries: Some(1), ..Limits::default() };
+/// let (files, skipped) = get_dir_files_limited("./src", &|name| name == "lib.rs", &limits).unwrap();
+/// assert!(files.is_empty());
+/// assert_eq!(skipped[0].path, "./src");
+/// ```
+pub fn get_dir_files_limited(
+    path: &str,
+    matches: &dyn Fn(&str) -> bool,
+    limits: &Limits,
+) -> Result<(Vec<File>, Vec<Skipped>), std::io::Error> {
+    let mut skipped = vec![];
+    let files = walk(&vfs::Local, path, matches, limits, &mut skipped)?;
+    Ok((files, skipped))
+}
+
+/// Reads the entries of `dir` into `entries` (reusing its buffer), returning `false` when the directory
+/// exceeds `limits` and was added to `skipped` instead. The name of every entry is read once, so they can be
+/// sorted without allocating on every comparison, and the metadata is kept when it had to be read early.
+/// A subdirectory that can't be listed is added to `skipped` as well, only the `root` failing the scan.
+fn read_entries(
+    vfs: &dyn Vfs,
+    dir: &str,
+    root: bool,
+    limits: &Limits,
+    entries: &mut Vec<vfs::DirEntry>,
+    skipped: &mut Vec<Skipped>,
+) -> Result<bool, std::io::Error> {
+    entries.clear();
+    if limits.is_protected(Path::new(dir)) {
+        skipped.push(Skipped { path: dir.to_string(), reason: String::from("system-protected directory") });
+        return Ok(false);
+    }
+    if limits.skip_repos && repos::is_repository(Path::new(dir)) {
+        skipped.push(Skipped { path: dir.to_string(), reason: String::from("git repository") });
+        return Ok(false);
+    }
+    let listing = match vfs.read_dir(Path::new(dir)) {
+        Ok(listing) => listing,
+        Err(err) if !root => {
+            skipped.push(Skipped { path: dir.to_string(), reason: format!("unreadable: {}", err) });
+            return Ok(false);
+        }
+        Err(err) => return Err(err),
+    };
+    for entry in listing {
+        entries.push(entry);
+        if let Some(max) = limits.max_entries.filter(|max| entries.len() > *max) {
+            let reason = format!("more than {} entries", max);
+            skipped.push(Skipped { path: dir.to_string(), reason });
+            return Ok(false);
+        }
+    }
+    if let Some(max) = limits.max_bytes {
+        let mut bytes = 0;
+        for entry in entries.iter_mut() {
+            if entry.meta.is_none() {
+                entry.meta = vfs.symlink_metadata(&entry.path).ok();
             }
-        })
-        .filter(|fi| {
-            if fi.path_buf.is_file().not() {
-                sub_dirs.push(fi.path_buf.to_str().unwrap().to_string());
+            bytes += entry.meta.filter(|meta| meta.kind == vfs::Kind::File).map_or(0, |meta| meta.len);
+        }
+        if bytes > max {
+            let reason = format!("more than {} stored directly on it", units::format_size(max));
+            skipped.push(Skipped { path: dir.to_string(), reason });
+            return Ok(false);
+        }
+    }
+    entries.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    Ok(true)
+}
+
+/// Directories waiting to be scanned.
+enum Pending {
+    /// Depth first, by name.
+    Stack(Vec<String>),
+    /// The biggest first (by their [`estimate::direct_bytes`]), then by name.
+    Biggest(BinaryHeap<(u64, Reverse<String>)>),
+}
+
+impl Pending {
+    fn new(root: &str, limits: &Limits) -> Self {
+        match limits.biggest_first {
+            true => Pending::Biggest(BinaryHeap::from([(0, Reverse(root.to_string()))])),
+            false => Pending::Stack(vec![root.to_string()]),
+        }
+    }
+
+    /// Moves the sub directories listed on `dirs` (by name) to the queue, sizing them through `vfs`.
+    fn extend(&mut self, vfs: &dyn Vfs, dirs: &mut Vec<String>) {
+        match self {
+            Pending::Stack(stack) => stack.extend(dirs.drain(..).rev()),
+            Pending::Biggest(heap) => {
+                heap.extend(dirs.drain(..).map(|dir| (estimate::direct_bytes(vfs, Path::new(&dir)), Reverse(dir))))
             }
-            fi.path_buf.is_file()
-        })
-        .map(|fi| {
-            let file_path = &fi.path_buf.to_str().unwrap();
-            let entry_name = &fi.path_buf.file_name().unwrap();
-            let entry_name = entry_name.to_str().unwrap();
-            let creation_date = fi.metadata.created().unwrap();
-            let creation_date: DateTime<Utc> = creation_date.clone().into();
-            let creation_date = creation_date.format("%Y-%m-%d %H:%M:%S").to_string();
-            File::new(entry_name, &path, &creation_date, file_path)
-        })
-        .filter(|f| f.name.eq(file_name))
-        .collect();
+        }
+    }
+
+    fn pop(&mut self) -> Option<String> {
+        match self {
+            Pending::Stack(stack) => stack.pop(),
+            Pending::Biggest(heap) => heap.pop().map(|(_, Reverse(dir))| dir),
+        }
+    }
+}
+
+/// Gathers the matching files depth first, every directory listing its files before the ones of its
+/// subdirectories (both by name), or the biggest directories first with [`Limits::biggest_first`]. The
+/// directories are kept on a stack rather than recursed into, and a single buffer of entries is reused across
+/// them. Every entry is listed and inspected through `vfs`.
+pub(crate) fn walk(
+    vfs: &dyn Vfs,
+    path: &str,
+    matches: &dyn Fn(&str) -> bool,
+    limits: &Limits,
+    skipped: &mut Vec<Skipped>,
+) -> Result<Vec<File>, std::io::Error> {
+    walk_notifying(vfs, path, matches, limits, skipped, &mut |_| true)
+}
 
-    for sub_dir in sub_dirs {
-        let mut sub_files = get_dir_files(&sub_dir, file_name)?;
-        files.append(&mut sub_files);
+/// Same as [`walk`], handing the files found on every directory to `found` once it's listed, which may take them
+/// (they're left out of the returned files then). The walk stops early when `found` returns `false` (E.G: once
+/// nobody is waiting for its files anymore).
+pub(crate) fn walk_notifying(
+    vfs: &dyn Vfs,
+    path: &str,
+    matches: &dyn Fn(&str) -> bool,
+    limits: &Limits,
+    skipped: &mut Vec<Skipped>,
+    found: &mut dyn FnMut(&mut Vec<File>) -> bool,
+) -> Result<Vec<File>, std::io::Error> {
+    let mut files: Vec<File> = vec![];
+    let mut pending = Pending::new(path, limits);
+    let mut entries: Vec<vfs::DirEntry> = vec![];
+    let mut sub_dirs: Vec<String> = vec![];
+    let mut names = Interner::default();
+    // The links may lead to a directory that was already scanned (or to one of its parents).
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut tracker = limits.dir_summaries.then(|| summaries::Tracker::new(path));
+    let mut ignores = limits.respect_gitignore.then(|| gitignore::Ignores::new(vfs, Path::new(path)));
+    // The device the root is stored on, the other ones being mounted under it.
+    let device = match limits.one_file_system {
+        true => vfs.metadata(Path::new(path)).ok().and_then(|root| root.id).map(|(device, _)| device),
+        false => None,
+    };
+    let mut previous: Option<String> = None;
+    while let Some(dir) = pending.pop() {
+        pause::checkpoint();
+        if interrupt::interrupted() {
+            break;
+        }
+        if let Some(tracker) = tracker.as_mut() {
+            if let Some(summary) = previous.replace(dir.clone()).and_then(|previous| tracker.done(&previous)) {
+                eprintln!("{}", summary);
+            }
+        }
+        if limits.follow_symlinks && vfs.canonicalize(Path::new(&dir)).is_ok_and(|real| !visited.insert(real)) {
+            continue;
+        }
+        let deepest = limits.max_depth.is_some_and(|max| {
+            Path::new(&dir).strip_prefix(path).map_or(0, |relative| relative.components().count()) >= max
+        });
+        let left_out = skipped.len();
+        let listed = read_entries(vfs, &dir, dir == path, limits, &mut entries, skipped)?;
+        if !entries.is_empty() {
+            accounting::SCAN.listed(Path::new(&dir), entries.len());
+        }
+        let (listed_entries, previously_found, mut unreadable) = (entries.len(), files.len(), 0);
+        if !listed {
+            if let Some(tracker) = tracker.as_mut() {
+                tracker.scanned(&dir, 0, 0, skipped.len() - left_out);
+            }
+            continue;
+        }
+        progress::SCAN.walked(entries.iter().filter(|entry| entry.kind == vfs::Kind::File).count());
+        let folder: Arc<str> = Arc::from(dir.as_str());
+        for vfs::DirEntry { name, path: entry_path, mut kind, mut meta } in entries.drain(..) {
+            if limits.skip_hidden && name.to_str().is_some_and(|name| name.starts_with('.')) {
+                continue;
+            }
+            let excluded = |kind| {
+                let relative = exclude::relative(Path::new(path), &entry_path);
+                limits.exclude.excludes(&relative, kind == vfs::Kind::Dir)
+            };
+            if kind == vfs::Kind::Symlink && limits.follow_symlinks {
+                match vfs.metadata(&entry_path) {
+                    Ok(target) => {
+                        kind = target.kind;
+                        meta = Some(target);
+                    }
+                    Err(_) => {
+                        unreadable += 1;
+                        let path = entry_path.to_string_lossy().to_string();
+                        skipped.push(Skipped { path, reason: String::from(limits::UNREADABLE) });
+                        continue;
+                    }
+                }
+            }
+            if !limits.exclude.is_empty() && excluded(kind) {
+                continue;
+            }
+            if ignores.as_mut().is_some_and(|ignores| ignores.ignores(&entry_path, kind == vfs::Kind::Dir)) {
+                continue;
+            }
+            if kind == vfs::Kind::Dir {
+                if deepest {
+                    continue;
+                }
+                let mounted = |meta: vfs::Meta| meta.id.is_some_and(|(other, _)| Some(other) != device);
+                if device.is_some() && meta.or_else(|| vfs.symlink_metadata(&entry_path).ok()).is_some_and(mounted) {
+                    let reason = String::from("mount point of another filesystem");
+                    skipped.push(Skipped { path: entry_path.to_string_lossy().to_string(), reason });
+                    continue;
+                }
+                if let Some(sub_dir) = entry_path.to_str() {
+                    sub_dirs.push(sub_dir.to_string());
+                }
+                continue;
+            }
+            let file_name = match name.to_str() {
+                Some(file_name) if kind == vfs::Kind::File && matches(file_name) => file_name,
+                _ => continue,
+            };
+            if !limits.extensions.allows(file_name) {
+                continue;
+            }
+            let entry_path = match entry_path.to_str() {
+                Some(entry_path) => entry_path,
+                None => continue,
+            };
+            let meta = match meta.map_or_else(|| vfs.symlink_metadata(Path::new(entry_path)), Ok) {
+                Ok(meta) => meta,
+                Err(_) => {
+                    unreadable += 1;
+                    skipped.push(Skipped { path: entry_path.to_string(), reason: String::from(limits::UNREADABLE) });
+                    continue;
+                }
+            };
+            if !limits.fits(meta.len) || !limits.dates.contains(meta.modified, meta.created) {
+                continue;
+            }
+            let mime = match limits.types.is_empty() {
+                true => None,
+                false => match mime::detect(vfs, Path::new(entry_path)) {
+                    Ok(mime) if limits.types.allows(mime) => mime,
+                    Ok(_) => continue,
+                    Err(_) => {
+                        unreadable += 1;
+                        let reason = String::from(limits::UNREADABLE_CONTENT);
+                        skipped.push(Skipped { path: entry_path.to_string(), reason });
+                        continue;
+                    }
+                },
+            };
+            files.push(File {
+                name: names.intern(file_name),
+                folder: Arc::clone(&folder),
+                creation_date: format_date(&meta),
+                size: meta.len,
+                digest: None,
+                matched_by: None,
+                mime: mime.map(String::from),
+                location: File::location(&folder, file_name, entry_path),
+            });
+        }
+        if let Some(tracker) = tracker.as_mut() {
+            tracker.scanned(&dir, listed_entries, files.len() - previously_found, unreadable);
+            sub_dirs.iter().for_each(|sub_dir| tracker.queued(sub_dir));
+        }
+        let mut listed = files.split_off(previously_found);
+        let go_on = found(&mut listed);
+        files.append(&mut listed);
+        if !go_on {
+            break;
+        }
+        pending.extend(vfs, &mut sub_dirs);
+    }
+    if let Some(summary) = tracker.as_mut().zip(previous).and_then(|(tracker, previous)| tracker.done(&previous)) {
+        eprintln!("{}", summary);
     }
 
     Ok(files)
 }
 
-/// Prints the provided `message` to `stdout` and proceeds to get user `input`.
+/// Formats the creation date stored on the provided `meta` (the modification date on the platforms, or
+/// filesystems, that don't keep track of it), or `unknown` when neither could be read.
+fn format_date(meta: &vfs::Meta) -> String {
+    let date = match meta.created.or(meta.modified) {
+        Some(date) => date,
+        None => return String::from("unknown"),
+    };
+    let date: DateTime<Utc> = date.into();
+    date.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Prints the provided `message` to `stdout` and proceeds to get user `input` (see [`prompt`] for the prompts
+/// that time out).
 /// ## Examples
 /// ```
 /// # use dir_cleaner::get_input;
@@ -257,17 +2187,13 @@ pub fn get_dir_files(path: &str, file_name: &str) -> Result<Vec<File>, std::io::
 /// // Prints: "Please provide a number" and gets the input provided by the user.
 /// ```
 pub fn get_input(message: &str) -> String {
-    println!("{}", message);
-    let mut input = String::new();
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Couldn't read the provided information.");
-    input
+    prompt::ask(message, "")
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::scratch::Scratch;
 
     #[test]
     fn file_constructor() {
@@ -278,10 +2204,14 @@ mod test {
         let path=  "./test.txt";
 
         let file = File {
-            name: name.to_owned(),
-            folder: folder.to_owned(),
+            name: Arc::from(name),
+            folder: Arc::from(folder),
             creation_date: creation_date.to_owned(),
-            path: path.to_owned(),
+            size: 0,
+            digest: None,
+            matched_by: None,
+            mime: None,
+            location: None,
         };
 
         // Act and Assert
@@ -296,7 +2226,7 @@ mod test {
         let path = "./test.txt";
         let metadata = &f.metadata().unwrap();
         let creation_date = metadata.created().unwrap();
-        let creation_date: DateTime<Utc> = creation_date.clone().into();
+        let creation_date: DateTime<Utc> = creation_date.into();
         let creation_date = creation_date.format("%Y-%m-%d %H:%M:%S").to_string();
         let file = File::new(name, ".", &creation_date, path);
 
@@ -316,9 +2246,10 @@ mod test {
         let path = path.to_str().unwrap();
         let metadata = &f.metadata().unwrap();
         let creation_date = metadata.created().unwrap();
-        let creation_date: DateTime<Utc> = creation_date.clone().into();
+        let creation_date: DateTime<Utc> = creation_date.into();
         let creation_date = creation_date.format("%Y-%m-%d %H:%M:%S").to_string();
-        let expected_file = File::new(name, "./", &creation_date, &path);
+        let mut expected_file = File::new(name, "./", &creation_date, path);
+        expected_file.size = metadata.len();
 
         // Act
         let files = super::get_dir_files("./", "text.txt").unwrap_or_else(|err| {
@@ -332,4 +2263,201 @@ mod test {
         //teardown.
         files[0].delete()
     }
+
+    #[test]
+    fn get_dir_files_limited_by_bytes() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("walk");
+        fs::create_dir_all(root.join("b/big"))?;
+        fs::create_dir_all(root.join("a"))?;
+        fs::write(root.join("b/a.txt"), vec![0; 10])?;
+        fs::write(root.join("a/a.txt"), vec![0; 20])?;
+        fs::write(root.join("b/big/a.txt"), vec![0; 2000])?;
+        fs::create_dir_all(root.join("c"))?;
+        fs::write(root.join("c/a.txt"), vec![0; 1])?;
+        let limits = Limits { max_bytes: Some(1024), protected: vec![root.join("c")], ..Limits::default() };
+
+        // Act
+        let (files, skipped) = super::get_dir_files_limited(root.to_str().unwrap(), &|name| name == "a.txt", &limits)?;
+
+        // Assert
+        let found: Vec<(&str, u64)> = files.iter().map(|f| (&*f.folder, f.size)).collect();
+        let (a, b) = (root.join("a"), root.join("b"));
+        assert_eq!(found, vec![(a.to_str().unwrap(), 20), (b.to_str().unwrap(), 10)]);
+        assert!(Arc::ptr_eq(&files[0].name, &files[1].name));
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0].path, root.join("b/big").to_str().unwrap());
+        let protected = root.join("c").to_string_lossy().to_string();
+        assert_eq!(skipped[1], Skipped { path: protected, reason: String::from("system-protected directory") });
+        Ok(())
+    }
+
+    #[test]
+    fn get_dir_files_skipping_hidden() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("hidden");
+        fs::create_dir_all(root.join(".cache"))?;
+        fs::create_dir_all(root.join("docs"))?;
+        for file in [".a.txt", ".cache/a.txt", "docs/a.txt"] {
+            fs::write(root.join(file), "same")?;
+        }
+        let limits = Limits { skip_hidden: true, ..Limits::default() };
+
+        // Act
+        let (files, _) = super::get_dir_files_limited(root.to_str().unwrap(), &|name| name.contains("a.txt"), &limits)?;
+
+        // Assert
+        let found: Vec<String> = files.iter().map(|f| f.path().into_owned()).collect();
+        assert_eq!(found, vec![root.join("docs/a.txt").to_str().unwrap()]);
+        Ok(())
+    }
+
+    #[test]
+    fn get_dir_files_within_sizes() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("sizes");
+        for (file, size) in [("a.txt", 10), ("b.txt", 100), ("c.txt", 1000)] {
+            fs::write(root.join(file), vec![0; size])?;
+        }
+        let limits = Limits { min_size: Some(100), max_size: Some(999), ..Limits::default() };
+
+        // Act
+        let (files, _) = super::get_dir_files_limited(root.to_str().unwrap(), &|_| true, &limits)?;
+
+        // Assert
+        let sizes: Vec<u64> = files.iter().map(|f| f.size).collect();
+        assert_eq!(sizes, vec![100]);
+        Ok(())
+    }
+
+    #[test]
+    fn get_dir_files_of_types() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("types");
+        fs::write(root.join("a.txt"), b"GIF89a\x01\0\x01\0")?;
+        fs::write(root.join("b.gif"), "Not an image.")?;
+        fs::write(root.join("c.bin"), [0, 1, 2])?;
+        let limits = Limits { types: "image,video".parse()?, ..Limits::default() };
+
+        // Act
+        let (files, _) = super::get_dir_files_limited(root.to_str().unwrap(), &|_| true, &limits)?;
+
+        // Assert
+        let found: Vec<(&str, Option<&str>)> = files.iter().map(|f| (&*f.name, f.mime.as_deref())).collect();
+        assert_eq!(found, vec![("a.txt", Some("image/gif"))]);
+        Ok(())
+    }
+
+    #[test]
+    fn get_dir_files_within_dates() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("dates");
+        let now = std::time::SystemTime::now();
+        for (file, date) in [("a.txt", "2019-06-01"), ("b.txt", "2021-06-01"), ("c.txt", "2023-06-01")] {
+            let modified = dates::parse_bound(date, now)?;
+            fs::File::create(root.join(file))?.set_times(fs::FileTimes::new().set_modified(modified))?;
+        }
+        let range = dates::DateRange {
+            before: Some(dates::parse_bound("2022-01-01", now)?),
+            after: Some(dates::parse_bound("2020-01-01", now)?),
+        };
+        let limits = Limits { dates: range, ..Limits::default() };
+
+        // Act
+        let (files, _) = super::get_dir_files_limited(root.to_str().unwrap(), &|_| true, &limits)?;
+
+        // Assert
+        let found: Vec<&str> = files.iter().map(|f| &*f.name).collect();
+        assert_eq!(found, vec!["b.txt"]);
+        Ok(())
+    }
+
+    #[test]
+    fn format_date_falls_back_to_the_modification() {
+        // Arrange
+        let modified = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(86400);
+        let meta = vfs::Meta { kind: vfs::Kind::File, len: 0, created: None, modified: Some(modified), id: None };
+        let unknown = vfs::Meta { modified: None, ..meta };
+
+        // Act and Assert
+        assert_eq!(format_date(&meta), "1970-01-02 00:00:00");
+        assert_eq!(format_date(&unknown), "unknown");
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn get_dir_files_on_one_file_system() -> Result<(), std::io::Error> {
+        // Arrange
+        let limits = Limits { one_file_system: true, max_depth: Some(1), ..Limits::default() };
+
+        // Act
+        let (_, skipped) = super::get_dir_files_limited("/", &|_| false, &limits)?;
+
+        // Assert
+        let proc = Skipped { path: String::from("/proc"), reason: String::from("mount point of another filesystem") };
+        assert!(skipped.contains(&proc));
+        Ok(())
+    }
+
+    #[test]
+    fn get_dir_files_biggest_first() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("biggest");
+        for (dir, size) in [("a", 10), ("b", 3000), ("c", 200)] {
+            fs::create_dir_all(root.join(dir))?;
+            fs::write(root.join(dir).join("a.txt"), vec![0; size])?;
+        }
+        let limits = Limits { biggest_first: true, ..Limits::default() };
+
+        // Act
+        let (files, _) = super::get_dir_files_limited(root.to_str().unwrap(), &|name| name == "a.txt", &limits)?;
+
+        // Assert
+        let sizes: Vec<u64> = files.iter().map(|f| f.size).collect();
+        assert_eq!(sizes, vec![3000, 200, 10]);
+        Ok(())
+    }
+
+    #[test]
+    fn piped_runs_dont_wait_for_answers() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let parse = |args: &[&str]| Options::parse(["dir_cleaner", "./test"].iter().chain(args).map(|a| a.to_string()));
+        let named = parse(&["--name", "a.txt", "--audit-log", "audit.log"])?;
+        let unnamed = parse(&[])?;
+        let timed = parse(&["--prompt-timeout", "5s", "--default-answer", "keep"])?;
+
+        // Act
+        let piped = unattended_when_piped(&named, false)?;
+
+        // Assert
+        assert_eq!(piped.as_ref().map(|unattended| unattended.timeout), Some(Duration::ZERO));
+        assert_eq!(piped.and_then(|unattended| unattended.audit_log).as_deref(), Some("audit.log"));
+        assert_eq!(unattended_when_piped(&named, true)?, None);
+        assert_eq!(unattended_when_piped(&timed, false)?, None);
+        assert!(unattended_when_piped(&unnamed, false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn replays_keep_the_last_copy() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("replay-last-copy");
+        std::fs::write(root.join("a.txt"), "same")?;
+        std::fs::write(root.join("b.txt"), "same")?;
+        let directory = root.to_string_lossy();
+        let options = Options::parse(["dir_cleaner", &directory, "--by-content"].iter().map(|a| a.to_string()))?;
+        let comparison = scanner::Comparison { content: true, ..scanner::Comparison::default() };
+        let files = scanner::Scanner::new(&directory).comparison(comparison).scan()?.files;
+        let recorded = [Action::Delete { path: String::from("a.txt") }, Action::Delete { path: String::from("b.txt") }];
+        let (companions, mut decisions) = (Companions::default(), Decisions::default());
+
+        // Act
+        let replayed = replay(files, &recorded, &options, &companions, &mut decisions, &Theme::plain());
+
+        // Assert
+        let err = replayed.expect_err("deleting both copies should be refused");
+        assert!(err.is::<verification::LastCopyError>());
+        assert!(root.join("a.txt").exists() && root.join("b.txt").exists());
+        Ok(())
+    }
 }
@@ -0,0 +1,147 @@
+//! Guards skipping pathological directories (maildirs, cache shards holding millions of files...) instead of
+//! stalling the scan on them (`--skip-dirs-larger-than 1M-entries`, `--skip-dirs-larger-than 10GB`).
+
+use crate::dates::DateRange;
+use crate::exclude::Exclusions;
+use crate::extensions::Extensions;
+use crate::mime::Types;
+use crate::units::{parse_size, UnitError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Limits a directory must respect in order to be scanned.
+/// ## Example
+/// ```
+/// # use dir_cleaner::limits::{Limit, Limits};
+/// let mut limits = Limits::default();
+/// limits.add("1M-entries".parse::<Limit>().unwrap());
+/// limits.add("10GB".parse::<Limit>().unwrap());
+/// assert_eq!(limits.max_entries, Some(1_000_000));
+/// assert_eq!(limits.max_bytes, Some(10 * 1024 * 1024 * 1024));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Limits {
+    /// Highest amount of entries stored directly on a directory.
+    pub max_entries: Option<usize>,
+    /// Highest amount of bytes stored directly on a directory (its subdirectories aren't counted).
+    pub max_bytes: Option<u64>,
+    /// Directories that are never scanned (E.G: the system-managed ones, see [`drives`](crate::drives)).
+    pub protected: Vec<PathBuf>,
+    /// Leave the git repositories out of the scan (see [`repos`](crate::repos)).
+    pub skip_repos: bool,
+    /// Scan the biggest directories first (see [`estimate::direct_bytes`](crate::estimate::direct_bytes)).
+    pub biggest_first: bool,
+    /// Deepest level of subdirectories scanned (`0` only scans the files stored directly on the root).
+    pub max_depth: Option<usize>,
+    /// Follow the symbolic links, scanning every directory they lead to once.
+    pub follow_symlinks: bool,
+    /// Leave out the directories stored on another filesystem than the root (the mount points of network shares,
+    /// external drives...), on the platforms telling the devices apart (not Windows).
+    pub one_file_system: bool,
+    /// Print a summary of every top-level directory once it's scanned (see [`summaries`](crate::summaries)).
+    pub dir_summaries: bool,
+    /// Entries left out of the scan, without listing the excluded directories (see [`exclude`](crate::exclude)).
+    pub exclude: Exclusions,
+    /// Honor the `.gitignore` and `.ignore` files, as well as the global excludes of git (see
+    /// [`gitignore`](crate::gitignore)).
+    pub respect_gitignore: bool,
+    /// Leave out the entries whose name starts with a dot (E.G: `.bashrc` or `.cache`, along with everything
+    /// stored on them).
+    pub skip_hidden: bool,
+    /// Smallest size of the collected files, in bytes (`--min-size`).
+    pub min_size: Option<u64>,
+    /// Biggest size of the collected files, in bytes (`--max-size`).
+    pub max_size: Option<u64>,
+    /// Dates the collected files must fall between (see [`dates`](crate::dates)).
+    pub dates: DateRange,
+    /// Extensions the collected files bear one of (see [`extensions`](crate::extensions)).
+    pub extensions: Extensions,
+    /// Types of content the collected files hold one of, sniffing them (see [`mime`](crate::mime)).
+    pub types: Types,
+}
+
+impl Limits {
+    /// Whether `dir` is one of the `protected` directories (ignoring the case on Windows).
+    pub fn is_protected(&self, dir: &Path) -> bool {
+        self.protected.iter().any(|protected| match cfg!(windows) {
+            true => protected.to_string_lossy().to_lowercase() == dir.to_string_lossy().to_lowercase(),
+            false => protected == dir,
+        })
+    }
+
+    /// Whether a file of `size` bytes falls within `min_size` and `max_size` (both included).
+    pub fn fits(&self, size: u64) -> bool {
+        self.min_size.is_none_or(|min| size >= min) && self.max_size.is_none_or(|max| size <= max)
+    }
+
+    pub fn add(&mut self, limit: Limit) {
+        match limit {
+            Limit::Entries(entries) => self.max_entries = Some(entries),
+            Limit::Bytes(bytes) => self.max_bytes = Some(bytes),
+        }
+    }
+}
+
+/// A single limit, as provided through the command line.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Limit {
+    /// E.G: `500K-entries` (decimal multiples: `K`, `M` and `G`).
+    Entries(usize),
+    /// E.G: `10GB` (see the units accepted by the `size` field of the queries).
+    Bytes(u64),
+}
+
+impl FromStr for Limit {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let count = match s.trim().strip_suffix("-entries").or_else(|| s.trim().strip_suffix(" entries")) {
+            Some(count) => count,
+            None => return parse_size(s).map(Limit::Bytes),
+        };
+        let (number, multiplier) = match count.chars().last().map(|c| c.to_ascii_uppercase()) {
+            Some('K') => (&count[..count.len() - 1], 1_000.0),
+            Some('M') => (&count[..count.len() - 1], 1_000_000.0),
+            Some('G') => (&count[..count.len() - 1], 1_000_000_000.0),
+            _ => (count, 1.0),
+        };
+        match number.parse::<f64>() {
+            Ok(number) if number >= 0.0 => Ok(Limit::Entries((number * multiplier).round() as usize)),
+            _ => Err(UnitError(s.to_string())),
+        }
+    }
+}
+
+/// Reason of the entries left out because their metadata couldn't be read.
+pub const UNREADABLE: &str = "metadata unavailable";
+
+/// Reason of the entries left out because their content couldn't be sniffed (see [`mime`](crate::mime)).
+pub const UNREADABLE_CONTENT: &str = "content unavailable";
+
+/// A directory left out of the scan (or an entry, when its metadata couldn't be read).
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Skipped {
+    pub path: String,
+    pub reason: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_limits() {
+        // Act and Assert
+        assert_eq!("1M-entries".parse::<Limit>(), Ok(Limit::Entries(1_000_000)));
+        assert_eq!("2.5k-entries".parse::<Limit>(), Ok(Limit::Entries(2_500)));
+        assert_eq!("300-entries".parse::<Limit>(), Ok(Limit::Entries(300)));
+        assert_eq!("1GB".parse::<Limit>(), Ok(Limit::Bytes(1024 * 1024 * 1024)));
+        assert!("many-entries".parse::<Limit>().is_err());
+        assert!("huge".parse::<Limit>().is_err());
+        let sizes = Limits { min_size: Some(10), max_size: Some(20), ..Limits::default() };
+        assert!(sizes.fits(10) && sizes.fits(20));
+        assert!(!sizes.fits(9) && !sizes.fits(21));
+        assert!(Limits::default().fits(0));
+    }
+}
@@ -0,0 +1,120 @@
+//! Symbolic links and shortcuts (`.lnk` and `.desktop` files) pointing to the found files, which break once
+//! their target is deleted (`--check-links`).
+//!
+//! The scanned directories are walked again looking for them: symbolic links are resolved, while the shortcuts
+//! are searched for the path of every found file (written as UTF-8 or as UTF-16, as `.lnk` files do).
+
+use crate::File;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Links and shortcuts found under the scanned directories, by the file they point to.
+#[derive(Debug, Default)]
+pub struct References {
+    referrers: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl References {
+    /// Finds the links and shortcuts located under `roots` that point to one of the `files`.
+    pub fn find(roots: &[String], files: &[File]) -> Self {
+        let targets: HashMap<PathBuf, PathBuf> = files
+            .iter()
+            .filter_map(|file| {
+                let canonical = fs::canonicalize(&*file.path()).ok()?;
+                Some((canonical, std::path::absolute(&*file.path()).ok()?))
+            })
+            .collect();
+        let mut references = References::default();
+        let mut pending: Vec<PathBuf> = roots.iter().map(PathBuf::from).collect();
+        while let Some(dir) = pending.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                match entry.file_type() {
+                    Ok(kind) if kind.is_symlink() => {
+                        if let Some(target) = fs::canonicalize(&path).ok().filter(|t| targets.contains_key(t)) {
+                            references.referrers.entry(target).or_default().push(path);
+                        }
+                    }
+                    Ok(kind) if kind.is_dir() => pending.push(path),
+                    Ok(_) if is_shortcut(&path) => references.add_shortcut(path, &targets),
+                    _ => {}
+                }
+            }
+        }
+        for referrers in references.referrers.values_mut() {
+            referrers.sort();
+        }
+        references
+    }
+
+    /// Records the shortcut located at `path` as a referrer of the `targets` it mentions.
+    fn add_shortcut(&mut self, path: PathBuf, targets: &HashMap<PathBuf, PathBuf>) {
+        let content = match fs::read(&path) {
+            Ok(content) => content,
+            Err(_) => return,
+        };
+        // Shortcuts are small: looking for every target on each of them is cheaper than parsing their formats.
+        for (canonical, absolute) in targets {
+            if mentions(&content, absolute) || mentions(&content, canonical) {
+                self.referrers.entry(canonical.clone()).or_default().push(path.clone());
+            }
+        }
+    }
+
+    /// Links and shortcuts pointing to the file located at `path`.
+    pub fn of(&self, path: &Path) -> &[PathBuf] {
+        fs::canonicalize(path).ok().and_then(|path| self.referrers.get(&path)).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Whether the file located at `path` is a shortcut.
+fn is_shortcut(path: &Path) -> bool {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    extension == "lnk" || extension == "desktop"
+}
+
+/// Whether `content` holds `path`, written as UTF-8 or as UTF-16.
+fn mentions(content: &[u8], path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    let utf16: Vec<u8> = path.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let contains = |needle: &[u8]| content.windows(needle.len()).any(|window| window == needle);
+    contains(path.as_bytes()) || contains(&utf16)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn links_and_shortcuts_are_found() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("links");
+        fs::create_dir_all(root.join("docs"))?;
+        let target = root.join("docs").join("a.txt");
+        fs::write(&target, "a")?;
+        fs::write(root.join("b.txt"), "b")?;
+        fs::write(root.join("a.desktop"), format!("[Desktop Entry]\nExec=edit {}\n", target.display()))?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&target, root.join("latest.txt"))?;
+        let file = |path: &Path| File::new("a.txt", "", "2022-07-23 12:33:01", &path.to_string_lossy());
+        let files = vec![file(&target), file(&root.join("b.txt"))];
+
+        // Act
+        let references = References::find(&[root.to_string_lossy().to_string()], &files);
+
+        // Assert
+        let mut expected = vec![root.join("a.desktop")];
+        if cfg!(unix) {
+            expected.push(root.join("latest.txt"));
+        }
+        assert_eq!(references.of(&target), expected.as_slice());
+        assert!(references.of(&root.join("b.txt")).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+//! Paged listing of the found files on the command line.
+//!
+//! The entries are written straight from the typed [`File`]s when a page is displayed, instead of formatting
+//! the whole result set up front: presenting hundreds of thousands of entries only costs the page on screen.
+//!
+//! On a terminal, the entries fit its width (see [`Layout`]): the middle of the long names and folders is
+//! replaced by `…`, and the warnings are wrapped. Otherwise the formatting stays plain and stable. Every entry
+//! tells what its location is likely to hold (see [`safety`]), and why it was found (see [`provenance`]).
+//!
+//! `--plain` writes every detail on a labeled line of its own instead, without indentation, icons or colors, for
+//! screen readers and dumb terminals.
+
+use crate::provenance;
+use crate::safety;
+use crate::theme::Theme;
+use crate::File;
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::Path;
+
+/// Entries shown at once unless `--page-size` says otherwise.
+pub const PAGE_SIZE: usize = 100;
+
+/// Columns taken by the tab indenting the details of an entry.
+const TAB_WIDTH: usize = 8;
+
+/// How the entries are laid out.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Layout {
+    /// Width of the terminal the listing is written to (`None` leaves the lines as they are).
+    pub width: Option<usize>,
+    /// Prefix the entries with an icon telling the type of the file (`--icons`).
+    pub icons: bool,
+    /// Write simple labeled lines (`--plain`), leaving the other fields aside.
+    pub plain: bool,
+}
+
+/// `text` fitting `width` columns, its middle being replaced by `…` when it's too long.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::listing::truncate_middle;
+/// assert_eq!(truncate_middle("/home/user/photos/2019/summer", 16), "/home/us…/summer");
+/// assert_eq!(truncate_middle("/home", 16), "/home");
+/// ```
+pub fn truncate_middle(text: &str, width: usize) -> Cow<'_, str> {
+    let len = text.chars().count();
+    if len <= width || width < 3 {
+        return Cow::Borrowed(text);
+    }
+    let tail = (width - 1) / 2;
+    let head = width - 1 - tail;
+    let head: String = text.chars().take(head).collect();
+    let tail: String = text.chars().skip(len - tail).collect();
+    Cow::Owned(format!("{}…{}", head, tail))
+}
+
+/// `text` split in lines of at most `width` columns, between words.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+/// Icon telling the type of the file named `name`, judging by its extension.
+pub fn icon(name: &str) -> &'static str {
+    let extension = Path::new(name).extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" | "png" | "gif" | "heic" | "webp" | "bmp" | "tiff" | "raw" | "cr2" | "nef" | "arw"
+        | "dng" => "🖼",
+        "mp4" | "mkv" | "mov" | "avi" | "webm" | "m4v" => "🎬",
+        "mp3" | "flac" | "wav" | "ogg" | "m4a" | "aac" | "opus" => "🎵",
+        "zip" | "tar" | "gz" | "7z" | "rar" | "xz" | "bz2" | "zst" => "📦",
+        "pdf" | "doc" | "docx" | "odt" | "txt" | "md" | "rtf" => "📄",
+        "exe" | "msi" | "dmg" | "pkg" | "deb" | "rpm" | "appimage" => "⚙",
+        _ => "•",
+    }
+}
+
+/// A page of the listing, ready to be displayed.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::listing::Page;
+/// # use dir_cleaner::theme::Theme;
+/// let files = vec![
+///     File::new("a.txt", ".", "2022-07-23 12:33:01", "./a.txt"),
+///     File::new("a.txt", "./old", "2022-07-23 12:33:01", "./old/a.txt"),
+/// ];
+/// let theme = Theme::plain();
+/// let page = Page::new(&files, 1, 1, &theme).to_string();
+/// assert!(page.starts_with("Entry 2\n\tfile name: a.txt \n\tdirectory: ./old \n"));
+/// assert!(page.contains("\n\tcreation date: 2022-07-23 12:33:01 \n"));
+/// assert!(page.contains("\n\tlocation: "));
+/// assert!(page.contains("\n\tmatched by: same name\n"));
+/// ```
+pub struct Page<'a> {
+    files: &'a [File],
+    /// Index of the first entry of the page.
+    start: usize,
+    theme: &'a Theme,
+    layout: Layout,
+}
+
+impl<'a> Page<'a> {
+    /// Page `number` (starting at 0) of `files`, cut in pages of `size` entries (`0` shows them all at once).
+    pub fn new(files: &'a [File], number: usize, size: usize, theme: &'a Theme) -> Self {
+        if size == 0 {
+            return Self { files, start: 0, theme, layout: Layout::default() };
+        }
+        let start = number.saturating_mul(size).min(files.len());
+        let end = start.saturating_add(size).min(files.len());
+        Self { files: &files[start..end], start, theme, layout: Layout::default() }
+    }
+
+    /// Lays the entries out as `layout` says.
+    pub fn with_layout(self, layout: Layout) -> Self {
+        Self { layout, ..self }
+    }
+
+    /// Amount of pages needed to list `len` entries.
+    pub fn count(len: usize, size: usize) -> usize {
+        match size {
+            0 => 1,
+            size => len.div_ceil(size).max(1),
+        }
+    }
+
+    /// Line telling which entries are shown, when the listing doesn't fit on a single page.
+    pub fn footer(&self, len: usize) -> Option<String> {
+        if self.files.len() == len {
+            return None;
+        }
+        Some(format!(
+            "Showing entries {}-{} of {}. Write more (or page followed by a number) to see the others.",
+            self.start + 1,
+            self.start + self.files.len(),
+            len
+        ))
+    }
+}
+
+impl fmt::Display for Page<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let theme = self.theme;
+        // Columns left for a detail once indented, labeled and followed by a space.
+        let fit = |text, label: &str| match self.layout.width {
+            Some(width) => truncate_middle(text, width.saturating_sub(TAB_WIDTH + label.len() + 1)),
+            None => Cow::Borrowed(text),
+        };
+        if self.layout.plain {
+            for (i, file) in self.files.iter().enumerate() {
+                writeln!(f, "Entry {}", self.start + i + 1)?;
+                writeln!(f, "File name: {}\nDirectory: {}", file.name, file.folder)?;
+                writeln!(f, "Creation date: {}", file.creation_date)?;
+                writeln!(f, "Location: {}", safety::classify(Path::new(&*file.path())))?;
+                writeln!(f, "Matched by: {}", provenance::describe(file))?;
+                if file.heuristic_match() {
+                    let confidence = file.confidence();
+                    writeln!(f, "Warning: heuristic match with {}% confidence, compare the copies.", confidence)?;
+                }
+            }
+            return Ok(());
+        }
+        for (i, file) in self.files.iter().enumerate() {
+            let entry = match self.layout.icons {
+                true => format!("{} Entry {}", icon(&file.name), self.start + i + 1),
+                false => format!("Entry {}", self.start + i + 1),
+            };
+            writeln!(f, "{}", theme.painted(theme.entry, &entry))?;
+            writeln!(
+                f,
+                "\tfile name: {} \n\tdirectory: {} \n\tcreation date: {} \n\tlocation: {} \n\tmatched by: {}",
+                theme.painted(theme.name, &fit(&file.name, "file name:")),
+                theme.painted(theme.folder, &fit(&file.folder, "directory:")),
+                theme.painted(theme.date, &file.creation_date),
+                safety::classify(Path::new(&*file.path())),
+                provenance::describe(file)
+            )?;
+            if file.heuristic_match() {
+                let warning = format!(
+                    "heuristic match ({}% confidence), compare the copies before deleting them",
+                    file.confidence()
+                );
+                let lines = match self.layout.width {
+                    Some(width) => wrap(&warning, width.saturating_sub(TAB_WIDTH).max(20)),
+                    None => vec![warning],
+                };
+                for line in lines {
+                    writeln!(f, "{}", theme.painted(theme.warning, &format!("\t{}", line)))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn files(count: usize) -> Vec<File> {
+        (0..count)
+            .map(|i| File::new("a.txt", &format!("./{}", i), "2022-07-23 12:33:01", &format!("./{}/a.txt", i)))
+            .collect()
+    }
+
+    #[test]
+    fn pages_cover_every_entry() {
+        // Arrange
+        let files = files(5);
+        let theme = Theme::plain();
+
+        // Act
+        let last = Page::new(&files, 2, 2, &theme);
+        let past_the_end = Page::new(&files, 7, 2, &theme);
+
+        // Assert
+        assert_eq!(Page::count(files.len(), 2), 3);
+        assert_eq!(Page::count(0, 2), 1);
+        assert!(last.to_string().starts_with("Entry 5\n"));
+        assert!(last.footer(files.len()).unwrap().starts_with("Showing entries 5-5 of 5."));
+        assert_eq!(past_the_end.to_string(), "");
+        assert_eq!(Page::new(&files, 0, 0, &theme).footer(files.len()), None);
+    }
+
+    #[test]
+    fn layout_fits_the_terminal() {
+        // Arrange
+        let mut files = vec![File::new("movie.mkv", "/home/user/videos/2019/summer/trip", "2022-07-23 12:33:01", "")];
+        files[0].digest = Some(String::from("~video:0@87"));
+        let theme = Theme::plain();
+        let layout = Layout { width: Some(40), icons: true, plain: false };
+
+        // Act
+        let page = Page::new(&files, 0, 1, &theme).with_layout(layout).to_string();
+
+        // Assert
+        assert!(page.starts_with("🎬 Entry 1\n"));
+        assert!(page.contains("\tdirectory: /home/user…ummer/trip \n"));
+        assert!(page.contains("\theuristic match (87%\n\tconfidence), compare the copies\n\tbefore deleting them\n"));
+        assert_eq!(wrap("a bb ccc", 4), vec!["a bb", "ccc"]);
+        let plain = Page::new(&files, 0, 1, &theme).with_layout(Layout { plain: true, ..layout }).to_string();
+        assert_eq!(
+            plain,
+            "Entry 1\nFile name: movie.mkv\nDirectory: /home/user/videos/2019/summer/trip\n\
+            Creation date: 2022-07-23 12:33:01\nLocation: unknown\nMatched by: similar video (87%)\n\
+            Warning: heuristic match with 87% confidence, compare the copies.\n"
+        );
+    }
+}
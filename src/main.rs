@@ -1,11 +1,13 @@
+use dir_cleaner::{cli, errors};
 use std::env;
+use std::io;
 use std::process;
 
 fn main() {
-    let args = env::args();
+    let args: Vec<String> = env::args().collect();
 
-    dir_cleaner::run(args).unwrap_or_else(|err| {
-        eprintln!("{}", err);
+    dir_cleaner::run(args.clone().into_iter()).unwrap_or_else(|err| {
+        let _ = errors::write(&mut io::stderr(), err.as_ref(), cli::requested_output(&args));
         process::exit(1);
     });
 }
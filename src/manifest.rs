@@ -0,0 +1,278 @@
+//! Manifests of the content digests of a tree, so the duplicates spread across two machines can be found
+//! without any connection between them: `dir_cleaner export-hashes <dir> <manifest>` writes the manifest on
+//! the first machine, and `dir_cleaner import-hashes <dir> <manifest>` lists the files of the second one
+//! whose content is already found on the first.
+//!
+//! The contents are compared by their SHA-256 digest, or by the one picked through `--hash` on the first machine
+//! (see [`hash`](crate::hash)), which the second one follows. Only the files whose size is found on the manifest
+//! are read on the second machine.
+//!
+//! A manifest can be published on a web server as well (E.G: the one of a team share), and checked by the
+//! scheduled scans of several machines through `--reference-manifest https://…/hashes.json`. It's downloaded
+//! through the `curl` program rather than an HTTP client built in, so it has to be installed: it ships with
+//! macOS, Windows 10 (since 1803) and later, and most Linux distributions, but minimal images often lack it.
+
+use crate::hash;
+use crate::limits::{Limits, Skipped};
+use crate::schema::{self, DocumentError, Failed, MANIFEST_VERSION};
+use crate::storage::{map_parallel, Reading};
+use crate::units::format_size;
+use crate::File;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Returned when a manifest can't be downloaded because `curl` isn't installed.
+#[derive(Debug, Clone)]
+pub struct CurlError {
+    pub url: String,
+}
+
+impl Error for CurlError {}
+
+impl fmt::Display for CurlError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Downloading the manifest `{}` needs `curl`, which wasn't found: install it, or download the manifest \
+             and pass its path instead.",
+            self.url
+        )
+    }
+}
+
+/// Digest of a file of the exported tree.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct HashEntry {
+    /// Path of the file, relative to the root of the manifest.
+    pub path: String,
+    pub size: u64,
+    /// Content digest, as stored on [`File::digest`].
+    pub digest: String,
+}
+
+/// Content digests of every file of a tree.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::manifest::{HashEntry, Manifest};
+/// let entry = HashEntry { path: String::from("a.txt"), size: 3, digest: String::from("=sha256:ba78") };
+/// let manifest = Manifest::new("/home/me", vec![entry]);
+/// let parsed = Manifest::parse(&manifest.to_json().unwrap()).unwrap();
+/// assert_eq!(parsed, manifest);
+/// ```
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Version of the format the manifest was written with (see [`schema`](crate::schema)).
+    pub version: u32,
+    /// Directory the manifest was exported from.
+    pub root: String,
+    /// When the manifest was exported, formatted as `%Y-%m-%d %H:%M:%S` (UTC).
+    pub exported_at: String,
+    pub entries: Vec<HashEntry>,
+}
+
+/// A file whose content is already found on a manifest.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Match {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Path of the copy, relative to the root of the manifest.
+    pub copy: String,
+}
+
+impl Manifest {
+    pub fn new(root: &str, entries: Vec<HashEntry>) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            root: root.to_string(),
+            exported_at: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            entries,
+        }
+    }
+
+    /// Digests every file located under `root` (leaving out the directories exceeding `limits`, which are
+    /// returned as well). The files that can't be read are reported and left out.
+    pub fn export(root: &str, limits: &Limits, reading: &Reading) -> Result<(Self, Vec<Skipped>), io::Error> {
+        let (files, skipped) = crate::get_dir_files_limited(root, &|_| true, limits)?;
+        let digests = digests(&files.iter().collect::<Vec<&File>>(), reading.algorithm, reading);
+        let entries = files
+            .iter()
+            .zip(digests)
+            .filter_map(|(file, digest)| {
+                let path = file.path();
+                let path = Path::new(&*path);
+                let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string();
+                Some(HashEntry { path: relative, size: file.size, digest: digest? })
+            })
+            .collect();
+        Ok((Manifest::new(root, entries), skipped))
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, Box<dyn Error>> {
+        let document: Value = serde_json::from_str(content)?;
+        schema::version("manifest", &document, MANIFEST_VERSION)?;
+        Ok(serde_json::from_value(document)?)
+    }
+
+    /// Writes the manifest to the file located at `path`.
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_json()?)
+            .map_err(|e| DocumentError::new("manifest", path, Failed::Save, e).into())
+    }
+
+    /// Reads the manifest stored on the file located at `path`, or downloads it when `path` is an `http://` or
+    /// `https://` URL.
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let content = match path.starts_with("http://") || path.starts_with("https://") {
+            true => download(path)?,
+            false => fs::read_to_string(path).map_err(|e| DocumentError::new("manifest", path, Failed::Read, e))?,
+        };
+        Manifest::parse(&content).map_err(|e| DocumentError::new("manifest", path, Failed::Parse, e).into())
+    }
+
+    /// Files located under `root` (leaving out the directories exceeding `limits`, which are returned as well)
+    /// whose content is found on the manifest.
+    pub fn matches(
+        &self,
+        root: &str,
+        limits: &Limits,
+        reading: &Reading,
+    ) -> Result<(Vec<Match>, Vec<Skipped>), io::Error> {
+        let (files, skipped) = crate::get_dir_files_limited(root, &|_| true, limits)?;
+        Ok((self.matching(&files, reading), skipped))
+    }
+
+    /// Those of `files` whose content is found on the manifest (digested with the algorithm of the manifest).
+    pub fn matching(&self, files: &[File], reading: &Reading) -> Vec<Match> {
+        let algorithm = self.entries.first().and_then(|entry| hash::Algorithm::of(&entry.digest));
+        let sizes: HashSet<u64> = self.entries.iter().map(|entry| entry.size).collect();
+        let files: Vec<&File> = files.iter().filter(|file| sizes.contains(&file.size)).collect();
+        let mut copies: HashMap<&str, &str> = HashMap::new();
+        for entry in &self.entries {
+            copies.entry(&entry.digest).or_insert(&entry.path);
+        }
+        files
+            .iter()
+            .zip(digests(&files, algorithm.unwrap_or(reading.algorithm), reading))
+            .filter_map(|(file, digest)| {
+                let copy = copies.get(digest?.as_str())?;
+                Some(Match { path: PathBuf::from(&*file.path()), size: file.size, copy: copy.to_string() })
+            })
+            .collect()
+    }
+
+    /// Lines describing the `matches` found on the manifest.
+    pub fn summary(&self, matches: &[Match]) -> String {
+        let mut summary = String::new();
+        for found in matches {
+            let path = found.path.display();
+            summary.push_str(&format!("`{}` is already found on `{}` as `{}`.\n", path, self.root, found.copy));
+        }
+        let bytes = matches.iter().map(|found| found.size).sum();
+        let (count, bytes) = (matches.len(), format_size(bytes));
+        summary.push_str(&format!("{} file(s) ({}) already found on `{}`.\n", count, bytes, self.root));
+        summary
+    }
+}
+
+/// Downloads the manifest published at `url`.
+fn download(url: &str) -> Result<String, Box<dyn Error>> {
+    fetch("curl", url)
+}
+
+/// Downloads the document published at `url` through `curl`, found as `program`.
+fn fetch(program: &str, url: &str) -> Result<String, Box<dyn Error>> {
+    let output = Command::new(program)
+        .args(["--fail", "--silent", "--show-error", "--location", "--max-time", "300", url])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| -> Box<dyn Error> {
+            match e.kind() {
+                io::ErrorKind::NotFound => CurlError { url: url.to_string() }.into(),
+                _ => {
+                    let reason = format!("couldn't run `curl`: {}", e);
+                    DocumentError::new("manifest", url, Failed::Download, reason).into()
+                }
+            }
+        })?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(DocumentError::new("manifest", url, Failed::Download, error.trim()).into());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Content digests of `files` computed with `algorithm` (reusing the cached ones). The files that can't be read
+/// are reported and left without a digest.
+fn digests(files: &[&File], algorithm: hash::Algorithm, reading: &Reading) -> Vec<Option<String>> {
+    map_parallel(files, reading.readers, |file| {
+        hash::content_digest(Path::new(&*file.path()), algorithm, reading.cache)
+            .inspect_err(|err| hash::unreadable(file, err))
+            .ok()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cache::Cache;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn files_already_on_the_manifest_are_found() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("manifest");
+        let (first, second) = (root.join("machine-a"), root.join("machine-b"));
+        fs::create_dir_all(first.join("photos"))?;
+        fs::create_dir_all(&second)?;
+        fs::write(first.join("photos").join("a.jpg"), "same")?;
+        fs::write(first.join("notes.txt"), "only on a")?;
+        fs::write(second.join("a-renamed.jpg"), "same")?;
+        fs::write(second.join("b.jpg"), "diff")?;
+        fs::write(second.join("notes.txt"), "only on b")?;
+        let path = root.join("hashes.json");
+        let cache = Cache::default();
+        let algorithm = hash::Algorithm::Sha256;
+        let reading = Reading { readers: 2, cache: &cache, include_placeholders: false, algorithm };
+
+        // Act
+        let (manifest, _) = Manifest::export(&first.to_string_lossy(), &Limits::default(), &reading)?;
+        manifest.save(&path.to_string_lossy())?;
+        let loaded = Manifest::load(&path.to_string_lossy())?;
+        let (matches, _) = loaded.matches(&second.to_string_lossy(), &Limits::default(), &reading)?;
+
+        // Assert
+        assert_eq!(loaded, manifest);
+        assert_eq!(loaded.entries.len(), 2);
+        let copy = Path::new("photos").join("a.jpg").to_string_lossy().to_string();
+        assert_eq!(matches, vec![Match { path: second.join("a-renamed.jpg"), size: 4, copy }]);
+        assert!(Manifest::parse("{\"version\": 99}").is_err());
+        let summary = format!("1 file(s) (4 B) already found on `{}`.\n", loaded.root);
+        assert!(loaded.summary(&matches).ends_with(&summary));
+        assert!(Manifest::load("http://127.0.0.1:9/hashes.json").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn a_missing_curl_is_reported_as_such() {
+        // Act
+        let result = fetch("dir_cleaner-missing-curl", "https://files.example.com/hashes.json");
+
+        // Assert
+        let err = result.unwrap_err();
+        assert!(err.is::<CurlError>());
+        assert!(err.to_string().contains("needs `curl`, which wasn't found"));
+    }
+}
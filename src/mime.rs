@@ -0,0 +1,237 @@
+//! Types of content detected from the first bytes of the files (their magic numbers), since the extensions may
+//! lie. `--type image,video` (repeatable) only collects the files whose content is of one of the listed
+//! [`Category`]s, sniffing them as they're found, and records the detected MIME type on [`File::mime`] for the
+//! outputs.
+//!
+//! The files whose content matches no known signature (nor holds text) are left out as soon as a type is given,
+//! along with the ones that can't be read.
+
+use crate::vfs::Vfs;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::{self, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(doc)]
+use crate::File;
+
+/// Amount of bytes read from the start of the files (the signature of the tar archives lies at `257`).
+pub const HEAD: usize = 512;
+
+/// Signatures found at the start of the files, along with the MIME type they reveal.
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\xFF\xD8\xFF", "image/jpeg"),
+    (b"\x89PNG\r\n\x1A\n", "image/png"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"II*\0", "image/tiff"),
+    (b"MM\0*", "image/tiff"),
+    (b"\x1AE\xDF\xA3", "video/x-matroska"),
+    (b"\0\0\x01\xBA", "video/mpeg"),
+    (b"ID3", "audio/mpeg"),
+    (b"\xFF\xFB", "audio/mpeg"),
+    (b"\xFF\xF3", "audio/mpeg"),
+    (b"fLaC", "audio/flac"),
+    (b"OggS", "audio/ogg"),
+    (b"%PDF-", "application/pdf"),
+    (b"{\\rtf", "application/rtf"),
+    (b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1", "application/x-ole-storage"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1F\x8B", "application/gzip"),
+    (b"BZh", "application/x-bzip2"),
+    (b"\xFD7zXZ\0", "application/x-xz"),
+    (b"7z\xBC\xAF\x27\x1C", "application/x-7z-compressed"),
+    (b"Rar!\x1A\x07", "application/vnd.rar"),
+];
+
+/// MIME type revealed by `head`, the first bytes of a file (up to [`HEAD`]).
+/// ## Examples
+/// ```
+/// # use dir_cleaner::mime::sniff;
+/// assert_eq!(sniff(b"\x89PNG\r\n\x1A\n\0\0\0\rIHDR"), Some("image/png"));
+/// assert_eq!(sniff(b"\0\0\0\x18ftypmp42\0\0\0\0"), Some("video/mp4"));
+/// assert_eq!(sniff(b"Plain old text."), Some("text/plain"));
+/// assert_eq!(sniff(b"\0\x01\x02"), None);
+/// ```
+pub fn sniff(head: &[u8]) -> Option<&'static str> {
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(signature, _)| head.starts_with(signature)) {
+        return Some(mime);
+    }
+    match (head.get(..4), head.get(4..8), head.get(8..12)) {
+        (Some(b"RIFF"), _, Some(b"WEBP")) => return Some("image/webp"),
+        (Some(b"RIFF"), _, Some(b"WAVE")) => return Some("audio/wav"),
+        (Some(b"RIFF"), _, Some(b"AVI ")) => return Some("video/x-msvideo"),
+        (_, Some(b"ftyp"), Some(brand)) => return Some(iso_media(brand)),
+        _ => {}
+    }
+    if head.get(257..262) == Some(b"ustar") {
+        return Some("application/x-tar");
+    }
+    match std::str::from_utf8(head) {
+        _ if head.is_empty() || head.contains(&0) => None,
+        Ok(_) => Some("text/plain"),
+        // The head may end in the middle of a character.
+        Err(e) if e.error_len().is_none() => Some("text/plain"),
+        Err(_) => None,
+    }
+}
+
+/// MIME type of the ISO media files (MP4, QuickTime, HEIF...) bearing `brand`.
+fn iso_media(brand: &[u8]) -> &'static str {
+    match brand {
+        b"heic" | b"heix" | b"mif1" | b"msf1" => "image/heic",
+        b"avif" => "image/avif",
+        b"M4A " | b"M4B " => "audio/mp4",
+        b"qt  " => "video/quicktime",
+        _ => "video/mp4",
+    }
+}
+
+/// MIME type of the file located at `path`, sniffed from its first bytes.
+pub fn detect(vfs: &dyn Vfs, path: &Path) -> Result<Option<&'static str>, io::Error> {
+    let mut head = Vec::with_capacity(HEAD);
+    vfs.open(path)?.take(HEAD as u64).read_to_end(&mut head)?;
+    Ok(sniff(&head))
+}
+
+/// Returned when an unknown type of content is provided.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeError(pub String);
+
+impl Error for TypeError {}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Unknown type `{}` (expected image, video, audio, text, document or archive).", self.0)
+    }
+}
+
+/// Broad types of content, as provided through `--type`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Category {
+    Image,
+    Video,
+    Audio,
+    Text,
+    /// PDF, RTF and the legacy office formats (the recent ones are ZIP archives).
+    Document,
+    /// ZIP, gzip, bzip2, xz, 7-Zip, RAR and tar.
+    Archive,
+}
+
+impl FromStr for Category {
+    type Err = TypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "image" => Ok(Category::Image),
+            "video" => Ok(Category::Video),
+            "audio" => Ok(Category::Audio),
+            "text" => Ok(Category::Text),
+            "document" => Ok(Category::Document),
+            "archive" => Ok(Category::Archive),
+            _ => Err(TypeError(s.to_string())),
+        }
+    }
+}
+
+impl Category {
+    /// Category the `mime` type belongs to.
+    pub fn of(mime: &str) -> Option<Category> {
+        match mime.split_once('/') {
+            Some(("image", _)) => Some(Category::Image),
+            Some(("video", _)) => Some(Category::Video),
+            Some(("audio", _)) => Some(Category::Audio),
+            Some(("text", _)) => Some(Category::Text),
+            _ => match mime {
+                "application/pdf" | "application/rtf" | "application/x-ole-storage" => Some(Category::Document),
+                "application/zip" | "application/gzip" | "application/x-bzip2" | "application/x-xz"
+                | "application/x-7z-compressed" | "application/vnd.rar" | "application/x-tar" => {
+                    Some(Category::Archive)
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Categories the content of the collected files belongs to one of (any content when empty).
+/// ## Examples
+/// ```
+/// # use dir_cleaner::mime::Types;
+/// let types: Types = "image, video".parse().unwrap();
+/// assert!(types.allows(Some("image/png")));
+/// assert!(!types.allows(Some("application/pdf")));
+/// assert!(!types.allows(None));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Types {
+    categories: BTreeSet<Category>,
+}
+
+impl FromStr for Types {
+    type Err = TypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut types = Types::default();
+        types.add(s)?;
+        Ok(types)
+    }
+}
+
+impl Types {
+    /// Collects the files whose content belongs to one of the comma separated categories of `list` as well.
+    pub fn add(&mut self, list: &str) -> Result<(), TypeError> {
+        for category in list.split(',') {
+            self.categories.insert(category.parse()?);
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty()
+    }
+
+    /// Whether a file whose content was detected as `mime` belongs to one of the categories.
+    pub fn allows(&self, mime: Option<&str>) -> bool {
+        self.is_empty() || mime.and_then(Category::of).is_some_and(|category| self.categories.contains(&category))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::vfs;
+
+    #[test]
+    fn contents_are_told_by_their_first_bytes() -> Result<(), io::Error> {
+        // Arrange
+        let mut tar = vec![0; HEAD];
+        tar[257..262].copy_from_slice(b"ustar");
+        let mut memory = vfs::Memory::default();
+        memory.add_file("/photos/lying.txt", b"\xFF\xD8\xFF\xE0\0\x10JFIF".to_vec());
+        memory.add_file("/photos/notes.jpg", "Not a photo at all, é.");
+        memory.add_file("/photos/clip.mov", b"\0\0\0\x14ftypqt  \0\0\0\0".to_vec());
+        memory.add_file("/photos/backup.bin", tar);
+
+        // Act
+        let detected = ["lying.txt", "notes.jpg", "clip.mov", "backup.bin"]
+            .map(|name| detect(&memory, &Path::new("/photos").join(name)));
+
+        // Assert
+        let detected: Vec<Option<&str>> = detected.into_iter().collect::<Result<_, _>>()?;
+        assert_eq!(
+            detected,
+            vec![Some("image/jpeg"), Some("text/plain"), Some("video/quicktime"), Some("application/x-tar")]
+        );
+        let categories: Vec<Option<Category>> = detected.iter().map(|mime| mime.and_then(Category::of)).collect();
+        let expected = [Category::Image, Category::Text, Category::Video, Category::Archive].map(Some);
+        assert_eq!(categories, expected);
+        assert_eq!(sniff("é".as_bytes().get(..1).unwrap_or_default()), Some("text/plain"));
+        assert!("spreadsheet".parse::<Types>().is_err());
+        Ok(())
+    }
+}
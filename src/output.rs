@@ -0,0 +1,291 @@
+//! How the found files are written out: the listing meant to be read (`text`, the default), a JSON array meant
+//! to be processed by other programs (`--output json`) or a CSV table meant to be opened on a spreadsheet
+//! (`--output csv`), in which case nothing is asked nor deleted.
+//!
+//! The dates of the JSON and CSV outputs follow `--date-format` (a `strftime` pattern or `rfc3339`), in UTC unless
+//! `--local-dates` is passed, since spreadsheets and databases are picky about them. The listing is left as is.
+
+use crate::groups;
+use crate::provenance;
+use crate::theme::Theme;
+use crate::File;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, NaiveDateTime, SecondsFormat, Utc};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// Returned when the requested output format doesn't exist.
+#[derive(Debug, Clone)]
+pub struct OutputError(pub String);
+
+impl Error for OutputError {}
+
+impl fmt::Display for OutputError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Unknown output format `{}` (available: text, json, csv).", self.0)
+    }
+}
+
+/// Returned when a date format holds an unknown specifier.
+#[derive(Debug, Clone)]
+pub struct DateFormatError(pub String);
+
+impl Error for DateFormatError {}
+
+impl fmt::Display for DateFormatError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid date format `{}` (expected `rfc3339` or a strftime pattern such as `%d/%m/%Y`).", self.0)
+    }
+}
+
+/// How the dates are written by the JSON and CSV outputs.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::output::DateFormat;
+/// let format: DateFormat = "%d/%m/%Y".parse().unwrap();
+/// assert_eq!(format.format("2022-07-23 12:33:01"), "23/07/2022");
+/// let format: DateFormat = "rfc3339".parse().unwrap();
+/// assert_eq!(format.format("2022-07-23 12:33:01"), "2022-07-23T12:33:01Z");
+/// assert_eq!(format.format("unknown"), "unknown");
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct DateFormat {
+    /// A `strftime` pattern, or `None` for RFC 3339.
+    pub pattern: Option<String>,
+    /// Write the dates in the local time zone rather than in UTC.
+    pub local: bool,
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        DateFormat { pattern: Some(String::from("%Y-%m-%d %H:%M:%S")), local: false }
+    }
+}
+
+impl FromStr for DateFormat {
+    type Err = DateFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("rfc3339") {
+            return Ok(DateFormat { pattern: None, local: false });
+        }
+        match StrftimeItems::new(s).any(|item| item == Item::Error) {
+            true => Err(DateFormatError(s.to_string())),
+            false => Ok(DateFormat { pattern: Some(s.to_string()), local: false }),
+        }
+    }
+}
+
+impl DateFormat {
+    /// Rewrites `date`, formatted as `%Y-%m-%d %H:%M:%S` (UTC) like the [`File::creation_date`]. The dates that
+    /// can't be read (E.G: `unknown`) are left as is.
+    pub fn format(&self, date: &str) -> String {
+        match NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S") {
+            Ok(date) => self.format_date(date.and_utc()),
+            Err(_) => date.to_string(),
+        }
+    }
+
+    pub fn format_date(&self, date: DateTime<Utc>) -> String {
+        match (&self.pattern, self.local) {
+            (Some(pattern), false) => date.format(pattern).to_string(),
+            (Some(pattern), true) => date.with_timezone(&Local).format(pattern).to_string(),
+            (None, false) => date.to_rfc3339_opts(SecondsFormat::Secs, true),
+            (None, true) => date.with_timezone(&Local).to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Output {
+    /// The information of every file, an indented line per field.
+    #[default]
+    Text,
+    /// An array holding an object per file (`name`, `folder`, `creation_date`, `size`, `digest`, `path` and
+    /// `matched_by`, see [`provenance`]), along with `mime` once their content was sniffed (see [`mime`](crate::mime)).
+    Json,
+    /// A row per file (`group`, `path`, `name`, `size`, `created`, `modified`, `matched_by` and `mime`), the copies
+    /// sharing a group id.
+    Csv,
+}
+
+impl FromStr for Output {
+    type Err = OutputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Output::Text),
+            "json" => Ok(Output::Json),
+            "csv" => Ok(Output::Csv),
+            _ => Err(OutputError(s.to_string())),
+        }
+    }
+}
+
+impl Output {
+    /// Writes `files` to `out`, coloring the text output according to `theme`.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::File;
+    /// # use dir_cleaner::output::Output;
+    /// # use dir_cleaner::theme::Theme;
+    /// let files = vec![File::new("a.txt", "./test", "2022-07-23 12:33:01", "./test/a.txt")];
+    /// let mut out = vec![];
+    /// Output::Json.write_files(&mut out, &files, &Theme::plain()).unwrap();
+    /// let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    /// assert_eq!(json[0]["path"], "./test/a.txt");
+    /// ```
+    pub fn write_files(self, out: &mut impl Write, files: &[File], theme: &Theme) -> Result<(), io::Error> {
+        self.write_files_dated(out, files, theme, &DateFormat::default())
+    }
+
+    /// Same as [`Output::write_files`], but writes the dates of the JSON and CSV outputs following `dates`.
+    pub fn write_files_dated(
+        self,
+        out: &mut impl Write,
+        files: &[File],
+        theme: &Theme,
+        dates: &DateFormat,
+    ) -> Result<(), io::Error> {
+        match self {
+            Output::Text => files.iter().try_for_each(|file| {
+                writeln!(out, "{} \n\tmatched by: {}", file.info(theme), provenance::describe(file))?;
+                match &file.mime {
+                    Some(mime) => writeln!(out, "\tcontent type: {}", mime),
+                    None => Ok(()),
+                }
+            }),
+            Output::Json => {
+                let mut json = serde_json::to_value(files)?;
+                for (file, object) in files.iter().zip(json.as_array_mut().into_iter().flatten()) {
+                    if let Some(date) = object.get_mut("creation_date") {
+                        *date = dates.format(date.as_str().unwrap_or_default()).into();
+                    }
+                    object["matched_by"] = provenance::describe(file).into();
+                }
+                serde_json::to_writer_pretty(&mut *out, &json)?;
+                writeln!(out)
+            }
+            Output::Csv => csv(out, files, dates),
+        }
+    }
+}
+
+/// Writes `files` as a CSV table. The groups of duplicates are numbered in the order they're first found, the
+/// files without copies are left without a group.
+fn csv(out: &mut impl Write, files: &[File], dates: &DateFormat) -> Result<(), io::Error> {
+    let mut duplicates = groups::duplicates(files);
+    duplicates.sort_by_key(|group| group.indexes[0]);
+    let mut ids: HashMap<usize, usize> = HashMap::new();
+    for (id, group) in duplicates.iter().enumerate() {
+        ids.extend(group.indexes.iter().map(|i| (*i, id + 1)));
+    }
+    writeln!(out, "group,path,name,size,created,modified,matched_by,mime")?;
+    for (i, file) in files.iter().enumerate() {
+        let group = ids.get(&i).map(usize::to_string).unwrap_or_default();
+        let modified = std::fs::metadata(&*file.path()).and_then(|metadata| metadata.modified());
+        let modified = modified.map(|date| dates.format_date(date.into()));
+        let row = [
+            group,
+            field(&file.path()),
+            field(&file.name),
+            file.size.to_string(),
+            field(&dates.format(&file.creation_date)),
+            field(&modified.unwrap_or_default()),
+            field(&provenance::describe(file)),
+            field(file.mime.as_deref().unwrap_or_default()),
+        ];
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+/// `value`, quoted when it holds a separator, a quote or a line break.
+pub(crate) fn field(value: &str) -> String {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn files_are_written_in_every_format() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let mut file = File::new("a.txt", "./test", "2022-07-23 12:33:01", "./test/a.txt");
+        file.size = 3;
+        let files = vec![file, File::new("a.txt", "./test/old", "2022-07-24 08:00:00", "./test/old/a.txt")];
+        let (mut text, mut json) = (vec![], vec![]);
+
+        // Act
+        Output::Text.write_files(&mut text, &files, &Theme::plain())?;
+        Output::Json.write_files(&mut json, &files, &Theme::plain())?;
+
+        // Assert
+        let text = String::from_utf8(text)?;
+        assert!(text.starts_with("\tfile name: a.txt \n\tdirectory: ./test \n\tcreation date: 2022-07-23 12:33:01 \n"));
+        let json: serde_json::Value = serde_json::from_slice(&json)?;
+        assert_eq!(json.as_array().map(Vec::len), Some(2));
+        assert_eq!(json[0]["name"], "a.txt");
+        assert_eq!(json[0]["folder"], "./test");
+        assert_eq!(json[0]["creation_date"], "2022-07-23 12:33:01");
+        assert_eq!(json[0]["size"], 3);
+        assert_eq!(json[1]["path"], "./test/old/a.txt");
+        assert_eq!(json[1]["matched_by"], "same name");
+        assert!(text.contains("\n\tcreation date: 2022-07-24 08:00:00 \n\tmatched by: same name\n"));
+        let error = "yaml".parse::<Output>().unwrap_err();
+        assert_eq!(error.to_string(), "Unknown output format `yaml` (available: text, json, csv).");
+        Ok(())
+    }
+
+    #[test]
+    fn duplicates_share_a_group_in_csv() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let files = vec![
+            File::new("a.txt", "./test", "2022-07-23 12:33:01", "./test/a.txt"),
+            File::new("b,c.txt", "./test", "2022-07-23 12:34:00", "./test/b,c.txt"),
+            File::new("a.txt", "./test/old", "2022-07-24 08:00:00", "./test/old/a.txt"),
+        ];
+        let mut out = vec![];
+
+        // Act
+        Output::Csv.write_files(&mut out, &files, &Theme::plain())?;
+
+        // Assert
+        let csv = String::from_utf8(out)?;
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], "group,path,name,size,created,modified,matched_by,mime");
+        assert_eq!(rows[1], "1,./test/a.txt,a.txt,0,2022-07-23 12:33:01,,same name,");
+        assert_eq!(rows[2], ",\"./test/b,c.txt\",\"b,c.txt\",0,2022-07-23 12:34:00,,same name,");
+        assert_eq!(rows[3], "1,./test/old/a.txt,a.txt,0,2022-07-24 08:00:00,,same name,");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        Ok(())
+    }
+
+    #[test]
+    fn dates_follow_the_requested_format() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let files = vec![File::new("a.txt", "./test", "2022-07-23 12:33:01", "./test/a.txt")];
+        let (mut json, mut csv) = (vec![], vec![]);
+        let dates: DateFormat = "rfc3339".parse()?;
+
+        // Act
+        Output::Json.write_files_dated(&mut json, &files, &Theme::plain(), &dates)?;
+        Output::Csv.write_files_dated(&mut csv, &files, &Theme::plain(), &"%d/%m/%Y, %H:%M".parse()?)?;
+
+        // Assert
+        let json: serde_json::Value = serde_json::from_slice(&json)?;
+        assert_eq!(json[0]["creation_date"], "2022-07-23T12:33:01Z");
+        let row = Some(",./test/a.txt,a.txt,0,\"23/07/2022, 12:33\",,same name,");
+        assert_eq!(String::from_utf8(csv)?.lines().nth(1), row);
+        assert!("%Y-%Q".parse::<DateFormat>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+//! Records of the same file found more than once, when the roots overlap (E.G: `~/photos` and `~/photos/2023`)
+//! or when the followed links lead to an entry reached otherwise. They're collapsed into the first one found, so
+//! the counts and the reclaimable sizes aren't inflated by files that are actually stored once.
+//!
+//! The files are told apart by their device and inode where the filesystem provides them (which holds the hard
+//! links as well, whose content is stored once too), and by their canonical path otherwise.
+
+use crate::vfs::Vfs;
+use crate::File;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// What tells a file apart from the others.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Identity {
+    Inode(u64, u64),
+    Path(PathBuf),
+}
+
+fn identity(vfs: &dyn Vfs, path: &Path) -> Option<Identity> {
+    match vfs.metadata(path).ok()?.id {
+        Some((device, inode)) => Some(Identity::Inode(device, inode)),
+        None => vfs.canonicalize(path).ok().map(Identity::Path),
+    }
+}
+
+/// Leaves out the records of `files` (found on `vfs`) pointing to a file recorded earlier, returning how many
+/// were left out. The files that can't be inspected anymore are kept.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::overlap;
+/// # use dir_cleaner::scanner::Scanner;
+/// # use dir_cleaner::vfs::Memory;
+/// let mut memory = Memory::default();
+/// memory.add_file("/photos/2023/a.jpg", "jpeg");
+/// let mut files = Scanner::new("/photos").vfs(&memory).scan().unwrap().files;
+/// files.append(&mut Scanner::new("/photos/2023").vfs(&memory).scan().unwrap().files);
+/// assert_eq!(overlap::collapse(&mut files, &memory), 1);
+/// assert_eq!(files.len(), 1);
+/// ```
+pub fn collapse(files: &mut Vec<File>, vfs: &dyn Vfs) -> usize {
+    let mut seen = HashSet::new();
+    let before = files.len();
+    files.retain(|file| identity(vfs, Path::new(&*file.path())).is_none_or(|identity| seen.insert(identity)));
+    before - files.len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+    use crate::vfs::Local;
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn files_reached_twice_are_recorded_once() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("overlap");
+        fs::create_dir_all(root.join("nested"))?;
+        fs::write(root.join("a.txt"), "same")?;
+        fs::write(root.join("nested").join("a.txt"), "same")?;
+        let (outer, inner) = (root.to_string_lossy(), root.join("nested").to_string_lossy().to_string());
+        let mut files = crate::get_dir_files(&outer, "a.txt")?;
+        files.append(&mut crate::get_dir_files(&inner, "a.txt")?);
+        files.append(&mut crate::get_dir_files(&format!("{}/../nested", inner), "a.txt")?);
+
+        // Act
+        let collapsed = collapse(&mut files, &Local);
+
+        // Assert
+        assert_eq!(collapsed, 2);
+        assert_eq!(files.len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//! Restriction of the results to the files owned by the invoking user (`--only-mine`).
+//!
+//! Scanning a shared directory finds the copies of other users as well, which can't be deleted without their
+//! permission anyway: leaving them out of the results and of the deletions avoids the failed attempts.
+
+use crate::File;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::Path;
+
+/// Returned when the owner of the files can't be told on this platform.
+#[derive(Debug, Clone)]
+pub struct OwnershipError;
+
+impl Error for OwnershipError {}
+
+impl fmt::Display for OwnershipError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "`--only-mine` is only available on Unix-like systems.")
+    }
+}
+
+/// Fails when the owner of the files can't be told on this platform.
+pub fn check_supported() -> Result<(), OwnershipError> {
+    match cfg!(unix) {
+        true => Ok(()),
+        false => Err(OwnershipError),
+    }
+}
+
+/// Whether the file located at `path` belongs to the user running dir_cleaner.
+#[cfg(unix)]
+pub fn is_mine(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    extern "C" {
+        fn geteuid() -> u32;
+    }
+    // SAFETY: `geteuid` takes no arguments and can't fail.
+    let user = unsafe { geteuid() };
+    std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.uid() == user)
+}
+
+/// Whether the file located at `path` belongs to the user running dir_cleaner.
+#[cfg(not(unix))]
+pub fn is_mine(_path: &Path) -> bool {
+    true
+}
+
+/// Keeps the `files` owned by the user running dir_cleaner, returning how many were left out.
+pub fn retain_mine(files: &mut Vec<File>) -> usize {
+    let len = files.len();
+    files.retain(|file| is_mine(Path::new(&*file.path())));
+    len - files.len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn retain_mine_leaves_out_missing_files() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("ownership");
+        fs::write(root.join("a.txt"), "a")?;
+        let folder = root.to_string_lossy().to_string();
+        let path = |name: &str| root.join(name).to_string_lossy().to_string();
+        let mut files = vec![
+            File::new("a.txt", &folder, "2022-07-23 12:33:01", &path("a.txt")),
+            File::new("b.txt", &folder, "2022-07-23 12:33:01", &path("b.txt")),
+        ];
+
+        // Act
+        let left_out = retain_mine(&mut files);
+
+        // Assert
+        assert!(check_supported().is_ok());
+        assert_eq!(left_out, 1);
+        assert_eq!(&*files[0].name, "a.txt");
+        Ok(())
+    }
+}
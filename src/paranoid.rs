@@ -0,0 +1,108 @@
+//! Byte by byte comparison of the files found by content with their copies right before deleting them
+//! (`--paranoid`), which guards against the collisions of the digests and against the files modified since they
+//! were digested.
+
+use crate::File;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Fills `buffer` with the next bytes of `file`, returning how many were read (fewer at the end of the file).
+fn fill(file: &mut fs::File, buffer: &mut [u8]) -> Result<usize, io::Error> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read(&mut buffer[filled..])? {
+            0 => break,
+            read => filled += read,
+        }
+    }
+    Ok(filled)
+}
+
+/// Whether the files located at `a` and `b` hold the same bytes.
+pub fn same_bytes(a: &Path, b: &Path) -> Result<bool, io::Error> {
+    let (mut a, mut b) = (fs::File::open(a)?, fs::File::open(b)?);
+    if a.metadata()?.len() != b.metadata()?.len() {
+        return Ok(false);
+    }
+    let (mut left, mut right) = (vec![0; 64 * 1024], vec![0; 64 * 1024]);
+    loop {
+        let read = fill(&mut a, &mut left)?;
+        if read != fill(&mut b, &mut right)? || left[..read] != right[..read] {
+            return Ok(false);
+        }
+        if read == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Whether the group of `file` was told apart by the digest of its content.
+fn compared_by_content(file: &File) -> bool {
+    file.digest.as_deref().is_some_and(|digest| digest.split('+').any(|part| part.starts_with('=')))
+}
+
+/// Checks that `file` still holds the same bytes as one of its copies among `others`, returning why it must be
+/// kept otherwise. The files that weren't compared by content, or whose copies are all gone, pass.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::{paranoid, File};
+/// # std::fs::write("./paranoid-a.txt", "same").unwrap();
+/// # std::fs::write("./paranoid-b.txt", "edit").unwrap();
+/// let mut a = File::new("paranoid-a.txt", ".", "2022-07-23 12:33:01", "./paranoid-a.txt");
+/// let mut b = File::new("paranoid-b.txt", ".", "2022-07-23 12:33:01", "./paranoid-b.txt");
+/// a.digest = Some(String::from("=sha256:ba78"));
+/// b.digest = Some(String::from("=sha256:ba78"));
+/// assert!(paranoid::verify(&a, [&b]).is_err());
+/// # std::fs::remove_file("./paranoid-a.txt").unwrap();
+/// # std::fs::remove_file("./paranoid-b.txt").unwrap();
+/// ```
+pub fn verify<'a>(file: &File, others: impl IntoIterator<Item = &'a File>) -> Result<(), String> {
+    if !compared_by_content(file) {
+        return Ok(());
+    }
+    let path = file.path();
+    let copies: Vec<&File> =
+        others.into_iter().filter(|other| other.group_key() == file.group_key() && !other.is_at(&path)).collect();
+    let intact = |copy: &&File| same_bytes(Path::new(&*path), Path::new(&*copy.path())).unwrap_or_default();
+    match copies.is_empty() || copies.iter().any(intact) {
+        true => Ok(()),
+        false => Err(format!("`{}` doesn't hold the same bytes as its copies anymore", file.path())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn only_the_copies_still_identical_pass() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("paranoid");
+        let big = vec![7; 200 * 1024];
+        let mut edited = big.clone();
+        edited[150 * 1024] = 8;
+        fs::write(root.join("a.bin"), &big)?;
+        fs::write(root.join("b.bin"), &big)?;
+        fs::write(root.join("c.bin"), &edited)?;
+        let folder = root.to_string_lossy();
+        let file = |name: &str| {
+            let mut file = File::new(name, &folder, "2022-07-23 12:33:01", &root.join(name).to_string_lossy());
+            file.digest = Some(String::from("=sha256:0a1b"));
+            file
+        };
+        let (a, b, c) = (file("a.bin"), file("b.bin"), file("c.bin"));
+        let mut named = file("a.bin");
+        named.digest = None;
+
+        // Act and Assert
+        assert!(same_bytes(&root.join("a.bin"), &root.join("b.bin"))?);
+        assert!(!same_bytes(&root.join("a.bin"), &root.join("c.bin"))?);
+        assert!(verify(&a, [&b, &c]).is_ok());
+        assert!(verify(&c, [&a, &b]).is_err());
+        assert!(verify(&c, []).is_ok());
+        assert!(verify(&named, [&c]).is_ok());
+        Ok(())
+    }
+}
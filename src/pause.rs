@@ -0,0 +1,183 @@
+//! Pausing an in-progress scan, so the disk can be handed to another task for a while.
+//!
+//! The scan stops at its next checkpoint (before walking a directory, before reading a file) and waits there,
+//! keeping everything it found so far, until it's resumed. While scanning from a terminal, write `p` and press
+//! enter in order to pause it, and `r` in order to resume it; on Unix, `SIGUSR1` pauses it and `SIGUSR2` resumes
+//! it as well (E.G: `kill -USR1 <pid>`).
+
+use crate::prompt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How often a paused scan (and the controls) check whether they should go on.
+const POLL: Duration = Duration::from_millis(100);
+
+/// Whether a scan should wait before going on.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::pause::Pause;
+/// let pause = Pause::new();
+/// pause.pause();
+/// assert!(pause.is_paused());
+/// pause.resume();
+/// pause.wait(); // Returns right away.
+/// ```
+#[derive(Debug, Default)]
+pub struct Pause {
+    paused: AtomicBool,
+}
+
+impl Pause {
+    pub const fn new() -> Self {
+        Pause { paused: AtomicBool::new(false) }
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Blocks for as long as the scan is paused.
+    pub fn wait(&self) {
+        while self.is_paused() {
+            thread::sleep(POLL);
+        }
+    }
+}
+
+/// Pause shared by the scan and its controls.
+pub static SCAN: Pause = Pause::new();
+
+/// Waits there while the scan is paused.
+pub fn checkpoint() {
+    SCAN.wait();
+}
+
+/// Controls of a scan, listening until they're dropped (which resumes the scan, if it was left paused).
+pub struct Controls {
+    done: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl Controls {
+    /// Starts listening to the signals and, when `interactive`, to the lines written to the standard input.
+    pub fn start(interactive: bool) -> Self {
+        platform::handle_signals();
+        let done = Arc::new(AtomicBool::new(false));
+        let listener = interactive.then(|| {
+            println!("Scanning... write `p` and press enter in order to pause the scan (`r` resumes it).");
+            let done = Arc::clone(&done);
+            thread::spawn(move || listen(&done))
+        });
+        Controls { done, listener }
+    }
+}
+
+impl Drop for Controls {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Relaxed);
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+        SCAN.resume();
+    }
+}
+
+/// Applies the controls written to the standard input until `done`.
+fn listen(done: &AtomicBool) {
+    let lines = prompt::lines();
+    while !done.load(Ordering::Relaxed) {
+        match lines.recv_timeout(POLL).as_deref().map(str::trim) {
+            Ok("p") if !SCAN.is_paused() => {
+                SCAN.pause();
+                println!("Scan paused, write `r` and press enter in order to resume it.");
+            }
+            Ok("r") if SCAN.is_paused() => {
+                SCAN.resume();
+                println!("Scan resumed.");
+            }
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::SCAN;
+    use std::os::raw::c_int;
+    use std::sync::Once;
+
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    const SIGUSR1: c_int = 30;
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    const SIGUSR2: c_int = 31;
+    #[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "freebsd")))]
+    const SIGUSR1: c_int = 10;
+    #[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "freebsd")))]
+    const SIGUSR2: c_int = 12;
+
+    extern "C" {
+        fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+    }
+
+    /// Only stores an atomic, which is safe to do from a signal handler.
+    extern "C" fn on_signal(signum: c_int) {
+        match signum {
+            SIGUSR1 => SCAN.pause(),
+            _ => SCAN.resume(),
+        }
+    }
+
+    pub fn handle_signals() {
+        static HANDLED: Once = Once::new();
+        HANDLED.call_once(|| {
+            // SAFETY: `on_signal` is async-signal-safe, and stays valid for the whole life of the process.
+            unsafe {
+                signal(SIGUSR1, on_signal);
+                signal(SIGUSR2, on_signal);
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    pub fn handle_signals() {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn paused_scans_wait_until_resumed() {
+        // Arrange
+        let pause = Pause::new();
+        let steps = AtomicUsize::new(0);
+        pause.pause();
+
+        // Act and Assert
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                pause.wait();
+                steps.fetch_add(1, Ordering::Relaxed);
+            });
+            thread::sleep(POLL * 2);
+            assert_eq!(steps.load(Ordering::Relaxed), 0);
+            pause.resume();
+        });
+        assert_eq!(steps.load(Ordering::Relaxed), 1);
+    }
+}
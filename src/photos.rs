@@ -0,0 +1,128 @@
+//! Awareness of the `RAW` + `JPEG` pairs written by cameras (E.G: `IMG_0001.CR2` and `IMG_0001.JPG`).
+//!
+//! Both files of a pair hold the same picture, yet they aren't duplicates of each other: the `RAW` keeps
+//! data the `JPEG` lost. Since duplicates must share their name, a pair is never proposed for deletion by
+//! default. Culling the `JPEG`s whose `RAW` still exists has to be asked explicitly (`--cull-jpegs`).
+
+use crate::limits::{Limits, Skipped};
+use crate::{get_dir_files_limited, File};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const RAW_EXTENSIONS: [&str; 14] = [
+    "3fr", "arw", "cr2", "cr3", "dng", "erf", "nef", "nrw", "orf", "pef", "raf", "raw", "rw2", "srw",
+];
+
+const JPEG_EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "jpe"];
+
+fn extension(name: &str) -> Option<String> {
+    Path::new(name).extension()?.to_str().map(|e| e.to_lowercase())
+}
+
+/// Whether `name` belongs to a camera `RAW` file.
+pub fn is_raw(name: &str) -> bool {
+    extension(name).is_some_and(|e| RAW_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Whether `name` belongs to a `JPEG` file.
+pub fn is_jpeg(name: &str) -> bool {
+    extension(name).is_some_and(|e| JPEG_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Folder and (case insensitive) stem shared by both files of a pair.
+fn pair_key(file: &File) -> (&str, String) {
+    let stem = Path::new(&*file.name).file_stem().unwrap_or_default();
+    (&file.folder, stem.to_string_lossy().to_lowercase())
+}
+
+/// Whether both files form a `RAW` + `JPEG` pair.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::photos;
+/// let raw = File::new("IMG_0001.CR2", "./dcim", "2022-07-23 12:33:01", "./dcim/IMG_0001.CR2");
+/// let jpeg = File::new("IMG_0001.JPG", "./dcim", "2022-07-23 12:33:01", "./dcim/IMG_0001.JPG");
+/// assert!(photos::is_pair(&raw, &jpeg));
+/// ```
+pub fn is_pair(a: &File, b: &File) -> bool {
+    let kinds = (is_raw(&a.name), is_jpeg(&a.name), is_raw(&b.name), is_jpeg(&b.name));
+    matches!(kinds, (true, _, _, true) | (_, true, true, _)) && pair_key(a) == pair_key(b)
+}
+
+/// `RAW` sibling of the `JPEG` located at `path`, if it still exists.
+pub fn raw_sibling(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?.to_lowercase();
+    let folder = path.parent()?;
+    let entries = std::fs::read_dir(folder).ok()?;
+    entries.flatten().map(|e| e.path()).find(|sibling| {
+        let name = sibling.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let sibling_stem = sibling.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        is_raw(name) && sibling_stem.to_lowercase() == stem && sibling.is_file()
+    })
+}
+
+/// Keeps the `JPEG`s among `files` that have a `RAW` sibling.
+pub(crate) fn jpegs_with_raw(files: Vec<File>) -> Vec<File> {
+    let mut pairs: BTreeMap<(String, String), (bool, Vec<File>)> = BTreeMap::new();
+    for file in files {
+        let (folder, stem) = pair_key(&file);
+        let entry = pairs.entry((folder.to_string(), stem)).or_default();
+        if is_raw(&file.name) {
+            entry.0 = true;
+        } else {
+            entry.1.push(file);
+        }
+    }
+    let mut jpegs: Vec<File> = pairs.into_values().filter(|(raw, _)| *raw).flat_map(|(_, jpegs)| jpegs).collect();
+    jpegs.sort_by(|a, b| a.path().cmp(&b.path()));
+    jpegs
+}
+
+/// Gathers the `JPEG`s located on `path` (or its subdirectories) whose `RAW` sibling exists, along with the
+/// directories skipped because of `limits`.
+pub fn find_culls(path: &str, limits: &Limits) -> Result<(Vec<File>, Vec<Skipped>), io::Error> {
+    let (files, skipped) = get_dir_files_limited(path, &|name| is_raw(name) || is_jpeg(name), limits)?;
+    Ok((jpegs_with_raw(files), skipped))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    fn file(folder: &str, name: &str) -> File {
+        File::new(name, folder, "2022-07-23 12:33:01", &format!("{}/{}", folder, name))
+    }
+
+    #[test]
+    fn detect_pairs() {
+        // Act and Assert
+        assert!(is_pair(&file("./a", "img_1.jpeg"), &file("./a", "IMG_1.NEF")));
+        assert!(!is_pair(&file("./a", "IMG_1.JPG"), &file("./b", "IMG_1.NEF")));
+        assert!(!is_pair(&file("./a", "IMG_1.JPG"), &file("./a", "IMG_1.JPG")));
+        assert!(!is_pair(&file("./a", "IMG_1.NEF"), &file("./a", "IMG_2.JPG")));
+    }
+
+    #[test]
+    fn cull_jpegs_with_raw() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("photos");
+        std::fs::create_dir_all(root.join("b"))?;
+        for name in ["a/IMG_1.CR2", "a/IMG_1.JPG", "a/IMG_2.JPG", "b/IMG_1.jpg", "b/IMG_3.dng", "b/img_3.jpeg"] {
+            let path = root.join(name);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, b"")?;
+        }
+
+        // Act
+        let (culls, _) = find_culls(root.to_str().unwrap(), &Limits::default())?;
+
+        // Assert
+        let names: Vec<&str> = culls.iter().map(|f| &*f.name).collect();
+        assert_eq!(names, vec!["IMG_1.JPG", "img_3.jpeg"]);
+        assert_eq!(raw_sibling(&root.join("a/IMG_1.JPG")), Some(root.join("a/IMG_1.CR2")));
+        assert_eq!(raw_sibling(&root.join("a/IMG_2.JPG")), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+//! Policies of the automated cleanups, written as `TOML` files, and their dry runs: `dir_cleaner simulate <dir>
+//! --compare <policy> <policy>` scans `dir` once, applies both policies to the same results without deleting
+//! anything, and tells which files only the first one would delete, which ones only the second one would, and which
+//! ones both would. That way, a rule can be refined before it's trusted with a real cleanup.
+//!
+//! ```toml
+//! # Copy kept out of every group: `oldest`, `newest`, or a `--keep` strategy such as `highest-bitrate`.
+//! keep = "oldest"
+//! # Lowest confidence (in percent) of the heuristic matches whose copies get deleted.
+//! min_confidence = 90
+//! # Glob patterns of the paths never deleted (`*` matches across the separators).
+//! protect = ["*/masters/*"]
+//! ```
+
+use crate::glob::Pattern;
+use crate::groups;
+use crate::keep::{self, Strategy};
+use crate::schema::{DocumentError, Failed};
+use crate::units::format_size;
+use crate::File;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::str::FromStr;
+
+/// Copy a policy keeps out of every group of duplicates.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Keep {
+    /// The copy created first (the first one found wins the ties), as `--keep-one` does.
+    Oldest,
+    /// The copy created last (the first one found wins the ties).
+    Newest,
+    /// The copy a `--keep` strategy prefers (see [`keep`]).
+    Strategy(Strategy),
+}
+
+impl FromStr for Keep {
+    type Err = keep::StrategyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "oldest" => Ok(Keep::Oldest),
+            "newest" => Ok(Keep::Newest),
+            _ => s.parse().map(Keep::Strategy),
+        }
+    }
+}
+
+/// Policy as it's written on its file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Document {
+    keep: String,
+    #[serde(default)]
+    min_confidence: Option<u8>,
+    #[serde(default)]
+    protect: Vec<String>,
+}
+
+/// Rules deciding which copies an automated cleanup deletes.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::policy::Policy;
+/// let mut files = vec![
+///     File::new("a.txt", "./new", "2023-01-01 00:00:00", "./new/a.txt"),
+///     File::new("a.txt", "./old", "2022-07-23 12:33:01", "./old/a.txt"),
+/// ];
+/// files.iter_mut().for_each(|file| file.digest = Some(String::from("=sha256:ab")));
+/// let policy = Policy::parse("keep = \"oldest\"").unwrap();
+/// assert_eq!(policy.deletions(&files).into_iter().collect::<Vec<usize>>(), vec![0]);
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Policy {
+    pub keep: Keep,
+    /// Lowest confidence (in percent) of the matches whose copies get deleted.
+    pub min_confidence: Option<u8>,
+    /// Patterns of the paths never deleted.
+    pub protect: Vec<Pattern>,
+}
+
+impl Policy {
+    /// Parses the `TOML` document stored on `content`.
+    pub fn parse(content: &str) -> Result<Self, Box<dyn Error>> {
+        let document: Document = toml::from_str(content)?;
+        if document.min_confidence.is_some_and(|min| min > 100) {
+            return Err("`min_confidence` is a percentage, it can't exceed 100.".into());
+        }
+        let protect = document.protect.iter().map(|pattern| pattern.parse()).collect::<Result<_, _>>()?;
+        Ok(Policy { keep: document.keep.parse()?, min_confidence: document.min_confidence, protect })
+    }
+
+    /// Reads the policy stored on the file located at `path`.
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path).map_err(|e| DocumentError::new("policy", path, Failed::Read, e))?;
+        Policy::parse(&content).map_err(|e| DocumentError::new("policy", path, Failed::Parse, e).into())
+    }
+
+    /// Indexes of the `files` the policy deletes.
+    pub fn deletions(&self, files: &[File]) -> BTreeSet<usize> {
+        let chosen: Vec<(usize, Vec<usize>)> = match self.keep {
+            Keep::Strategy(strategy) => {
+                let suggestions = keep::suggest(strategy, files).into_iter();
+                suggestions.map(|suggestion| (suggestion.keep, suggestion.delete)).collect()
+            }
+            Keep::Oldest | Keep::Newest => groups::duplicates(files)
+                .into_iter()
+                .map(|group| {
+                    let dates = group.indexes.iter().map(|i| (*i, &files[*i].creation_date));
+                    let kept = match self.keep {
+                        Keep::Newest => dates.rev().max_by_key(|(_, date)| *date),
+                        _ => dates.min_by_key(|(_, date)| *date),
+                    };
+                    let kept = kept.map_or(group.indexes[0], |(i, _)| i);
+                    (kept, group.indexes.into_iter().filter(|i| *i != kept).collect())
+                })
+                .collect(),
+        };
+        let confident = |file: &File| self.min_confidence.is_none_or(|min| file.confidence() >= min);
+        let protected = |file: &File| self.protect.iter().any(|pattern| pattern.matches(&file.path()));
+        chosen
+            .into_iter()
+            .filter(|(kept, _)| confident(&files[*kept]))
+            .flat_map(|(_, deleted)| deleted)
+            .filter(|i| !protected(&files[*i]))
+            .collect()
+    }
+}
+
+/// Deletions of two policies applied to the same files, as indexes of those files.
+#[derive(Debug, PartialEq, Default)]
+pub struct Comparison {
+    pub only_first: Vec<usize>,
+    pub only_second: Vec<usize>,
+    pub both: Vec<usize>,
+}
+
+impl Comparison {
+    /// Lists the files of every side of the comparison, the policies being named `first` and `second`.
+    pub fn render(&self, files: &[File], first: &str, second: &str) -> String {
+        let mut rendered = String::new();
+        let sides = [
+            (format!("Only `{}` deletes", first), &self.only_first),
+            (format!("Only `{}` deletes", second), &self.only_second),
+            (String::from("Both delete"), &self.both),
+        ];
+        for (title, indexes) in sides {
+            let bytes: u64 = indexes.iter().map(|i| files[*i].size).sum();
+            let _ = writeln!(rendered, "{} {} file(s) ({}):", title, indexes.len(), format_size(bytes));
+            for i in indexes {
+                let _ = writeln!(rendered, "  {}", files[*i].path());
+            }
+        }
+        rendered
+    }
+}
+
+/// Applies the `first` and `second` policies to `files`, without deleting anything.
+pub fn compare(files: &[File], first: &Policy, second: &Policy) -> Comparison {
+    let (first, second) = (first.deletions(files), second.deletions(files));
+    Comparison {
+        only_first: first.difference(&second).copied().collect(),
+        only_second: second.difference(&first).copied().collect(),
+        both: first.intersection(&second).copied().collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn both_policies_are_applied_to_the_same_files() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let dated = [
+            ("./a/masters/x.txt", "2021-01-01 00:00:00", "=sha256:ab"),
+            ("./b/x.txt", "2022-01-01 00:00:00", "=sha256:ab"),
+            ("./c/x.txt", "2023-01-01 00:00:00", "=sha256:ab"),
+            ("./a/y.txt", "2021-01-01 00:00:00", "~text:1@70"),
+            ("./b/y.txt", "2022-01-01 00:00:00", "~text:1@70"),
+        ];
+        let files: Vec<File> = dated
+            .iter()
+            .map(|(path, date, digest)| {
+                let mut file = File::new(&path[path.len() - 5..], ".", date, path);
+                file.digest = Some(digest.to_string());
+                file
+            })
+            .collect();
+        let oldest = Policy::parse("keep = \"oldest\"\nmin_confidence = 90")?;
+        let newest = Policy::parse("keep = \"newest\"\nprotect = [\"*/masters/*\"]")?;
+
+        // Act
+        let comparison = compare(&files, &oldest, &newest);
+
+        // Assert
+        assert_eq!(comparison, Comparison { only_first: vec![2], only_second: vec![3], both: vec![1] });
+        assert!(comparison.render(&files, "a.toml", "b.toml").starts_with("Only `a.toml` deletes 1 file(s) (0 B):\n"));
+        assert!(Policy::parse("keep = \"largest\"").is_err());
+        assert!(Policy::parse("keep = \"oldest\"\nmin_confidence = 120").is_err());
+        assert!(Policy::parse("keep = \"oldest\"\ndelete = \"all\"").is_err());
+        Ok(())
+    }
+}
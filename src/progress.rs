@@ -0,0 +1,145 @@
+//! Progress of the scans, redrawn on the standard error while they run so the long ones don't look stuck: the
+//! directories walked, the files examined and the bytes hashed so far. It's left out when the standard error
+//! isn't a terminal, or when `--no-progress` is passed (E.G: by the scripts capturing it).
+
+use crate::units::format_size;
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How often the progress is redrawn.
+const REDRAW: Duration = Duration::from_millis(200);
+
+/// What a scan went through so far.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Totals {
+    pub dirs: u64,
+    pub files: u64,
+    /// Bytes of content read by the comparisons.
+    pub bytes: u64,
+}
+
+impl fmt::Display for Totals {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} directory(ies) walked, {} file(s) examined, {} hashed",
+            self.dirs,
+            self.files,
+            format_size(self.bytes)
+        )
+    }
+}
+
+/// Counters updated by the scan as it goes, from any thread.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::progress::{Counters, Totals};
+/// let counters = Counters::new();
+/// counters.walked(12);
+/// counters.hashed(1536);
+/// assert_eq!(counters.totals(), Totals { dirs: 1, files: 12, bytes: 1536 });
+/// ```
+#[derive(Debug, Default)]
+pub struct Counters {
+    dirs: AtomicU64,
+    files: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl Counters {
+    pub const fn new() -> Self {
+        Counters { dirs: AtomicU64::new(0), files: AtomicU64::new(0), bytes: AtomicU64::new(0) }
+    }
+
+    /// Counts a directory walked, along with the `files` it lists.
+    pub fn walked(&self, files: usize) {
+        self.dirs.fetch_add(1, Ordering::Relaxed);
+        self.files.fetch_add(files as u64, Ordering::Relaxed);
+    }
+
+    /// Counts `bytes` of content read.
+    pub fn hashed(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn totals(&self) -> Totals {
+        Totals {
+            dirs: self.dirs.load(Ordering::Relaxed),
+            files: self.files.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Progress of the scan of the process.
+pub static SCAN: Counters = Counters::new();
+
+/// Line redrawing the progress of [`SCAN`] until it's dropped (which erases it).
+pub struct Display {
+    done: Arc<AtomicBool>,
+    painter: Option<JoinHandle<()>>,
+}
+
+impl Display {
+    /// Starts redrawing the progress when `enabled`.
+    pub fn start(enabled: bool) -> Self {
+        let done = Arc::new(AtomicBool::new(false));
+        let painter = enabled.then(|| {
+            let done = Arc::clone(&done);
+            thread::spawn(move || paint(&done))
+        });
+        Display { done, painter }
+    }
+}
+
+impl Drop for Display {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Relaxed);
+        if let Some(painter) = self.painter.take() {
+            let _ = painter.join();
+        }
+    }
+}
+
+/// Redraws the progress on the standard error until `done`, erasing it then.
+fn paint(done: &AtomicBool) {
+    let mut drawn = 0;
+    while !done.load(Ordering::Relaxed) {
+        let line = format!("Scanning: {}...", SCAN.totals());
+        // The line only grows, but the padding keeps a shorter one from leaving characters behind.
+        eprint!("\r{:width$}", line, width = drawn);
+        let _ = io::stderr().flush();
+        drawn = drawn.max(line.len());
+        thread::sleep(REDRAW);
+    }
+    if drawn > 0 {
+        eprint!("\r{:width$}\r", "", width = drawn);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn totals_add_up() {
+        // Arrange
+        let counters = Counters::new();
+
+        // Act
+        counters.walked(3);
+        counters.walked(0);
+        counters.hashed(2048);
+        counters.hashed(1024);
+
+        // Assert
+        let totals = counters.totals();
+        assert_eq!(totals, Totals { dirs: 2, files: 3, bytes: 3072 });
+        assert_eq!(totals.to_string(), "2 directory(ies) walked, 3 file(s) examined, 3.0 KiB hashed");
+    }
+}
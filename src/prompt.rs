@@ -0,0 +1,183 @@
+//! Prompts that don't wait forever for an answer.
+//!
+//! Runs launched by a scheduler may have nobody in front of them: with `--prompt-timeout 60s --default-answer
+//! keep`, a prompt left unanswered for that long gets the safe answer instead (keeping the files), and the event
+//! is recorded on the `--audit-log`, so what happened can be told afterwards. The batch mode (`--yes`) doesn't
+//! wait at all: every prompt gets the safe answer right away, without reading the standard input.
+
+use crate::units::format_duration;
+use chrono::Utc;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// Answer applied to the prompts left unanswered.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DefaultAnswer {
+    /// Keep the files: decline the deletions and quit once asked for more.
+    Keep,
+}
+
+/// Returned when an unknown default answer is provided.
+#[derive(Debug, Clone)]
+pub struct UnknownAnswerError(pub String);
+
+impl Error for UnknownAnswerError {}
+
+impl fmt::Display for UnknownAnswerError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Unknown default answer `{}` (expected `keep`).", self.0)
+    }
+}
+
+impl FromStr for DefaultAnswer {
+    type Err = UnknownAnswerError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "keep" => Ok(DefaultAnswer::Keep),
+            _ => Err(UnknownAnswerError(value.to_string())),
+        }
+    }
+}
+
+/// How the prompts behave when nobody answers them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Unattended {
+    /// How long a prompt waits for an answer (`0` doesn't read the standard input at all).
+    pub timeout: Duration,
+    /// Answer applied once `timeout` runs out.
+    pub default: DefaultAnswer,
+    /// File the applied answers are appended to.
+    pub audit_log: Option<String>,
+}
+
+static UNATTENDED: OnceLock<Unattended> = OnceLock::new();
+
+/// Lines read from the standard input by a thread of their own, so waiting for them can time out.
+static ANSWERS: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+
+/// Makes the following prompts time out according to `unattended` (only the first call matters).
+pub fn unattend(unattended: Unattended) {
+    let _ = UNATTENDED.set(unattended);
+}
+
+/// Whether the prompts were [`unattend`]ed, so nobody may answer them.
+pub fn unattended() -> bool {
+    UNATTENDED.get().is_some()
+}
+
+/// Prints `message` and returns the line written by the user. Once the prompts are [`unattend`]ed, the `keep`
+/// answer (keeping the files) is returned when nobody answers in time.
+pub fn ask(message: &str, keep: &str) -> String {
+    println!("{}", message);
+    let unattended = match UNATTENDED.get() {
+        Some(unattended) => unattended,
+        // The standard input was handed to a thread, which the lines have to come from from then on.
+        None if ANSWERS.get().is_some() => return lines().recv().unwrap_or_default(),
+        None => {
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("Couldn't read the provided information.");
+            return input;
+        }
+    };
+    if unattended.timeout.is_zero() {
+        return apply(message, keep, "batch mode", unattended);
+    }
+    answer(&lines(), message, keep, unattended)
+}
+
+/// Lines of the standard input, read by a thread of their own from the first call on (the controls of the
+/// [`pause`](crate::pause)d scans read them as well).
+pub(crate) fn lines() -> MutexGuard<'static, Receiver<String>> {
+    ANSWERS.get_or_init(|| Mutex::new(read_lines())).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Sends the lines of the standard input as they're written, until it's closed.
+fn read_lines() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || loop {
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) | Err(_) => break,
+            Ok(_) if sender.send(input).is_err() => break,
+            Ok(_) => {}
+        }
+    });
+    receiver
+}
+
+/// The next line sent through `answers`, or the `keep` answer when none shows up in time.
+fn answer(answers: &Receiver<String>, message: &str, keep: &str, unattended: &Unattended) -> String {
+    let why = match answers.recv_timeout(unattended.timeout) {
+        Ok(input) => return input,
+        Err(RecvTimeoutError::Timeout) => format!("no answer within {}", format_duration(unattended.timeout)),
+        Err(RecvTimeoutError::Disconnected) => String::from("the input was closed"),
+    };
+    apply(message, keep, &why, unattended)
+}
+
+/// Applies the `keep` answer to the prompt printing `message`, recording `why` on the audit log.
+fn apply(message: &str, keep: &str, why: &str, unattended: &Unattended) -> String {
+    let question = message.lines().next().unwrap_or_default().trim();
+    println!("Answered `{}` ({}), keeping the files.", keep, why);
+    if let Some(path) = &unattended.audit_log {
+        let event = format!("`{}` answered `{}` ({}, default answer `keep`)", question, keep, why);
+        if let Err(err) = record(path, &event) {
+            println!("Couldn't write to the audit log `{}`: {}", path, err);
+        }
+    }
+    String::from(keep)
+}
+
+/// Appends `event` to the audit log located at `path`, along with the current time.
+pub(crate) fn record(path: &str, event: &str) -> Result<(), io::Error> {
+    let mut log = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(log, "{} {}", Utc::now().format("%Y-%m-%d %H:%M:%S"), event)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn unanswered_prompts_keep_the_files() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let scratch = Scratch::new("audit");
+        let path = scratch.join("audit.log");
+        let unattended = Unattended {
+            timeout: Duration::from_millis(10),
+            default: "keep".parse()?,
+            audit_log: Some(path.to_string_lossy().to_string()),
+        };
+        let (sender, answers) = mpsc::channel();
+        sender.send(String::from("y\n"))?;
+
+        // Act
+        let answered = answer(&answers, "Delete it anyway? (y/n)", "n", &unattended);
+        let unanswered = answer(&answers, "Delete it anyway? (y/n)", "n", &unattended);
+        drop(sender);
+        let closed = answer(&answers, "Please provide the number\nWrite done to quit", "done", &unattended);
+
+        // Assert
+        assert_eq!(answered, "y\n");
+        assert_eq!(unanswered, "n");
+        assert_eq!(closed, "done");
+        let log = fs::read_to_string(&path)?;
+        assert!(log.contains(" `Delete it anyway? (y/n)` answered `n` (no answer within less than a second"));
+        assert!(log.contains(" `Please provide the number` answered `done` (the input was closed, default answer"));
+        assert!("delete".parse::<DefaultAnswer>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+//! Why every file ended up among the results, so they can be audited before trusting an automated cleanup with
+//! them: the criterion its name matched (the exact name, the `--glob` pattern or the `--regex` expression, recorded
+//! by the scan on [`File::matched_by`]) followed by the comparisons its [`digest`](File::digest) went through, with
+//! the score of the heuristic ones (E.G: ``glob `*.mp4`, similar video (87%)``).
+//!
+//! The listing prints it on the `matched by` line of every entry, the JSON output on the `matched_by` field of
+//! every file and the CSV output on the `matched_by` column.
+
+use crate::File;
+
+/// Criterion matched by the names of the files found searching `name`, `glob` or `regex` telling how it's read.
+pub fn criterion(name: &str, glob: bool, regex: bool) -> String {
+    match (glob, regex) {
+        (true, _) => format!("glob `{}`", name),
+        (_, true) => format!("regex `{}`", name),
+        _ => format!("name `{}`", name),
+    }
+}
+
+/// Sentence telling which comparison produced a single part of a digest.
+fn comparison(part: &str) -> String {
+    let (kind, value) = part.split_once(':').unwrap_or((part, ""));
+    let score = value.rsplit_once('@').map(|(_, score)| score);
+    match (kind, score) {
+        ("size", _) => String::from("no other file of its size"),
+        ("partial", _) => String::from("no other file starting with the same bytes"),
+        ("text", _) => String::from("same extracted text"),
+        ("siblings", _) => format!("sibling folders (cluster {})", value),
+        ("~split", Some(score)) => format!("split by hand ({}%)", score),
+        _ => match (kind.strip_prefix('~'), kind.strip_prefix('='), score) {
+            (Some(heuristic), _, Some(score)) => format!("similar {} ({}%)", heuristic, score),
+            (Some(heuristic), _, None) => format!("similar {}", heuristic),
+            (_, Some(algorithm), _) => format!("identical content ({})", algorithm),
+            _ => part.to_string(),
+        },
+    }
+}
+
+/// Tells why `file` was found: its name criterion, then the comparisons of its digest in the order they ran.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::provenance;
+/// let mut file = File::new("clip.mp4", ".", "2022-07-23 12:33:01", "./clip.mp4");
+/// assert_eq!(provenance::describe(&file), "same name");
+/// file.matched_by = Some(provenance::criterion("*.mp4", true, false).into());
+/// file.digest = Some(String::from("siblings:0+~video:3@87"));
+/// assert_eq!(provenance::describe(&file), "glob `*.mp4`, sibling folders (cluster 0), similar video (87%)");
+/// ```
+pub fn describe(file: &File) -> String {
+    let mut reasons: Vec<String> = file.matched_by.iter().map(|criterion| criterion.to_string()).collect();
+    let digest = file.digest.as_deref().unwrap_or_default();
+    reasons.extend(digest.split('+').filter(|part| !part.is_empty()).map(comparison));
+    match reasons.is_empty() {
+        true => String::from("same name"),
+        false => reasons.join(", "),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn every_comparison_is_described() {
+        // Arrange
+        let mut file = File::new("a.txt", ".", "2022-07-23 12:33:01", "./a.txt");
+        file.matched_by = Some(criterion("a.*", false, true).into());
+
+        // Act
+        let described: Vec<String> = ["=sha256:ab", "size:3", "text:0123", "~split:0a@70", "~fuzzy"]
+            .iter()
+            .map(|digest| {
+                file.digest = Some(digest.to_string());
+                describe(&file)
+            })
+            .collect();
+
+        // Assert
+        assert_eq!(
+            described,
+            vec![
+                "regex `a.*`, identical content (sha256)",
+                "regex `a.*`, no other file of its size",
+                "regex `a.*`, same extracted text",
+                "regex `a.*`, split by hand (70%)",
+                "regex `a.*`, similar fuzzy",
+            ]
+        );
+        assert_eq!(criterion("a.txt", false, false), "name `a.txt`");
+    }
+}
@@ -0,0 +1,393 @@
+//! Small expression language evaluated over saved results (`dir_cleaner query session.dcs "<expression>"`).
+//!
+//! An expression is made of comparisons joined by `and`, `or` and `not` (parentheses can be used to group
+//! them). The available fields are:
+//!
+//! - `size`: compared with `<`, `<=`, `>`, `>=`, `=` or `!=` against sizes such as `100MB` (see `parse_size`).
+//! - `age`: time elapsed since the file was created, compared against durations such as `30d` or `12h`.
+//! - `created`: creation date, compared against dates such as `2024-01-01` or `'2024-01-01 10:30:00'`.
+//! - `name`, `folder` and `path`: compared with `=`, `!=` or `contains` against (optionally quoted) text.
+//!
+//! ```text
+//! size > 100MB and path contains 'Downloads' and age > 30d
+//! ```
+
+use crate::units::{parse_duration, parse_size};
+use crate::File;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::time::Duration;
+
+/// Returned when an expression can't be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError(pub String);
+
+impl Error for QueryError {}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid query: {}", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(&'static str),
+    Open,
+    Close,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = vec![];
+    let mut chars = source.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '\'' | '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => text.extend(chars.next()),
+                        Some(q) if q == c => break,
+                        Some(other) => text.push(other),
+                        None => return Err(QueryError(String::from("unterminated string"))),
+                    }
+                }
+                tokens.push(Token::Quoted(text));
+            }
+            '<' | '>' | '=' | '!' => {
+                chars.next();
+                let double = chars.peek() == Some(&'=');
+                if double {
+                    chars.next();
+                }
+                let op = match (c, double) {
+                    ('<', false) => "<",
+                    ('<', true) => "<=",
+                    ('>', false) => ">",
+                    ('>', true) => ">=",
+                    ('=', _) => "=",
+                    ('!', true) => "!=",
+                    _ => return Err(QueryError(String::from("unexpected `!`"))),
+                };
+                tokens.push(Token::Op(op));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "()<>=!'\"".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Contains,
+}
+
+impl Op {
+    fn compare<T: PartialOrd>(&self, left: T, right: T) -> bool {
+        match self {
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+            Op::Contains => false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum TextField {
+    Name,
+    Folder,
+    Path,
+}
+
+#[derive(Debug, PartialEq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Size(Op, u64),
+    Age(Op, Duration),
+    Created(Op, NaiveDateTime),
+    Text(TextField, Op, String),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.unary()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.peek() == Some(&Token::Open) {
+            self.position += 1;
+            let expr = self.or()?;
+            return match self.next() {
+                Some(Token::Close) => Ok(expr),
+                _ => Err(QueryError(String::from("missing `)`"))),
+            };
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, QueryError> {
+        let field = match self.next() {
+            Some(Token::Word(word)) => word.to_lowercase(),
+            Some(token) => return Err(QueryError(format!("expected a field, found {:?}", token))),
+            None => return Err(QueryError(String::from("expected a field"))),
+        };
+        let op = match self.next() {
+            Some(Token::Op("<")) => Op::Lt,
+            Some(Token::Op("<=")) => Op::Le,
+            Some(Token::Op(">")) => Op::Gt,
+            Some(Token::Op(">=")) => Op::Ge,
+            Some(Token::Op("=")) => Op::Eq,
+            Some(Token::Op("!=")) => Op::Ne,
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("contains") => Op::Contains,
+            _ => return Err(QueryError(format!("expected an operator after `{}`", field))),
+        };
+        let value = match self.next() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => value,
+            _ => return Err(QueryError(format!("expected a value after `{}`", field))),
+        };
+
+        let invalid = |e: &dyn Error| QueryError(format!("{} ({})", e, field));
+        let ordered = |op: Op| match op {
+            Op::Contains => Err(QueryError(format!("`contains` can't be used with `{}`", field))),
+            op => Ok(op),
+        };
+        match field.as_str() {
+            "size" => Ok(Expr::Size(ordered(op)?, parse_size(&value).map_err(|e| invalid(&e))?)),
+            "age" => Ok(Expr::Age(ordered(op)?, parse_duration(&value).map_err(|e| invalid(&e))?)),
+            "created" => Ok(Expr::Created(ordered(op)?, parse_date(&value)?)),
+            "name" | "folder" | "path" => {
+                let field = match field.as_str() {
+                    "name" => TextField::Name,
+                    "folder" => TextField::Folder,
+                    _ => TextField::Path,
+                };
+                match op {
+                    Op::Eq | Op::Ne | Op::Contains => Ok(Expr::Text(field, op, value)),
+                    _ => Err(QueryError(String::from("text can only be compared with `=`, `!=` or `contains`"))),
+                }
+            }
+            _ => Err(QueryError(format!("unknown field `{}`", field))),
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDateTime, QueryError> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|d| d.and_hms_opt(0, 0, 0).unwrap()))
+        .map_err(|_| QueryError(format!("invalid date `{}` (expected YYYY-MM-DD)", value)))
+}
+
+/// A parsed expression, ready to be evaluated against files.
+/// ## Example
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::query::Query;
+/// # use chrono::Utc;
+/// let query = Query::parse("size > 1KB and path contains 'Downloads'").unwrap();
+/// let mut file = File::new("a.iso", "./Downloads", "2022-07-23 12:33:01", "./Downloads/a.iso");
+/// file.size = 4096;
+/// assert!(query.matches(&file, Utc::now()));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Query {
+    expr: Expr,
+}
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Query, QueryError> {
+        let mut parser = Parser { tokens: tokenize(source)?, position: 0 };
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(Query { expr }),
+            Some(token) => Err(QueryError(format!("unexpected {:?}", token))),
+        }
+    }
+
+    /// Evaluates the expression against `file`; `now` is used to compute its `age`.
+    pub fn matches(&self, file: &File, now: DateTime<Utc>) -> bool {
+        evaluate(&self.expr, file, now)
+    }
+}
+
+fn evaluate(expr: &Expr, file: &File, now: DateTime<Utc>) -> bool {
+    let created = || NaiveDateTime::parse_from_str(&file.creation_date, "%Y-%m-%d %H:%M:%S").ok();
+    match expr {
+        Expr::And(left, right) => evaluate(left, file, now) && evaluate(right, file, now),
+        Expr::Or(left, right) => evaluate(left, file, now) || evaluate(right, file, now),
+        Expr::Not(expr) => !evaluate(expr, file, now),
+        Expr::Size(op, size) => op.compare(file.size, *size),
+        Expr::Created(op, date) => created().is_some_and(|created| op.compare(created, *date)),
+        Expr::Age(op, age) => created()
+            .and_then(|created| (now.naive_utc() - created).to_std().ok())
+            .is_some_and(|elapsed| op.compare(elapsed, *age)),
+        Expr::Text(field, op, value) => {
+            let text = match field {
+                TextField::Name => Cow::Borrowed(&*file.name),
+                TextField::Folder => Cow::Borrowed(&*file.folder),
+                TextField::Path => file.path(),
+            };
+            match op {
+                Op::Contains => text.contains(value.as_str()),
+                op => op.compare(&*text, value.as_str()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn file(path: &str, size: u64, creation_date: &str) -> File {
+        let (folder, name) = path.rsplit_once('/').unwrap();
+        let mut file = File::new(name, folder, creation_date, path);
+        file.size = size;
+        file
+    }
+
+    #[test]
+    fn tokenize_expression() {
+        // Act
+        let tokens = tokenize("size>=10K and (name = \"a b\\\"c\")").unwrap();
+
+        // Assert
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Word("size".to_string()),
+                Token::Op(">="),
+                Token::Word("10K".to_string()),
+                Token::Word("and".to_string()),
+                Token::Open,
+                Token::Word("name".to_string()),
+                Token::Op("="),
+                Token::Quoted("a b\"c".to_string()),
+                Token::Close,
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluate_queries() {
+        // Arrange
+        let now: DateTime<Utc> = "2024-03-01T00:00:00Z".parse().unwrap();
+        let old_iso = file("./Downloads/a.iso", 200 * 1024 * 1024, "2024-01-01 00:00:00");
+        let new_iso = file("./Downloads/b.iso", 200 * 1024 * 1024, "2024-02-28 00:00:00");
+        let note = file("./Documents/note.txt", 10, "unknown");
+        let query = Query::parse("size > 100MB and path contains 'Downloads' and age > 30d").unwrap();
+        let either = Query::parse("not (name = note.txt) or created < 2000-01-01").unwrap();
+
+        // Act and Assert
+        assert!(query.matches(&old_iso, now));
+        assert!(!query.matches(&new_iso, now));
+        assert!(!query.matches(&note, now));
+        assert!(either.matches(&old_iso, now));
+        assert!(!either.matches(&note, now));
+    }
+
+    #[test]
+    fn precedence() {
+        // Arrange
+        let now = Utc::now();
+        let small = file("./a/small.txt", 1, "2024-01-01 00:00:00");
+
+        // Act
+        let query = Query::parse("name = nope and size = 2 or folder = ./a").unwrap();
+
+        // Assert (`and` binds tighter than `or`).
+        assert!(query.matches(&small, now));
+    }
+
+    #[test]
+    fn invalid_queries() {
+        // Act and Assert
+        assert!(Query::parse("").is_err());
+        assert!(Query::parse("size >").is_err());
+        assert!(Query::parse("size contains 10").is_err());
+        assert!(Query::parse("name > a").is_err());
+        assert!(Query::parse("colour = red").is_err());
+        assert!(Query::parse("(size > 1").is_err());
+        assert!(Query::parse("size > 1 size").is_err());
+        assert!(Query::parse("name = 'open").is_err());
+        assert!(Query::parse("age > 10 parsecs").is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! Regular expressions matched against the file names (`--regex`), so versioned files such as
+//! `backup_\d{4}\.sql` can be found in one pass.
+//!
+//! The usual syntax is supported: `.`, sets (`[a-z]`, `[^0-9]`), the `\d`, `\w` and `\s` classes (and their
+//! negations), anchors (`^`, `$`), groups (`(...)`, `(?:...)`) with alternations (`|`), and the greedy or lazy
+//! quantifiers (`*`, `+`, `?`, `{n}`, `{n,}`, `{n,m}`). Like `grep`, an expression matches the names holding a
+//! match anywhere: anchor it (`^...$`) to match whole names.
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// Returned when an expression can't be compiled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegexError {
+    pub expression: String,
+    pub reason: &'static str,
+}
+
+impl Error for RegexError {}
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid regular expression `{}`: {}.", self.expression, self.reason)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Char(char),
+    Any,
+    /// Ranges of characters, and whether the set is negated.
+    Set(Vec<(char, char)>, bool),
+    Start,
+    End,
+    /// Alternative sequences.
+    Alternation(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+}
+
+const DIGITS: [(char, char); 1] = [('0', '9')];
+const WORDS: [(char, char); 4] = [('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')];
+const SPACES: [(char, char); 3] = [(' ', ' '), ('\t', '\r'), ('\u{a0}', '\u{a0}')];
+
+/// A compiled regular expression.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::regex::Regex;
+/// let regex: Regex = r"^backup_\d{4}\.sql$".parse().unwrap();
+/// assert!(regex.is_match("backup_2023.sql"));
+/// assert!(!regex.is_match("backup_23.sql"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regex {
+    nodes: Vec<Node>,
+}
+
+impl FromStr for Regex {
+    type Err = RegexError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let error = |reason| RegexError { expression: expression.to_string(), reason };
+        let mut parser = Parser { chars: expression.chars().collect(), position: 0 };
+        let nodes = parser.alternation().map_err(error)?;
+        match parser.peek() {
+            Some(_) => Err(error("a `)` is never opened")),
+            None => Ok(Regex { nodes: vec![nodes] }),
+        }
+    }
+}
+
+impl Regex {
+    /// Whether `name` holds a match of the expression.
+    pub fn is_match(&self, name: &str) -> bool {
+        let input: Vec<char> = name.chars().collect();
+        let matcher = Matcher { input: &input };
+        (0..=input.len()).any(|start| matcher.sequence(&self.nodes, start, &|_| true))
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.position += 1;
+        c
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    /// Sequences separated by `|`, up to the end of the expression or of the enclosing group.
+    fn alternation(&mut self) -> Result<Node, &'static str> {
+        let mut branches = vec![self.sequence()?];
+        while self.eat('|') {
+            branches.push(self.sequence()?);
+        }
+        Ok(Node::Alternation(branches))
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>, &'static str> {
+        let mut nodes = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(nodes)
+    }
+
+    fn atom(&mut self) -> Result<Node, &'static str> {
+        match self.next() {
+            Some('(') => {
+                if self.eat('?') && !self.eat(':') {
+                    return Err("only `(?:` groups are supported");
+                }
+                let group = self.alternation()?;
+                match self.eat(')') {
+                    true => Ok(group),
+                    false => Err("a `(` is never closed"),
+                }
+            }
+            Some('[') => self.set(),
+            Some('.') => Ok(Node::Any),
+            Some('^') => Ok(Node::Start),
+            Some('$') => Ok(Node::End),
+            Some('\\') => self.escape(),
+            Some('*' | '+' | '?' | '{') => Err("a quantifier doesn't follow anything"),
+            Some(c) => Ok(Node::Char(c)),
+            None => Err("the expression ends unexpectedly"),
+        }
+    }
+
+    /// The `\` escape whose backslash was just read.
+    fn escape(&mut self) -> Result<Node, &'static str> {
+        let class = |ranges: &[(char, char)], negated| Ok(Node::Set(ranges.to_vec(), negated));
+        match self.next() {
+            Some('d') => class(&DIGITS, false),
+            Some('D') => class(&DIGITS, true),
+            Some('w') => class(&WORDS, false),
+            Some('W') => class(&WORDS, true),
+            Some('s') => class(&SPACES, false),
+            Some('S') => class(&SPACES, true),
+            Some('t') => Ok(Node::Char('\t')),
+            Some(c) if !c.is_ascii_alphanumeric() => Ok(Node::Char(c)),
+            Some(_) => Err("unknown escape sequence"),
+            None => Err("the expression ends with `\\`"),
+        }
+    }
+
+    /// The set whose `[` was just read.
+    fn set(&mut self) -> Result<Node, &'static str> {
+        let negated = self.eat('^');
+        let mut ranges = vec![];
+        let mut first = true;
+        loop {
+            let start = match self.next() {
+                Some(']') if !first => break,
+                Some('\\') => match self.next() {
+                    Some('d') => {
+                        ranges.extend(DIGITS);
+                        continue;
+                    }
+                    Some('w') => {
+                        ranges.extend(WORDS);
+                        continue;
+                    }
+                    Some('s') => {
+                        ranges.extend(SPACES);
+                        continue;
+                    }
+                    Some(c) => c,
+                    None => return Err("a `[` is never closed"),
+                },
+                Some(c) => c,
+                None => return Err("a `[` is never closed"),
+            };
+            first = false;
+            let end = match (self.peek(), self.chars.get(self.position + 1)) {
+                (Some('-'), Some(end)) if *end != ']' => {
+                    let end = *end;
+                    self.position += 2;
+                    end
+                }
+                _ => start,
+            };
+            if end < start {
+                return Err("a range of a set is reversed");
+            }
+            ranges.push((start, end));
+        }
+        Ok(Node::Set(ranges, negated))
+    }
+
+    /// `node`, along with the quantifier following it, if any.
+    fn quantified(&mut self, node: Node) -> Result<Node, &'static str> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                let close = self.chars[self.position..].iter().position(|c| *c == '}');
+                let close = close.ok_or("a `{` is never closed")? + self.position;
+                let bounds: String = self.chars[self.position + 1..close].iter().collect();
+                let parse = |bound: &str| bound.trim().parse::<usize>().map_err(|_| "invalid repetition bounds");
+                let (min, max) = match bounds.split_once(',') {
+                    None => (parse(&bounds)?, Some(parse(&bounds)?)),
+                    Some((min, max)) if max.trim().is_empty() => (parse(min)?, None),
+                    Some((min, max)) => (parse(min)?, Some(parse(max)?)),
+                };
+                if max.is_some_and(|max| max < min) {
+                    return Err("invalid repetition bounds");
+                }
+                self.position = close;
+                (min, max)
+            }
+            _ => return Ok(node),
+        };
+        self.position += 1;
+        let greedy = !self.eat('?');
+        Ok(Node::Repeat { node: Box::new(node), min, max, greedy })
+    }
+}
+
+/// Backtracking matcher: every step hands the position it reached to the continuation `k`, which tells whether
+/// the rest of the expression matches from there.
+struct Matcher<'a> {
+    input: &'a [char],
+}
+
+impl Matcher<'_> {
+    fn sequence(&self, nodes: &[Node], i: usize, k: &dyn Fn(usize) -> bool) -> bool {
+        match nodes.split_first() {
+            None => k(i),
+            Some((node, rest)) => self.node(node, i, &|j| self.sequence(rest, j, k)),
+        }
+    }
+
+    fn node(&self, node: &Node, i: usize, k: &dyn Fn(usize) -> bool) -> bool {
+        let accepts = |accepts: &dyn Fn(char) -> bool| i < self.input.len() && accepts(self.input[i]) && k(i + 1);
+        match node {
+            Node::Char(c) => accepts(&|input| input == *c),
+            Node::Any => accepts(&|_| true),
+            Node::Set(ranges, negated) => {
+                accepts(&|input| ranges.iter().any(|(start, end)| (*start..=*end).contains(&input)) != *negated)
+            }
+            Node::Start => i == 0 && k(i),
+            Node::End => i == self.input.len() && k(i),
+            Node::Alternation(branches) => branches.iter().any(|branch| self.sequence(branch, i, k)),
+            Node::Repeat { node, min, max, greedy } => self.repeat(node, (*min, *max, *greedy), 0, i, k),
+        }
+    }
+
+    /// Matches `node` once more than the `count` times it already did, as long as the bounds allow it.
+    fn repeat(
+        &self,
+        node: &Node,
+        bounds: (usize, Option<usize>, bool),
+        count: usize,
+        i: usize,
+        k: &dyn Fn(usize) -> bool,
+    ) -> bool {
+        let (min, max, greedy) = bounds;
+        if count < min {
+            return self.node(node, i, &|j| self.repeat(node, bounds, count + 1, j, k));
+        }
+        // Repetitions that don't consume anything are cut short, so `(a*)*` can't loop forever.
+        let more = || {
+            max.is_none_or(|max| count < max)
+                && self.node(node, i, &|j| j != i && self.repeat(node, bounds, count + 1, j, k))
+        };
+        match greedy {
+            true => more() || k(i),
+            false => k(i) || more(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn expressions_match_names() -> Result<(), RegexError> {
+        // Arrange
+        let backups: Regex = r"backup_\d{4}\.sql".parse()?;
+        let reports: Regex = r"^(report|invoice)s?-[^.]+\.(?:pdf|docx)$".parse()?;
+        let lazy: Regex = "^a.*?b$".parse()?;
+
+        // Act and Assert
+        assert!(backups.is_match("backup_2023.sql"));
+        assert!(backups.is_match("old-backup_2023.sql.gz"));
+        assert!(!backups.is_match("backup_20x3.sql"));
+        assert!(reports.is_match("invoices-march.pdf") && reports.is_match("report-2023.docx"));
+        assert!(!reports.is_match("report-2023.final.pdf") && !reports.is_match("memo-2023.pdf"));
+        assert!(lazy.is_match("aXXbYYb") && !lazy.is_match("aXXbYYc"));
+        assert!("(a*)*$".parse::<Regex>()?.is_match("aaaa"));
+        assert!("x{2,3}".parse::<Regex>()?.is_match("axxb") && !"^x{2,3}$".parse::<Regex>()?.is_match("xxxx"));
+        assert_eq!("a(b".parse::<Regex>().unwrap_err().reason, "a `(` is never closed");
+        assert_eq!("a)b".parse::<Regex>().unwrap_err().reason, "a `)` is never opened");
+        assert!("*a".parse::<Regex>().is_err() && "[z-a]".parse::<Regex>().is_err() && r"\q".parse::<Regex>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+//! Manual corrections of the groups of duplicates, for when the heuristic comparisons got them wrong (the `split`
+//! and `merge` commands). The corrections are made on the digests, so the listings, the `--keep` suggestions and
+//! the saved sessions all see them.
+//!
+//! Only the heuristic groups can be split, the copies holding the same bytes can't be told apart. Merging happens
+//! between groups sharing their name (a heuristic match is only looked for between files of the same name), and
+//! never between files holding different bytes.
+
+use crate::checksum::fnv1a;
+use crate::File;
+
+/// Moves the entries at `indexes` out of their heuristic group, into a group of their own (they stay heuristic
+/// matches, with the same confidence).
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::regroup;
+/// let mut files: Vec<File> = ["./a/clip.mp4", "./b/clip.mp4", "./c/clip.mp4"]
+///     .iter()
+///     .map(|path| File::new("clip.mp4", ".", "2022-07-23 12:33:01", path))
+///     .collect();
+/// files.iter_mut().for_each(|file| file.digest = Some(String::from("~video:3@80")));
+/// regroup::split(&mut files, &[2]).unwrap();
+/// assert_eq!(files[0].group_key(), files[1].group_key());
+/// assert_ne!(files[0].group_key(), files[2].group_key());
+/// assert_eq!(files[2].confidence(), 80);
+/// ```
+pub fn split(files: &mut [File], indexes: &[usize]) -> Result<(), String> {
+    let first = *indexes.first().ok_or("Please provide the entries to move out of their group")?;
+    if indexes.iter().any(|i| *i >= files.len()) {
+        return Err(String::from("Please provide some of the listed numbers"));
+    }
+    if !files[first].heuristic_match() {
+        return Err(format!("Entry {} holds the same bytes as its copies, its group can't be split", first + 1));
+    }
+    let key = files[first].group_key();
+    if let Some(other) = indexes.iter().find(|i| files[**i].group_key() != key) {
+        return Err(format!("Entries {} and {} aren't in the same group", first + 1, other + 1));
+    }
+    let members = files.iter().filter(|file| file.group_key() == key).count();
+    if indexes.len() >= members {
+        return Err(String::from("Some entries have to stay in the group"));
+    }
+    let part = fnv1a(files[first].path().as_bytes());
+    for i in indexes {
+        let confidence = files[*i].confidence();
+        files[*i].add_digest(&format!("~split:{:016x}@{}", part, confidence));
+    }
+    Ok(())
+}
+
+/// Moves the group of the entry at `from` to the group of the entry at `into`, returning how many entries moved.
+pub fn merge(files: &mut [File], into: usize, from: usize) -> Result<usize, String> {
+    if into >= files.len() || from >= files.len() {
+        return Err(String::from("Please provide two of the listed numbers"));
+    }
+    let (target, source) = (&files[into], &files[from]);
+    if target.group_key() == source.group_key() {
+        return Err(format!("Entries {} and {} are already in the same group", into + 1, from + 1));
+    }
+    if target.name != source.name {
+        return Err(format!("Entries {} and {} don't share their name, they can't be grouped", into + 1, from + 1));
+    }
+    let exact = |file: &File| file.digest.as_deref().is_some_and(|digest| digest.starts_with('='));
+    if exact(target) && exact(source) {
+        return Err(format!("Entries {} and {} hold different bytes, they can't be grouped", into + 1, from + 1));
+    }
+    let (key, digest) = (source.group_key(), target.digest.clone());
+    let moved: Vec<usize> = (0..files.len()).filter(|i| files[*i].group_key() == key).collect();
+    for i in &moved {
+        files[*i].digest = digest.clone();
+    }
+    Ok(moved.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn clips(digests: &[&str]) -> Vec<File> {
+        let files = digests.iter().enumerate().map(|(i, digest)| {
+            let mut file = File::new("clip.mp4", ".", "2022-07-23 12:33:01", &format!("./{}/clip.mp4", i));
+            file.digest = Some(digest.to_string());
+            file
+        });
+        files.collect()
+    }
+
+    #[test]
+    fn groups_are_split_and_merged() {
+        // Arrange
+        let digests = ["~video:1@90", "~video:1@90", "~video:1@90", "~video:2@70", "=sha256:ab", "=sha256:cd"];
+        let mut files = clips(&digests);
+
+        // Act
+        let split = split(&mut files, &[1, 2]);
+        let merged = merge(&mut files, 0, 3);
+
+        // Assert
+        assert_eq!(split, Ok(()));
+        assert_eq!(files[1].group_key(), files[2].group_key());
+        assert_ne!(files[0].group_key(), files[1].group_key());
+        assert_eq!(merged, Ok(1));
+        assert_eq!(files[3].group_key(), files[0].group_key());
+        assert!(super::split(&mut files, &[0, 3]).is_err());
+        assert!(super::split(&mut files, &[4]).is_err());
+        assert!(super::split(&mut files, &[0, 1]).is_err());
+        assert!(merge(&mut files, 4, 5).is_err());
+        assert!(merge(&mut files, 1, 2).is_err());
+    }
+}
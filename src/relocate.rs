@@ -0,0 +1,223 @@
+//! Moving and copying files without silently stripping their metadata.
+//!
+//! Moves within a filesystem are plain renames, which keep everything. Otherwise the file is copied along with
+//! its permissions, its timestamps and its extended attributes (which hold the POSIX ACLs and the security
+//! labels on Linux, and the Finder metadata on macOS). On Windows the copy keeps the alternate data streams
+//! and the attributes, but the access control list is inherited from the destination instead.
+//!
+//! Whatever couldn't be carried over is reported through [`Relocated::unpreserved`].
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Outcome of a move or a copy.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Relocated {
+    /// Descriptions of the metadata that couldn't be preserved (E.G: the name of an extended attribute).
+    pub unpreserved: Vec<String>,
+}
+
+/// Moves the file located at `from` to `to`, copying it (see [`copy_file`]) when both paths live on different
+/// filesystems.
+pub fn move_file(from: &Path, to: &Path) -> Result<Relocated, io::Error> {
+    match fs::rename(from, to) {
+        Ok(_) => Ok(Relocated::default()),
+        Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+            let relocated = copy_file(from, to)?;
+            fs::remove_file(from)?;
+            Ok(relocated)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Copies the file located at `from` to `to`, along with as much of its metadata as the platform allows.
+pub fn copy_file(from: &Path, to: &Path) -> Result<Relocated, io::Error> {
+    fs::copy(from, to)?;
+    let mut relocated = Relocated::default();
+    let metadata = fs::metadata(from)?;
+    let mut times = fs::FileTimes::new();
+    if let Ok(modified) = metadata.modified() {
+        times = times.set_modified(modified);
+    }
+    if let Ok(accessed) = metadata.accessed() {
+        times = times.set_accessed(accessed);
+    }
+    if fs::File::options().write(true).open(to).and_then(|file| file.set_times(times)).is_err() {
+        relocated.unpreserved.push(String::from("timestamps"));
+    }
+    relocated.unpreserved.extend(copy_attributes(from, to));
+    Ok(relocated)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn copy_attributes(from: &Path, to: &Path) -> Vec<String> {
+    let names = match xattr::list(from) {
+        Ok(names) => names,
+        Err(_) => return vec![],
+    };
+    names
+        .into_iter()
+        .filter(|name| xattr::get(from, name).and_then(|value| xattr::set(to, name, &value)).is_err())
+        .map(|name| format!("extended attribute `{}`", String::from_utf8_lossy(&name)))
+        .collect()
+}
+
+#[cfg(windows)]
+fn copy_attributes(_from: &Path, _to: &Path) -> Vec<String> {
+    vec![String::from("access control list")]
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn copy_attributes(_from: &Path, _to: &Path) -> Vec<String> {
+    vec![]
+}
+
+/// Thin wrappers over the extended attribute calls of the C library.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub(crate) mod xattr {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::raw::{c_char, c_int, c_void};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    #[cfg(target_os = "linux")]
+    extern "C" {
+        fn listxattr(path: *const c_char, list: *mut c_char, size: usize) -> isize;
+        fn getxattr(path: *const c_char, name: *const c_char, value: *mut c_void, size: usize) -> isize;
+        fn setxattr(path: *const c_char, name: *const c_char, value: *const c_void, size: usize, flags: c_int) -> c_int;
+    }
+
+    #[cfg(target_os = "macos")]
+    extern "C" {
+        #[link_name = "listxattr"]
+        fn listxattr_with(path: *const c_char, list: *mut c_char, size: usize, options: c_int) -> isize;
+        #[link_name = "getxattr"]
+        fn getxattr_with(
+            path: *const c_char,
+            name: *const c_char,
+            value: *mut c_void,
+            size: usize,
+            position: u32,
+            options: c_int,
+        ) -> isize;
+        #[link_name = "setxattr"]
+        fn setxattr_with(
+            path: *const c_char,
+            name: *const c_char,
+            value: *const c_void,
+            size: usize,
+            position: u32,
+            options: c_int,
+        ) -> c_int;
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe fn listxattr(path: *const c_char, list: *mut c_char, size: usize) -> isize {
+        listxattr_with(path, list, size, 0)
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe fn getxattr(path: *const c_char, name: *const c_char, value: *mut c_void, size: usize) -> isize {
+        getxattr_with(path, name, value, size, 0, 0)
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe fn setxattr(
+        path: *const c_char,
+        name: *const c_char,
+        value: *const c_void,
+        size: usize,
+        flags: c_int,
+    ) -> c_int {
+        setxattr_with(path, name, value, size, 0, flags)
+    }
+
+    fn c_path(path: &Path) -> Result<CString, io::Error> {
+        CString::new(path.as_os_str().as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+
+    fn c_name(name: &[u8]) -> Result<CString, io::Error> {
+        CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+
+    /// Names of the extended attributes of the file located at `path`.
+    pub fn list(path: &Path) -> Result<Vec<Vec<u8>>, io::Error> {
+        let path = c_path(path)?;
+        // SAFETY: `path` is NUL terminated, and a null buffer of size 0 only queries the needed size.
+        let size = unsafe { listxattr(path.as_ptr(), std::ptr::null_mut(), 0) };
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut buffer = vec![0u8; size as usize];
+        // SAFETY: `buffer` holds `buffer.len()` writable bytes.
+        let size = unsafe { listxattr(path.as_ptr(), buffer.as_mut_ptr() as *mut c_char, buffer.len()) };
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        buffer.truncate(size as usize);
+        Ok(buffer.split(|b| *b == 0).filter(|n| !n.is_empty()).map(<[u8]>::to_vec).collect())
+    }
+
+    pub fn get(path: &Path, name: &[u8]) -> Result<Vec<u8>, io::Error> {
+        let (path, name) = (c_path(path)?, c_name(name)?);
+        // SAFETY: both strings are NUL terminated, and a null buffer of size 0 only queries the needed size.
+        let size = unsafe { getxattr(path.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0) };
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut value = vec![0u8; size as usize];
+        // SAFETY: `value` holds `value.len()` writable bytes.
+        let size = unsafe { getxattr(path.as_ptr(), name.as_ptr(), value.as_mut_ptr() as *mut c_void, value.len()) };
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        value.truncate(size as usize);
+        Ok(value)
+    }
+
+    pub fn set(path: &Path, name: &[u8], value: &[u8]) -> Result<(), io::Error> {
+        let (path, name) = (c_path(path)?, c_name(name)?);
+        // SAFETY: both strings are NUL terminated and `value` holds `value.len()` readable bytes.
+        let result = unsafe { setxattr(path.as_ptr(), name.as_ptr(), value.as_ptr() as *const c_void, value.len(), 0) };
+        match result {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn copy_keeps_metadata() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("relocate");
+        let (from, to, moved) = (root.join("a.txt"), root.join("b.txt"), root.join("c.txt"));
+        fs::write(&from, "a")?;
+        let modified = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+        fs::File::options().write(true).open(&from)?.set_times(fs::FileTimes::new().set_modified(modified))?;
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        let tagged = xattr::set(&from, b"user.dir_cleaner", b"tag").is_ok();
+
+        // Act
+        let copied = copy_file(&from, &to)?;
+        let relocated = move_file(&to, &moved)?;
+
+        // Assert
+        assert!(!copied.unpreserved.contains(&String::from("timestamps")));
+        assert_eq!(relocated, Relocated::default());
+        assert_eq!(fs::metadata(&moved)?.modified()?, modified);
+        assert!(!to.exists());
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if tagged {
+            assert_eq!(xattr::get(&moved, b"user.dir_cleaner")?, b"tag");
+        }
+        Ok(())
+    }
+}
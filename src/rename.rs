@@ -0,0 +1,64 @@
+//! Renaming of the copy kept out of a group (the `keep-as <number> <new name>` command), E.G: stripping the
+//! ` (1)` suffix a download left on it once the other copies are deleted. A file is never renamed over another
+//! one: the name must be free (or held by a copy deleted along the way).
+
+use crate::relocate;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Location the file stored at `path` takes once renamed to `new_name` (same directory), refused when `new_name`
+/// isn't a plain file name.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::rename;
+/// # use std::path::{Path, PathBuf};
+/// let renamed = rename::target(Path::new("./photos/beach (1).jpg"), "beach.jpg");
+/// assert_eq!(renamed, Ok(PathBuf::from("./photos/beach.jpg")));
+/// assert!(rename::target(Path::new("./photos/beach (1).jpg"), "../beach.jpg").is_err());
+/// ```
+pub fn target(path: &Path, new_name: &str) -> Result<PathBuf, String> {
+    let name = Path::new(new_name);
+    let plain = name.file_name().is_some_and(|file_name| file_name == name.as_os_str());
+    if new_name.trim().is_empty() || !plain {
+        return Err(format!("`{}` isn't a valid file name", new_name));
+    }
+    Ok(path.with_file_name(name))
+}
+
+/// Renames the file located at `from` to `to`, refusing to replace an existing entry.
+pub fn rename(from: &Path, to: &Path) -> Result<(), io::Error> {
+    if to.symlink_metadata().is_ok() {
+        let message = format!("`{}` already exists", to.display());
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, message));
+    }
+    relocate::move_file(from, to).map(|_| ())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn renames_never_replace_another_file() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("rename");
+        let (copy, taken) = (root.join("a (1).txt"), root.join("taken.txt"));
+        fs::write(&copy, "kept")?;
+        fs::write(&taken, "other")?;
+
+        // Act
+        let refused = rename(&copy, &target(&copy, "taken.txt").unwrap_or_default());
+        let renamed = rename(&copy, &target(&copy, "a.txt").unwrap_or_default());
+
+        // Assert
+        assert_eq!(refused.map_err(|e| e.kind()), Err(io::ErrorKind::AlreadyExists));
+        assert!(renamed.is_ok());
+        assert_eq!(fs::read_to_string(root.join("a.txt"))?, "kept");
+        assert_eq!(fs::read_to_string(&taken)?, "other");
+        assert!(target(&copy, "").is_err());
+        assert!(target(&copy, "nested/a.txt").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,315 @@
+//! Reports summarizing the duplicates found by a scan, meant to be shared with other people
+//! (E.G: pasted into an issue tracker while coordinating a cleanup).
+
+use crate::groups;
+use crate::units::format_size;
+use crate::warnings::Warnings;
+use crate::File;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// Returned when the requested report format doesn't exist.
+#[derive(Debug, Clone)]
+pub struct FormatError(pub String);
+
+impl Error for FormatError {}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Unknown report format `{}` (available: markdown, dot).", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Format {
+    /// GitHub-flavored Markdown, with collapsible groups and a checkbox per file.
+    #[default]
+    Markdown,
+    /// Graphviz graph linking the directories that share duplicates, weighted by the shared bytes.
+    Dot,
+}
+
+impl FromStr for Format {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(Format::Markdown),
+            "dot" | "graphviz" => Ok(Format::Dot),
+            _ => Err(FormatError(s.to_string())),
+        }
+    }
+}
+
+/// Renders the report of the `files` found while searching `name` on `root`, noting the `warnings` of the scan.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::report::{self, Format};
+/// # use dir_cleaner::warnings::Warnings;
+/// let files = vec![
+///     File::new("a.txt", "./test", "2022-07-23 12:33:01", "./test/a.txt"),
+///     File::new("a.txt", "./test/old", "2022-07-24 08:00:00", "./test/old/a.txt"),
+/// ];
+/// let report = report::render(Format::Markdown, "./test", "a.txt", &files, &Warnings::default());
+/// assert!(report.contains("- [ ] `./test/old/a.txt`"));
+/// ```
+pub fn render(format: Format, root: &str, name: &str, files: &[File], warnings: &Warnings) -> String {
+    match format {
+        Format::Markdown => markdown(root, name, files, warnings),
+        Format::Dot => dot(files, warnings),
+    }
+}
+
+/// Sentence noting the cloud placeholders that weren't compared, if any.
+pub fn placeholders_note(placeholders: usize) -> Option<String> {
+    (placeholders > 0).then(|| {
+        format!(
+            "{} cloud placeholder(s) weren't read, pass --include-placeholders to compare them as well.",
+            placeholders
+        )
+    })
+}
+
+/// Space reclaimed by the deletions of a session, attributed to the rule that chose them (E.G: the `--keep`
+/// strategy), so the user can tell which rules earn their keep.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::report::Reclaimed;
+/// let mut reclaimed = Reclaimed::default();
+/// reclaimed.add("highest-bitrate", 3 * 1024 * 1024);
+/// reclaimed.add("by hand", 1024);
+/// reclaimed.add("highest-bitrate", 1024 * 1024);
+/// assert_eq!(
+///     reclaimed.to_string(),
+///     "Reclaimed 4.0 MiB: rule `highest-bitrate`: 4.0 MiB (2 files); rule `by hand`: 1.0 KiB (1 file)."
+/// );
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Reclaimed {
+    /// Files deleted and bytes reclaimed by every rule, in the order the rules were first applied.
+    rules: Vec<(String, usize, u64)>,
+}
+
+impl Reclaimed {
+    /// Attributes the deletion of `bytes` to `rule`. Nothing is counted when nothing was reclaimed (the file was
+    /// kept after all).
+    pub fn add(&mut self, rule: &str, bytes: u64) {
+        if bytes == 0 {
+            return;
+        }
+        match self.rules.iter_mut().find(|(name, _, _)| name == rule) {
+            Some((_, files, total)) => {
+                *files += 1;
+                *total += bytes;
+            }
+            None => self.rules.push((rule.to_string(), 1, bytes)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl fmt::Display for Reclaimed {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let total: u64 = self.rules.iter().map(|(_, _, bytes)| bytes).sum();
+        let rules: Vec<String> = self
+            .rules
+            .iter()
+            .map(|(rule, files, bytes)| {
+                let plural = if *files == 1 { "" } else { "s" };
+                format!("rule `{}`: {} ({} file{})", rule, format_size(*bytes), files, plural)
+            })
+            .collect();
+        write!(f, "Reclaimed {}: {}.", format_size(total), rules.join("; "))
+    }
+}
+
+fn markdown(root: &str, name: &str, files: &[File], warnings: &Warnings) -> String {
+    let groups = groups::duplicates(files);
+    let mut report = String::from("# dir_cleaner report\n\n");
+    report.push_str(&format!(
+        "Searched {} for {}: {} file(s) found, {} duplicate group(s).\n",
+        code(root),
+        code(name),
+        files.len(),
+        groups.len()
+    ));
+    if !warnings.is_empty() {
+        report.push_str("\nWarnings:\n\n");
+        for warning in warnings.iter() {
+            report.push_str(&format!("- {}\n", warning.message));
+        }
+    }
+
+    for group in groups {
+        let heuristic = if group.heuristic_match() { ", heuristic match" } else { "" };
+        report.push_str(&format!(
+            "\n<details>\n<summary>{} ({} copies{})</summary>\n\n",
+            html_escape(group.name()),
+            group.len(),
+            heuristic
+        ));
+        for file in group.files() {
+            report.push_str(&format!("- [ ] {} (created {})\n", code(&file.path()), file.creation_date));
+        }
+        report.push_str("\n</details>\n");
+    }
+    report
+}
+
+/// Undirected graph whose nodes are directories. Two directories are linked when they hold copies of the
+/// same file, and the edge is weighted by the bytes they share (one copy per group).
+fn dot(files: &[File], warnings: &Warnings) -> String {
+    let mut edges: BTreeMap<(&str, &str), u64> = BTreeMap::new();
+    for group in groups::duplicates(files) {
+        let mut folders: Vec<&str> = group.files().map(|f| &*f.folder).collect();
+        folders.sort_unstable();
+        folders.dedup();
+        let shared = group.files().map(|f| f.size).min().unwrap_or(0);
+        for (i, a) in folders.iter().enumerate() {
+            for b in &folders[i + 1..] {
+                *edges.entry((a, b)).or_insert(0) += shared;
+            }
+        }
+    }
+
+    let mut graph = String::from("graph duplicates {\n");
+    for warning in warnings.iter() {
+        graph.push_str(&format!("    // warning: {}\n", warning.message));
+    }
+    graph.push_str("    node [shape=folder];\n");
+    for ((a, b), bytes) in edges {
+        graph.push_str(&format!(
+            "    {} -- {} [weight={}, label=\"{}\"];\n",
+            dot_id(a),
+            dot_id(b),
+            bytes.max(1),
+            format_size(bytes)
+        ));
+    }
+    graph.push_str("}\n");
+    graph
+}
+
+fn dot_id(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Wraps `text` on a code span, using as many backticks as needed to keep it intact.
+fn code(text: &str) -> String {
+    let mut longest_run = 0;
+    let mut run = 0;
+    for c in text.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest_run = longest_run.max(run);
+    }
+    let fence = "`".repeat(longest_run + 1);
+    let padding = if text.starts_with('`') || text.ends_with('`') { " " } else { "" };
+    format!("{}{}{}{}{}", fence, padding, text, padding, fence)
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::limits::Skipped;
+    use crate::warnings::Kind;
+
+    #[test]
+    fn markdown_report() {
+        // Arrange
+        let files = vec![
+            File::new("a.txt", "./t", "2022-07-23 12:33:01", "./t/a.txt"),
+            File::new("a.txt", "./t/b", "2022-07-24 12:33:01", "./t/b/a.txt"),
+            File::new("<b>.txt", "./t", "2022-07-25 12:33:01", "./t/<b>.txt"),
+        ];
+
+        // Act
+        let report = render(Format::Markdown, "./t", "a.txt", &files, &Warnings::default());
+
+        // Assert
+        assert_eq!(
+            report,
+            "# dir_cleaner report\n\n\
+            Searched `./t` for `a.txt`: 3 file(s) found, 1 duplicate group(s).\n\
+            \n<details>\n<summary>a.txt (2 copies)</summary>\n\n\
+            - [ ] `./t/a.txt` (created 2022-07-23 12:33:01)\n\
+            - [ ] `./t/b/a.txt` (created 2022-07-24 12:33:01)\n\
+            \n</details>\n"
+        );
+    }
+
+    #[test]
+    fn dot_graph() {
+        // Arrange
+        let mut files = vec![
+            File::new("a.txt", "./t/x", "2022-07-23 12:33:01", "./t/x/a.txt"),
+            File::new("a.txt", "./t/\"y\"", "2022-07-24 12:33:01", "./t/\"y\"/a.txt"),
+            File::new("b.txt", "./t/x", "2022-07-25 12:33:01", "./t/x/b.txt"),
+            File::new("b.txt", "./t/\"y\"", "2022-07-25 12:33:01", "./t/\"y\"/b.txt"),
+            File::new("b.txt", "./t/x/z", "2022-07-25 12:33:01", "./t/x/z/b.txt"),
+        ];
+        for (file, size) in files.iter_mut().zip([1024, 1024, 512, 512, 512]) {
+            file.size = size;
+        }
+
+        // Act
+        let graph = render(Format::Dot, "./t", "*", &files, &Warnings::default());
+
+        // Assert
+        assert_eq!(
+            graph,
+            "graph duplicates {\n    node [shape=folder];\n\
+            \x20   \"./t/\\\"y\\\"\" -- \"./t/x\" [weight=1536, label=\"1.5 KiB\"];\n\
+            \x20   \"./t/\\\"y\\\"\" -- \"./t/x/z\" [weight=512, label=\"512 B\"];\n\
+            \x20   \"./t/x\" -- \"./t/x/z\" [weight=512, label=\"512 B\"];\n\
+            }\n"
+        );
+    }
+
+    #[test]
+    fn code_spans_keep_backticks() {
+        // Act and Assert
+        assert_eq!(code("a`b"), "``a`b``");
+        assert_eq!(code("`a"), "`` `a ``");
+        assert_eq!(code("plain"), "`plain`");
+    }
+
+    #[test]
+    fn parse_format() {
+        // Act and Assert
+        assert_eq!("markdown".parse::<Format>().unwrap(), Format::Markdown);
+        assert_eq!("MD".parse::<Format>().unwrap(), Format::Markdown);
+        assert_eq!("dot".parse::<Format>().unwrap(), Format::Dot);
+        assert!("pdf".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn reports_list_the_warnings() {
+        // Arrange
+        let skipped = vec![Skipped { path: String::from("./t/cache"), reason: String::from("more than 10 entries") }];
+        let mut warnings = Warnings::default();
+        warnings.skipped(&skipped);
+        warnings.push(Kind::Placeholders, None, placeholders_note(3).unwrap());
+
+        // Act
+        let report = render(Format::Markdown, "./t", "a.txt", &[], &warnings);
+        let graph = render(Format::Dot, "./t", "a.txt", &[], &warnings);
+
+        // Assert
+        assert!(report.contains("\nWarnings:\n\n- Skipped `./t/cache`: more than 10 entries.\n- 3 cloud placeholder"));
+        assert!(graph.contains("    // warning: Skipped `./t/cache`: more than 10 entries.\n"));
+        assert!(graph.contains("    // warning: 3 cloud placeholder(s) weren't read"));
+        assert!(!render(Format::Markdown, "./t", "a.txt", &[], &Warnings::default()).contains("Warnings"));
+        assert_eq!(placeholders_note(0), None);
+    }
+}
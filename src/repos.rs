@@ -0,0 +1,95 @@
+//! Detection of the git repositories holding the found files.
+//!
+//! Deleting a "duplicate" tracked by git only leaves a dirty checkout behind, so the entries living in a
+//! repository are pointed out in the listing, and `--skip-repos` leaves the repositories out of the scan.
+//!
+//! With `--git-rm-script <file>` the tracked entries aren't deleted at all: a script of `git rm` commands is
+//! written instead, one repository at a time, so the cleanup goes through the usual review.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Whether `dir` is the root of a git repository (or of a worktree, whose `.git` is a file).
+pub fn is_repository(dir: &Path) -> bool {
+    dir.join(".git").exists()
+}
+
+/// Root of the innermost git repository holding the file located at `path`.
+pub fn repository_of(path: &Path) -> Option<PathBuf> {
+    path.ancestors().skip(1).find(|dir| is_repository(dir)).map(Path::to_path_buf)
+}
+
+/// Files to remove through git instead of deleting them, grouped by repository.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::repos::Removals;
+/// # use std::path::Path;
+/// let mut removals = Removals::default();
+/// removals.add(Path::new("/src/app"), Path::new("/src/app/docs/it's.md"));
+/// assert!(removals.script().ends_with("git -C '/src/app' rm -- 'docs/it'\\''s.md'\n"));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Removals {
+    by_repository: BTreeMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl Removals {
+    /// Records the removal of the file located at `path`, tracked by the repository whose root is `repository`.
+    pub fn add(&mut self, repository: &Path, path: &Path) {
+        let relative = path.strip_prefix(repository).unwrap_or(path).to_path_buf();
+        self.by_repository.entry(repository.to_path_buf()).or_default().push(relative);
+    }
+
+    /// Shell script removing the recorded files, with a `git rm` command per repository.
+    pub fn script(&self) -> String {
+        let mut script = String::from("#!/bin/sh\n");
+        script.push_str("# Written by dir_cleaner: review it, then run it and commit the removals.\nset -e\n");
+        for (repository, paths) in &self.by_repository {
+            let paths: Vec<String> = paths.iter().map(|p| quote(p)).collect();
+            script.push_str(&format!("git -C {} rm -- {}\n", quote(repository), paths.join(" ")));
+        }
+        script
+    }
+
+    /// Writes the [`script`](Removals::script) to `path`.
+    pub fn save(&self, path: &Path) -> Result<(), io::Error> {
+        fs::write(path, self.script())
+    }
+}
+
+/// `path` as a single quoted shell word.
+fn quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::limits::Limits;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn repositories_are_found_and_skipped() -> Result<(), Box<dyn std::error::Error>> {
+        // Arrange
+        let root = Scratch::new("repos");
+        let repository = root.join("project");
+        fs::create_dir_all(repository.join(".git"))?;
+        fs::create_dir_all(root.join("notes"))?;
+        fs::write(repository.join("a.txt"), "a")?;
+        fs::write(root.join("notes").join("a.txt"), "a")?;
+        let limits = Limits { skip_repos: true, ..Limits::default() };
+
+        // Act
+        let (files, skipped) = crate::get_dir_files_limited(&root.to_string_lossy(), &|n| n == "a.txt", &limits)?;
+
+        // Assert
+        assert_eq!(repository_of(&repository.join("a.txt")), Some(repository.clone()));
+        assert_eq!(repository_of(&root.join("notes").join("a.txt")), None);
+        assert_eq!(files.len(), 1);
+        assert_eq!(skipped[0].reason, "git repository");
+        Ok(())
+    }
+}
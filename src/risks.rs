@@ -0,0 +1,90 @@
+//! Risk notes about the planned deletions (the entries suggested by `--keep`, `--cull-jpegs` or the earlier
+//! decisions), printed before `auto` applies them so the riskiest deletions are reviewed first.
+//!
+//! A deletion is risky when it removes the only copy left outside of the backups, when the file was modified
+//! recently (it may still be in use), or when a symbolic link or a shortcut found under the scanned directories
+//! points to it (see [`links`](crate::links)).
+
+use crate::links::References;
+use crate::File;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Files modified more recently than this are flagged.
+pub const RECENT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Whether the file located at `path` is stored in a backup: one of its folders mentions backups or snapshots.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::risks;
+/// # use std::path::Path;
+/// assert!(risks::in_backup(Path::new("/mnt/Backups/2023/a.txt")));
+/// assert!(!risks::in_backup(Path::new("/home/ana/backup-notes.txt")));
+/// ```
+pub fn in_backup(path: &Path) -> bool {
+    let folders = path.parent().into_iter().flat_map(Path::iter);
+    folders.map(|folder| folder.to_string_lossy().to_lowercase()).any(|folder| {
+        ["backup", "snapshot", "time machine"].iter().any(|word| folder.contains(word))
+    })
+}
+
+/// Risk notes about the deletion of the entry stored at `index` of `files`.
+pub fn assess(files: &[File], index: usize, references: &References, now: SystemTime) -> Vec<String> {
+    let file = &files[index];
+    let path = file.path();
+    let path = Path::new(&*path);
+    let mut risks = vec![];
+    let copies: Vec<&File> = files
+        .iter()
+        .enumerate()
+        .filter(|(i, copy)| *i != index && copy.group_key() == file.group_key())
+        .map(|(_, copy)| copy)
+        .collect();
+    if !copies.is_empty() && !in_backup(path) && copies.iter().all(|copy| in_backup(Path::new(&*copy.path()))) {
+        risks.push(String::from("only remaining copy outside backups"));
+    }
+    let modified = fs::metadata(path).and_then(|metadata| metadata.modified());
+    if modified.is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() < RECENT) {
+        risks.push(String::from("file modified in the last 24h"));
+    }
+    if let Some(referrer) = references.of(path).first() {
+        risks.push(format!("referenced by `{}`, which would break", referrer.display()));
+    }
+    risks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn risky_deletions_are_noted() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("risks");
+        fs::create_dir_all(root.join("backups"))?;
+        let (kept, copy) = (root.join("backups").join("a.txt"), root.join("a.txt"));
+        fs::write(&kept, "a")?;
+        fs::write(&copy, "a")?;
+        fs::write(root.join("a.desktop"), format!("[Desktop Entry]\nExec=edit {}\n", copy.display()))?;
+        let file = |path: &Path| {
+            let folder = path.parent().unwrap().to_string_lossy();
+            File::new("a.txt", &folder, "2022-07-23 12:33:01", &path.to_string_lossy())
+        };
+        let files = vec![file(&kept), file(&copy)];
+        let references = References::find(&[root.to_string_lossy().to_string()], &files);
+
+        // Act
+        let risks = assess(&files, 1, &references, SystemTime::now());
+        let later = assess(&files, 0, &References::default(), SystemTime::now() + RECENT * 2);
+
+        // Assert
+        assert_eq!(risks.len(), 3);
+        assert_eq!(risks[0], "only remaining copy outside backups");
+        assert_eq!(risks[1], "file modified in the last 24h");
+        assert!(risks[2].starts_with("referenced by `") && risks[2].ends_with("a.desktop`, which would break"));
+        assert!(later.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+//! Classification of the found paths by what they're likely to hold, using the well-known directories of every
+//! platform (the home and profile directories, the caches and temporary directories, the system directories).
+//!
+//! The class of every entry is shown in the listing, and deleting anything that isn't classified as user data
+//! or as an application cache takes an extra confirmation.
+
+use std::env;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+
+/// What a path is likely to hold.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Class {
+    /// Documents, pictures and the like, under the home directory of a user.
+    UserData,
+    /// Data applications can download or compute again (caches, temporary files).
+    Cache,
+    /// Files managed by the operating system or by the installed applications.
+    System,
+    /// Anything else, including the configuration and state of the applications.
+    Unknown,
+}
+
+impl Class {
+    /// Whether deleting the files of this class takes an extra confirmation.
+    pub fn needs_confirmation(self) -> bool {
+        matches!(self, Class::System | Class::Unknown)
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let label = match self {
+            Class::UserData => "user data",
+            Class::Cache => "application cache",
+            Class::System => "system",
+            Class::Unknown => "unknown",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Well-known directories of a machine, the most specific ones first.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Locations {
+    /// Caches and temporary directories.
+    pub caches: Vec<PathBuf>,
+    /// Directories holding the configuration and state of the applications, under `homes`.
+    pub private: Vec<PathBuf>,
+    /// Directories managed by the operating system and the package managers.
+    pub system: Vec<PathBuf>,
+    /// Home (or profile) directories, whose hidden entries count as `private`.
+    pub homes: Vec<PathBuf>,
+}
+
+impl Locations {
+    /// Well-known directories of the current machine and user.
+    pub fn current() -> Self {
+        let mut locations = platform::locations();
+        // Directories built out of unset variables end up relative, and would never match an absolute path.
+        for dirs in [&mut locations.caches, &mut locations.private, &mut locations.system, &mut locations.homes] {
+            dirs.retain(|dir| dir.is_absolute());
+        }
+        locations
+    }
+
+    /// Class of `path`, which is expected to be absolute.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::safety::{Class, Locations};
+    /// # use std::path::{Path, PathBuf};
+    /// let locations = Locations {
+    ///     caches: vec![PathBuf::from("/home/ana/.cache")],
+    ///     homes: vec![PathBuf::from("/home/ana")],
+    ///     ..Locations::default()
+    /// };
+    /// assert_eq!(locations.classify(Path::new("/home/ana/.cache/thumbnails/a.png")), Class::Cache);
+    /// assert_eq!(locations.classify(Path::new("/home/ana/.ssh/id_ed25519")), Class::Unknown);
+    /// assert_eq!(locations.classify(Path::new("/home/ana/Pictures/a.png")), Class::UserData);
+    /// ```
+    pub fn classify(&self, path: &Path) -> Class {
+        if self.caches.iter().any(|dir| under(path, dir)) {
+            return Class::Cache;
+        }
+        if self.private.iter().any(|dir| under(path, dir)) {
+            return Class::Unknown;
+        }
+        if self.system.iter().any(|dir| under(path, dir)) {
+            return Class::System;
+        }
+        let home = self.homes.iter().find(|dir| under(path, dir));
+        match home.and_then(|home| path.strip_prefix(home).ok()?.iter().next()) {
+            Some(entry) if entry.to_string_lossy().starts_with('.') => Class::Unknown,
+            Some(_) => Class::UserData,
+            None => Class::Unknown,
+        }
+    }
+}
+
+/// Class of the file located at `path` on the current machine.
+pub fn classify(path: &Path) -> Class {
+    match std::path::absolute(path) {
+        Ok(path) => Locations::current().classify(&path),
+        Err(_) => Class::Unknown,
+    }
+}
+
+/// Whether `path` is located under `dir` (ignoring the case on Windows).
+fn under(path: &Path, dir: &Path) -> bool {
+    match cfg!(windows) {
+        true => {
+            let path = path.to_string_lossy().to_lowercase();
+            Path::new(&path).starts_with(dir.to_string_lossy().to_lowercase())
+        }
+        false => path.starts_with(dir),
+    }
+}
+
+/// Directory named by the environment variable `name`, if set.
+fn var(name: &str) -> PathBuf {
+    env::var_os(name).map(PathBuf::from).unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{var, Locations};
+    use std::path::PathBuf;
+
+    pub fn locations() -> Locations {
+        let home = var("HOME");
+        let system = ["/System", "/Library", "/Applications", "/bin", "/sbin", "/usr", "/private", "/opt"];
+        Locations {
+            caches: vec![
+                home.join("Library/Caches"),
+                PathBuf::from("/Library/Caches"),
+                PathBuf::from("/private/tmp"),
+                PathBuf::from("/private/var/folders"),
+                PathBuf::from("/tmp"),
+            ],
+            private: vec![home.join("Library")],
+            system: system.iter().map(PathBuf::from).collect(),
+            homes: vec![home],
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{var, Locations};
+
+    pub fn locations() -> Locations {
+        let (profile, local) = (var("USERPROFILE"), var("LOCALAPPDATA"));
+        let system = ["SystemRoot", "ProgramFiles", "ProgramFiles(x86)", "ProgramData"];
+        Locations {
+            caches: vec![
+                var("TEMP"),
+                local.join("Temp"),
+                local.join("Microsoft\\Windows\\INetCache"),
+                local.join("CrashDumps"),
+            ],
+            private: vec![profile.join("AppData")],
+            system: system.iter().map(|name| var(name)).collect(),
+            homes: vec![profile],
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+mod platform {
+    use super::{var, Locations};
+    use std::path::PathBuf;
+
+    pub fn locations() -> Locations {
+        let home = var("HOME");
+        let cache = match var("XDG_CACHE_HOME") {
+            cache if cache.as_os_str().is_empty() => home.join(".cache"),
+            cache => cache,
+        };
+        let system = [
+            "/bin", "/boot", "/dev", "/etc", "/lib", "/lib64", "/opt", "/proc", "/sbin", "/snap", "/sys", "/usr",
+            "/var",
+        ];
+        Locations {
+            caches: vec![
+                cache,
+                home.join(".npm/_cacache"),
+                home.join(".cargo/registry"),
+                PathBuf::from("/var/cache"),
+                PathBuf::from("/var/tmp"),
+                PathBuf::from("/tmp"),
+            ],
+            private: vec![],
+            system: system.iter().map(PathBuf::from).collect(),
+            homes: vec![home],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn paths_are_classified_by_their_location() {
+        // Arrange
+        let locations = Locations {
+            caches: vec![PathBuf::from("/var/cache"), PathBuf::from("/home/ana/.cache")],
+            private: vec![PathBuf::from("/home/ana/AppData")],
+            system: vec![PathBuf::from("/usr"), PathBuf::from("/var")],
+            homes: vec![PathBuf::from("/home/ana")],
+        };
+
+        // Act and Assert
+        assert_eq!(locations.classify(Path::new("/var/cache/apt/a.deb")), Class::Cache);
+        assert_eq!(locations.classify(Path::new("/var/lib/dpkg/status")), Class::System);
+        assert_eq!(locations.classify(Path::new("/usr/share/doc/a.txt")), Class::System);
+        assert_eq!(locations.classify(Path::new("/home/ana/AppData/Roaming/a.ini")), Class::Unknown);
+        assert_eq!(locations.classify(Path::new("/home/ana/Documents/a.txt")), Class::UserData);
+        assert_eq!(locations.classify(Path::new("/home/ana")), Class::Unknown);
+        assert_eq!(locations.classify(Path::new("/srv/share/a.txt")), Class::Unknown);
+        assert!(Class::System.needs_confirmation() && !Class::Cache.needs_confirmation());
+    }
+}
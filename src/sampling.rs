@@ -0,0 +1,89 @@
+//! Review of a random sample of the groups of duplicates (the `sample` command), along with statistics about
+//! all of them. On result sets too big to be read whole, it lets the user check the choices of an automated
+//! policy (`--keep`, `--cull-jpegs`...) on a few groups before applying it to every one of them.
+
+use crate::groups;
+use crate::units::format_size;
+use crate::File;
+use std::fmt::Write;
+
+/// Groups shown by `sample` unless told otherwise.
+pub const SAMPLE_SIZE: usize = 20;
+
+/// Picks `count` distinct indexes below `len` at random (all of them when `count` exceeds `len`).
+/// ## Examples
+/// ```
+/// # use dir_cleaner::sampling;
+/// let mut picked = sampling::pick(10, 4, 42);
+/// picked.sort();
+/// picked.dedup();
+/// assert_eq!(picked.len(), 4);
+/// assert!(picked.iter().all(|i| *i < 10));
+/// ```
+pub fn pick(len: usize, count: usize, seed: u64) -> Vec<usize> {
+    let mut indexes: Vec<usize> = (0..len).collect();
+    // xorshift64*, which is plenty for picking what to show.
+    let mut state = seed | 1;
+    let count = count.min(len);
+    for i in 0..count {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        let random = state.wrapping_mul(0x2545F4914F6CDD1D);
+        let j = i + (random % (len - i) as u64) as usize;
+        indexes.swap(i, j);
+    }
+    indexes.truncate(count);
+    indexes
+}
+
+/// Statistics about every group of duplicates, followed by `count` groups picked at random (using `seed`) that
+/// show which entries are `suggested` for deletion.
+pub fn review(files: &[File], count: usize, suggested: &[String], seed: u64) -> String {
+    let groups = groups::duplicates(files);
+    let copies: usize = groups.iter().map(|group| group.len() - 1).sum();
+    let reclaimable: u64 = groups.iter().map(|group| group.reclaimable_size()).sum();
+    let mut review = format!(
+        "{} group(s) of duplicates, holding {} extra copy(ies) ({} reclaimable). {} deletion(s) suggested.\n",
+        groups.len(),
+        copies,
+        format_size(reclaimable),
+        suggested.len()
+    );
+    let mut picked = pick(groups.len(), count, seed);
+    picked.sort_unstable();
+    for group in picked.into_iter().map(|i| &groups[i]) {
+        let _ = writeln!(review, "Group of `{}` ({} copies):", group.name(), group.len());
+        for i in &group.indexes {
+            let file = &files[*i];
+            let verdict = match suggested.iter().any(|path| file.is_at(path)) {
+                true => "delete",
+                false => "keep",
+            };
+            let _ = writeln!(review, "\tEntry {} `{}`: {}", i + 1, file.path(), verdict);
+        }
+    }
+    review
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn review_shows_the_suggestions() {
+        // Arrange
+        let file = |folder: &str| File::new("a.txt", folder, "2022-07-23 12:33:01", &format!("{}/a.txt", folder));
+        let files = vec![file("./x"), file("./y"), file("./z")];
+        let suggested = vec![String::from("./y/a.txt")];
+
+        // Act
+        let review = review(&files, SAMPLE_SIZE, &suggested, 7);
+
+        // Assert
+        assert!(review.starts_with("1 group(s) of duplicates, holding 2 extra copy(ies) (0 B reclaimable)."));
+        assert!(review.contains("Group of `a.txt` (3 copies):\n\tEntry 1 `./x/a.txt`: keep\n"));
+        assert!(review.contains("\tEntry 2 `./y/a.txt`: delete\n"));
+        assert_eq!(pick(3, 5, 7).len(), 3);
+    }
+}
@@ -0,0 +1,358 @@
+//! Builder of scans, gathering in a single place what the command line options configure (several roots, the
+//! names looked for, filters on the found files, the depth of the scan, the symbolic links, the [`Limits`] and the
+//! comparisons of the contents). A file reached through several roots or links is only recorded once.
+//!
+//! The command line scans through it as well, so the library users get the same files and [`Warnings`] out of
+//! the same options.
+
+use crate::cache::Cache;
+use crate::hash::{self, Algorithm};
+use crate::limits::{Limits, Skipped};
+use crate::storage::{self, Reading};
+use crate::vfs::{self, Vfs};
+use crate::warnings::{self, Kind, Warnings};
+use crate::File;
+use crate::{cloud, has_duplicate, interrupt, overlap, ownership, photos, report, siblings};
+use std::io;
+use std::path::Path;
+
+/// Tells whether a file name is looked for.
+type Matcher<'a> = Box<dyn Fn(&str) -> bool + 'a>;
+
+/// Tells whether a found file is kept (E.G: [`Extensions`](crate::extensions::Extensions)). Every
+/// `Fn(&File) -> bool` is a filter as well.
+pub trait Filter {
+    fn keeps(&self, file: &File) -> bool;
+}
+
+impl<F: Fn(&File) -> bool> Filter for F {
+    fn keeps(&self, file: &File) -> bool {
+        self(file)
+    }
+}
+
+/// Outcome of a scan.
+#[derive(Debug, PartialEq, Default)]
+pub struct Scan {
+    /// Found files, root after root.
+    pub files: Vec<File>,
+    /// Directories left out because they exceeded the limits.
+    pub skipped: Vec<Skipped>,
+    /// Records left out because their file was already found through another root or link (see [`overlap`]).
+    pub collapsed: usize,
+    /// Files left out because they're owned by other users (see [`Scanner::only_mine`]).
+    pub others: usize,
+    /// What was left out or couldn't be compared, filled once the files are compared (see [`Scanner::compare`]).
+    pub warnings: Warnings,
+    /// Whether the comparison was interrupted, only the files compared so far being kept.
+    pub interrupted: bool,
+}
+
+/// How the found files are compared, besides their names (see [`Scanner::comparison`]).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Comparison {
+    /// Through the digest of their content, so only the copies holding the same bytes are grouped. Without any name
+    /// looked for, every file is compared to the others and only the ones having a copy are kept.
+    pub content: bool,
+    /// Through their extracted text (with the `documents` feature).
+    pub text: bool,
+    /// Through the look of their frames (with the `video` feature).
+    pub video: bool,
+    /// Only to the files of their sibling directories (see [`siblings`]).
+    pub siblings: bool,
+}
+
+impl Comparison {
+    /// Whether the contents of the files are read.
+    pub fn reads_contents(&self) -> bool {
+        self.content || self.text || self.video
+    }
+}
+
+/// Scan of one or several roots.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::scanner::Scanner;
+/// let scan = Scanner::new("./src").name("lib.rs").name("main.rs").max_depth(0).scan().unwrap();
+/// let names: Vec<&str> = scan.files.iter().map(|file| &*file.name).collect();
+/// assert_eq!(names, vec!["lib.rs", "main.rs"]);
+/// ```
+#[derive(Default)]
+pub struct Scanner<'a> {
+    roots: Vec<String>,
+    matchers: Vec<Matcher<'a>>,
+    filters: Vec<Box<dyn Filter + 'a>>,
+    limits: Limits,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+    vfs: Option<&'a dyn Vfs>,
+    cull_jpegs: bool,
+    only_mine: bool,
+    comparison: Comparison,
+    reading: Option<&'a Reading<'a>>,
+    min_confidence: Option<u8>,
+}
+
+impl<'a> Scanner<'a> {
+    /// Scans the directory located at `root`, gathering every file unless some names are provided.
+    pub fn new(root: &str) -> Self {
+        Scanner { roots: vec![root.to_string()], ..Scanner::default() }
+    }
+
+    /// Scans the directory located at `root` as well.
+    pub fn root(mut self, root: &str) -> Self {
+        self.roots.push(root.to_string());
+        self
+    }
+
+    /// Gathers the files named `name` (along with the ones the other names and matchers accept).
+    pub fn name(self, name: &'a str) -> Self {
+        self.matching(move |file_name| file_name == name)
+    }
+
+    /// Gathers the files whose name satisfies `matches` (along with the ones the other names and matchers accept).
+    pub fn matching(mut self, matches: impl Fn(&str) -> bool + 'a) -> Self {
+        self.matchers.push(Box::new(matches));
+        self
+    }
+
+    /// Only keeps the found files satisfying `keep` (as well as every other filter).
+    pub fn filter(self, keep: impl Fn(&File) -> bool + 'a) -> Self {
+        self.with_filter(keep)
+    }
+
+    /// Only keeps the found files `filter` keeps (as well as the ones every other filter keeps).
+    pub fn with_filter(mut self, filter: impl Filter + 'a) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Leaves out the directories exceeding `limits`.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Scans up to `depth` levels of subdirectories (`0` only scans the files stored directly on the roots).
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Follows the symbolic links, scanning every directory they lead to once (they're left out by default).
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Scans the roots found on `vfs` instead of the filesystem of the machine.
+    pub fn vfs(mut self, vfs: &'a dyn Vfs) -> Self {
+        self.vfs = Some(vfs);
+        self
+    }
+
+    /// Gathers the `JPEG`s having a `RAW` sibling instead of the files matching the names (see [`photos`]).
+    pub fn cull_jpegs(mut self, cull: bool) -> Self {
+        self.cull_jpegs = cull;
+        self
+    }
+
+    /// Leaves out the files owned by other users (see [`ownership`]).
+    pub fn only_mine(mut self, only_mine: bool) -> Self {
+        self.only_mine = only_mine;
+        self
+    }
+
+    /// Compares the found files as told by `comparison` (only by their names by default).
+    pub fn comparison(mut self, comparison: Comparison) -> Self {
+        self.comparison = comparison;
+        self
+    }
+
+    /// Reads the contents as told by `reading` (E.G: reusing its cache), instead of with the readers suiting the
+    /// storage of the first root and without any cache.
+    pub fn reading(mut self, reading: &'a Reading<'a>) -> Self {
+        self.reading = Some(reading);
+        self
+    }
+
+    /// Warns about the heuristic matches scoring below `min` (in percent).
+    pub fn min_confidence(mut self, min: u8) -> Self {
+        self.min_confidence = Some(min);
+        self
+    }
+
+    /// Lists the files and compares them (see [`Scanner::list`] and [`Scanner::compare`]).
+    pub fn scan(&self) -> Result<Scan, io::Error> {
+        let mut scan = self.list()?;
+        self.compare(&mut scan);
+        Ok(scan)
+    }
+
+    /// Lists the files of the roots, without comparing their contents yet.
+    pub fn list(&self) -> Result<Scan, io::Error> {
+        let limits = Limits {
+            max_depth: self.max_depth.or(self.limits.max_depth),
+            follow_symlinks: self.follow_symlinks || self.limits.follow_symlinks,
+            ..self.limits.clone()
+        };
+        let matches = |name: &str| match self.cull_jpegs {
+            true => photos::is_raw(name) || photos::is_jpeg(name),
+            false => self.matchers.is_empty() || self.matchers.iter().any(|matches| matches(name)),
+        };
+        let vfs = self.vfs.unwrap_or(&vfs::Local);
+        let mut scan = Scan::default();
+        for root in &self.roots {
+            let mut skipped = vec![];
+            let mut files = crate::walk(vfs, root, &matches, &limits, &mut skipped)?;
+            if self.cull_jpegs {
+                files = photos::jpegs_with_raw(files);
+            }
+            files.retain(|file| self.filters.iter().all(|filter| filter.keeps(file)));
+            scan.files.append(&mut files);
+            scan.skipped.append(&mut skipped);
+        }
+        if self.roots.len() > 1 || limits.follow_symlinks {
+            scan.collapsed = overlap::collapse(&mut scan.files, vfs);
+        }
+        if self.only_mine {
+            scan.others = ownership::retain_mine(&mut scan.files);
+        }
+        Ok(scan)
+    }
+
+    /// Compares the files `scan` listed, then warns about what was left out of it. An interruption keeps the files
+    /// compared so far.
+    pub fn compare(&self, scan: &mut Scan) {
+        let cache = Cache::default();
+        let fallback;
+        let reading = match self.reading {
+            Some(reading) => reading,
+            None => {
+                let root = self.roots.first().map_or(".", String::as_str);
+                let readers = storage::readers_for(Path::new(root), None, None, None);
+                let algorithm = Algorithm::default();
+                fallback = Reading { readers, cache: &cache, include_placeholders: false, algorithm };
+                &fallback
+            }
+        };
+        let reads_contents = self.comparison.reads_contents();
+        // The files found by content only didn't match any name, the ones without a copy are left out.
+        let any_name = self.comparison.content && self.matchers.is_empty() && !self.cull_jpegs;
+        // The files whose content can't be read are warned about along with the rest.
+        warnings::gather_content_failures();
+        if any_name {
+            hash::keep_duplicates(&mut scan.files, reading);
+        }
+        if self.comparison.siblings {
+            siblings::scope(&mut scan.files);
+        }
+        let placeholders = match reads_contents && !reading.include_placeholders {
+            true => cloud::skipped(&scan.files),
+            false => 0,
+        };
+        if self.comparison.content && !any_name {
+            hash::digest_files(&mut scan.files, reading);
+        }
+        #[cfg(feature = "documents")]
+        if self.comparison.text {
+            crate::documents::digest_files(&mut scan.files, reading);
+        }
+        #[cfg(feature = "video")]
+        if self.comparison.video {
+            crate::video::digest_files(&mut scan.files, reading);
+        }
+        scan.interrupted = interrupt::interrupted();
+        if scan.interrupted && reads_contents {
+            // The files left without a digest weren't compared.
+            scan.files.retain(|file| file.digest.is_some());
+            if any_name {
+                let mut duplicated = has_duplicate(&scan.files).into_iter();
+                scan.files.retain(|_| duplicated.next().unwrap_or_default());
+            }
+        }
+        scan.warnings.skipped(&scan.skipped);
+        scan.warnings.content_failures();
+        if let Some(note) = report::placeholders_note(placeholders) {
+            scan.warnings.push(Kind::Placeholders, None, note);
+        }
+        if let Some(min) = self.min_confidence {
+            scan.warnings.low_confidence(&scan.files, min);
+        }
+        if scan.interrupted {
+            let message = String::from("The scan was interrupted, only what it found so far is kept.");
+            scan.warnings.push(Kind::Interrupted, None, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn scans_are_configured_through_the_builder() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("scanner");
+        let (first, second) = (root.join("first"), root.join("second"));
+        fs::create_dir_all(first.join("a").join("b"))?;
+        fs::create_dir_all(&second)?;
+        fs::write(first.join("notes.txt"), "1")?;
+        fs::write(first.join("a").join("notes.txt"), "22")?;
+        fs::write(first.join("a").join("b").join("notes.txt"), "333")?;
+        fs::write(first.join("a").join("todo.txt"), "4444")?;
+        fs::write(second.join("notes.txt"), "")?;
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&first, second.join("link"))?;
+            std::os::unix::fs::symlink(&second, second.join("loop"))?;
+        }
+        let (first, second) = (first.to_string_lossy(), second.to_string_lossy());
+
+        // Act
+        let shallow = Scanner::new(&first).root(&second).name("notes.txt").max_depth(1).scan()?;
+        let overlapping = Scanner::new(&first).root(&format!("{}/a", first)).name("todo.txt").scan()?;
+        let filtered = Scanner::new(&first).filter(|file| file.size > 2).scan()?;
+        let followed = Scanner::new(&second).name("todo.txt").follow_symlinks(true).scan()?;
+
+        // Assert
+        let sizes: Vec<u64> = shallow.files.iter().map(|file| file.size).collect();
+        assert_eq!(sizes, vec![1, 2, 0]);
+        assert_eq!((overlapping.files.len(), overlapping.collapsed), (1, 1));
+        let names: Vec<&str> = filtered.files.iter().map(|file| &*file.name).collect();
+        assert_eq!(names, vec!["todo.txt", "notes.txt"]);
+        #[cfg(unix)]
+        assert_eq!(followed.files.len(), 1);
+        #[cfg(not(unix))]
+        assert!(followed.files.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn scans_compare_the_contents() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("scanner-contents");
+        fs::create_dir_all(root.join("a"))?;
+        fs::write(root.join("notes.txt"), "same")?;
+        fs::write(root.join("a").join("notes.txt"), "same")?;
+        fs::write(root.join("a").join("copy.txt"), "same")?;
+        fs::write(root.join("a").join("todo.txt"), "other")?;
+        let root = root.to_string_lossy();
+        let content = Comparison { content: true, ..Comparison::default() };
+
+        // Act
+        let named = Scanner::new(&root).name("notes.txt").comparison(content).scan()?;
+        let any_name = Scanner::new(&root).comparison(content).scan()?;
+
+        // Assert
+        assert_eq!(named.files.len(), 2);
+        assert!(named.files.iter().all(|file| file.digest.is_some()));
+        let mut names: Vec<&str> = any_name.files.iter().map(|file| &*file.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["copy.txt", "notes.txt", "notes.txt"]);
+        assert!(!any_name.interrupted && any_name.warnings.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! Scheduled cleanups (`--delete-all --at <HH:MM>`), so the heavy deletions of a file server happen off-hours: the
+//! scan runs and the deletions are confirmed right away, then the process waits until the given local time before
+//! applying them. Until then, Ctrl+C cancels them without deleting anything. The entries gone meanwhile are
+//! skipped.
+
+use crate::interrupt;
+use crate::units::{format_duration, UnitError};
+use chrono::{Duration as Days, Local, NaiveDateTime, NaiveTime};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the wait checks whether it was cancelled.
+const TICK: Duration = Duration::from_millis(250);
+
+/// Parses times of the day such as `02:00` or `23:30`.
+pub fn parse_time(value: &str) -> Result<NaiveTime, UnitError> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| UnitError(value.to_string()))
+}
+
+/// How long it takes from `now` to the next time the clock reads `at` (tomorrow when it's past already).
+/// ## Examples
+/// ```
+/// # use dir_cleaner::schedule;
+/// # use std::time::Duration;
+/// let now = chrono::NaiveDate::from_ymd_opt(2022, 7, 23).unwrap().and_hms_opt(23, 30, 0).unwrap();
+/// let at = schedule::parse_time("02:00").unwrap();
+/// assert_eq!(schedule::delay(now, at), Duration::from_secs(150 * 60));
+/// ```
+pub fn delay(now: NaiveDateTime, at: NaiveTime) -> Duration {
+    let mut next = now.date().and_time(at);
+    if next < now {
+        next += Days::days(1);
+    }
+    (next - now).to_std().unwrap_or_default()
+}
+
+/// Waits until the clock reads `at`, returning whether the wait went through (`false` when it was cancelled
+/// through Ctrl+C).
+pub fn wait(at: NaiveTime) -> bool {
+    let delay = delay(Local::now().naive_local(), at);
+    println!(
+        "The deletions are scheduled at {} (in {}), press Ctrl+C until then in order to cancel them.",
+        at.format("%H:%M"),
+        format_duration(delay)
+    );
+    let guard = interrupt::guard();
+    let deadline = Instant::now() + delay;
+    while Instant::now() < deadline && !interrupt::interrupted() {
+        thread::sleep(TICK.min(deadline.saturating_duration_since(Instant::now())));
+    }
+    drop(guard);
+    !interrupt::take()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn the_next_occurrence_is_waited_for() {
+        // Arrange
+        let now = NaiveDate::from_ymd_opt(2022, 7, 23).and_then(|date| date.and_hms_opt(1, 0, 0)).unwrap();
+
+        // Act
+        let later = delay(now, parse_time("02:00").unwrap());
+        let past = delay(now, parse_time("00:59").unwrap());
+        let current = delay(now, parse_time("01:00").unwrap());
+
+        // Assert
+        assert_eq!(later, Duration::from_secs(60 * 60));
+        assert_eq!(past, Duration::from_secs(24 * 60 * 60 - 60));
+        assert_eq!(current, Duration::ZERO);
+        assert!(parse_time("2am").is_err());
+        assert!(parse_time("25:00").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Versioning of the data dir_cleaner writes to disk, so it survives upgrades of the crate.
+//!
+//! Every persisted document carries a `version` field. Documents written by older versions are migrated
+//! when read (and can be rewritten through `dir_cleaner migrate <file>`), while the ones written by newer
+//! versions are refused instead of being misread.
+//!
+//! Session schema history:
+//!
+//! - `0`: sessions saved before the schema was versioned (no `version` field).
+//! - `1`: adds the `version` field.
+//!
+//! Cache schema history:
+//!
+//! - `1`: `version`, the `checksum` of the entries and the `entries` (see [`cache`](crate::cache)).
+//! - `2`: same layout, but the `xxh64` digests cached by the version `1` were miscomputed, and are dropped.
+//!
+//! The journal of a cache (one `{"key", "size", "modified", "value", "checksum"}` line per entry) isn't versioned
+//! on its own: the lines a build can't read are skipped, like the ones failing their checksum.
+//!
+//! Decisions schema history:
+//!
+//! - `1`: `version` and the `decisions` (see [`decisions`](crate::decisions)).
+//!
+//! Manifest schema history:
+//!
+//! - `1`: `version`, `root`, `exported_at` and the `entries` (see [`manifest`](crate::manifest)).
+//!
+//! History schema history:
+//!
+//! - `1`: `version` and the `runs` (see [`history`](crate::history)).
+//!
+//! Snapshot schema history:
+//!
+//! - `1`: `version` and the listings of the `dirs` (see [`incremental`](crate::incremental)).
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+
+/// Version of the sessions written by this build.
+pub const SESSION_VERSION: u32 = 1;
+/// Version of the caches written by this build.
+pub const CACHE_VERSION: u32 = 2;
+/// Version of the decision files written by this build.
+pub const DECISIONS_VERSION: u32 = 1;
+/// Version of the hash manifests written by this build.
+pub const MANIFEST_VERSION: u32 = 1;
+/// Version of the run histories written by this build.
+pub const HISTORY_VERSION: u32 = 1;
+/// Version of the scan snapshots written by this build.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Returned when a document was written by a newer version of dir_cleaner.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SchemaError {
+    /// What the document holds (E.G: `session`).
+    pub kind: &'static str,
+    pub found: u64,
+    pub supported: u32,
+}
+
+impl Error for SchemaError {}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "This {} uses version {} of the format, but this build of dir_cleaner only reads up to version {}. \
+            Please upgrade dir_cleaner.",
+            self.kind, self.found, self.supported
+        )
+    }
+}
+
+/// What couldn't be done with a document.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Failed {
+    Read,
+    /// The document was read, but its content isn't valid (or was written by a newer version).
+    Parse,
+    Save,
+    Download,
+}
+
+/// Returned when a document dir_cleaner keeps (a session, a manifest, the configuration...) can't be read, parsed,
+/// saved or downloaded.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::schema::{DocumentError, Failed};
+/// let err = DocumentError::new("session", "scan.dcs", Failed::Read, std::io::Error::other("denied"));
+/// assert_eq!(err.to_string(), "Couldn't read the session `scan.dcs`: denied");
+/// ```
+#[derive(Debug)]
+pub struct DocumentError {
+    /// What the document holds (E.G: `session`).
+    pub kind: &'static str,
+    /// Where the document is located (a path, or the URL of a downloaded manifest).
+    pub location: String,
+    pub failed: Failed,
+    pub source: Box<dyn Error>,
+}
+
+impl DocumentError {
+    pub fn new(
+        kind: &'static str,
+        location: impl fmt::Display,
+        failed: Failed,
+        source: impl Into<Box<dyn Error>>,
+    ) -> Self {
+        DocumentError { kind, location: location.to_string(), failed, source: source.into() }
+    }
+}
+
+impl Error for DocumentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let (kind, location, source) = (self.kind, &self.location, &self.source);
+        match self.failed {
+            Failed::Read => write!(f, "Couldn't read the {} `{}`: {}", kind, location, source),
+            Failed::Parse => write!(f, "Invalid {} `{}`: {}", kind, location, source),
+            Failed::Save => write!(f, "Couldn't save the {} to `{}`: {}", kind, location, source),
+            Failed::Download => write!(f, "Couldn't download the {} `{}`: {}", kind, location, source),
+        }
+    }
+}
+
+/// Version of `document` (`0` when it predates the versioning), refused when newer than `supported`.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::schema;
+/// let legacy = serde_json::json!({ "root": "." });
+/// let future = serde_json::json!({ "version": 99 });
+/// assert_eq!(schema::version("session", &legacy, 1), Ok(0));
+/// assert!(schema::version("session", &future, 1).is_err());
+/// ```
+pub fn version(kind: &'static str, document: &Value, supported: u32) -> Result<u32, SchemaError> {
+    let found = document.get("version").and_then(Value::as_u64).unwrap_or(0);
+    match u32::try_from(found) {
+        Ok(found) if found <= supported => Ok(found),
+        _ => Err(SchemaError { kind, found, supported }),
+    }
+}
+
+/// Brings a session `document` of version `from` up to [`SESSION_VERSION`].
+pub fn migrate_session(document: &mut Value, from: u32) {
+    for version in from..SESSION_VERSION {
+        match version {
+            // Only the version itself was added.
+            0 => {}
+            _ => unreachable!("every older version has a migration"),
+        }
+    }
+    if let Some(document) = document.as_object_mut() {
+        document.insert(String::from("version"), Value::from(SESSION_VERSION));
+    }
+}
+
+/// Migrates the `entries` of a cache written with the version `from` to the current one.
+pub fn migrate_cache<V>(entries: &mut BTreeMap<String, V>, from: u32) {
+    for version in from..CACHE_VERSION {
+        match version {
+            // Caches aren't older than the version 1.
+            0 => {}
+            // The `xxh64` digests were computed with a wrong constant.
+            1 => entries.retain(|key, _| !key.starts_with("xxh64:")),
+            _ => unreachable!("every older version has a migration"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrate_legacy_session() {
+        // Arrange
+        let mut document = json!({ "root": ".", "name": "a.txt", "scanned_at": "2022-07-23 12:33:01", "files": [] });
+
+        // Act
+        let from = version("session", &document, SESSION_VERSION).unwrap();
+        migrate_session(&mut document, from);
+
+        // Assert
+        assert_eq!(from, 0);
+        assert_eq!(document["version"], json!(SESSION_VERSION));
+        assert_eq!(version("session", &document, SESSION_VERSION), Ok(SESSION_VERSION));
+    }
+
+    #[test]
+    fn migrate_cache_drops_the_xxh64_digests() {
+        // Arrange
+        let mut entries = BTreeMap::from([("xxh64:/a.txt", 1), ("blake3:/a.txt", 2)].map(|(k, v)| (k.to_string(), v)));
+
+        // Act
+        migrate_cache(&mut entries, 1);
+
+        // Assert
+        assert_eq!(entries.keys().collect::<Vec<_>>(), ["blake3:/a.txt"]);
+    }
+}
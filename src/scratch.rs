@@ -0,0 +1,47 @@
+//! Scratch directories of the tests: every [`Scratch`] gets a fresh directory of its own under the temporary one,
+//! removed once it's dropped (even if the test fails), so the tests neither share nor leave files behind.
+//!
+//! The integration tests include this file too (`#[path = "../src/scratch.rs"]`), so it only relies on `std`.
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Directories created so far by this process, numbering the next one.
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+/// Temporary directory removed when dropped.
+pub struct Scratch(PathBuf);
+
+impl Scratch {
+    /// Creates an empty directory named after `label`, the process and a counter, so the tests running at the same
+    /// time (or the leftovers of a killed run) never clash.
+    pub fn new(label: &str) -> Self {
+        let number = CREATED.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("dir_cleaner-{}-{}-{}", label, std::process::id(), number));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("the scratch directory should be created");
+        Scratch(path)
+    }
+}
+
+impl Deref for Scratch {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for Scratch {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,153 @@
+//! Scan results saved to disk (`--save session.dcs`), so they can be inspected later without rescanning.
+
+use crate::intern::Interner;
+use crate::schema::{self, DocumentError, Failed, SESSION_VERSION};
+use crate::File;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+
+/// A saved scan: where it happened, what was searched and everything that was found.
+/// ## Example
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::session::Session;
+/// let files = vec![File::new("a.txt", "./test", "2022-07-23 12:33:01", "./test/a.txt")];
+/// let session = Session::new("./test", "a.txt", files);
+/// let parsed = Session::parse(&session.to_json().unwrap()).unwrap();
+/// assert_eq!(parsed, session);
+/// ```
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Version of the format the session was written with (see [`schema`](crate::schema)).
+    #[serde(default)]
+    pub version: u32,
+    pub root: String,
+    pub name: String,
+    /// When the scan finished, formatted as `%Y-%m-%d %H:%M:%S` (UTC).
+    pub scanned_at: String,
+    pub files: Vec<File>,
+}
+
+impl Session {
+    pub fn new(root: &str, name: &str, files: Vec<File>) -> Self {
+        Self {
+            version: SESSION_VERSION,
+            root: root.to_string(),
+            name: name.to_string(),
+            scanned_at: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            files,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parses a saved session, migrating it when it was written by an older version of dir_cleaner. The names
+    /// and folders of its files are shared again, as they were after the scan.
+    pub fn parse(content: &str) -> Result<Self, Box<dyn Error>> {
+        let mut document: Value = serde_json::from_str(content)?;
+        let version = schema::version("session", &document, SESSION_VERSION)?;
+        schema::migrate_session(&mut document, version);
+        let mut session: Session = serde_json::from_value(document)?;
+        let mut interner = Interner::default();
+        for file in &mut session.files {
+            file.name = interner.intern(&file.name);
+            file.folder = interner.intern(&file.folder);
+        }
+        Ok(session)
+    }
+
+    /// Writes the session to the file located at `path`.
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_json()?)
+            .map_err(|e| DocumentError::new("session", path, Failed::Save, e).into())
+    }
+
+    /// Reads the session stored on the file located at `path`.
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let content =
+            fs::read_to_string(path).map_err(|e| DocumentError::new("session", path, Failed::Read, e))?;
+        Session::parse(&content).map_err(|e| DocumentError::new("session", path, Failed::Parse, e).into())
+    }
+
+    /// Rewrites the session stored on the file located at `path` with the current version of the format,
+    /// keeping a copy of the original next to it. Returns the version it was migrated from, or `None` when it
+    /// was already current.
+    pub fn migrate(path: &str) -> Result<Option<u32>, Box<dyn Error>> {
+        let content =
+            fs::read_to_string(path).map_err(|e| DocumentError::new("session", path, Failed::Read, e))?;
+        let document: Value =
+            serde_json::from_str(&content).map_err(|e| DocumentError::new("session", path, Failed::Parse, e))?;
+        let version = schema::version("session", &document, SESSION_VERSION)?;
+        if version == SESSION_VERSION {
+            return Ok(None);
+        }
+        let session = Session::parse(&content)?;
+        let backup = format!("{}.v{}.bak", path, version);
+        fs::copy(path, &backup).map_err(|e| DocumentError::new("session backup", &backup, Failed::Save, e))?;
+        session.save(path)?;
+        Ok(Some(version))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn save_and_load() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let scratch = Scratch::new("session");
+        let path = scratch.join("session.dcs");
+        let path = path.to_str().unwrap();
+        let mut file = File::new("a.txt", "./test", "2022-07-23 12:33:01", "./test/a.txt");
+        file.size = 42;
+        let session = Session::new("./test", "a.txt", vec![file]);
+
+        // Act
+        session.save(path)?;
+        let loaded = Session::load(path)?;
+
+        // Assert
+        assert_eq!(loaded, session);
+        assert_eq!(loaded.files[0].path(), "./test/a.txt");
+        Ok(())
+    }
+
+    #[test]
+    fn load_invalid_session() {
+        // Act and Assert
+        assert!(Session::load("./does-not-exist.dcs").is_err());
+        assert!(Session::parse("{\"root\": 1}").is_err());
+        assert!(Session::parse("{\"version\": 2, \"root\": \".\"}").is_err());
+    }
+
+    #[test]
+    fn migrate_legacy_session() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let scratch = Scratch::new("legacy");
+        let path = scratch.join("legacy.dcs");
+        let path = path.to_str().unwrap();
+        let legacy = r#"{"root": "./test", "name": "a.txt", "scanned_at": "2022-07-23 12:33:01", "files": [
+            {"name": "a.txt", "folder": "./test", "creation_date": "2022-07-23 12:33:01", "size": 1,
+             "path": "./test/a.txt"}
+        ]}"#;
+        fs::write(path, legacy)?;
+
+        // Act
+        let migrated = Session::migrate(path)?;
+        let again = Session::migrate(path)?;
+
+        // Assert
+        assert_eq!(migrated, Some(0));
+        assert_eq!(again, None);
+        assert_eq!(Session::load(path)?.version, SESSION_VERSION);
+        assert_eq!(fs::read_to_string(format!("{}.v0.bak", path))?, legacy);
+        Ok(())
+    }
+}
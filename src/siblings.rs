@@ -0,0 +1,121 @@
+//! Duplicate detection scoped to sibling directories (`--compare-siblings`).
+//!
+//! Instead of comparing every copy against every other one, a directory is only compared against its
+//! siblings: two copies are grouped when their paths only differ on the name of a single directory, as in
+//! `2023/jan/a.jpg` and `2023 copy/jan/a.jpg`. On huge trees this keeps most files out of any group, so far
+//! fewer of them have to be compared by content afterwards.
+
+use crate::File;
+use std::collections::HashMap;
+use std::path::{Component, Path};
+
+/// Smallest index of the set `i` belongs to, flattening the path along the way.
+fn find(sets: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while sets[root] != root {
+        root = sets[root];
+    }
+    let mut i = i;
+    while sets[i] != root {
+        let next = sets[i];
+        sets[i] = root;
+        i = next;
+    }
+    root
+}
+
+fn union(sets: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(sets, a), find(sets, b));
+    sets[a.max(b)] = a.min(b);
+}
+
+/// Index of the sibling cluster of every file (the index of its first member).
+fn clusters(files: &[File]) -> Vec<usize> {
+    let mut sets: Vec<usize> = (0..files.len()).collect();
+    let mut seen: HashMap<(usize, Vec<String>), usize> = HashMap::new();
+    for (i, file) in files.iter().enumerate() {
+        let components: Vec<String> = Path::new(&*file.path())
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        // Every directory of the path is replaced by a wildcard in turn: the files sharing the resulting
+        // pattern live on sibling directories.
+        for level in 0..components.len().saturating_sub(1) {
+            let mut pattern = components.clone();
+            pattern[level].clear();
+            match seen.get(&(level, pattern.clone())) {
+                Some(other) => union(&mut sets, *other, i),
+                None => {
+                    seen.insert((level, pattern), i);
+                }
+            }
+        }
+    }
+    (0..files.len()).map(|i| find(&mut sets, i)).collect()
+}
+
+/// Restricts the groups of duplicates to the copies found on sibling directories, adding the cluster of
+/// every file to its `digest`.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::siblings;
+/// let mut files = vec![
+///     File::new("a.jpg", "./2023/jan", "2022-07-23 12:33:01", "./2023/jan/a.jpg"),
+///     File::new("a.jpg", "./2023 copy/jan", "2022-07-23 12:33:01", "./2023 copy/jan/a.jpg"),
+///     File::new("a.jpg", "./2024/feb", "2022-07-23 12:33:01", "./2024/feb/a.jpg"),
+/// ];
+/// siblings::scope(&mut files);
+/// assert_eq!(files[0].group_key(), files[1].group_key());
+/// assert_ne!(files[0].group_key(), files[2].group_key());
+/// ```
+pub fn scope(files: &mut [File]) {
+    let clusters = clusters(files);
+    for (file, cluster) in files.iter_mut().zip(clusters) {
+        file.add_digest(&format!("siblings:{}", cluster));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn file(folder: &str) -> File {
+        File::new("a.txt", folder, "2022-07-23 12:33:01", &format!("{}/a.txt", folder))
+    }
+
+    #[test]
+    fn cluster_sibling_directories() {
+        // Arrange
+        let files = vec![
+            file("./r/2023/jan"),
+            file("./r/2023 copy/jan"),
+            file("./r/2023 copy/feb"),
+            file("./r/2024/mar"),
+            file("./r/2023 (1)/jan"),
+            file("./other/x/y"),
+        ];
+
+        // Act
+        let clusters = clusters(&files);
+
+        // Assert
+        assert_eq!(clusters, vec![0, 0, 0, 3, 0, 5]);
+    }
+
+    #[test]
+    fn scope_keeps_previous_digests() {
+        // Arrange
+        let mut files = vec![file("./r/a"), file("./r/b")];
+        files[0].digest = Some(String::from("~video:0"));
+
+        // Act
+        scope(&mut files);
+
+        // Assert
+        assert_eq!(files[0].digest.as_deref(), Some("~video:0+siblings:0"));
+        assert_eq!(files[1].digest.as_deref(), Some("siblings:0"));
+        assert!(files[0].heuristic_match());
+    }
+}
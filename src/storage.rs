@@ -0,0 +1,198 @@
+//! Storage awareness, so the contents of the files are read with a concurrency the underlying device
+//! handles well: spinning disks suffer badly from parallel random reads, while SSDs (and NVMe drives)
+//! are only fast when they're fed several requests at once.
+//!
+//! The kind of device is detected on Linux (through `/sys/dev/block`); it can be provided explicitly through
+//! `--storage hdd|ssd`, and the amount of readers through `--readers <n>`.
+
+use crate::cache::Cache;
+use crate::hash::Algorithm;
+use crate::pause;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Returned when the requested storage kind doesn't exist.
+#[derive(Debug, Clone)]
+pub struct StorageError(pub String);
+
+impl Error for StorageError {}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Unknown storage kind `{}` (available: hdd, ssd).", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Storage {
+    /// Spinning disk: a single reader avoids seeking back and forth.
+    Rotational,
+    SolidState,
+    /// The kind of device couldn't be detected (network shares, virtual filesystems, other platforms...).
+    Unknown,
+}
+
+impl FromStr for Storage {
+    type Err = StorageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hdd" | "rotational" => Ok(Storage::Rotational),
+            "ssd" | "nvme" | "solid-state" => Ok(Storage::SolidState),
+            _ => Err(StorageError(s.to_string())),
+        }
+    }
+}
+
+impl Storage {
+    /// Detects the kind of device storing the file (or directory) located at `path`.
+    #[cfg(target_os = "linux")]
+    pub fn detect(path: &Path) -> Self {
+        use std::os::unix::fs::MetadataExt;
+
+        let dev = match std::fs::metadata(path) {
+            Ok(metadata) => metadata.dev(),
+            Err(_) => return Storage::Unknown,
+        };
+        let major = ((dev >> 8) & 0xfff) | ((dev >> 32) & !0xfff);
+        let minor = (dev & 0xff) | ((dev >> 12) & !0xff);
+        let device = match std::fs::canonicalize(format!("/sys/dev/block/{}:{}", major, minor)) {
+            Ok(device) => device,
+            Err(_) => return Storage::Unknown,
+        };
+        // Partitions don't have a queue of their own, their disk does.
+        let rotational = [device.join("queue/rotational"), device.join("../queue/rotational")]
+            .iter()
+            .find_map(|path| std::fs::read_to_string(path).ok());
+        match rotational.as_deref().map(str::trim) {
+            Some("1") => Storage::Rotational,
+            Some("0") => Storage::SolidState,
+            _ => Storage::Unknown,
+        }
+    }
+
+    /// Detects the kind of device storing the file (or directory) located at `path`.
+    #[cfg(not(target_os = "linux"))]
+    pub fn detect(_path: &Path) -> Self {
+        Storage::Unknown
+    }
+
+    /// Amount of files that should be read at once on this kind of device.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::storage::Storage;
+    /// assert_eq!(Storage::Rotational.readers(), 1);
+    /// assert!(Storage::SolidState.readers() >= Storage::Unknown.readers().min(2));
+    /// ```
+    pub fn readers(&self) -> usize {
+        let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        match self {
+            Storage::Rotational => 1,
+            Storage::SolidState => cores.clamp(1, 16),
+            Storage::Unknown => cores.clamp(1, 2),
+        }
+    }
+}
+
+/// How the content comparisons read the files.
+pub struct Reading<'a> {
+    /// Amount of files read at once.
+    pub readers: usize,
+    /// Values computed on previous scans for the unchanged files.
+    pub cache: &'a Cache,
+    /// Read the cloud placeholders as well (see [`cloud`](crate::cloud)).
+    pub include_placeholders: bool,
+    /// Algorithm the contents are digested with.
+    pub algorithm: Algorithm,
+}
+
+/// Amount of readers to use for the files stored under `root`: `readers` when provided, otherwise what suits
+/// `storage` (detected when missing), bounded by `threads`.
+pub fn readers_for(root: &Path, storage: Option<Storage>, readers: Option<usize>, threads: Option<usize>) -> usize {
+    let readers = match readers {
+        Some(readers) => readers.max(1),
+        None => storage.unwrap_or_else(|| Storage::detect(root)).readers(),
+    };
+    readers.min(threads.unwrap_or(usize::MAX).max(1))
+}
+
+/// Applies `f` to every item using up to `readers` threads, keeping the order of the results. Every item waits
+/// for a [`pause`]d scan to be resumed first.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::storage::map_parallel;
+/// assert_eq!(map_parallel(&[1, 2, 3], 2, |n| n * 10), vec![10, 20, 30]);
+/// ```
+pub fn map_parallel<T, R, F>(items: &[T], readers: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    if readers <= 1 || items.len() <= 1 {
+        return items
+            .iter()
+            .map(|item| {
+                pause::checkpoint();
+                f(item)
+            })
+            .collect();
+    }
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..items.len()).map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..readers.min(items.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= items.len() {
+                    break;
+                }
+                pause::checkpoint();
+                let result = f(&items[i]);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+    results.into_inner().unwrap().into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_storage() {
+        // Act and Assert
+        assert_eq!("HDD".parse::<Storage>().unwrap(), Storage::Rotational);
+        assert_eq!("nvme".parse::<Storage>().unwrap(), Storage::SolidState);
+        assert!("tape".parse::<Storage>().is_err());
+    }
+
+    #[test]
+    fn readers_overrides() {
+        // Act and Assert
+        assert_eq!(readers_for(Path::new("."), Some(Storage::Rotational), None, None), 1);
+        assert_eq!(readers_for(Path::new("."), Some(Storage::Rotational), Some(4), None), 4);
+        assert_eq!(readers_for(Path::new("."), None, Some(0), None), 1);
+        assert_eq!(readers_for(Path::new("."), Some(Storage::SolidState), Some(8), Some(3)), 3);
+        assert_eq!(readers_for(Path::new("."), Some(Storage::Rotational), None, Some(0)), 1);
+    }
+
+    #[test]
+    fn map_parallel_keeps_order() {
+        // Arrange
+        let items: Vec<u64> = (0..100).collect();
+
+        // Act
+        let results = map_parallel(&items, 8, |n| n * n);
+
+        // Assert
+        assert_eq!(results, items.iter().map(|n| n * n).collect::<Vec<u64>>());
+    }
+}
@@ -0,0 +1,108 @@
+//! Detection of the alternate data streams (NTFS) and resource forks (HFS+/APFS) carried by a file.
+//!
+//! They are invisible to the size and content comparisons, so two "duplicates" may still differ at the platform
+//! level (E.G: a `Zone.Identifier` stream marking a download, or the resource fork of a classic Mac file). The
+//! files slated for deletion that carry any of them are reported before deleting them.
+
+use std::path::Path;
+
+/// Names of the streams of the file located at `path` on top of its main content.
+pub fn extra_streams(path: &Path) -> Vec<String> {
+    platform::extra_streams(path).unwrap_or_default()
+}
+
+/// Name of the stream described by `raw`, as returned by `FindNextStreamW` (E.G: `:Zone.Identifier:$DATA`),
+/// leaving out the main (unnamed) stream.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::streams;
+/// assert_eq!(streams::stream_name(":Zone.Identifier:$DATA"), Some("Zone.Identifier"));
+/// assert_eq!(streams::stream_name("::$DATA"), None);
+/// ```
+pub fn stream_name(raw: &str) -> Option<&str> {
+    let name = raw.strip_prefix(':')?;
+    let name = name.rsplit_once(':').map_or(name, |(name, _)| name);
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+
+    const MAX_STREAM_NAME: usize = 260 + 36;
+    const ERROR_HANDLE_EOF: i32 = 38;
+    const INVALID_HANDLE_VALUE: isize = -1;
+
+    #[repr(C)]
+    struct StreamData {
+        size: i64,
+        name: [u16; MAX_STREAM_NAME],
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn FindFirstStreamW(path: *const u16, level: u32, data: *mut StreamData, flags: u32) -> isize;
+        fn FindNextStreamW(handle: isize, data: *mut StreamData) -> i32;
+        fn FindClose(handle: isize) -> i32;
+    }
+
+    pub fn extra_streams(path: &Path) -> Result<Vec<String>, io::Error> {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        let mut data = StreamData { size: 0, name: [0; MAX_STREAM_NAME] };
+        // SAFETY: `wide` is NUL terminated and `data` matches the layout of `WIN32_FIND_STREAM_DATA`.
+        let handle = unsafe { FindFirstStreamW(wide.as_ptr(), 0, &mut data, 0) };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+        let mut names = vec![];
+        loop {
+            let len = data.name.iter().position(|c| *c == 0).unwrap_or(MAX_STREAM_NAME);
+            let raw = String::from_utf16_lossy(&data.name[..len]);
+            if let Some(name) = super::stream_name(&raw) {
+                names.push(format!("alternate data stream `{}`", name));
+            }
+            // SAFETY: `handle` was returned by `FindFirstStreamW` and isn't closed yet.
+            if unsafe { FindNextStreamW(handle, &mut data) } == 0 {
+                break;
+            }
+        }
+        let err = io::Error::last_os_error();
+        // SAFETY: `handle` was returned by `FindFirstStreamW` and is closed only once.
+        unsafe { FindClose(handle) };
+        match err.raw_os_error() {
+            Some(ERROR_HANDLE_EOF) => Ok(names),
+            _ => Err(err),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use crate::relocate::xattr;
+    use std::io;
+    use std::path::Path;
+
+    const RESOURCE_FORK: &[u8] = b"com.apple.ResourceFork";
+
+    pub fn extra_streams(path: &Path) -> Result<Vec<String>, io::Error> {
+        let names = xattr::list(path)?;
+        Ok(names
+            .iter()
+            .filter(|name| name.as_slice() == RESOURCE_FORK)
+            .filter(|name| xattr::get(path, name).is_ok_and(|fork| !fork.is_empty()))
+            .map(|_| String::from("resource fork"))
+            .collect())
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    use std::io;
+    use std::path::Path;
+
+    pub fn extra_streams(_path: &Path) -> Result<Vec<String>, io::Error> {
+        Ok(vec![])
+    }
+}
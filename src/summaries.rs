@@ -0,0 +1,138 @@
+//! Summaries of the top-level directories of a scan, printed on the standard error as soon as each of them is
+//! scanned (`--dir-summaries`), so the long scans can be followed without waiting for their end. The files stored
+//! directly on the root aren't summarized.
+
+use crate::units::format_duration;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// What was found under a top-level directory.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Summary {
+    pub dir: String,
+    /// Entries listed by the directory and its subdirectories.
+    pub entries: usize,
+    /// Files matching the scan.
+    pub matches: usize,
+    /// Directories left out, and entries that couldn't be inspected.
+    pub errors: usize,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}`: {} entry(ies), {} match(es), {} error(s), in {}.",
+            self.dir,
+            self.entries,
+            self.matches,
+            self.errors,
+            format_duration(self.elapsed)
+        )
+    }
+}
+
+/// A top-level directory being scanned.
+struct Progress {
+    summary: Summary,
+    started: Option<Instant>,
+    /// Directories under it queued but not scanned yet.
+    pending: usize,
+}
+
+/// Follows the top-level directories of the scan of `root`, telling when each of them is done.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::summaries::Tracker;
+/// let mut tracker = Tracker::new("./photos");
+/// tracker.queued("./photos/2023");
+/// tracker.scanned("./photos/2023", 12, 3, 0);
+/// tracker.queued("./photos/2023/summer");
+/// assert_eq!(tracker.done("./photos/2023"), None);
+/// tracker.scanned("./photos/2023/summer", 4, 1, 1);
+/// let summary = tracker.done("./photos/2023/summer").unwrap();
+/// assert_eq!((summary.dir.as_str(), summary.entries, summary.matches), ("./photos/2023", 16, 4));
+/// ```
+pub struct Tracker {
+    root: PathBuf,
+    dirs: HashMap<PathBuf, Progress>,
+}
+
+impl Tracker {
+    pub fn new(root: &str) -> Self {
+        Tracker { root: PathBuf::from(root), dirs: HashMap::new() }
+    }
+
+    /// Top-level directory holding `dir`, `None` for the root itself.
+    fn top(&self, dir: &str) -> Option<PathBuf> {
+        let first = Path::new(dir).strip_prefix(&self.root).ok()?.components().next()?;
+        Some(self.root.join(first))
+    }
+
+    /// Tells that `dir` was queued in order to be scanned.
+    pub fn queued(&mut self, dir: &str) {
+        if let Some(top) = self.top(dir) {
+            let summary = Summary { dir: top.to_string_lossy().to_string(), ..Summary::default() };
+            self.dirs.entry(top).or_insert(Progress { summary, started: None, pending: 0 }).pending += 1;
+        }
+    }
+
+    /// Tells what the scan of `dir` found (the directory isn't done until [`Tracker::done`] is called).
+    pub fn scanned(&mut self, dir: &str, entries: usize, matches: usize, errors: usize) {
+        if let Some(progress) = self.top(dir).and_then(|top| self.dirs.get_mut(&top)) {
+            progress.started.get_or_insert_with(Instant::now);
+            progress.summary.entries += entries;
+            progress.summary.matches += matches;
+            progress.summary.errors += errors;
+        }
+    }
+
+    /// Tells that `dir` is done (its subdirectories being queued already), returning the summary of its top-level
+    /// directory once nothing is left to scan under it.
+    pub fn done(&mut self, dir: &str) -> Option<Summary> {
+        let top = self.top(dir)?;
+        let progress = self.dirs.get_mut(&top)?;
+        progress.pending -= 1;
+        if progress.pending > 0 {
+            return None;
+        }
+        let mut progress = self.dirs.remove(&top)?;
+        progress.summary.elapsed = progress.started.map(|started| started.elapsed()).unwrap_or_default();
+        Some(progress.summary)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn top_level_directories_are_summarized_once_done() {
+        // Arrange
+        let mut tracker = Tracker::new("/data");
+
+        // Act
+        tracker.scanned("/data", 3, 1, 0);
+        tracker.queued("/data/a");
+        tracker.queued("/data/b");
+        tracker.scanned("/data/a", 2, 1, 0);
+        tracker.queued("/data/a/nested");
+        let a = tracker.done("/data/a");
+        tracker.scanned("/data/a/nested", 5, 2, 1);
+        let nested = tracker.done("/data/a/nested");
+        tracker.scanned("/data/b", 0, 0, 1);
+        let b = tracker.done("/data/b");
+
+        // Assert
+        assert_eq!(a, None);
+        let nested = nested.unwrap_or_default();
+        assert_eq!((nested.dir.as_str(), nested.entries, nested.matches, nested.errors), ("/data/a", 7, 3, 1));
+        assert_eq!(b.map(|b| b.errors), Some(1));
+        assert_eq!(tracker.done("/data"), None);
+        assert!(nested.to_string().starts_with("`/data/a`: 7 entry(ies), 3 match(es), 1 error(s), in "));
+    }
+}
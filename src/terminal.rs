@@ -0,0 +1,103 @@
+//! What the listings need to know about the terminal they're written to.
+
+use std::io::{self, IsTerminal};
+
+/// Width (in columns) of the terminal the standard output is written to, or `None` when it isn't written to a
+/// terminal, in which case the listings stick to a plain and stable formatting. `COLUMNS` overrides it.
+pub fn width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    match std::env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok()) {
+        Some(columns) => Some(columns),
+        None => platform::width(),
+    }
+    .filter(|columns| *columns > 0)
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::os::raw::{c_int, c_ulong, c_ushort};
+
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    const TIOCGWINSZ: c_ulong = 0x40087468;
+    #[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "freebsd")))]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+
+    #[repr(C)]
+    struct WinSize {
+        rows: c_ushort,
+        columns: c_ushort,
+        x_pixels: c_ushort,
+        y_pixels: c_ushort,
+    }
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    pub fn width() -> Option<usize> {
+        let mut size = WinSize { rows: 0, columns: 0, x_pixels: 0, y_pixels: 0 };
+        // SAFETY: the standard output stays open for the duration of the call and `size` outlives it.
+        match unsafe { ioctl(1, TIOCGWINSZ, &mut size as *mut WinSize) } {
+            0 => Some(size.columns as usize),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+
+    #[repr(C)]
+    struct Coord {
+        x: i16,
+        y: i16,
+    }
+
+    #[repr(C)]
+    struct SmallRect {
+        left: i16,
+        top: i16,
+        right: i16,
+        bottom: i16,
+    }
+
+    #[repr(C)]
+    struct ScreenBufferInfo {
+        size: Coord,
+        cursor: Coord,
+        attributes: u16,
+        window: SmallRect,
+        maximum_window_size: Coord,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetStdHandle(handle: u32) -> isize;
+        fn GetConsoleScreenBufferInfo(console: isize, info: *mut ScreenBufferInfo) -> i32;
+    }
+
+    pub fn width() -> Option<usize> {
+        let mut info = ScreenBufferInfo {
+            size: Coord { x: 0, y: 0 },
+            cursor: Coord { x: 0, y: 0 },
+            attributes: 0,
+            window: SmallRect { left: 0, top: 0, right: 0, bottom: 0 },
+            maximum_window_size: Coord { x: 0, y: 0 },
+        };
+        // SAFETY: `GetStdHandle` only returns a handle, and `info` matches `CONSOLE_SCREEN_BUFFER_INFO`.
+        match unsafe { GetConsoleScreenBufferInfo(GetStdHandle(STD_OUTPUT_HANDLE), &mut info) } {
+            0 => None,
+            _ => Some((info.window.right - info.window.left + 1).max(0) as usize),
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    pub fn width() -> Option<usize> {
+        None
+    }
+}
@@ -0,0 +1,354 @@
+//! Colors used by the listings and the two-pane interface.
+//!
+//! A theme starts from a preset (`dark`, `light`, `high-contrast` or `colorblind`) and can override the
+//! style of any role through the `[theme]` section of the config file:
+//!
+//! ```toml
+//! [theme]
+//! preset = "light"
+//!
+//! [theme.colors]
+//! entry = "bold blue"
+//! selection = "black on #f0e442"
+//! ```
+//! Styles are made of an optional `bold`, a foreground color and an optional `on <background>`; colors
+//! can be named (`red`, `bright-blue`, ...) or written as `#rrggbb`. The plain listing is only colored
+//! when `stdout` is a terminal and `NO_COLOR` isn't set.
+
+use crate::config::ThemeConfig;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+
+/// Returned when the `[theme]` section of the config file can't be understood.
+#[derive(Debug, Clone)]
+pub struct ThemeError(String);
+
+impl Error for ThemeError {}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid theme: {}", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    Rgb(u8, u8, u8),
+}
+
+const NAMED: [(&str, Color); 16] = [
+    ("black", Color::Black),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("yellow", Color::Yellow),
+    ("blue", Color::Blue),
+    ("magenta", Color::Magenta),
+    ("cyan", Color::Cyan),
+    ("white", Color::White),
+    ("bright-black", Color::BrightBlack),
+    ("bright-red", Color::BrightRed),
+    ("bright-green", Color::BrightGreen),
+    ("bright-yellow", Color::BrightYellow),
+    ("bright-blue", Color::BrightBlue),
+    ("bright-magenta", Color::BrightMagenta),
+    ("bright-cyan", Color::BrightCyan),
+    ("bright-white", Color::BrightWhite),
+];
+
+impl Color {
+    /// Parses a named color (E.G: `bright-blue`) or an `#rrggbb` one.
+    pub fn parse(value: &str) -> Result<Color, ThemeError> {
+        let value = value.to_lowercase();
+        if let Some(hex) = value.strip_prefix('#') {
+            let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+            return match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok(Color::Rgb(r, g, b)),
+                _ => Err(ThemeError(format!("invalid color `{}`", value))),
+            };
+        }
+        NAMED
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, color)| *color)
+            .ok_or_else(|| ThemeError(format!("unknown color `{}`", value)))
+    }
+
+    fn write_ansi(&self, f: &mut Formatter, background: bool) -> fmt::Result {
+        let offset = if background { 10 } else { 0 };
+        match self {
+            Color::Rgb(r, g, b) => write!(f, "{};2;{};{};{}", 38 + offset, r, g, b),
+            named => {
+                let index = NAMED.iter().position(|(_, c)| c == named).unwrap_or(0) as u8;
+                let base = if index < 8 { 30 + index } else { 90 + index - 8 };
+                write!(f, "{}", base + offset)
+            }
+        }
+    }
+}
+
+/// Foreground, background and weight of a piece of text.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    const fn fg(color: Color) -> Self {
+        Self { fg: Some(color), bg: None, bold: false }
+    }
+
+    const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    const fn on(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Parses styles such as `bold cyan`, `black on yellow` or `#e69f00`.
+    pub fn parse(value: &str) -> Result<Style, ThemeError> {
+        let mut style = Style::default();
+        let mut words = value.split_whitespace();
+        while let Some(word) = words.next() {
+            match word {
+                "bold" => style.bold = true,
+                "on" => {
+                    let color = words
+                        .next()
+                        .ok_or_else(|| ThemeError(format!("missing background in `{}`", value)))?;
+                    style.bg = Some(Color::parse(color)?);
+                }
+                color => style.fg = Some(Color::parse(color)?),
+            }
+        }
+        Ok(style)
+    }
+
+    /// Writes the escape codes of the style, separated by `;`.
+    fn write_ansi(&self, f: &mut Formatter) -> fmt::Result {
+        let mut separator = "";
+        if self.bold {
+            f.write_str("1")?;
+            separator = ";";
+        }
+        if let Some(fg) = self.fg {
+            f.write_str(separator)?;
+            fg.write_ansi(f, false)?;
+            separator = ";";
+        }
+        if let Some(bg) = self.bg {
+            f.write_str(separator)?;
+            bg.write_ansi(f, true)?;
+        }
+        Ok(())
+    }
+}
+
+/// Style of every role that can be themed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Theme {
+    /// Entry numbers of the listing.
+    pub entry: Style,
+    pub name: Style,
+    pub folder: Style,
+    pub date: Style,
+    /// Markers pointing at duplicates.
+    pub duplicate: Style,
+    /// Highlighted entry of the interactive views.
+    pub selection: Style,
+    /// Border of the focused pane.
+    pub border: Style,
+    pub warning: Style,
+    /// Whether `paint` actually emits colors.
+    pub enabled: bool,
+}
+
+impl Theme {
+    /// Theme that never emits colors.
+    pub fn plain() -> Self {
+        let mut theme = Theme::preset("dark").expect("the dark preset exists");
+        theme.enabled = false;
+        theme
+    }
+
+    /// One of the built-in palettes: `dark`, `light`, `high-contrast` or `colorblind`.
+    pub fn preset(name: &str) -> Option<Theme> {
+        use Color::*;
+        let theme = match name {
+            "dark" => Theme {
+                entry: Style::fg(Cyan).bold(),
+                name: Style::fg(BrightWhite),
+                folder: Style::fg(BrightBlue),
+                date: Style::fg(BrightBlack),
+                duplicate: Style::fg(Yellow),
+                selection: Style::fg(Black).on(Cyan),
+                border: Style::fg(Cyan),
+                warning: Style::fg(BrightRed).bold(),
+                enabled: true,
+            },
+            "light" => Theme {
+                entry: Style::fg(Blue).bold(),
+                name: Style::fg(Black),
+                folder: Style::fg(Magenta),
+                date: Style::fg(BrightBlack),
+                duplicate: Style::fg(Red),
+                selection: Style::fg(White).on(Blue),
+                border: Style::fg(Blue),
+                warning: Style::fg(Red).bold(),
+                enabled: true,
+            },
+            "high-contrast" => Theme {
+                entry: Style::fg(BrightWhite).bold(),
+                name: Style::fg(BrightWhite).bold(),
+                folder: Style::fg(BrightYellow),
+                date: Style::fg(BrightWhite),
+                duplicate: Style::fg(BrightYellow).bold(),
+                selection: Style::fg(Black).on(BrightYellow),
+                border: Style::fg(BrightWhite).bold(),
+                warning: Style::fg(BrightWhite).on(Red).bold(),
+                enabled: true,
+            },
+            // Okabe-Ito palette, distinguishable with the common forms of color blindness.
+            "colorblind" => Theme {
+                entry: Style::fg(Rgb(0x56, 0xB4, 0xE9)).bold(),
+                name: Style::default(),
+                folder: Style::fg(Rgb(0x56, 0xB4, 0xE9)),
+                date: Style::fg(Rgb(0xCC, 0x79, 0xA7)),
+                duplicate: Style::fg(Rgb(0xE6, 0x9F, 0x00)),
+                selection: Style::fg(Black).on(Rgb(0xF0, 0xE4, 0x42)),
+                border: Style::fg(Rgb(0x56, 0xB4, 0xE9)),
+                warning: Style::fg(Rgb(0xD5, 0x5E, 0x00)).bold(),
+                enabled: true,
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Builds the theme described by the `[theme]` section of the config file (`dark` by default).
+    /// ## Example
+    /// ```
+    /// # use dir_cleaner::config::Config;
+    /// # use dir_cleaner::theme::{Color, Theme};
+    /// let config = Config::parse("[theme]\npreset = \"light\"\n[theme.colors]\nname = \"bold #ff8800\"").unwrap();
+    /// let theme = Theme::from_config(&config.theme).unwrap();
+    /// assert_eq!(theme.name.fg, Some(Color::Rgb(0xff, 0x88, 0x00)));
+    /// assert!(theme.name.bold);
+    /// ```
+    pub fn from_config(config: &ThemeConfig) -> Result<Theme, ThemeError> {
+        let preset = config.preset.as_deref().unwrap_or("dark");
+        let mut theme =
+            Theme::preset(preset).ok_or_else(|| ThemeError(format!("unknown preset `{}`", preset)))?;
+        for (role, value) in &config.colors {
+            let style = Style::parse(value)?;
+            match role.as_str() {
+                "entry" => theme.entry = style,
+                "name" => theme.name = style,
+                "folder" => theme.folder = style,
+                "date" => theme.date = style,
+                "duplicate" => theme.duplicate = style,
+                "selection" => theme.selection = style,
+                "border" => theme.border = style,
+                "warning" => theme.warning = style,
+                _ => return Err(ThemeError(format!("unknown role `{}`", role))),
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Wraps `text` on the escape sequences of `style` (or returns it untouched when colors are disabled).
+    pub fn paint(&self, style: Style, text: &str) -> String {
+        self.painted(style, text).to_string()
+    }
+
+    /// Same as [`Theme::paint`], but without allocating: the escape sequences are written when displayed.
+    pub fn painted<'a>(&self, style: Style, text: &'a str) -> Painted<'a> {
+        let style = if self.enabled { style } else { Style::default() };
+        Painted { style, text }
+    }
+}
+
+/// Text displayed along with the escape sequences of its style.
+pub struct Painted<'a> {
+    style: Style,
+    text: &'a str,
+}
+
+impl fmt::Display for Painted<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.style == Style::default() {
+            return f.write_str(self.text);
+        }
+        f.write_str("\x1b[")?;
+        self.style.write_ansi(f)?;
+        write!(f, "m{}\x1b[0m", self.text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn parse_styles() {
+        // Act and Assert
+        assert_eq!(
+            Style::parse("bold red on #0a0B0c").unwrap(),
+            Style { fg: Some(Color::Red), bg: Some(Color::Rgb(10, 11, 12)), bold: true }
+        );
+        assert_eq!(Style::parse("bright-cyan").unwrap(), Style::fg(Color::BrightCyan));
+        assert!(Style::parse("red on").is_err());
+        assert!(Style::parse("#12345").is_err());
+        assert!(Style::parse("ultraviolet").is_err());
+    }
+
+    #[test]
+    fn paint_emits_ansi_sequences() {
+        // Arrange
+        let theme = Theme::preset("high-contrast").unwrap();
+
+        // Act
+        let painted = theme.paint(Style::fg(Color::BrightRed).on(Color::Blue).bold(), "x");
+        let plain = Theme::plain().paint(theme.entry, "x");
+
+        // Assert
+        assert_eq!(painted, "\x1b[1;91;44mx\x1b[0m");
+        assert_eq!(plain, "x");
+    }
+
+    #[test]
+    fn from_config_rejects_unknown_values() {
+        // Arrange
+        let mut colors = BTreeMap::new();
+        colors.insert("sidebar".to_string(), "red".to_string());
+        let unknown_role = ThemeConfig { preset: None, colors };
+        let unknown_preset = ThemeConfig { preset: Some("neon".to_string()), colors: BTreeMap::new() };
+
+        // Act and Assert
+        assert!(Theme::from_config(&unknown_role).is_err());
+        assert!(Theme::from_config(&unknown_preset).is_err());
+        assert!(Theme::from_config(&ThemeConfig::default()).is_ok());
+    }
+}
@@ -0,0 +1,278 @@
+//! Moving the deleted files to the trash of the system, so a deletion can be undone from the file manager
+//! (`--permanent` deletes them for good instead).
+//!
+//! The trash follows the Freedesktop specification on Linux and the other Unix systems (the home trash, or the
+//! `.Trash-<uid>` directory at the top of the other drives), the Recycle Bin on Windows, and `~/.Trash` on macOS.
+
+use std::io;
+use std::path::Path;
+
+/// Moves the file located at `path` to the trash.
+pub fn trash(path: &Path) -> Result<(), io::Error> {
+    let path = std::path::absolute(path)?;
+    // Fails when there's nothing to trash, the way deleting it would.
+    std::fs::symlink_metadata(&path)?;
+    platform::trash(&path)
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::ffi::OsString;
+    use std::fs;
+    use std::io;
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::path::{Path, PathBuf};
+
+    extern "C" {
+        fn geteuid() -> u32;
+    }
+
+    /// Identifier of the current user, which names the trash directories at the top of the drives.
+    pub fn uid() -> u32 {
+        // SAFETY: `geteuid` takes no arguments and can't fail.
+        unsafe { geteuid() }
+    }
+
+    /// Outermost directory of the drive storing `path`.
+    pub fn top_dir(path: &Path) -> Result<PathBuf, io::Error> {
+        let device = fs::symlink_metadata(path)?.dev();
+        let mut top = path;
+        while let Some(parent) = top.parent() {
+            if fs::metadata(parent)?.dev() != device {
+                break;
+            }
+            top = parent;
+        }
+        Ok(top.to_path_buf())
+    }
+
+    /// Creates `dir` (and its missing ancestors), only readable by the current user.
+    pub fn create_private_dir(dir: &Path) -> Result<(), io::Error> {
+        fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)
+    }
+
+    /// Whether `dir` is a directory shared by the users of the drive: not a symbolic link, and sticky.
+    pub fn is_shared_dir(dir: &Path) -> bool {
+        fs::symlink_metadata(dir).is_ok_and(|m| m.is_dir() && m.permissions().mode() & 0o1000 != 0)
+    }
+
+    /// `name`, numbered with `n` when it isn't the first attempt (`a.txt`, `a.2.txt`, `a.3.txt`...).
+    pub fn numbered(name: &Path, n: usize) -> OsString {
+        if n == 1 {
+            return name.as_os_str().to_os_string();
+        }
+        let mut numbered = name.file_stem().unwrap_or(name.as_os_str()).to_os_string();
+        numbered.push(format!(".{}", n));
+        if let Some(extension) = name.extension() {
+            numbered.push(".");
+            numbered.push(extension);
+        }
+        numbered
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::unix::{create_private_dir, is_shared_dir, numbered, top_dir, uid};
+    use chrono::Local;
+    use std::env;
+    use std::fs::{self, OpenOptions};
+    use std::io::{self, Write};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+
+    pub fn trash(path: &Path) -> Result<(), io::Error> {
+        match move_to(path, &home_trash()?) {
+            Err(err) if err.kind() == io::ErrorKind::CrossesDevices => move_to(path, &drive_trash(path)?),
+            result => result,
+        }
+    }
+
+    /// Trash of the current user, stored on their home directory.
+    fn home_trash() -> Result<PathBuf, io::Error> {
+        let data = match (env::var_os("XDG_DATA_HOME"), env::var_os("HOME")) {
+            (Some(data), _) if Path::new(&data).is_absolute() => PathBuf::from(data),
+            (_, Some(home)) => Path::new(&home).join(".local/share"),
+            _ => return Err(io::Error::new(io::ErrorKind::NotFound, "neither XDG_DATA_HOME nor HOME are set")),
+        };
+        Ok(data.join("Trash"))
+    }
+
+    /// Trash of the current user at the top of the drive storing `path`: the `.Trash/<uid>` directory when the
+    /// administrator set a shared `.Trash` up, `.Trash-<uid>` otherwise.
+    fn drive_trash(path: &Path) -> Result<PathBuf, io::Error> {
+        let top = top_dir(path)?;
+        let shared = top.join(".Trash");
+        match is_shared_dir(&shared) {
+            true => Ok(shared.join(uid().to_string())),
+            false => Ok(top.join(format!(".Trash-{}", uid()))),
+        }
+    }
+
+    /// Moves the file located at `path` to the `files` directory of `trash`, describing it on `info`.
+    pub(super) fn move_to(path: &Path, trash: &Path) -> Result<(), io::Error> {
+        let (files, info) = (trash.join("files"), trash.join("info"));
+        create_private_dir(&files)?;
+        create_private_dir(&info)?;
+        let name = Path::new(path.file_name().unwrap_or(path.as_os_str()));
+        // The info file is created first, so it reserves the name on the trash.
+        let mut n = 1;
+        let (trashed, description) = loop {
+            let trashed = numbered(name, n);
+            let mut description = trashed.clone();
+            description.push(".trashinfo");
+            let description = info.join(description);
+            match OpenOptions::new().write(true).create_new(true).open(&description) {
+                Ok(mut file) => {
+                    let date = Local::now().format("%Y-%m-%dT%H:%M:%S");
+                    write!(file, "[Trash Info]\nPath={}\nDeletionDate={}\n", encode(path), date)?;
+                    break (files.join(trashed), description);
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => n += 1,
+                Err(err) => return Err(err),
+            }
+        };
+        fs::rename(path, &trashed).inspect_err(|_| {
+            let _ = fs::remove_file(&description);
+        })
+    }
+
+    /// `path`, with the bytes URLs can't hold escaped.
+    fn encode(path: &Path) -> String {
+        let mut encoded = String::new();
+        for byte in path.as_os_str().as_bytes() {
+            match byte {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                    encoded.push(*byte as char)
+                }
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::unix::{create_private_dir, numbered, top_dir, uid};
+    use std::env;
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+
+    pub fn trash(path: &Path) -> Result<(), io::Error> {
+        let home = env::var_os("HOME").ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "HOME isn't set"))?;
+        match move_to(path, &Path::new(&home).join(".Trash")) {
+            Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+                move_to(path, &top_dir(path)?.join(".Trashes").join(uid().to_string()))
+            }
+            result => result,
+        }
+    }
+
+    fn move_to(path: &Path, trash: &Path) -> Result<(), io::Error> {
+        create_private_dir(trash)?;
+        let name = Path::new(path.file_name().unwrap_or(path.as_os_str()));
+        let mut trashed = (1..).map(|n| trash.join(numbered(name, n)));
+        match trashed.find(|trashed| fs::symlink_metadata(trashed).is_err()) {
+            Some(trashed) => fs::rename(path, trashed),
+            None => Err(io::Error::new(io::ErrorKind::AlreadyExists, "the trash is full")),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::c_void;
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use std::ptr;
+
+    const FO_DELETE: u32 = 3;
+    const FOF_SILENT: u16 = 0x4;
+    const FOF_NOCONFIRMATION: u16 = 0x10;
+    const FOF_ALLOWUNDO: u16 = 0x40;
+    const FOF_NOERRORUI: u16 = 0x400;
+
+    #[repr(C)]
+    struct FileOperation {
+        window: isize,
+        function: u32,
+        from: *const u16,
+        to: *const u16,
+        flags: u16,
+        aborted: i32,
+        name_mappings: *mut c_void,
+        progress_title: *const u16,
+    }
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHFileOperationW(operation: *mut FileOperation) -> i32;
+    }
+
+    pub fn trash(path: &Path) -> Result<(), io::Error> {
+        // The list of paths ends with an empty one.
+        let from: Vec<u16> = path.as_os_str().encode_wide().chain([0, 0]).collect();
+        let mut operation = FileOperation {
+            window: 0,
+            function: FO_DELETE,
+            from: from.as_ptr(),
+            to: ptr::null(),
+            flags: FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_SILENT | FOF_NOERRORUI,
+            aborted: 0,
+            name_mappings: ptr::null_mut(),
+            progress_title: ptr::null(),
+        };
+        // SAFETY: `from` outlives the call, and the rest of the pointers are null as the operation allows.
+        match unsafe { SHFileOperationW(&mut operation) } {
+            0 if operation.aborted == 0 => Ok(()),
+            0 => Err(io::Error::new(io::ErrorKind::Interrupted, "moving it to the Recycle Bin was aborted")),
+            code => Err(io::Error::other(format!("the Recycle Bin refused it (error {:#x})", code))),
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use std::io;
+    use std::path::Path;
+
+    pub fn trash(_path: &Path) -> Result<(), io::Error> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "there's no trash on this platform, use --permanent"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn trashed_files_can_be_restored() -> Result<(), io::Error> {
+        // Arrange
+        let root = Scratch::new("trash");
+        let trash = root.join("Trash");
+        let (first, second) = (root.join("a b.txt"), root.join("docs").join("a b.txt"));
+        fs::create_dir_all(second.parent().unwrap())?;
+        fs::write(&first, "first")?;
+        fs::write(&second, "second")?;
+
+        // Act
+        platform::move_to(&first, &trash)?;
+        platform::move_to(&second, &trash)?;
+
+        // Assert
+        assert!(!first.exists() && !second.exists());
+        assert_eq!(fs::read_to_string(trash.join("files/a b.txt"))?, "first");
+        assert_eq!(fs::read_to_string(trash.join("files/a b.2.txt"))?, "second");
+        let info = fs::read_to_string(trash.join("info/a b.2.txt.trashinfo"))?;
+        assert!(info.starts_with("[Trash Info]\nPath=/"));
+        assert!(info.contains("/docs/a%20b.txt\nDeletionDate="));
+        assert!(super::trash(&root.join("missing.txt")).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+//! Key bindings of the two-pane mode, built out of the `[keys]` section of the config file.
+
+use crate::config::KeysConfig;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+
+/// Everything a key can be bound to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Action {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    SwitchPane,
+    Mark,
+    Keep,
+    Apply,
+    Preview,
+    Copy,
+    Help,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [
+        Action::Up,
+        Action::Down,
+        Action::PageUp,
+        Action::PageDown,
+        Action::SwitchPane,
+        Action::Mark,
+        Action::Keep,
+        Action::Apply,
+        Action::Preview,
+        Action::Copy,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// Name used by the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::SwitchPane => "switch_pane",
+            Action::Mark => "mark",
+            Action::Keep => "keep",
+            Action::Apply => "apply",
+            Action::Preview => "preview",
+            Action::Copy => "copy_paths",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Up => "move up",
+            Action::Down => "move down",
+            Action::PageUp => "move ten entries up",
+            Action::PageDown => "move ten entries down",
+            Action::SwitchPane => "switch pane",
+            Action::Mark => "mark (or unmark) for deletion",
+            Action::Keep => "keep (unmark)",
+            Action::Apply => "delete the marked files",
+            Action::Preview => "preview the selected file",
+            Action::Copy => "copy the marked (or selected) paths",
+            Action::Help => "show this help",
+            Action::Quit => "quit",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|a| a.name() == name)
+    }
+}
+
+/// Returned when the `[keys]` section of the config file can't be understood.
+#[derive(Debug, Clone)]
+pub struct KeyConfigError(String);
+
+impl Error for KeyConfigError {}
+
+impl fmt::Display for KeyConfigError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid key bindings: {}", self.0)
+    }
+}
+
+/// A single key, optionally combined with `ctrl` or `alt`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Parses names such as `j`, `Space`, `PageDown`, `F1` or `ctrl+d`.
+    pub fn parse(name: &str) -> Result<Key, KeyConfigError> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = name;
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "esc" | "escape" => KeyCode::Esc,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                f if f.starts_with('f') => match f[1..].parse::<u8>() {
+                    Ok(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(KeyConfigError(format!("unknown key `{}`", name))),
+                },
+                _ => return Err(KeyConfigError(format!("unknown key `{}`", name))),
+            },
+        };
+        Ok(Key { code, modifiers })
+    }
+
+    fn matches(&self, event: &KeyEvent) -> bool {
+        // Shift is implied by upper case characters, so it's ignored when comparing.
+        let modifiers = event.modifiers - KeyModifiers::SHIFT;
+        self.code == event.code && self.modifiers == modifiers
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Active bindings, in the order they were declared.
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<Key>)>,
+}
+
+impl Keymap {
+    /// Builds the bindings of the configured `preset`, replacing the ones overridden on `[keys.bindings]`.
+    pub fn from_config(config: &KeysConfig) -> Result<Self, KeyConfigError> {
+        let preset = config.preset.as_deref().unwrap_or("default");
+        let mut bindings = Vec::with_capacity(Action::ALL.len());
+        for action in Action::ALL {
+            let keys = preset_keys(preset, action)
+                .ok_or_else(|| KeyConfigError(format!("unknown preset `{}`", preset)))?;
+            let keys = keys.iter().map(|k| Key::parse(k)).collect::<Result<_, _>>()?;
+            bindings.push((action, keys));
+        }
+        for (name, keys) in &config.bindings {
+            let action = Action::from_name(name)
+                .ok_or_else(|| KeyConfigError(format!("unknown action `{}`", name)))?;
+            let keys = keys.iter().map(|k| Key::parse(k)).collect::<Result<_, _>>()?;
+            if let Some(binding) = bindings.iter_mut().find(|(a, _)| *a == action) {
+                binding.1 = keys;
+            }
+        }
+        Ok(Self { bindings })
+    }
+
+    /// Action bound to the pressed key, if any.
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|k| k.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Human readable keys bound to `action`, E.G: `j, Down`.
+    pub fn describe(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>().join(", "))
+            .unwrap_or_default()
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::from_config(&KeysConfig::default()).expect("the default preset is valid")
+    }
+}
+
+fn preset_keys(preset: &str, action: Action) -> Option<&'static [&'static str]> {
+    let keys: &[&str] = match (preset, action) {
+        ("default", Action::Up) => &["Up", "k"],
+        ("default", Action::Down) => &["Down", "j"],
+        ("default", Action::SwitchPane) => &["Tab", "Left", "Right"],
+        ("default", Action::Mark) => &["Space"],
+        ("default", Action::Keep) => &["Backspace"],
+        ("default", Action::Apply) => &["a"],
+        ("default", Action::Preview) => &["p"],
+        ("default", Action::Copy) => &["c"],
+        ("default", Action::Quit) => &["q", "Esc"],
+        ("arrows", Action::Up) => &["Up"],
+        ("arrows", Action::Down) => &["Down"],
+        ("arrows", Action::SwitchPane) => &["Left", "Right", "Tab"],
+        ("arrows", Action::Mark) => &["Space", "Enter"],
+        ("arrows", Action::Keep) => &["Backspace"],
+        ("arrows", Action::Apply) => &["Delete"],
+        ("arrows", Action::Preview) => &["F3"],
+        ("arrows", Action::Copy) => &["F5"],
+        ("arrows", Action::Help) => &["F1"],
+        ("arrows", Action::Quit) => &["Esc"],
+        ("vim", Action::Up) => &["k"],
+        ("vim", Action::Down) => &["j"],
+        ("vim", Action::PageUp) => &["ctrl+u"],
+        ("vim", Action::PageDown) => &["ctrl+d"],
+        ("vim", Action::SwitchPane) => &["h", "l", "Tab"],
+        ("vim", Action::Mark) => &["x", "Space"],
+        ("vim", Action::Keep) => &["u"],
+        ("vim", Action::Apply) => &["D"],
+        ("vim", Action::Preview) => &["o"],
+        ("vim", Action::Copy) => &["y"],
+        ("vim", Action::Quit) => &["q"],
+        ("default" | "arrows", Action::PageUp) => &["PageUp"],
+        ("default" | "arrows", Action::PageDown) => &["PageDown"],
+        ("default" | "vim", Action::Help) => &["?"],
+        _ => return None,
+    };
+    Some(keys)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parse_keys() {
+        // Act and Assert
+        assert_eq!(Key::parse("j").unwrap().to_string(), "j");
+        assert_eq!(Key::parse("Space").unwrap().to_string(), "Space");
+        assert_eq!(Key::parse("ctrl+d").unwrap().to_string(), "ctrl+d");
+        assert_eq!(Key::parse("F5").unwrap().to_string(), "F5");
+        assert!(Key::parse("hyper").is_err());
+        assert!(Key::parse("F13").is_err());
+    }
+
+    #[test]
+    fn presets_and_overrides() {
+        // Arrange
+        let mut bindings = BTreeMap::new();
+        bindings.insert("apply".to_string(), vec!["ctrl+x".to_string()]);
+        let config = KeysConfig {
+            preset: Some("vim".to_string()),
+            bindings,
+        };
+
+        // Act
+        let keymap = Keymap::from_config(&config).unwrap();
+
+        // Assert
+        assert_eq!(keymap.action_for(&press(KeyCode::Char('j'), KeyModifiers::NONE)), Some(Action::Down));
+        assert_eq!(keymap.action_for(&press(KeyCode::Down, KeyModifiers::NONE)), None);
+        assert_eq!(keymap.action_for(&press(KeyCode::Char('D'), KeyModifiers::SHIFT)), None);
+        assert_eq!(keymap.action_for(&press(KeyCode::Char('x'), KeyModifiers::CONTROL)), Some(Action::Apply));
+        assert_eq!(keymap.describe(Action::Mark), "x, Space");
+    }
+
+    #[test]
+    fn invalid_config() {
+        // Arrange
+        let mut bindings = BTreeMap::new();
+        bindings.insert("explode".to_string(), vec!["e".to_string()]);
+        let unknown_action = KeysConfig { preset: None, bindings };
+        let unknown_preset = KeysConfig { preset: Some("emacs".to_string()), bindings: BTreeMap::new() };
+
+        // Act and Assert
+        assert!(Keymap::from_config(&unknown_action).is_err());
+        assert!(Keymap::from_config(&unknown_preset).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! Live reviews (`--tui --live`): the scan runs on a thread of its own while the two-pane interface is already
+//! usable, the files being added to the tree as soon as their directory is listed. The marks and the selection
+//! are kept as the tree grows, and the status line tells how far the scan went until it's over. Quitting stops
+//! the scan at its next directory.
+//!
+//! Only the scans by name of a single root qualify: the content comparisons and the merge of several roots need
+//! every file beforehand.
+
+use crate::limits::{Limits, Skipped};
+use crate::{ownership, vfs, File};
+use std::error::Error;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread;
+
+/// What the scan thread tells the interface.
+#[derive(Debug, PartialEq)]
+pub enum Update {
+    /// Files found on a directory that was just listed.
+    Found(Vec<File>),
+    /// The scan is over, pointing out the entries left out.
+    Done(Vec<Skipped>),
+    /// The scan stopped on an error.
+    Failed(String),
+}
+
+/// How far the scan went, shown on the status line.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Progress {
+    /// Directories listed so far.
+    pub dirs: usize,
+    /// Files found so far.
+    pub files: usize,
+    pub done: bool,
+}
+
+impl Progress {
+    /// Line telling how far a running scan went.
+    pub fn describe(&self) -> Option<String> {
+        (!self.done).then(|| format!("Scanning: {} directories listed, {} file(s) found.", self.dirs, self.files))
+    }
+}
+
+/// What the scan looks for.
+pub struct Search<'a> {
+    pub root: &'a str,
+    pub matches: &'a (dyn Fn(&str) -> bool + Sync),
+    /// Criterion recorded on the found files (see [`provenance`](crate::provenance)).
+    pub criterion: Option<Arc<str>>,
+    pub limits: &'a Limits,
+    /// Leave out the files owned by other users (`--only-mine`).
+    pub only_mine: bool,
+}
+
+/// Walks the root of `search`, sending what it finds through `sender` until nobody listens anymore.
+fn scan(search: &Search, sender: Sender<Update>) {
+    let mut skipped = vec![];
+    let mut found = |files: &mut Vec<File>| {
+        let mut files = std::mem::take(files);
+        files.iter_mut().for_each(|file| file.matched_by = search.criterion.clone());
+        if search.only_mine {
+            ownership::retain_mine(&mut files);
+        }
+        sender.send(Update::Found(files)).is_ok()
+    };
+    let (root, limits) = (search.root, search.limits);
+    let walked = crate::walk_notifying(&vfs::Local, root, search.matches, limits, &mut skipped, &mut found);
+    let _ = sender.send(match walked {
+        Ok(_) => Update::Done(skipped),
+        Err(e) => Update::Failed(e.to_string()),
+    });
+}
+
+/// Takes over the terminal while `search` runs, the files being reviewed as they're found.
+pub fn review(
+    search: Search,
+    options: &crate::cli::Options,
+    config: &crate::config::Config,
+) -> Result<(), Box<dyn Error>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        scope.spawn(|| scan(&search, sender));
+        // The receiver is dropped along with the interface, which stops the scan.
+        super::run(vec![], options, config, Some(receiver))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+    use std::fs;
+
+    #[test]
+    fn every_listed_directory_is_sent() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("live");
+        fs::create_dir_all(root.join("a"))?;
+        fs::write(root.join("a.txt"), "same")?;
+        fs::write(root.join("a").join("a.txt"), "same")?;
+        let (sender, receiver) = mpsc::channel();
+        let limits = Limits::default();
+        let search = Search {
+            root: root.to_str().unwrap(),
+            matches: &|name| name == "a.txt",
+            criterion: Some(Arc::from("name `a.txt`")),
+            limits: &limits,
+            only_mine: false,
+        };
+
+        // Act
+        scan(&search, sender);
+
+        // Assert
+        let updates: Vec<Update> = receiver.try_iter().collect();
+        assert_eq!(updates.len(), 3);
+        assert!(matches!(&updates[0], Update::Found(files) if files[0].matched_by.as_deref() == Some("name `a.txt`")));
+        assert!(matches!(&updates[1], Update::Found(files) if files.len() == 1));
+        assert_eq!(updates[2], Update::Done(vec![]));
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+//! Optional two-pane, file-manager style interface (requires the `tui` feature).
+//!
+//! The left pane displays the directory tree of the found files, marking the directories that hold
+//! duplicates; the right pane lists the files of the highlighted directory along with the details of
+//! the selected one. Files can be marked and deleted in a single action.
+//!
+//! Keys can be remapped through the `[keys]` section of the config file (see [`crate::config`]), and the
+//! active bindings are displayed by the help overlay. Entries can also be selected by clicking them,
+//! scrolled with the mouse wheel and previewed with a double click.
+
+pub mod keys;
+pub mod live;
+pub mod state;
+
+use keys::{Action, Keymap};
+use ratatui::crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton,
+    MouseEvent, MouseEventKind,
+};
+use ratatui::crossterm::execute;
+use ratatui::layout::{Constraint, Direction, Flex, Layout, Position, Rect};
+use crate::apps;
+use crate::theme::{self, Theme};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use state::{scroll, App, Pane};
+use std::error::Error;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+const SCROLL_LINES: isize = 3;
+/// How often the tree is refreshed while the scan runs.
+const LIVE_REFRESH: Duration = Duration::from_millis(200);
+
+/// Drawing state kept between frames, so mouse events can be mapped to the entries they landed on.
+#[derive(Default)]
+struct View {
+    dir_state: ListState,
+    file_state: ListState,
+    /// First rows shown on both lists: only the visible rows are turned into list items.
+    dir_offset: usize,
+    file_offset: usize,
+    tree_area: Rect,
+    files_area: Rect,
+    last_click: Option<(Instant, Pane, usize)>,
+}
+
+/// Takes over the terminal until the user quits, letting them browse and delete the provided `files`.
+/// With `live`, the files arrive meanwhile from the scan (see [`live`]).
+pub fn run(
+    mut files: Vec<crate::File>,
+    options: &crate::cli::Options,
+    config: &crate::config::Config,
+    live: Option<Receiver<live::Update>>,
+) -> Result<(), Box<dyn Error>> {
+    let keymap = Keymap::from_config(&config.keys)?;
+    let theme = Theme::from_config(&config.theme)?;
+    let mut suggested = vec![];
+    let mut status = format!("Press {} for help.", keymap.describe(Action::Help));
+    if let Some(strategy) = options.keep {
+        for suggestion in crate::keep::suggest(strategy, &files) {
+            suggestion.mark(&mut files);
+            suggested.extend(suggestion.delete.into_iter().map(|i| PathBuf::from(&*files[i].path())));
+        }
+        status = format!("{} file(s) marked by --keep {}.", suggested.len(), strategy.name());
+    } else if options.cull_jpegs {
+        suggested.extend(files.iter().map(|f| PathBuf::from(&*f.path())));
+        status = format!("{} JPEG(s) with a RAW sibling marked.", suggested.len());
+    }
+    let mut app = App::new(&options.directory, files, options.contain_strict);
+    app.check_secrets = options.check_secrets;
+    app.cull_jpegs = options.cull_jpegs;
+    app.permanent = options.permanent;
+    app.paranoid = options.paranoid;
+    app.allow_delete_all = options.allow_delete_all;
+    app.companions = crate::companions::Companions::from_config(&config.companions);
+    app.marked.extend(suggested);
+    app.status = status;
+    if live.is_some() {
+        app.scan = Some(live::Progress::default());
+    }
+
+    let mut terminal = ratatui::init();
+    let result = execute!(io::stdout(), EnableMouseCapture)
+        .map_err(|e| e.into())
+        .and_then(|_| event_loop(&mut terminal, &mut app, &keymap, &theme, live.as_ref()));
+    let _ = execute!(io::stdout(), DisableMouseCapture);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    keymap: &Keymap,
+    theme: &Theme,
+    live: Option<&Receiver<live::Update>>,
+) -> Result<(), Box<dyn Error>> {
+    let mut view = View::default();
+    while !app.quit {
+        if let Some(receiver) = live {
+            app.receive(receiver.try_iter());
+        }
+        terminal.draw(|frame| draw(frame, app, keymap, theme, &mut view))?;
+        // While the scan runs, the tree is refreshed even though no key is pressed.
+        if app.scan.is_some_and(|progress| !progress.done) && !event::poll(LIVE_REFRESH)? {
+            continue;
+        }
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => handle_key(app, keymap, key),
+            Event::Mouse(mouse) => handle_mouse(app, &mut view, mouse),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn handle_key(app: &mut App, keymap: &Keymap, key: KeyEvent) {
+    if app.confirming {
+        match key.code {
+            KeyCode::Char('y') => app.apply(),
+            _ => app.cancel(),
+        }
+        return;
+    }
+    if app.help || app.preview.is_some() {
+        app.help = false;
+        app.preview = None;
+        return;
+    }
+    match keymap.action_for(&key) {
+        Some(Action::Quit) => app.quit = true,
+        Some(Action::SwitchPane) => app.switch_pane(),
+        Some(Action::Up) => app.move_by(-1),
+        Some(Action::Down) => app.move_by(1),
+        Some(Action::PageUp) => app.move_by(-10),
+        Some(Action::PageDown) => app.move_by(10),
+        Some(Action::Mark) => app.toggle_mark(),
+        Some(Action::Keep) => app.keep(),
+        Some(Action::Apply) => app.request_apply(),
+        Some(Action::Preview) => app.open_preview(),
+        Some(Action::Copy) => app.copy_paths(),
+        Some(Action::Help) => app.help = true,
+        None => {}
+    }
+}
+
+fn handle_mouse(app: &mut App, view: &mut View, mouse: MouseEvent) {
+    if app.confirming {
+        return;
+    }
+    let clicked = matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left));
+    if app.help || app.preview.is_some() {
+        if clicked {
+            app.help = false;
+            app.preview = None;
+        }
+        return;
+    }
+
+    let position = Position::new(mouse.column, mouse.row);
+    let (pane, area, offset) = if view.tree_area.contains(position) {
+        (Pane::Tree, view.tree_area, view.dir_offset)
+    } else if view.files_area.contains(position) {
+        (Pane::Files, view.files_area, view.file_offset)
+    } else {
+        return;
+    };
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            // The first and last rows of the area are taken by the borders.
+            if mouse.row <= area.y || mouse.row + 1 >= area.bottom() {
+                return;
+            }
+            let index = offset + (mouse.row - area.y - 1) as usize;
+            app.select(pane, index);
+            let double_click = matches!(
+                view.last_click,
+                Some((at, p, i)) if at.elapsed() <= DOUBLE_CLICK && p == pane && i == index
+            );
+            if double_click && pane == Pane::Files {
+                app.open_preview();
+                view.last_click = None;
+            } else {
+                view.last_click = Some((Instant::now(), pane, index));
+            }
+        }
+        MouseEventKind::ScrollUp => {
+            app.pane = pane;
+            app.move_by(-SCROLL_LINES);
+        }
+        MouseEventKind::ScrollDown => {
+            app.pane = pane;
+            app.move_by(SCROLL_LINES);
+        }
+        _ => {}
+    }
+}
+
+/// Converts a themed style into the one understood by `ratatui`.
+fn style(style: theme::Style) -> Style {
+    let color = |color: theme::Color| match color {
+        theme::Color::Black => Color::Black,
+        theme::Color::Red => Color::Red,
+        theme::Color::Green => Color::Green,
+        theme::Color::Yellow => Color::Yellow,
+        theme::Color::Blue => Color::Blue,
+        theme::Color::Magenta => Color::Magenta,
+        theme::Color::Cyan => Color::Cyan,
+        theme::Color::White => Color::Gray,
+        theme::Color::BrightBlack => Color::DarkGray,
+        theme::Color::BrightRed => Color::LightRed,
+        theme::Color::BrightGreen => Color::LightGreen,
+        theme::Color::BrightYellow => Color::LightYellow,
+        theme::Color::BrightBlue => Color::LightBlue,
+        theme::Color::BrightMagenta => Color::LightMagenta,
+        theme::Color::BrightCyan => Color::LightCyan,
+        theme::Color::BrightWhite => Color::White,
+        theme::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    };
+    let mut converted = Style::default();
+    if let Some(fg) = style.fg {
+        converted = converted.fg(color(fg));
+    }
+    if let Some(bg) = style.bg {
+        converted = converted.bg(color(bg));
+    }
+    if style.bold {
+        converted = converted.add_modifier(Modifier::BOLD);
+    }
+    converted
+}
+
+fn draw(frame: &mut Frame, app: &App, keymap: &Keymap, theme: &Theme, view: &mut View) {
+    let [body, status] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .areas(frame.area());
+    let [left, right] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .areas(body);
+    let [files_area, details_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(7)])
+        .areas(right);
+    view.tree_area = left;
+    view.files_area = files_area;
+
+    let highlight = style(theme.selection);
+    let block = |title: &'static str, pane: Option<Pane>| {
+        let block = Block::default().borders(Borders::ALL).title(title);
+        if pane == Some(app.pane) {
+            block.border_style(style(theme.border))
+        } else {
+            block
+        }
+    };
+
+    // The borders take two rows.
+    let rows = |area: Rect| area.height.saturating_sub(2) as usize;
+    view.dir_offset = scroll(view.dir_offset, app.dir_index, rows(left), app.dirs.len());
+    let dirs: Vec<ListItem> = app
+        .dirs
+        .iter()
+        .skip(view.dir_offset)
+        .take(rows(left))
+        .map(|dir| {
+            let marker = if dir.duplicates > 0 { "●" } else { " " };
+            let counts = if dir.matches > 0 { format!(" ({})", dir.matches) } else { String::new() };
+            ListItem::new(Line::from(vec![
+                Span::styled(marker, style(theme.duplicate)),
+                Span::styled(format!(" {}", dir.label()), style(theme.folder)),
+                Span::raw(counts),
+            ]))
+        })
+        .collect();
+    view.dir_state.select(Some(app.dir_index - view.dir_offset));
+    *view.dir_state.offset_mut() = 0;
+    frame.render_stateful_widget(
+        List::new(dirs).block(block("Directories", Some(Pane::Tree))).highlight_style(highlight),
+        left,
+        &mut view.dir_state,
+    );
+
+    let current_files = app.current_files();
+    view.file_offset = scroll(view.file_offset, app.file_index, rows(files_area), current_files.len());
+    let files: Vec<ListItem> = current_files
+        .iter()
+        .skip(view.file_offset)
+        .take(rows(files_area))
+        .map(|file| {
+            let mark = if app.is_marked(file) { "[x]" } else { "[ ]" };
+            ListItem::new(Line::from(vec![
+                Span::styled(mark, style(theme.warning)),
+                Span::styled(format!(" {}", file.name), style(theme.name)),
+            ]))
+        })
+        .collect();
+    let selected_file = app.file_index.checked_sub(view.file_offset).filter(|_| app.pane == Pane::Files);
+    view.file_state.select(selected_file);
+    *view.file_state.offset_mut() = 0;
+    frame.render_stateful_widget(
+        List::new(files).block(block("Files", Some(Pane::Files))).highlight_style(highlight),
+        files_area,
+        &mut view.file_state,
+    );
+
+    let details = match app.selected_file() {
+        Some(file) => vec![
+            Line::from(vec![Span::raw("file name: "), Span::styled(&*file.name, style(theme.name))]),
+            Line::from(vec![Span::raw("directory: "), Span::styled(&*file.folder, style(theme.folder))]),
+            Line::from(vec![
+                Span::raw("creation date: "),
+                Span::styled(file.creation_date.as_str(), style(theme.date)),
+            ]),
+            Line::from(format!("what it is: {}", apps::kind(&file.name).unwrap_or("unknown"))),
+            Line::from(format!(
+                "opens with: {}",
+                apps::application(Path::new(&*file.path())).unwrap_or_else(|| String::from("unknown"))
+            )),
+        ],
+        None => vec![Line::from("No file selected.")],
+    };
+    frame.render_widget(Paragraph::new(details).block(block("Details", None)), details_area);
+    let line = match app.scan.and_then(|progress| progress.describe()) {
+        Some(progress) => format!("{} {}", progress, app.status),
+        None => app.status.clone(),
+    };
+    frame.render_widget(Paragraph::new(line), status);
+
+    if app.help {
+        draw_help(frame, keymap, theme);
+    }
+    if let Some(lines) = &app.preview {
+        draw_preview(frame, app, lines);
+    }
+}
+
+/// Overlay displaying the beginning of the selected file; any key (or click) closes it.
+fn draw_preview(frame: &mut Frame, app: &App, lines: &[String]) {
+    let area = frame.area();
+    let area = centered(area, area.width.saturating_mul(4) / 5, area.height.saturating_mul(4) / 5);
+    let title = app.selected_file().map(|f| f.name.to_string()).unwrap_or_default();
+    let lines: Vec<Line> = lines.iter().map(|l| Line::from(l.as_str())).collect();
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+/// Centered overlay listing the active bindings; any key closes it.
+fn draw_help(frame: &mut Frame, keymap: &Keymap, theme: &Theme) {
+    let mut lines: Vec<Line> = Action::ALL
+        .iter()
+        .map(|action| Line::from(format!("{:<20} {}", keymap.describe(*action), action.description())))
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from("Press any key to close."));
+
+    let area = centered(frame.area(), 60, lines.len() as u16 + 2);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(style(theme.border))
+                .title("Key bindings"),
+        ),
+        area,
+    );
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)]).flex(Flex::Center).areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(area);
+    area
+}
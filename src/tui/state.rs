@@ -0,0 +1,662 @@
+//! Terminal-independent state of the two-pane mode, so navigation and marking can be tested
+//! without drawing anything.
+
+use super::live::{Progress, Update};
+use crate::companions::Companions;
+use crate::{clipboard, containment, paranoid, photos, secrets, trash, verification, File};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// A directory displayed on the left pane.
+#[derive(Debug, PartialEq)]
+pub struct DirNode {
+    pub path: PathBuf,
+    pub depth: usize,
+    /// Amount of found files stored directly on the directory.
+    pub matches: usize,
+    /// Amount of those files that have at least one duplicate somewhere else.
+    pub duplicates: usize,
+}
+
+impl DirNode {
+    /// Text shown on the tree, indented according to the `depth` of the directory.
+    pub fn label(&self) -> String {
+        let name = match (self.depth, self.path.file_name()) {
+            (0, _) | (_, None) => self.path.to_string_lossy(),
+            (_, Some(name)) => name.to_string_lossy(),
+        };
+        format!("{}{}", "  ".repeat(self.depth), name)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Pane {
+    Tree,
+    Files,
+}
+
+/// Everything the two-pane mode needs in order to be drawn.
+pub struct App {
+    pub root: PathBuf,
+    pub files: Vec<File>,
+    pub dirs: Vec<DirNode>,
+    pub pane: Pane,
+    pub dir_index: usize,
+    pub file_index: usize,
+    pub marked: BTreeSet<PathBuf>,
+    pub status: String,
+    pub confirming: bool,
+    pub help: bool,
+    /// Lines of the file being previewed, if any.
+    pub preview: Option<Vec<String>>,
+    pub quit: bool,
+    /// Warn about the marked files that look like they contain secrets before deleting them.
+    pub check_secrets: bool,
+    /// Only delete the `JPEG`s whose `RAW` sibling still exists.
+    pub cull_jpegs: bool,
+    /// Delete the files for good instead of moving them to the trash.
+    pub permanent: bool,
+    /// Compare the files found by content with their copies byte by byte right before deleting them.
+    pub paranoid: bool,
+    /// Let the marks cover every copy of a group of files holding the same bytes (see
+    /// [`verification`](crate::verification)).
+    pub allow_delete_all: bool,
+    /// Rules telling which files are deleted along with the marked ones.
+    pub companions: Companions,
+    /// How far the scan feeding the interface went, when it runs meanwhile (see [`live`](super::live)).
+    pub scan: Option<Progress>,
+    contain_strict: bool,
+}
+
+impl App {
+    /// Builds the tree of directories holding the provided `files` (along with their ancestors up to `root`).
+    pub fn new(root: &str, files: Vec<File>, contain_strict: bool) -> Self {
+        let root = PathBuf::from(root);
+        let mut app = Self {
+            root,
+            files,
+            dirs: vec![],
+            pane: Pane::Tree,
+            dir_index: 0,
+            file_index: 0,
+            marked: BTreeSet::new(),
+            status: String::new(),
+            confirming: false,
+            help: false,
+            preview: None,
+            quit: false,
+            check_secrets: false,
+            cull_jpegs: false,
+            permanent: false,
+            paranoid: false,
+            allow_delete_all: false,
+            companions: Companions::default(),
+            scan: None,
+            contain_strict,
+        };
+        app.rebuild();
+        app
+    }
+
+    fn rebuild(&mut self) {
+        let duplicated = crate::has_duplicate(&self.files);
+
+        let mut nodes: BTreeMap<PathBuf, DirNode> = BTreeMap::new();
+        let root_depth = self.root.components().count();
+        let insert = |path: &Path, nodes: &mut BTreeMap<PathBuf, DirNode>| {
+            nodes.entry(path.to_path_buf()).or_insert_with(|| DirNode {
+                path: path.to_path_buf(),
+                depth: path.components().count().saturating_sub(root_depth),
+                matches: 0,
+                duplicates: 0,
+            });
+        };
+        insert(&self.root, &mut nodes);
+        for (file, duplicated) in self.files.iter().zip(duplicated) {
+            let folder = Path::new(&*file.folder);
+            for ancestor in folder.ancestors().take_while(|a| a.starts_with(&self.root)) {
+                insert(ancestor, &mut nodes);
+            }
+            if let Some(node) = nodes.get_mut(folder) {
+                node.matches += 1;
+                if duplicated {
+                    node.duplicates += 1;
+                }
+            }
+        }
+
+        self.dirs = nodes.into_values().collect();
+        self.dir_index = self.dir_index.min(self.dirs.len().saturating_sub(1));
+        self.file_index = self.file_index.min(self.current_files().len().saturating_sub(1));
+    }
+
+    /// Adds the files found meanwhile, keeping the highlighted directory and file (as well as the marks) where
+    /// they were.
+    pub fn extend(&mut self, files: Vec<File>) {
+        if files.is_empty() {
+            return;
+        }
+        let dir = self.dirs.get(self.dir_index).map(|dir| dir.path.clone());
+        let file = self.selected_file().map(|file| file.path().into_owned());
+        self.files.extend(files);
+        self.rebuild();
+        if let Some(index) = dir.and_then(|dir| self.dirs.iter().position(|node| node.path == dir)) {
+            self.dir_index = index;
+        }
+        if let Some(index) = file.and_then(|file| self.current_files().iter().position(|f| f.is_at(&file))) {
+            self.file_index = index;
+        }
+    }
+
+    /// Applies the `updates` sent by the scan running meanwhile, rebuilding the tree once.
+    pub fn receive(&mut self, updates: impl IntoIterator<Item = Update>) {
+        let mut progress = self.scan.unwrap_or_default();
+        let mut found = vec![];
+        for update in updates {
+            match update {
+                Update::Found(mut files) => {
+                    progress.dirs += 1;
+                    progress.files += files.len();
+                    found.append(&mut files);
+                }
+                Update::Done(skipped) => {
+                    progress.done = true;
+                    self.status = format!("Scan complete: {} file(s) found.", progress.files);
+                    if !skipped.is_empty() {
+                        self.status.push_str(&format!(" {} entry(ies) were left out.", skipped.len()));
+                    }
+                }
+                Update::Failed(error) => {
+                    progress.done = true;
+                    self.status = format!("The scan stopped: {}", error);
+                }
+            }
+        }
+        self.scan = Some(progress);
+        self.extend(found);
+    }
+
+    /// Files stored directly on the selected directory.
+    pub fn current_files(&self) -> Vec<&File> {
+        let dir = match self.dirs.get(self.dir_index) {
+            Some(dir) => &dir.path,
+            None => return vec![],
+        };
+        self.files
+            .iter()
+            .filter(|f| Path::new(&*f.folder) == dir.as_path())
+            .collect()
+    }
+
+    /// File highlighted on the right pane.
+    pub fn selected_file(&self) -> Option<&File> {
+        self.current_files().get(self.file_index).copied()
+    }
+
+    pub fn is_marked(&self, file: &File) -> bool {
+        self.marked.contains(Path::new(&*file.path()))
+    }
+
+    pub fn switch_pane(&mut self) {
+        self.pane = match self.pane {
+            Pane::Tree => Pane::Files,
+            Pane::Files => Pane::Tree,
+        };
+    }
+
+    pub fn move_by(&mut self, offset: isize) {
+        let (index, len) = match self.pane {
+            Pane::Tree => (&mut self.dir_index, self.dirs.len()),
+            Pane::Files => {
+                let len = self.current_files().len();
+                (&mut self.file_index, len)
+            }
+        };
+        if len == 0 {
+            return;
+        }
+        *index = (*index as isize + offset).clamp(0, len as isize - 1) as usize;
+        if self.pane == Pane::Tree {
+            self.file_index = 0;
+        }
+    }
+
+    /// Highlights the entry found at `index` of the provided `pane` (E.G: after a click), focusing the pane.
+    pub fn select(&mut self, pane: Pane, index: usize) {
+        if pane == Pane::Tree && index != self.dir_index {
+            self.file_index = 0;
+        }
+        self.pane = pane;
+        let len = match pane {
+            Pane::Tree => self.dirs.len(),
+            Pane::Files => self.current_files().len(),
+        };
+        if index < len {
+            match pane {
+                Pane::Tree => self.dir_index = index,
+                Pane::Files => self.file_index = index,
+            }
+        }
+    }
+
+    /// Loads the first lines of the selected file, so they can be displayed on top of the panes.
+    pub fn open_preview(&mut self) {
+        let file = match self.selected_file() {
+            Some(file) => file,
+            None => return,
+        };
+        self.preview = Some(preview_lines(Path::new(&*file.path())));
+    }
+
+    /// Paths the marking actions apply to: the highlighted file, or every file of the highlighted
+    /// directory on the tree pane.
+    fn targets(&self) -> Vec<PathBuf> {
+        match self.pane {
+            Pane::Tree => self.current_files().iter().map(|f| PathBuf::from(&*f.path())).collect(),
+            Pane::Files => self.selected_file().map(|f| PathBuf::from(&*f.path())).into_iter().collect(),
+        }
+    }
+
+    /// Marks the targets for deletion, or unmarks them if all of them were already marked.
+    pub fn toggle_mark(&mut self) {
+        let paths = self.targets();
+        let all_marked = paths.iter().all(|p| self.marked.contains(p));
+        for path in paths {
+            if all_marked {
+                self.marked.remove(&path);
+            } else {
+                self.marked.insert(path);
+            }
+        }
+    }
+
+    /// Unmarks the targets, so they are kept.
+    pub fn keep(&mut self) {
+        for path in self.targets() {
+            self.marked.remove(&path);
+        }
+    }
+
+    /// Paths the copy action applies to: the marked files, or the highlighted one when nothing is marked.
+    pub fn paths_to_copy(&self) -> Vec<String> {
+        if self.marked.is_empty() {
+            return self.selected_file().map(|f| f.path().into_owned()).into_iter().collect();
+        }
+        self.marked.iter().map(|p| p.to_string_lossy().into_owned()).collect()
+    }
+
+    /// Copies the `paths_to_copy` to the clipboard.
+    pub fn copy_paths(&mut self) {
+        let paths = self.paths_to_copy();
+        self.status = match clipboard::copy_paths(&paths) {
+            Ok(_) => format!("{} path(s) copied to the clipboard.", paths.len()),
+            Err(err) => err.to_string(),
+        };
+    }
+
+    /// Asks for confirmation before deleting the marked files.
+    pub fn request_apply(&mut self) {
+        if self.marked.is_empty() {
+            self.status = String::from("Nothing marked yet.");
+            return;
+        }
+        let marked = self.marked.iter().filter_map(|path| path.to_str());
+        if let Err(err) = verification::Planned::new(&self.files, marked, self.allow_delete_all) {
+            self.status = err.to_string();
+            return;
+        }
+        self.confirming = true;
+        let followers: usize = self.marked.iter().map(|path| self.companions.of(path).len()).sum();
+        self.status = match followers {
+            0 => format!("Delete {} marked file(s)? (y/n)", self.marked.len()),
+            _ => format!("Delete {} marked file(s) and {} companion file(s)? (y/n)", self.marked.len(), followers),
+        };
+        let heuristic = self
+            .files
+            .iter()
+            .filter(|f| f.heuristic_match() && self.marked.contains(Path::new(&*f.path())))
+            .count();
+        if heuristic > 0 {
+            self.status = format!(
+                "{} of the {} marked file(s) were matched heuristically, compare them first. Delete anyway? (y/n)",
+                heuristic,
+                self.marked.len()
+            );
+        }
+        if !self.check_secrets {
+            return;
+        }
+        let flagged: Vec<(&PathBuf, Vec<secrets::Finding>)> = self
+            .marked
+            .iter()
+            .map(|path| (path, secrets::scan(path).unwrap_or_default()))
+            .filter(|(_, findings)| !findings.is_empty())
+            .collect();
+        if let Some((path, findings)) = flagged.first() {
+            let kinds: Vec<String> = findings.iter().map(|f| f.describe()).collect();
+            self.status = format!(
+                "{} of the {} marked file(s) may contain secrets ({}: {}). Delete anyway? (y/n)",
+                flagged.len(),
+                self.marked.len(),
+                path.display(),
+                kinds.join(", ")
+            );
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.confirming = false;
+        self.status = String::from("Cancelled.");
+    }
+
+    /// Deletes every marked file, keeping the ones that couldn't be removed.
+    pub fn apply(&mut self) {
+        self.confirming = false;
+        let mut deleted = 0;
+        let mut followers = vec![];
+        let mut failures = vec![];
+        let marked = std::mem::take(&mut self.marked);
+        // The copies that are kept must still hold the same bytes.
+        let unverified: HashMap<String, String> = match self.paranoid {
+            true => {
+                let (doomed, kept): (Vec<&File>, Vec<&File>) =
+                    self.files.iter().partition(|file| marked.contains(Path::new(&*file.path())));
+                let reason = |file: &File| paranoid::verify(file, kept.iter().copied()).err();
+                doomed.into_iter().filter_map(|file| Some((file.path().into_owned(), reason(file)?))).collect()
+            }
+            false => HashMap::new(),
+        };
+        self.files.retain(|file| {
+            if !marked.contains(Path::new(&*file.path())) {
+                return true;
+            }
+            let companions = self.companions.of(Path::new(&*file.path()));
+            let result = containment::verify(&self.root, Path::new(&*file.path()), self.contain_strict)
+                .and_then(|_| match unverified.get(&*file.path()) {
+                    Some(reason) => Err(reason.clone().into()),
+                    None => Ok(()),
+                })
+                .and_then(|_| match self.cull_jpegs && photos::raw_sibling(Path::new(&*file.path())).is_none() {
+                    true => Err(format!("the RAW sibling of `{}` is gone", file.path()).into()),
+                    false if self.permanent => file.delete().map_err(|e| e.into()),
+                    false => file.trash().map_err(|e| e.into()),
+                });
+            match result {
+                Ok(_) => {
+                    deleted += 1;
+                    followers.extend(companions);
+                    false
+                }
+                Err(err) => {
+                    failures.push(err.to_string());
+                    true
+                }
+            }
+        });
+        for follower in followers {
+            let result = containment::verify(&self.root, &follower, self.contain_strict)
+                .and_then(|_| match self.permanent {
+                    true => std::fs::remove_file(&follower).map_err(|e| e.into()),
+                    false => trash::trash(&follower).map_err(|e| e.into()),
+                });
+            match result {
+                Ok(_) => deleted += 1,
+                Err(err) => failures.push(err.to_string()),
+            }
+        }
+        let done = match self.permanent {
+            true => "deleted",
+            false => "moved to the trash",
+        };
+        self.status = match failures.first() {
+            None => format!("{} file(s) {}.", deleted, done),
+            Some(err) => format!("{} file(s) {}, {} failed: {}", deleted, done, failures.len(), err),
+        };
+        self.rebuild();
+    }
+}
+
+const PREVIEW_BYTES: u64 = 4096;
+
+/// Reads the beginning of the file located at `path`, replacing it with a short notice for binary files.
+fn preview_lines(path: &Path) -> Vec<String> {
+    let mut content = vec![];
+    let read = std::fs::File::open(path).and_then(|f| f.take(PREVIEW_BYTES).read_to_end(&mut content));
+    if let Err(err) = read {
+        return vec![format!("Couldn't read the file: {}", err)];
+    }
+    if content.contains(&0) {
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(content.len() as u64);
+        return vec![format!("Binary file ({} bytes).", size)];
+    }
+    String::from_utf8_lossy(&content)
+        .lines()
+        .map(|line| line.chars().map(|c| if c.is_control() { '�' } else { c }).collect())
+        .collect()
+}
+
+/// First row to show on a list of `len` rows where `rows` of them fit, moving `offset` just enough to keep
+/// `selected` visible.
+pub fn scroll(offset: usize, selected: usize, rows: usize, len: usize) -> usize {
+    if rows == 0 {
+        return selected.min(len.saturating_sub(1));
+    }
+    let offset = offset.min(len.saturating_sub(rows));
+    if selected < offset {
+        selected
+    } else if selected >= offset + rows {
+        selected + 1 - rows
+    } else {
+        offset
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn scroll_keeps_selection_visible() {
+        // Act and Assert
+        assert_eq!(scroll(0, 3, 10, 500_000), 0);
+        assert_eq!(scroll(0, 12, 10, 500_000), 3);
+        assert_eq!(scroll(40, 12, 10, 500_000), 12);
+        assert_eq!(scroll(40, 2, 10, 5), 0);
+        assert_eq!(scroll(0, 7, 0, 500_000), 7);
+    }
+
+    fn file(folder: &str, name: &str) -> File {
+        let path = Path::new(folder).join(name);
+        File::new(name, folder, "2022-07-23 12:33:01", path.to_str().unwrap())
+    }
+
+    #[test]
+    fn tree_includes_ancestors() {
+        // Arrange
+        let files = vec![file("./root/a/b", "x.txt"), file("./root", "x.txt"), file("./root/c", "y.txt")];
+
+        // Act
+        let app = App::new("./root", files, false);
+
+        // Assert
+        let labels: Vec<String> = app.dirs.iter().map(|d| d.label()).collect();
+        assert_eq!(labels, vec!["./root", "  a", "    b", "  c"]);
+        assert_eq!((app.dirs[0].matches, app.dirs[0].duplicates), (1, 1));
+        assert_eq!((app.dirs[1].matches, app.dirs[1].duplicates), (0, 0));
+        assert_eq!((app.dirs[3].matches, app.dirs[3].duplicates), (1, 0));
+    }
+
+    #[test]
+    fn files_found_meanwhile_keep_the_selection() {
+        // Arrange
+        let mut app = App::new("./root", vec![file("./root/b", "x.txt"), file("./root/b", "y.txt")], false);
+        app.receive(vec![]);
+        app.move_by(1);
+        app.switch_pane();
+        app.move_by(1);
+        app.toggle_mark();
+
+        // Act
+        app.receive(vec![Update::Found(vec![file("./root/a", "x.txt"), file("./root/b", "a.txt")])]);
+        let scanning = app.scan;
+        app.receive(vec![Update::Found(vec![]), Update::Done(vec![])]);
+
+        // Assert
+        assert_eq!(scanning, Some(Progress { dirs: 1, files: 2, done: false }));
+        assert_eq!(app.scan, Some(Progress { dirs: 2, files: 2, done: true }));
+        assert_eq!(app.dirs[app.dir_index].path, PathBuf::from("./root/b"));
+        assert_eq!(app.selected_file().map(|f| &*f.name), Some("y.txt"));
+        assert!(app.marked.contains(Path::new("./root/b/y.txt")));
+        assert_eq!(app.status, "Scan complete: 2 file(s) found.");
+    }
+
+    #[test]
+    fn navigation_and_marking() {
+        // Arrange
+        let files = vec![file("./root", "x.txt"), file("./root/a", "x.txt"), file("./root/a", "y.txt")];
+        let mut app = App::new("./root", files, false);
+
+        // Act
+        app.move_by(5);
+        app.toggle_mark();
+        app.switch_pane();
+        app.move_by(1);
+        app.toggle_mark();
+
+        // Assert
+        assert_eq!(app.dir_index, 1);
+        assert_eq!(&*app.selected_file().unwrap().name, "y.txt");
+        assert_eq!(app.marked.len(), 1);
+        assert!(app.marked.contains(Path::new("./root/a/x.txt")));
+    }
+
+    #[test]
+    fn paths_to_copy() {
+        // Arrange
+        let files = vec![file("./root", "x.txt"), file("./root", "y.txt")];
+        let mut app = App::new("./root", files, false);
+        app.switch_pane();
+        app.move_by(1);
+
+        // Act
+        let selected = app.paths_to_copy();
+        app.switch_pane();
+        app.toggle_mark();
+        let marked = app.paths_to_copy();
+
+        // Assert
+        assert_eq!(selected, vec!["./root/y.txt"]);
+        assert_eq!(marked, vec!["./root/x.txt", "./root/y.txt"]);
+    }
+
+    #[test]
+    fn keep_unmarks() {
+        // Arrange
+        let files = vec![file("./root", "x.txt"), file("./root", "y.txt")];
+        let mut app = App::new("./root", files, false);
+        app.toggle_mark();
+        app.switch_pane();
+
+        // Act
+        app.keep();
+
+        // Assert
+        assert_eq!(app.marked.len(), 1);
+        assert!(app.marked.contains(Path::new("./root/y.txt")));
+    }
+
+    #[test]
+    fn select_and_preview() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("preview");
+        let root_str = root.to_str().unwrap();
+        std::fs::write(root.join("a.txt"), b"first\nsecond")?;
+        std::fs::write(root.join("b.bin"), b"\0\x01\x02")?;
+        let files = vec![file(root_str, "a.txt"), file(root_str, "b.bin")];
+        let mut app = App::new(root_str, files, false);
+
+        // Act
+        app.select(Pane::Files, 0);
+        app.open_preview();
+        let text = app.preview.take();
+        app.select(Pane::Files, 1);
+        app.open_preview();
+        let binary = app.preview.take();
+        app.select(Pane::Files, 7);
+
+        // Assert
+        assert_eq!(app.pane, Pane::Files);
+        assert_eq!(app.file_index, 1);
+        assert_eq!(text, Some(vec!["first".to_string(), "second".to_string()]));
+        assert_eq!(binary, Some(vec!["Binary file (3 bytes).".to_string()]));
+        Ok(())
+    }
+
+    #[test]
+    fn apply_deletes_marked_files() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("tui");
+        let root_str = root.to_str().unwrap();
+        std::fs::write(root.join("x.txt"), b"")?;
+        let mut app = App::new(root_str, vec![file(root_str, "x.txt")], true);
+        app.permanent = true;
+        app.switch_pane();
+        app.toggle_mark();
+
+        // Act
+        app.request_apply();
+        app.apply();
+
+        // Assert
+        assert!(app.files.is_empty());
+        assert!(!root.join("x.txt").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn request_apply_flags_secrets() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("tui-secrets");
+        let root_str = root.to_str().unwrap();
+        std::fs::write(root.join("x.txt"), b"password = hunter2\n")?;
+        let mut app = App::new(root_str, vec![file(root_str, "x.txt")], false);
+        app.check_secrets = true;
+        app.switch_pane();
+        app.toggle_mark();
+
+        // Act
+        app.request_apply();
+
+        // Assert
+        assert!(app.confirming);
+        assert!(app.status.starts_with("1 of the 1 marked file(s) may contain secrets"));
+        assert!(app.status.contains("password assignment (line 1)"));
+        Ok(())
+    }
+
+    #[test]
+    fn apply_deletes_companions() -> Result<(), std::io::Error> {
+        // Arrange
+        let root = Scratch::new("tui-companions");
+        let root_str = root.to_str().unwrap();
+        std::fs::write(root.join("x.mkv"), b"")?;
+        std::fs::write(root.join("x.srt"), b"")?;
+        let mut app = App::new(root_str, vec![file(root_str, "x.mkv")], false);
+        app.permanent = true;
+        app.switch_pane();
+        app.toggle_mark();
+
+        // Act
+        app.request_apply();
+        let status = app.status.clone();
+        app.apply();
+
+        // Assert
+        assert_eq!(status, "Delete 1 marked file(s) and 1 companion file(s)? (y/n)");
+        assert_eq!(app.status, "2 file(s) deleted.");
+        assert!(!root.join("x.srt").exists());
+        Ok(())
+    }
+}
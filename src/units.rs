@@ -0,0 +1,271 @@
+//! Human friendly units used by the listings, reports and queries, as well as by the command line options and
+//! the configuration files. [`Size`] and [`Span`] hold the parsed values, and are (de)serialized as the text they
+//! were parsed from (E.G: `"1.5GB"` or `"90d"`).
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Returned when a size or a duration can't be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitError(pub String);
+
+impl Error for UnitError {}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid value `{}`.", self.0)
+    }
+}
+
+/// Splits `value` into its (possibly fractional) number and its unit suffix.
+fn split_number(value: &str) -> Result<(f64, &str), UnitError> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    match number.parse::<f64>() {
+        Ok(number) => Ok((number, unit.trim())),
+        Err(_) => Err(UnitError(value.to_string())),
+    }
+}
+
+/// Parses sizes such as `512`, `10K`, `1.5GB` or `3 MiB`. Every unit is a power of `1024`.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::units::parse_size;
+/// assert_eq!(parse_size("1.5GB"), Ok(1536 * 1024 * 1024));
+/// assert!(parse_size("10 parsecs").is_err());
+/// ```
+pub fn parse_size(value: &str) -> Result<u64, UnitError> {
+    let (number, unit) = split_number(value)?;
+    let exponent = match unit.to_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 1,
+        "m" | "mb" | "mib" => 2,
+        "g" | "gb" | "gib" => 3,
+        "t" | "tb" | "tib" => 4,
+        "p" | "pb" | "pib" => 5,
+        _ => return Err(UnitError(value.to_string())),
+    };
+    Ok((number * 1024f64.powi(exponent)).round() as u64)
+}
+
+/// Parses durations such as `90s`, `30m`, `12h`, `30d`, `2w` or `1y` (a year being 365 days).
+/// ## Examples
+/// ```
+/// # use dir_cleaner::units::parse_duration;
+/// # use std::time::Duration;
+/// assert_eq!(parse_duration("12h"), Ok(Duration::from_secs(12 * 60 * 60)));
+/// assert!(parse_duration("12").is_err());
+/// ```
+pub fn parse_duration(value: &str) -> Result<Duration, UnitError> {
+    let (number, unit) = split_number(value)?;
+    let seconds = match unit.to_lowercase().as_str() {
+        "s" => 1,
+        "m" | "min" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        "y" => 365 * 24 * 60 * 60,
+        _ => return Err(UnitError(value.to_string())),
+    };
+    Ok(Duration::from_secs_f64(number * seconds as f64))
+}
+
+/// Parses percentages such as `80` or `80%`.
+pub fn parse_percent(value: &str) -> Result<u8, UnitError> {
+    match value.trim().trim_end_matches('%').parse::<u8>() {
+        Ok(percent) if percent <= 100 => Ok(percent),
+        _ => Err(UnitError(value.to_string())),
+    }
+}
+
+const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+/// Formats `bytes` using binary units, E.G: `1536` becomes `1.5 KiB`.
+pub fn format_size(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Formats `duration` roughly, keeping its two most significant units, E.G: `6h 12m`, `3m 05s` or `12s`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds % 60)
+    } else if seconds > 0 {
+        format!("{}s", seconds)
+    } else {
+        String::from("less than a second")
+    }
+}
+
+/// A size in bytes.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::units::Size;
+/// let size: Size = "1.5GB".parse().unwrap();
+/// assert_eq!(size, Size(1536 * 1024 * 1024));
+/// assert_eq!(size.to_string(), "1.5 GiB");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Size(pub u64);
+
+impl FromStr for Size {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_size(s).map(Size)
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", format_size(self.0))
+    }
+}
+
+impl TryFrom<String> for Size {
+    type Error = UnitError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// The size in the biggest unit holding it exactly, so it's read back as is.
+impl From<Size> for String {
+    fn from(size: Size) -> Self {
+        let exact = |exponent: &u32| size.0 > 0 && size.0.is_multiple_of(1024u64.pow(*exponent));
+        let exponent = (1..UNITS.len() as u32).rev().find(exact);
+        match exponent {
+            Some(exponent) => format!("{}{}", size.0 / 1024u64.pow(exponent), UNITS[exponent as usize]),
+            None => size.0.to_string(),
+        }
+    }
+}
+
+/// A span of time.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::units::Span;
+/// # use std::time::Duration;
+/// let span: Span = "90d".parse().unwrap();
+/// assert_eq!(span, Span(Duration::from_secs(90 * 24 * 60 * 60)));
+/// assert_eq!(span.to_string(), "90d 0h");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Span(pub Duration);
+
+impl FromStr for Span {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).map(Span)
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", format_duration(self.0))
+    }
+}
+
+impl TryFrom<String> for Span {
+    type Error = UnitError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// The span in the biggest unit holding it exactly (whole seconds), so it's read back as is.
+impl From<Span> for String {
+    fn from(span: Span) -> Self {
+        let seconds = span.0.as_secs();
+        let units = [("w", 7 * 24 * 60 * 60), ("d", 24 * 60 * 60), ("h", 60 * 60), ("m", 60)];
+        match units.iter().find(|(_, length)| seconds > 0 && seconds.is_multiple_of(*length)) {
+            Some((unit, length)) => format!("{}{}", seconds / length, unit),
+            None => format!("{}s", seconds),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_sizes() {
+        // Act and Assert
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("10K"), Ok(10 * 1024));
+        assert_eq!(parse_size("1.5GB"), Ok(1024 * 1024 * 1536));
+        assert_eq!(parse_size("3 MiB"), Ok(3 * 1024 * 1024));
+        assert!(parse_size("MB").is_err());
+        assert!(parse_size("10 parsecs").is_err());
+    }
+
+    #[test]
+    fn parse_durations() {
+        // Act and Assert
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("12h"), Ok(Duration::from_secs(12 * 3600)));
+        assert_eq!(parse_duration("30d"), Ok(Duration::from_secs(30 * 86400)));
+        assert!(parse_duration("30").is_err());
+        assert!(parse_duration("d").is_err());
+    }
+
+    #[test]
+    fn format_sizes() {
+        // Act and Assert
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+
+    #[test]
+    fn format_durations() {
+        // Act and Assert
+        assert_eq!(format_duration(Duration::from_millis(300)), "less than a second");
+        assert_eq!(format_duration(Duration::from_secs(12)), "12s");
+        assert_eq!(format_duration(Duration::from_secs(185)), "3m 05s");
+        assert_eq!(format_duration(Duration::from_secs(6 * 3600 + 12 * 60)), "6h 12m");
+        assert_eq!(format_duration(Duration::from_secs(2 * 86400 + 3600)), "2d 1h");
+    }
+
+    #[test]
+    fn typed_units_survive_a_round_trip() -> Result<(), serde_json::Error> {
+        // Act and Assert
+        assert_eq!(serde_json::to_string(&Size(10 * 1024 * 1024 * 1024))?, "\"10GiB\"");
+        assert_eq!(serde_json::to_string(&Size(1500))?, "\"1500\"");
+        assert_eq!(serde_json::from_str::<Size>("\"1.5 KiB\"")?, Size(1536));
+        assert_eq!(serde_json::to_string(&Span(Duration::from_secs(90 * 86400)))?, "\"90d\"");
+        assert_eq!(serde_json::to_string(&Span(Duration::from_secs(90)))?, "\"90s\"");
+        assert_eq!(serde_json::from_str::<Span>("\"2w\"")?, Span(Duration::from_secs(14 * 86400)));
+        assert!(serde_json::from_str::<Span>("\"soon\"").is_err());
+        Ok(())
+    }
+}
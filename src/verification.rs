@@ -0,0 +1,257 @@
+//! Checks of the copies an automated cleanup relied on (`--verify-sample <count>`, after `--delete-all` or the
+//! `auto` command): a few of the files kept in the groups that lost a copy are picked at random and read again,
+//! so the unattended runs tell whether what they left behind is still there and intact.
+//!
+//! The plans are checked before anything is deleted as well: unless `--allow-delete-all` is passed, a plan can't
+//! delete every copy of a group of files holding the same bytes (see [`Planned::new`]), whichever interface
+//! built it, nor can several rules target the same path (see [`conflicts`]).
+
+use crate::companions::Companions;
+use crate::groups;
+use crate::hash::{self, Algorithm};
+use crate::sampling;
+use crate::File;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::Path;
+
+/// Outcome of the checks.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Verification {
+    /// Files read again.
+    pub checked: usize,
+    /// What's wrong with the checked files.
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.problems.is_empty() {
+            return write!(f, "Verified {} kept file(s) picked at random: all of them are intact.", self.checked);
+        }
+        let (checked, problems) = (self.checked, self.problems.len());
+        write!(f, "Verified {} kept file(s) picked at random, {} problem(s) found:", checked, problems)?;
+        for problem in &self.problems {
+            write!(f, "\n\t{}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+/// [`File::group_key`] outliving the file.
+type Key = (String, Option<String>);
+
+fn key(file: &File) -> Key {
+    let (name, digest) = file.group_key();
+    (name.to_string(), digest.map(str::to_string))
+}
+
+/// Returned when a plan deletes every copy of a group of files holding the same bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastCopyError {
+    /// Copies of the group.
+    pub paths: Vec<String>,
+}
+
+impl Error for LastCopyError {}
+
+impl fmt::Display for LastCopyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let paths: Vec<String> = self.paths.iter().map(|path| format!("`{}`", path)).collect();
+        write!(
+            f,
+            "The plan deletes every copy of the same content ({}), nothing was deleted. Keep one of them, or pass \
+            `--allow-delete-all` in order to delete all of them.",
+            paths.join(", ")
+        )
+    }
+}
+
+/// Returned when some files planned for deletion were kept (E.G: an unattended prompt gave the safe answer).
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeptError {
+    /// Files left in place.
+    pub paths: Vec<String>,
+}
+
+impl Error for KeptError {}
+
+impl fmt::Display for KeptError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let paths: Vec<String> = self.paths.iter().map(|path| format!("`{}`", path)).collect();
+        write!(f, "{} file(s) planned for deletion were kept: {}.", paths.len(), paths.join(", "))
+    }
+}
+
+/// The groups of the files planned for deletion, recorded before the cleanup runs.
+pub struct Planned {
+    files: Vec<(String, Key)>,
+}
+
+impl Planned {
+    /// Records the groups of the `files` located at `paths`, once checked that a copy of every group of the files
+    /// compared by content is left behind (unless `delete_all` of them is allowed).
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::File;
+    /// # use dir_cleaner::verification::Planned;
+    /// let mut files = vec![
+    ///     File::new("a.txt", "./test", "2022-07-23 12:33:01", "./test/a.txt"),
+    ///     File::new("b.txt", "./test", "2022-07-23 12:33:01", "./test/b.txt"),
+    /// ];
+    /// files.iter_mut().for_each(|file| file.digest = Some(String::from("=sha256:ba78")));
+    /// assert!(Planned::new(&files, ["./test/a.txt"], false).is_ok());
+    /// assert!(Planned::new(&files, ["./test/a.txt", "./test/b.txt"], false).is_err());
+    /// assert!(Planned::new(&files, ["./test/a.txt", "./test/b.txt"], true).is_ok());
+    /// ```
+    pub fn new<'a>(
+        files: &[File],
+        paths: impl IntoIterator<Item = &'a str>,
+        delete_all: bool,
+    ) -> Result<Self, LastCopyError> {
+        let paths: HashSet<&str> = paths.into_iter().collect();
+        // The path of every file is built once, whatever the amount of groups.
+        let planned: Vec<bool> = files.iter().map(|file| paths.contains(&*file.path())).collect();
+        let by_content = groups::all(files).into_iter().filter(|group| group.key().0.is_empty());
+        for group in by_content.filter(|_| !delete_all) {
+            if group.indexes.iter().all(|i| planned[*i]) {
+                return Err(LastCopyError { paths: group.files().map(|file| file.path().into_owned()).collect() });
+            }
+        }
+        let files = files.iter().zip(planned).filter(|(_, planned)| *planned);
+        Ok(Planned { files: files.map(|(file, _)| (file.path().into_owned(), key(file))).collect() })
+    }
+
+    /// Files of `remaining` sharing their group with a planned file that's gone from it: the copies the deletions
+    /// relied on.
+    pub fn relied_on<'a>(&self, remaining: &'a [File]) -> Vec<&'a File> {
+        let paths: HashSet<String> = remaining.iter().map(|file| file.path().into_owned()).collect();
+        let lost: HashSet<&Key> =
+            self.files.iter().filter(|(path, _)| !paths.contains(path)).map(|(_, key)| key).collect();
+        remaining.iter().filter(|file| lost.contains(&key(file))).collect()
+    }
+}
+
+/// Actions of different rules targeting the same path: the ones deleting it, and the ones deleting what it's
+/// stored on (a directory) or the file it's a companion of, which would delete it as well.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Conflict {
+    pub path: String,
+    /// Indexes of the conflicting actions among the planned ones, in the order they were planned.
+    pub actions: Vec<usize>,
+}
+
+/// Conflicts between the planned `actions` (the path deleted by every rule), which have to be resolved before
+/// applying the plan, ordered by path.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::companions::Companions;
+/// # use dir_cleaner::verification;
+/// let actions = [
+///     (String::from("./a/movie.mkv"), "highest-bitrate"),
+///     (String::from("./a/movie.srt"), "earlier decisions"),
+///     (String::from("./b/notes.txt"), "highest-bitrate"),
+/// ];
+/// let conflicts = verification::conflicts(&actions, &Companions::default());
+/// assert_eq!(conflicts.len(), 1);
+/// assert_eq!((conflicts[0].path.as_str(), conflicts[0].actions.clone()), ("./a/movie.srt", vec![0, 1]));
+/// ```
+pub fn conflicts(actions: &[(String, &'static str)], companions: &Companions) -> Vec<Conflict> {
+    let mut conflicting: BTreeMap<&str, BTreeSet<usize>> = BTreeMap::new();
+    for (i, (path, rule)) in actions.iter().enumerate() {
+        let target = Path::new(path);
+        let covering = actions.iter().enumerate().filter(|(j, (other, other_rule))| {
+            let other = Path::new(other);
+            *j != i && other_rule != rule && (target.starts_with(other) || companions.is_companion_of(target, other))
+        });
+        for (j, _) in covering {
+            conflicting.entry(path).or_default().extend([i, j]);
+        }
+    }
+    conflicting
+        .into_iter()
+        .map(|(path, actions)| Conflict { path: path.to_string(), actions: actions.into_iter().collect() })
+        .collect()
+}
+
+/// Checks that `file` is still there, with its size and (when it was compared by content) its digest.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::File;
+/// # use dir_cleaner::verification;
+/// let file = File::new("gone.txt", "./missing", "2022-07-23 12:33:01", "./missing/gone.txt");
+/// assert!(verification::check(&file).is_err());
+/// ```
+pub fn check(file: &File) -> Result<(), String> {
+    let path = file.path();
+    let path = Path::new(&*path);
+    let metadata = fs::metadata(path).map_err(|e| format!("`{}` can't be read anymore: {}", file.path(), e))?;
+    if metadata.len() != file.size {
+        return Err(format!("`{}` holds {} byte(s) instead of {}", file.path(), metadata.len(), file.size));
+    }
+    let (digest, algorithm) = match file.digest.as_deref().and_then(|d| Algorithm::of(d).map(|a| (d, a))) {
+        Some(expected) => expected,
+        None => return Ok(()),
+    };
+    let found = hash::file_digest(path, algorithm).map_err(|e| format!("`{}` can't be read: {}", file.path(), e))?;
+    match format!("={}:{}", algorithm, found) == digest {
+        true => Ok(()),
+        false => Err(format!("`{}` doesn't hold the content it was compared with anymore", file.path())),
+    }
+}
+
+/// Checks `count` files of `kept` picked at random (using `seed`).
+pub fn verify(kept: &[&File], count: usize, seed: u64) -> Verification {
+    let picked = sampling::pick(kept.len(), count, seed);
+    let problems = picked.iter().filter_map(|i| check(kept[*i]).err()).collect();
+    Verification { checked: picked.len(), problems }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    #[test]
+    fn kept_copies_are_checked() -> Result<(), Box<dyn Error>> {
+        // Arrange
+        let root = Scratch::new("verification");
+        let folder = root.to_string_lossy().to_string();
+        let mut files: Vec<File> = ["kept.txt", "changed.txt", "deleted.txt"]
+            .iter()
+            .map(|name| File::new(name, &folder, "2022-07-23 12:33:01", &root.join(name).to_string_lossy()))
+            .collect();
+        for file in &mut files {
+            fs::write(&*file.path(), "same")?;
+            file.size = 4;
+            file.digest = Some(format!("=sha256:{}", hash::sha256(Path::new(&*file.path()))?));
+        }
+        let other = File::new("other.txt", &folder, "2022-07-23 12:33:01", &root.join("other.txt").to_string_lossy());
+        files.push(other);
+        fs::write(&*files[1].path(), "diff")?;
+        fs::remove_file(&*files[2].path())?;
+        let planned = Planned::new(&files, [&*files[2].path()], false)?;
+        files.remove(2);
+
+        // Act
+        let kept = planned.relied_on(&files);
+        let verification = verify(&kept, 10, 42);
+
+        // Assert
+        assert_eq!(kept.len(), 2);
+        assert_eq!(verification.checked, 2);
+        assert_eq!(verification.problems.len(), 1);
+        assert!(verification.problems[0].contains("changed.txt"));
+        assert!(check(&files[0]).is_ok());
+        let paths: Vec<String> = files.iter().take(2).map(|file| file.path().into_owned()).collect();
+        assert!(Planned::new(&files, paths.iter().map(String::as_str), false).is_err());
+        let path = files[0].path().into_owned();
+        let twice = [(path.clone(), "highest-bitrate"), (path.clone(), "earlier decisions")];
+        let conflict = Conflict { path, actions: vec![0, 1] };
+        assert_eq!(conflicts(&twice, &Companions::default()), vec![conflict]);
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! Filesystems the scans can run on. The traversal only lists and inspects the entries through the [`Vfs`]
+//! trait, so other backends (archives, remote storage...) plug into the same scanner (see
+//! [`Scanner::vfs`](crate::scanner::Scanner::vfs)). [`Local`] is the filesystem of the machine, and [`Memory`]
+//! a fake one meant for the tests.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Read};
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// What an entry is.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    File,
+    Dir,
+    Symlink,
+    /// Sockets, devices, pipes...
+    Other,
+}
+
+impl From<fs::FileType> for Kind {
+    fn from(file_type: fs::FileType) -> Self {
+        match file_type {
+            _ if file_type.is_symlink() => Kind::Symlink,
+            _ if file_type.is_dir() => Kind::Dir,
+            _ if file_type.is_file() => Kind::File,
+            _ => Kind::Other,
+        }
+    }
+}
+
+/// What the scans need to know about an entry.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Meta {
+    pub kind: Kind,
+    pub len: u64,
+    /// When the entry was created, or modified when the filesystem doesn't keep track of the creation.
+    pub created: Option<SystemTime>,
+    /// When the entry was last modified (for a directory, when an entry was last added to it or removed from it).
+    pub modified: Option<SystemTime>,
+    /// Device and inode of the entry, when the filesystem provides them.
+    pub id: Option<(u64, u64)>,
+}
+
+impl From<fs::Metadata> for Meta {
+    fn from(metadata: fs::Metadata) -> Self {
+        Meta {
+            kind: metadata.file_type().into(),
+            len: metadata.len(),
+            created: metadata.created().or_else(|_| metadata.modified()).ok(),
+            modified: metadata.modified().ok(),
+            #[cfg(unix)]
+            id: Some((metadata.dev(), metadata.ino())),
+            #[cfg(not(unix))]
+            id: None,
+        }
+    }
+}
+
+/// An entry of a directory.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DirEntry {
+    pub name: OsString,
+    pub path: PathBuf,
+    /// What the entry is (links aren't followed).
+    pub kind: Kind,
+    /// The metadata of the entry, when the listing provides it for free.
+    pub meta: Option<Meta>,
+}
+
+/// A filesystem the scans can run on.
+pub trait Vfs: Sync {
+    /// Entries of the directory located at `dir`, in no particular order. The entries that can't be read are
+    /// left out.
+    fn read_dir(&self, dir: &Path) -> Result<Box<dyn Iterator<Item = DirEntry> + '_>, io::Error>;
+
+    /// Metadata of the entry located at `path`, without following the links.
+    fn symlink_metadata(&self, path: &Path) -> Result<Meta, io::Error>;
+
+    /// Metadata of the entry located at `path`, following the links.
+    fn metadata(&self, path: &Path) -> Result<Meta, io::Error>;
+
+    /// Absolute path of the entry located at `path`, without links.
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, io::Error>;
+
+    /// Reads the content of the file located at `path`.
+    fn open(&self, path: &Path) -> Result<Box<dyn Read + '_>, io::Error>;
+}
+
+/// The filesystem of the machine.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Local;
+
+impl Vfs for Local {
+    fn read_dir(&self, dir: &Path) -> Result<Box<dyn Iterator<Item = DirEntry> + '_>, io::Error> {
+        let entries = fs::read_dir(dir)?.flatten().filter_map(|entry| {
+            let kind = entry.file_type().ok()?.into();
+            // Listing a directory already provides the metadata of its entries on Windows.
+            let meta = match cfg!(windows) {
+                true => entry.metadata().ok().map(Meta::from),
+                false => None,
+            };
+            Some(DirEntry { name: entry.file_name(), path: entry.path(), kind, meta })
+        });
+        Ok(Box::new(entries))
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> Result<Meta, io::Error> {
+        fs::symlink_metadata(path).map(Meta::from)
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Meta, io::Error> {
+        fs::metadata(path).map(Meta::from)
+    }
+
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, io::Error> {
+        fs::canonicalize(path)
+    }
+
+    fn open(&self, path: &Path) -> Result<Box<dyn Read + '_>, io::Error> {
+        Ok(Box::new(fs::File::open(path)?))
+    }
+}
+
+/// A filesystem held in memory, meant for the tests. Every entry is created at the same date, and the parents
+/// of the added entries are created along with them. The directories added with [`Memory::deny`] can't be listed.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::scanner::Scanner;
+/// # use dir_cleaner::vfs::Memory;
+/// let mut memory = Memory::default();
+/// memory.add_file("/backup/a.txt", "same");
+/// memory.add_file("/backup/old/a.txt", "same");
+/// let scan = Scanner::new("/backup").name("a.txt").vfs(&memory).scan().unwrap();
+/// assert_eq!(scan.files.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Memory {
+    /// Contents of the files, `None` for the directories.
+    entries: BTreeMap<PathBuf, Option<Vec<u8>>>,
+    /// Directories whose listing is denied.
+    denied: BTreeSet<PathBuf>,
+}
+
+impl Memory {
+    pub fn add_dir(&mut self, path: impl AsRef<Path>) {
+        for dir in path.as_ref().ancestors().filter(|dir| !dir.as_os_str().is_empty()) {
+            self.entries.entry(dir.to_path_buf()).or_insert(None);
+        }
+    }
+
+    pub fn add_file(&mut self, path: impl AsRef<Path>, content: impl Into<Vec<u8>>) {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            self.add_dir(parent);
+        }
+        self.entries.insert(path.to_path_buf(), Some(content.into()));
+    }
+
+    /// Adds the directory located at `path`, whose listing fails as if it wasn't readable.
+    pub fn deny(&mut self, path: impl AsRef<Path>) {
+        self.add_dir(&path);
+        self.denied.insert(path.as_ref().to_path_buf());
+    }
+
+    fn get(&self, path: &Path) -> Result<&Option<Vec<u8>>, io::Error> {
+        self.entries.get(path).ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such entry"))
+    }
+}
+
+impl Vfs for Memory {
+    fn read_dir(&self, dir: &Path) -> Result<Box<dyn Iterator<Item = DirEntry> + '_>, io::Error> {
+        if self.get(dir)?.is_some() {
+            return Err(io::Error::new(io::ErrorKind::NotADirectory, "not a directory"));
+        }
+        if self.denied.contains(dir) {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "permission denied"));
+        }
+        let dir = dir.to_path_buf();
+        let entries = self.entries.iter().filter(move |(path, _)| path.parent() == Some(&dir)).map(|(path, content)| {
+            let (name, meta) = (path.file_name().unwrap_or_default().to_os_string(), meta(content));
+            DirEntry { name, path: path.clone(), kind: meta.kind, meta: Some(meta) }
+        });
+        Ok(Box::new(entries))
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> Result<Meta, io::Error> {
+        self.get(path).map(meta)
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Meta, io::Error> {
+        self.get(path).map(meta)
+    }
+
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, io::Error> {
+        self.get(path).map(|_| path.to_path_buf())
+    }
+
+    fn open(&self, path: &Path) -> Result<Box<dyn Read + '_>, io::Error> {
+        match self.get(path)? {
+            Some(content) => Ok(Box::new(content.as_slice())),
+            None => Err(io::Error::new(io::ErrorKind::IsADirectory, "is a directory")),
+        }
+    }
+}
+
+/// Metadata of an entry of a [`Memory`] filesystem.
+fn meta(content: &Option<Vec<u8>>) -> Meta {
+    let (created, modified) = (Some(SystemTime::UNIX_EPOCH), Some(SystemTime::UNIX_EPOCH));
+    match content {
+        Some(content) => Meta { kind: Kind::File, len: content.len() as u64, created, modified, id: None },
+        None => Meta { kind: Kind::Dir, len: 0, created, modified, id: None },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn memory_lists_and_reads_its_entries() -> Result<(), io::Error> {
+        // Arrange
+        let mut memory = Memory::default();
+        memory.add_file("/data/a.txt", "abc");
+        memory.add_file("/data/nested/b.txt", "");
+        memory.add_dir("/data/empty");
+        memory.deny("/data/denied");
+
+        // Act
+        let mut names: Vec<(OsString, Kind)> = memory.read_dir(Path::new("/data"))?.map(|e| (e.name, e.kind)).collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut content = String::new();
+        memory.open(Path::new("/data/a.txt"))?.read_to_string(&mut content)?;
+
+        // Assert
+        let expected = [("a.txt", Kind::File), ("denied", Kind::Dir), ("empty", Kind::Dir), ("nested", Kind::Dir)];
+        assert_eq!(names, expected.map(|(name, kind)| (OsString::from(name), kind)));
+        assert_eq!(content, "abc");
+        assert_eq!(memory.metadata(Path::new("/data/a.txt"))?.len, 3);
+        assert!(memory.read_dir(Path::new("/data/a.txt")).is_err());
+        assert!(memory.open(Path::new("/missing")).is_err());
+        let denied = memory.read_dir(Path::new("/data/denied")).err().map(|err| err.kind());
+        assert_eq!(denied, Some(io::ErrorKind::PermissionDenied));
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+//! Heuristic detection of re-encoded copies of the same video (requires the `video` feature).
+//!
+//! A few frames are sampled along every video (through `ffmpeg`, which must be on the `PATH`) and reduced to
+//! 64 bits perceptual hashes. Two videos are considered copies of each other when their durations are close
+//! and their sampled frames look alike. Since this is a heuristic, the resulting matches are labeled as such
+//! and must be confirmed manually before any of them is deleted.
+
+use crate::cache::Cache;
+use crate::cloud;
+use crate::storage::{map_parallel, Reading};
+use crate::warnings;
+use crate::File;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Points of the video (as a fraction of its duration) where frames are sampled.
+const SAMPLES: [f64; 5] = [0.1, 0.3, 0.5, 0.7, 0.9];
+/// Highest average amount of differing bits between the sampled frames of two copies.
+const MAX_DISTANCE: u32 = 10;
+/// Highest difference between the durations of two copies, as a fraction of the longest one.
+const DURATION_TOLERANCE: f64 = 0.02;
+
+/// Returned when `ffmpeg` (or `ffprobe`) fails to process a video.
+#[derive(Debug, Clone)]
+pub struct VideoError(pub String);
+
+impl Error for VideoError {}
+
+impl fmt::Display for VideoError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Couldn't sample the video ({}). Is `ffmpeg` installed?", self.0)
+    }
+}
+
+/// What a video looks like: its duration and the hashes of the sampled frames.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Signature {
+    pub duration: f64,
+    pub frames: Vec<u64>,
+}
+
+impl Signature {
+    /// Samples the video located at `path`.
+    pub fn of(path: &Path) -> Result<Self, VideoError> {
+        let duration = probe_duration(path)?;
+        let mut frames = vec![];
+        for sample in SAMPLES {
+            frames.push(frame_hash(path, duration * sample)?);
+        }
+        Ok(Self { duration, frames })
+    }
+
+    /// Same as [`Signature::of`], but reuses the signature stored on `cache` while the video is unchanged.
+    pub fn cached(path: &Path, cache: &Cache) -> Result<Self, VideoError> {
+        let cached = cache.get("video", path).and_then(|value| serde_json::from_str(&value).ok());
+        if let Some(signature) = cached {
+            return Ok(signature);
+        }
+        let signature = Signature::of(path)?;
+        if let Ok(value) = serde_json::to_string(&signature) {
+            cache.insert("video", path, &value);
+        }
+        Ok(signature)
+    }
+
+    /// Whether both signatures probably belong to the same video.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::video::Signature;
+    /// let original = Signature { duration: 600.0, frames: vec![0xff00, 0x0ff0] };
+    /// let reencoded = Signature { duration: 600.4, frames: vec![0xff01, 0x0ff0] };
+    /// let other = Signature { duration: 600.0, frames: vec![!0xff00, !0x0ff0] };
+    /// assert!(original.resembles(&reencoded));
+    /// assert!(!original.resembles(&other));
+    /// ```
+    pub fn resembles(&self, other: &Signature) -> bool {
+        let longest = self.duration.max(other.duration);
+        if (self.duration - other.duration).abs() > longest * DURATION_TOLERANCE {
+            return false;
+        }
+        if self.frames.is_empty() || self.frames.len() != other.frames.len() {
+            return false;
+        }
+        let distance: u32 = self
+            .frames
+            .iter()
+            .zip(&other.frames)
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum();
+        distance <= MAX_DISTANCE * self.frames.len() as u32
+    }
+
+    /// How confident (in percent) we are that both signatures belong to the same video, judging by the average
+    /// amount of differing bits between their sampled frames.
+    /// ## Examples
+    /// ```
+    /// # use dir_cleaner::video::Signature;
+    /// let original = Signature { duration: 600.0, frames: vec![0xff00, 0x0ff0] };
+    /// let reencoded = Signature { duration: 600.4, frames: vec![0xff03, 0x0ff0] };
+    /// assert_eq!(original.confidence(&original), 100);
+    /// assert_eq!(original.confidence(&reencoded), 98);
+    /// ```
+    pub fn confidence(&self, other: &Signature) -> u8 {
+        if self.frames.is_empty() || self.frames.len() != other.frames.len() {
+            return 0;
+        }
+        let distance: u32 = self.frames.iter().zip(&other.frames).map(|(a, b)| (a ^ b).count_ones()).sum();
+        let average = distance as f64 / self.frames.len() as f64;
+        (100.0 - average * 100.0 / 64.0).round().clamp(0.0, 100.0) as u8
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<Vec<u8>, VideoError> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| VideoError(format!("{}: {}", program, e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(VideoError(stderr.lines().next().unwrap_or(program).to_string()));
+    }
+    Ok(output.stdout)
+}
+
+fn probe_duration(path: &Path) -> Result<f64, VideoError> {
+    let path = path.to_string_lossy();
+    let output = run(
+        "ffprobe",
+        &["-v", "error", "-show_entries", "format=duration", "-of", "default=nw=1:nk=1", &path],
+    )?;
+    let output = String::from_utf8_lossy(&output);
+    output
+        .trim()
+        .parse()
+        .map_err(|_| VideoError(format!("unknown duration `{}`", output.trim())))
+}
+
+/// Grabs the frame shown at `seconds`, scaled down to 9x8 gray pixels, and hashes it.
+fn frame_hash(path: &Path, seconds: f64) -> Result<u64, VideoError> {
+    let path = path.to_string_lossy();
+    let seconds = format!("{:.3}", seconds);
+    let pixels = run(
+        "ffmpeg",
+        &[
+            "-v", "error", "-ss", &seconds, "-i", &path, "-frames:v", "1", "-vf", "scale=9:8,format=gray", "-f",
+            "rawvideo", "-",
+        ],
+    )?;
+    dhash(&pixels).ok_or_else(|| VideoError(format!("no frame at {}s", seconds)))
+}
+
+/// Difference hash of a 9x8 gray image: every bit tells whether a pixel is brighter than its right neighbour.
+fn dhash(pixels: &[u8]) -> Option<u64> {
+    if pixels.len() < 72 {
+        return None;
+    }
+    let mut hash = 0;
+    for row in pixels[..72].chunks(9) {
+        for pair in row.windows(2) {
+            hash = (hash << 1) | (pair[0] > pair[1]) as u64;
+        }
+    }
+    Some(hash)
+}
+
+/// Groups the `signatures` that resemble each other, returning the index of the group of every signature
+/// (`None` when it couldn't be computed).
+fn cluster(signatures: &[Option<Signature>]) -> Vec<Option<usize>> {
+    let mut groups: Vec<Option<usize>> = vec![None; signatures.len()];
+    for i in 0..signatures.len() {
+        let signature = match &signatures[i] {
+            Some(signature) => signature,
+            None => continue,
+        };
+        groups[i] = (0..i)
+            .find(|j| matches!(&signatures[*j], Some(other) if other.resembles(signature)))
+            .map(|j| groups[j].unwrap_or(j))
+            .or(Some(i));
+    }
+    groups
+}
+
+/// Confidence of every group found by [`cluster`]: the lowest one between its first signature and the others.
+fn confidences(signatures: &[Option<Signature>], groups: &[Option<usize>]) -> Vec<Option<u8>> {
+    let mut confidences: Vec<Option<u8>> = vec![None; signatures.len()];
+    for (signature, group) in signatures.iter().zip(groups) {
+        if let (Some(signature), Some(group)) = (signature, group) {
+            let first = signatures[*group].as_ref().unwrap_or(signature);
+            let confidence = signature.confidence(first);
+            confidences[*group] = Some(confidences[*group].map_or(confidence, |c| c.min(confidence)));
+        }
+    }
+    confidences
+}
+
+/// Refines the `digest` of the videos among `files`, so the ones looking alike are grouped together. The
+/// resulting digests are heuristic (see [`File::heuristic_match`]) and carry the confidence of their group (see
+/// [`File::confidence`]). Videos without duplicates are skipped
+/// (along with the ones only kept online, see [`cloud`](crate::cloud)), and the ones that can't be sampled are
+/// reported and left untouched.
+pub fn digest_files(files: &mut [File], reading: &Reading) {
+    let readable = cloud::readable(files, reading.include_placeholders);
+    let sampled: Vec<(&File, bool)> = files.iter().zip(readable).collect();
+    let signatures: Vec<Option<Signature>> = map_parallel(&sampled, reading.readers, |(file, readable)| {
+        match readable.then(|| Signature::cached(Path::new(&*file.path()), reading.cache)) {
+            Some(Ok(signature)) => Some(signature),
+            Some(Err(err)) => {
+                let path = file.path();
+                warnings::unreadable_content(&err, &path, format!("`{}`: {}", path, err));
+                None
+            }
+            None => None,
+        }
+    });
+    let groups = cluster(&signatures);
+    let confidences = confidences(&signatures, &groups);
+    for (file, group) in files.iter_mut().zip(groups) {
+        if let Some(group) = group {
+            file.add_digest(&format!("~video:{}@{}", group, confidences[group].unwrap_or(0)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dhash_compares_neighbours() {
+        // Arrange
+        let mut pixels = vec![0u8; 72];
+        pixels[0] = 255;
+        pixels[70] = 255;
+
+        // Act and Assert
+        assert_eq!(dhash(&pixels), Some(1 << 63 | 1));
+        assert_eq!(dhash(&pixels[..40]), None);
+    }
+
+    #[test]
+    fn cluster_similar_signatures() {
+        // Arrange
+        let signature = |duration, frame| Some(Signature { duration, frames: vec![frame; 5] });
+        let signatures = vec![
+            signature(120.0, 0xf0f0),
+            None,
+            signature(300.0, 0xf0f0),
+            signature(120.5, 0xf0f1),
+            signature(300.0, 0xf0f0),
+        ];
+
+        // Act
+        let groups = cluster(&signatures);
+
+        // Assert
+        assert_eq!(groups, vec![Some(0), None, Some(2), Some(0), Some(2)]);
+        assert_eq!(confidences(&signatures, &groups), vec![Some(98), None, Some(100), None, None]);
+    }
+}
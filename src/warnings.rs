@@ -0,0 +1,243 @@
+//! Warnings raised by a scan, kept apart from its results and from the errors (which abort the run): the
+//! directories left out, the entries whose metadata or content couldn't be read, the cloud placeholders that
+//! weren't read, the heuristic matches scoring below `--min-confidence`, and the interrupted scans.
+//!
+//! The listing prints them along with the found files. The `scan` subcommand and `--output` write them on the
+//! standard error instead, following the format of the output (`warning: …` lines, a JSON object per line or a CSV
+//! table), so the scripts can tell them from the results. The reports hold them as well.
+
+use crate::errors;
+use crate::groups;
+use crate::limits::{Skipped, UNREADABLE, UNREADABLE_CONTENT};
+use crate::output::{self, Output};
+use crate::File;
+use serde::{Serialize, Serializer};
+use std::error::Error;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// Content failures gathered for the warnings of the run (see [`gather_content_failures`]), `None` while they're
+/// reported right away.
+static CONTENT_FAILURES: Mutex<Option<Vec<Warning>>> = Mutex::new(None);
+
+/// What a warning is about.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Kind {
+    /// A directory exceeded the limits, or was protected.
+    SkippedDirectory,
+    /// The metadata of an entry couldn't be read, so it was left out.
+    UnreadableMetadata,
+    /// The content of a file couldn't be read (or its text extracted, or its frames sampled), so it wasn't
+    /// compared.
+    UnreadableContent,
+    /// Cloud placeholders weren't read, so their content wasn't compared.
+    Placeholders,
+    /// A heuristic match scored below `--min-confidence`, so its deletion isn't suggested.
+    LowConfidence,
+    /// The scan was interrupted, so only what it found so far is kept.
+    Interrupted,
+}
+
+impl Kind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::SkippedDirectory => "skipped-directory",
+            Kind::UnreadableMetadata => "unreadable-metadata",
+            Kind::UnreadableContent => "unreadable-content",
+            Kind::Placeholders => "placeholders",
+            Kind::LowConfidence => "low-confidence",
+            Kind::Interrupted => "interrupted",
+        }
+    }
+}
+
+impl Serialize for Kind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// A single warning.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Warning {
+    #[serde(rename = "warning")]
+    pub kind: Kind,
+    /// Entry the warning is about, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Sentence describing the warning.
+    pub message: String,
+}
+
+/// From now on, keeps the content failures (see [`unreadable_content`]) until they're taken by
+/// [`Warnings::content_failures`], instead of reporting them right away.
+pub fn gather_content_failures() {
+    CONTENT_FAILURES.lock().unwrap().get_or_insert_with(Vec::new);
+}
+
+/// Records that the content of the file located at `path` couldn't be read (`err`, described by `message`): as an
+/// `unreadable-content` warning while the failures are gathered, through [`errors::report`] otherwise.
+pub fn unreadable_content(err: &(dyn Error + 'static), path: &str, message: String) {
+    let warning = Warning { kind: Kind::UnreadableContent, path: Some(path.to_string()), message };
+    match CONTENT_FAILURES.lock().unwrap().as_mut() {
+        Some(gathered) => gathered.push(warning),
+        None => errors::report(err, Some(path), warning.message),
+    }
+}
+
+/// Warnings of a run, in the order they were raised.
+/// ## Examples
+/// ```
+/// # use dir_cleaner::limits::Skipped;
+/// # use dir_cleaner::output::Output;
+/// # use dir_cleaner::warnings::Warnings;
+/// let mut warnings = Warnings::default();
+/// warnings.skipped(&[Skipped { path: String::from("./cache"), reason: String::from("git repository") }]);
+/// let mut out = vec![];
+/// warnings.write(&mut out, Output::Json).unwrap();
+/// let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+/// assert_eq!(json["warning"], "skipped-directory");
+/// assert_eq!(json["path"], "./cache");
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Warnings {
+    warnings: Vec<Warning>,
+}
+
+impl Warnings {
+    pub fn push(&mut self, kind: Kind, path: Option<&str>, message: String) {
+        self.warnings.push(Warning { kind, path: path.map(str::to_string), message });
+    }
+
+    /// Warns about the entries left out of a scan.
+    pub fn skipped(&mut self, skipped: &[Skipped]) {
+        for dir in skipped {
+            match dir.reason.as_str() {
+                UNREADABLE => {
+                    let message = format!("Left `{}` out, its metadata couldn't be read.", dir.path);
+                    self.push(Kind::UnreadableMetadata, Some(&dir.path), message)
+                }
+                UNREADABLE_CONTENT => {
+                    let message = format!("Left `{}` out, its content couldn't be read.", dir.path);
+                    self.push(Kind::UnreadableContent, Some(&dir.path), message)
+                }
+                _ => {
+                    let message = format!("Skipped `{}`: {}.", dir.path, dir.reason);
+                    self.push(Kind::SkippedDirectory, Some(&dir.path), message)
+                }
+            }
+        }
+    }
+
+    /// Adds the content failures gathered so far (see [`gather_content_failures`]), and reports the next ones
+    /// right away.
+    pub fn content_failures(&mut self) {
+        self.warnings.extend(CONTENT_FAILURES.lock().unwrap().take().unwrap_or_default());
+    }
+
+    /// Warns about the heuristic matches among `files` whose confidence is lower than `min` (in percent).
+    pub fn low_confidence(&mut self, files: &[File], min: u8) {
+        for group in groups::duplicates(files).into_iter().filter(|group| group.heuristic_match()) {
+            let first = match group.files().next() {
+                Some(first) if first.confidence() < min => first,
+                _ => continue,
+            };
+            let message = format!(
+                "The {} copies of `{}` are only {}% confident matches, their deletion isn't suggested.",
+                group.len(),
+                group.name(),
+                first.confidence()
+            );
+            self.push(Kind::LowConfidence, Some(&first.path()), message);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Warning> {
+        self.warnings.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Writes the warnings to `out` following the format of `output`: a `warning: ` line per warning, a JSON object
+    /// per line, or a CSV table (`warning`, `path` and `message`).
+    pub fn write(&self, out: &mut impl Write, output: Output) -> Result<(), io::Error> {
+        if output == Output::Csv && !self.is_empty() {
+            writeln!(out, "warning,path,message")?;
+        }
+        for warning in &self.warnings {
+            match output {
+                Output::Text => writeln!(out, "warning: {}", warning.message)?,
+                Output::Json => writeln!(out, "{}", serde_json::to_string(warning)?)?,
+                Output::Csv => {
+                    let path = output::field(warning.path.as_deref().unwrap_or_default());
+                    writeln!(out, "{},{},{}", warning.kind.name(), path, output::field(&warning.message))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn warnings_are_written_in_every_format() -> Result<(), Box<dyn std::error::Error>> {
+        // Arrange
+        let mut files: Vec<File> = ["./a/clip.mp4", "./b/clip.mp4"]
+            .iter()
+            .map(|path| File::new("clip.mp4", ".", "2022-07-23 12:33:01", path))
+            .collect();
+        files.iter_mut().for_each(|file| file.digest = Some(String::from("~video:3@70")));
+        let mut warnings = Warnings::default();
+        let unreadable = Skipped { path: String::from("./a,b"), reason: String::from(UNREADABLE) };
+        let sniffed = Skipped { path: String::from("./a/clip.mp4"), reason: String::from(UNREADABLE_CONTENT) };
+
+        // Act
+        warnings.skipped(&[unreadable, sniffed]);
+        warnings.low_confidence(&files, 90);
+        warnings.low_confidence(&files, 60);
+        let (mut text, mut json, mut csv) = (vec![], vec![], vec![]);
+        warnings.write(&mut text, Output::Text)?;
+        warnings.write(&mut json, Output::Json)?;
+        warnings.write(&mut csv, Output::Csv)?;
+
+        // Assert
+        let kinds: Vec<Kind> = warnings.iter().map(|warning| warning.kind).collect();
+        assert_eq!(kinds, vec![Kind::UnreadableMetadata, Kind::UnreadableContent, Kind::LowConfidence]);
+        assert_eq!(
+            String::from_utf8(text)?,
+            "warning: Left `./a,b` out, its metadata couldn't be read.\n\
+            warning: Left `./a/clip.mp4` out, its content couldn't be read.\n\
+            warning: The 2 copies of `clip.mp4` are only 70% confident matches, their deletion isn't suggested.\n"
+        );
+        let lines: Vec<serde_json::Value> =
+            String::from_utf8(json)?.lines().map(serde_json::from_str).collect::<Result<_, _>>()?;
+        assert_eq!(lines[1]["warning"], "unreadable-content");
+        assert_eq!(lines[2]["warning"], "low-confidence");
+        assert_eq!(lines[2]["path"], "./a/clip.mp4");
+        let csv = String::from_utf8(csv)?;
+        assert!(csv.starts_with("warning,path,message\nunreadable-metadata,\"./a,b\",\"Left `./a,b` out, its"));
+        Ok(())
+    }
+
+    #[test]
+    fn content_failures_are_gathered_as_warnings() {
+        // Arrange
+        let mut warnings = Warnings::default();
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+
+        // Act
+        gather_content_failures();
+        unreadable_content(&denied, "./a.txt", String::from("Couldn't read `./a.txt`: permission denied"));
+        warnings.content_failures();
+
+        // Assert
+        let kinds: Vec<(Kind, Option<&str>)> = warnings.iter().map(|w| (w.kind, w.path.as_deref())).collect();
+        assert_eq!(kinds, vec![(Kind::UnreadableContent, Some("./a.txt"))]);
+        assert!(CONTENT_FAILURES.lock().unwrap().is_none());
+    }
+}
@@ -0,0 +1,50 @@
+//! Batch runs of the binary (`--delete-all --yes`), on a directory found outside the home directory and the
+//! caches, whose deletions take an extra confirmation nobody answers.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+#[path = "../src/scratch.rs"]
+mod scratch;
+
+use scratch::Scratch;
+
+/// Runs the binary on `dir` with `args`, the home directory being `home` (so `dir` is outside of it).
+fn run(home: &Path, dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dir_cleaner"))
+        .arg(dir)
+        .args(["--name", "foo.txt", "--delete-all", "--yes", "--permanent"])
+        .args(args)
+        .env("HOME", home)
+        .env("USERPROFILE", home)
+        .env("XDG_CACHE_HOME", home.join(".cache"))
+        .output()
+        .expect("the binary should run")
+}
+
+#[test]
+fn batch_runs_fail_when_they_keep_files() -> Result<(), std::io::Error> {
+    // Arrange
+    let home = Scratch::new("batch-home");
+    // Outside the temporary directory as well, which holds caches.
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("batch-{}", std::process::id()));
+    fs::create_dir_all(dir.join("a"))?;
+    fs::write(dir.join("foo.txt"), "1")?;
+    fs::write(dir.join("a").join("foo.txt"), "2")?;
+
+    // Act
+    let kept = run(&home, &dir, &[]);
+    let remaining = (dir.join("foo.txt").exists(), dir.join("a").join("foo.txt").exists());
+    let deleted = run(&home, &dir, &["--allow-unknown-locations"]);
+    let gone = (dir.join("foo.txt").exists(), dir.join("a").join("foo.txt").exists());
+    let _ = fs::remove_dir_all(&dir);
+
+    // Assert
+    assert!(!kept.status.success());
+    assert!(String::from_utf8_lossy(&kept.stderr).contains("2 file(s) planned for deletion were kept"));
+    assert_eq!(remaining, (true, true));
+    assert!(deleted.status.success(), "{}", String::from_utf8_lossy(&deleted.stdout));
+    assert_eq!(gone, (false, false));
+    Ok(())
+}
@@ -0,0 +1,265 @@
+//! Property tests for the traversal engine.
+//!
+//! Every case builds a random directory tree (deep nesting, huge fan-out, weird names and, on `unix`,
+//! symlink loops and links leaving the root) out of a seed, and checks that `get_dir_files` finds exactly
+//! the files that were planted, never reports anything outside the root and never panics. The trees built in
+//! memory (see `dir_cleaner::vfs::Memory`) also hold a directory that can't be listed, which is skipped.
+//! The generator is seeded, so a failing case can be reproduced by re-running its seed.
+
+use std::collections::BTreeSet;
+use std::fs;
+use dir_cleaner::scanner::Scanner;
+use dir_cleaner::vfs::Memory;
+use std::path::{Path, PathBuf};
+
+#[path = "../src/scratch.rs"]
+mod scratch;
+
+use scratch::Scratch;
+
+const TARGET: &str = "target file.txt";
+
+const NAMES: [&str; 12] = [
+    "a",
+    "nested",
+    "with space",
+    "-leading-dash",
+    "..dots..",
+    ".hidden",
+    "ünïcödé",
+    "日本語",
+    "emoji_📁",
+    "quote'\"mark",
+    "target file.txt.bak",
+    "TARGET FILE.TXT",
+];
+
+/// Small `xorshift` generator, so the trees are reproducible without extra dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    fn name(&mut self) -> &'static str {
+        NAMES[self.below(NAMES.len() as u64) as usize]
+    }
+}
+
+/// Populates `dir` with a random subtree, returning the paths of every planted `TARGET` file.
+fn grow(rng: &mut Rng, dir: &Path, root: &Path, outside: &Path, depth: u32) -> BTreeSet<PathBuf> {
+    let mut expected = BTreeSet::new();
+
+    if rng.chance(40) {
+        let path = dir.join(TARGET);
+        fs::write(&path, b"content").unwrap();
+        expected.insert(path);
+    }
+    for _ in 0..rng.below(4) {
+        let _ = fs::write(dir.join(rng.name()), b"noise");
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::symlink;
+        if rng.chance(30) {
+            let _ = symlink(root, dir.join(format!("loop-{}", depth)));
+        }
+        if rng.chance(30) {
+            let _ = symlink(outside, dir.join(format!("escape-{}", depth)));
+        }
+        if rng.chance(20) && !expected.contains(&dir.join(TARGET)) {
+            let _ = symlink(outside.join(TARGET), dir.join(TARGET));
+        }
+    }
+
+    if depth == 0 {
+        return expected;
+    }
+    for _ in 0..rng.below(4) {
+        let sub_dir = dir.join(rng.name());
+        if fs::create_dir(&sub_dir).is_ok() {
+            expected.append(&mut grow(rng, &sub_dir, root, outside, depth - 1));
+        }
+    }
+    expected
+}
+
+/// Populates the in-memory `dir` with a random subtree, returning every directory added and the paths of every
+/// planted `TARGET` file.
+fn grow_in_memory(rng: &mut Rng, memory: &mut Memory, dir: &Path, depth: u32) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let (mut dirs, mut expected) = (vec![], vec![]);
+    if rng.chance(40) {
+        memory.add_file(dir.join(TARGET), "content");
+        expected.push(dir.join(TARGET));
+    }
+    if depth == 0 {
+        return (dirs, expected);
+    }
+    for i in 0..rng.below(4) + 1 {
+        let sub_dir = dir.join(format!("{}-{}", rng.name(), i));
+        memory.add_dir(&sub_dir);
+        dirs.push(sub_dir.clone());
+        let (mut nested_dirs, mut nested) = grow_in_memory(rng, memory, &sub_dir, depth - 1);
+        dirs.append(&mut nested_dirs);
+        expected.append(&mut nested);
+    }
+    (dirs, expected)
+}
+
+fn scan(root: &Path) -> Vec<dir_cleaner::File> {
+    dir_cleaner::get_dir_files(root.to_str().unwrap(), TARGET).unwrap()
+}
+
+fn found_paths(root: &Path) -> Vec<PathBuf> {
+    scan(root)
+        .iter()
+        .map(|f| PathBuf::from(&*f.folder).join(&*f.name))
+        .collect()
+}
+
+#[test]
+fn random_trees_find_exactly_the_planted_files() {
+    for seed in 0..64 {
+        // Arrange
+        let sandbox = Scratch::new(&format!("random-{}", seed));
+        let root = sandbox.join("root");
+        let outside = sandbox.join("outside");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join(TARGET), b"outside").unwrap();
+        let mut rng = Rng::new(seed);
+        let depth = rng.below(6) as u32 + 1;
+        let expected = grow(&mut rng, &root, &root, &outside, depth);
+
+        // Act
+        let found = found_paths(&root);
+
+        // Assert
+        let found_set: BTreeSet<PathBuf> = found.iter().cloned().collect();
+        assert_eq!(found.len(), found_set.len(), "seed {}: duplicated entries", seed);
+        assert_eq!(expected, found_set, "seed {}", seed);
+        assert!(found.iter().all(|p| p.starts_with(&root)), "seed {}: escaped the root", seed);
+    }
+}
+
+#[test]
+fn traversal_is_deterministic() {
+    for seed in 0..16 {
+        // Arrange
+        let sandbox = Scratch::new(&format!("deterministic-{}", seed));
+        let mut rng = Rng::new(seed);
+        grow(&mut rng, &sandbox, &sandbox, &sandbox, 4);
+
+        // Act
+        let first = scan(&sandbox);
+        let second = scan(&sandbox);
+
+        // Assert
+        assert_eq!(first, second, "seed {}", seed);
+    }
+}
+
+#[test]
+fn unreadable_directories_are_skipped() {
+    for seed in 0..32 {
+        // Arrange
+        let mut rng = Rng::new(seed);
+        let mut memory = Memory::default();
+        let root = Path::new("/root");
+        memory.add_dir(root);
+        let (dirs, expected) = grow_in_memory(&mut rng, &mut memory, root, 4);
+        let denied = &dirs[rng.below(dirs.len() as u64) as usize];
+        memory.deny(denied);
+
+        // Act
+        let scan = Scanner::new("/root").name(TARGET).vfs(&memory).scan().unwrap();
+
+        // Assert
+        let found: BTreeSet<PathBuf> = scan.files.iter().map(|f| PathBuf::from(&*f.path())).collect();
+        let reachable: BTreeSet<PathBuf> = expected.into_iter().filter(|path| !path.starts_with(denied)).collect();
+        assert_eq!(found, reachable, "seed {}", seed);
+        assert_eq!(scan.skipped.len(), 1, "seed {}", seed);
+        assert_eq!(Path::new(&scan.skipped[0].path), denied, "seed {}", seed);
+        assert!(scan.skipped[0].reason.starts_with("unreadable: "), "seed {}", seed);
+    }
+}
+
+#[test]
+fn deep_nesting() {
+    // Arrange
+    let sandbox = Scratch::new("deep");
+    let mut dir = sandbox.to_path_buf();
+    for i in 0..64 {
+        dir = dir.join(format!("level-{}", i));
+    }
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(TARGET), b"deep").unwrap();
+
+    // Act
+    let found = found_paths(&sandbox);
+
+    // Assert
+    assert_eq!(found, vec![dir.join(TARGET)]);
+}
+
+#[test]
+fn huge_fan_out() {
+    // Arrange
+    let sandbox = Scratch::new("fan-out");
+    let mut expected = vec![];
+    for i in 0..500 {
+        let dir = sandbox.join(format!("dir-{:04}", i));
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join(format!("file-{}", i)), b"").unwrap();
+        if i % 7 == 0 {
+            fs::write(dir.join(TARGET), b"").unwrap();
+            expected.push(dir.join(TARGET));
+        }
+    }
+
+    // Act
+    let found = found_paths(&sandbox);
+
+    // Assert (entries are visited in lexicographic order).
+    assert_eq!(found, expected);
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_names_are_skipped() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    // Arrange
+    let sandbox = Scratch::new("non-utf8");
+    let dir = sandbox.join(OsStr::from_bytes(b"bad-\xff-name"));
+    if fs::create_dir(&dir).is_err() {
+        // Some filesystems refuse non UTF-8 names altogether.
+        return;
+    }
+    fs::write(dir.join(TARGET), b"").unwrap();
+    fs::write(sandbox.join(TARGET), b"").unwrap();
+
+    // Act
+    let found = found_paths(&sandbox);
+
+    // Assert
+    assert_eq!(found, vec![sandbox.join(TARGET)]);
+}